@@ -0,0 +1,112 @@
+//! Мерење брзине конверзије. Уз укупно трајање конверзије посебно се мери
+//! време проналажења делова текста који се не пресловљавају (веб адресе,
+//! путање, изузети делови), па се види колики део конверзије оно заузима.
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use crate::{Converter, Direction};
+
+// Текст од кога се прави уграђени узорак за мерење
+const SAMPLE: &str = "\
+Ćirko je konvertor srpske ćirilice i latinice. Džemper, njiva i ljubav se
+pišu dvoslovima (dž, nj, lj), a izuzeci poput reči konjunkcija i nadživeti se
+ne pretvaraju u jedno slovo. Detaljnije na https://github.com/igordejanovic/cirko,
+a pitanja se šalju na adresu podrska@primer.rs ili kao prijava u direktorijumu
+/usr/local/share/doc/cirko. Luka Đorđević je 12. marta u 14:30 časova pokrenuo
+build sa #hashtag oznakom i pomenuo @igordejanovic; XIX vek, 5 kWh i 100 km/h.
+";
+
+/// Уграђени латинични узорак за мерење величине око `size` бајтова.
+pub fn sample(size: usize) -> String {
+    SAMPLE.repeat(size.div_ceil(SAMPLE.len()).max(1))
+}
+
+/// Резултат мерења конверзије у једном смеру.
+#[derive(Debug, Clone)]
+pub struct Measurement {
+    pub direction: Direction,
+    /// Величина конвертованог текста у бајтовима.
+    pub bytes: usize,
+    /// Број понављања конверзије.
+    pub iterations: u32,
+    /// Просечно трајање конверзије.
+    pub total: Duration,
+    /// Просечно трајање проналажења делова текста који се прескачу.
+    pub skip: Duration,
+}
+
+impl Measurement {
+    /// Брзина конверзије у MB/s.
+    pub fn throughput(&self) -> f64 {
+        self.bytes as f64 / 1e6 / self.total.as_secs_f64().max(f64::MIN_POSITIVE)
+    }
+
+    /// Просечно трајање пресловљавања, тј. конверзије без проналажења делова
+    /// текста који се прескачу.
+    pub fn mapping(&self) -> Duration {
+        self.total.saturating_sub(self.skip)
+    }
+}
+
+/// Конвертује `input` у смеру `direction` док укупно трајање мерења не
+/// достигне `duration` (а најмање једном) и враћа просечна трајања.
+pub fn measure(
+    converter: &Converter,
+    input: &str,
+    direction: Direction,
+    duration: Duration,
+) -> Measurement {
+    let mut iterations = 0;
+    let (mut total, mut skip) = (Duration::ZERO, Duration::ZERO);
+    while iterations == 0 || total + skip < duration {
+        let start = Instant::now();
+        black_box(converter.convert(black_box(input), direction));
+        total += start.elapsed();
+        let start = Instant::now();
+        black_box(converter.scan_skipped(black_box(input)));
+        skip += start.elapsed();
+        iterations += 1;
+    }
+    Measurement {
+        direction,
+        bytes: input.len(),
+        iterations,
+        total: total / iterations,
+        skip: skip / iterations,
+    }
+}
+
+/// Мери конверзију корпуса `corpus` у оба смера. Корпус се најпре
+/// пресловљава на писмо са кога се конвертује, тако да се у оба смера
+/// конвертује исти текст без обзира на писмо корпуса.
+pub fn measure_all(converter: &Converter, corpus: &str, duration: Duration) -> [Measurement; 2] {
+    [Direction::ToCyrillic, Direction::ToLatin].map(|direction| {
+        let input = converter.convert(corpus, direction.reverse());
+        measure(converter, &input, direction, duration)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_measure() {
+        let converter = Converter::default();
+        let url = "https://github.com/igordejanovic/cirko";
+        assert_eq!(
+            url.len(),
+            converter.scan_skipped(&format!("Детаљније на {url} и тамо"))
+        );
+
+        let corpus = sample(1000);
+        assert!(corpus.len() >= 1000);
+        let [to_cyrillic, to_latin] = measure_all(&converter, &corpus, Duration::ZERO);
+        assert_eq!(Direction::ToCyrillic, to_cyrillic.direction);
+        assert_eq!(corpus.len(), to_cyrillic.bytes);
+        assert!(to_latin.bytes < to_cyrillic.bytes * 2);
+        assert_eq!(1, to_latin.iterations);
+        assert!(to_latin.mapping() <= to_latin.total);
+        assert!(to_latin.throughput() > 0.0);
+    }
+}
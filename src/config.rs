@@ -0,0 +1,195 @@
+//! Конфигурациони фајл са подразумеваним вредностима опција командне линије.
+//! Опције задате у командној линији имају предност над вредностима из
+//! конфигурационог фајла. Пример:
+//!
+//! ```toml
+//! direction = "ћирилица"
+//! alphabet = "српска"
+//! format = "markdown"
+//! tables = "табеле.toml"
+//! exceptions = ["nadživ"]
+//! replace = ["ђ=dj"]
+//! skip = ["веб", "путање"]
+//! no_skip = ["емоџи"]
+//! skip_words = ["iPhone"]
+//! skip_patterns = ['ID-\d+[A-Z]+']
+//! input_encoding = "windows-1250"
+//! ```
+use std::path::{Path, PathBuf};
+use std::{env, fs};
+
+use cirko::Error;
+use clap::Command;
+use serde::Deserialize;
+
+/// Смер конверзије задат у конфигурационом фајлу.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum Direction {
+    #[serde(rename = "латиница", alias = "latin")]
+    ToLatin,
+    #[serde(rename = "ћирилица", alias = "cyrillic")]
+    ToCyrillic,
+}
+
+/// Подешавања из конфигурационог фајла. Вредности имају исти облик као
+/// вредности одговарајућих опција командне линије.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    direction: Option<Direction>,
+    alphabet: Option<String>,
+    scheme: Option<String>,
+    format: Option<String>,
+    /// Фајл са корисничким табелама. Релативна путања се односи на
+    /// директоријум конфигурационог фајла.
+    tables: Option<PathBuf>,
+    #[serde(default)]
+    exceptions: Vec<String>,
+    #[serde(default)]
+    replace: Vec<String>,
+    #[serde(default)]
+    skip: Vec<String>,
+    #[serde(default)]
+    no_skip: Vec<String>,
+    #[serde(default)]
+    units: Vec<String>,
+    #[serde(default)]
+    skip_words: Vec<String>,
+    #[serde(default)]
+    skip_patterns: Vec<String>,
+    quotes: Option<String>,
+    typography: Option<bool>,
+    input_encoding: Option<String>,
+    output_encoding: Option<String>,
+    bom: Option<String>,
+    line_ending: Option<String>,
+}
+
+impl Config {
+    /// Подразумевана путања конфигурационог фајла:
+    /// `$XDG_CONFIG_HOME/cirko/config.toml`, односно
+    /// `~/.config/cirko/config.toml` (`%APPDATA%\cirko\config.toml` на
+    /// Windows-у).
+    pub fn default_path() -> Option<PathBuf> {
+        let var = |name| {
+            env::var_os(name)
+                .filter(|value| !value.is_empty())
+                .map(PathBuf::from)
+        };
+        let directory = if cfg!(windows) {
+            var("APPDATA")?
+        } else {
+            var("XDG_CONFIG_HOME").or_else(|| Some(var("HOME")?.join(".config")))?
+        };
+        Some(directory.join("cirko").join("config.toml"))
+    }
+
+    /// Учитава конфигурациони фајл.
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        let mut config: Config = toml::from_str(&fs::read_to_string(path)?)?;
+        if let (Some(tables), Some(directory)) = (&config.tables, path.parent()) {
+            config.tables = Some(directory.join(tables));
+        }
+        Ok(config)
+    }
+
+    /// Поставља вредности из конфигурације као подразумеване вредности опција
+    /// команде.
+    pub fn apply(&self, mut command: Command) -> Command {
+        let mut set = |id: &str, values: Vec<String>| {
+            if !values.is_empty() {
+                command =
+                    std::mem::take(&mut command).mut_arg(id, |arg| arg.default_values(values));
+            }
+        };
+        match self.direction {
+            Some(Direction::ToLatin) => set("латиница", vec!["true".into()]),
+            Some(Direction::ToCyrillic) => set("ћирилица", vec!["true".into()]),
+            None => {}
+        }
+        set("азбука", self.alphabet.iter().cloned().collect());
+        set("шема", self.scheme.iter().cloned().collect());
+        set("формат", self.format.iter().cloned().collect());
+        set(
+            "табела",
+            self.tables
+                .iter()
+                .map(|path| path.to_string_lossy().into_owned())
+                .collect(),
+        );
+        set("изузеци", self.exceptions.clone());
+        set("замени", self.replace.clone());
+        set("прескачи", self.skip.clone());
+        set("не-прескачи", self.no_skip.clone());
+        set("јединице", self.units.clone());
+        set("прескочи-речи", self.skip_words.clone());
+        set("прескочи-шаблон", self.skip_patterns.clone());
+        set("наводници", self.quotes.iter().cloned().collect());
+        set(
+            "типографија",
+            self.typography.iter().map(bool::to_string).collect(),
+        );
+        set(
+            "улазно-кодирање",
+            self.input_encoding.iter().cloned().collect(),
+        );
+        set(
+            "излазно-кодирање",
+            self.output_encoding.iter().cloned().collect(),
+        );
+        set("бом", self.bom.iter().cloned().collect());
+        set("крај-реда", self.line_ending.iter().cloned().collect());
+        command
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::{Arg, ArgAction};
+
+    #[test]
+    fn test_config() {
+        let path = env::temp_dir().join(format!("cirko-config-{}.toml", std::process::id()));
+        fs::write(
+            &path,
+            "direction = \"ћирилица\"\nalphabet = \"руска\"\ntables = \"табеле.toml\"\nskip = [\"веб\", \"путање\"]\n",
+        )
+        .unwrap();
+        let config = Config::load(&path).unwrap();
+        fs::write(&path, "smer = \"latin\"\n").unwrap();
+        let unknown = Config::load(&path);
+        fs::remove_file(&path).unwrap();
+        assert!(matches!(unknown, Err(Error::Toml(_))));
+        assert_eq!(Some(Direction::ToCyrillic), config.direction);
+        assert_eq!(Some(env::temp_dir().join("табеле.toml")), config.tables);
+
+        let command = Command::new("ћирко")
+            .arg(Arg::new("ћирилица").short('ћ').action(ArgAction::SetTrue))
+            .arg(Arg::new("азбука").short('а').default_value("српска"))
+            .arg(Arg::new("табела").short('т'))
+            .arg(
+                Arg::new("прескачи")
+                    .long("прескачи")
+                    .action(ArgAction::Append),
+            )
+            .arg(
+                Arg::new("не-прескачи")
+                    .long("не-прескачи")
+                    .action(ArgAction::Append),
+            );
+        let matches = config
+            .apply(command)
+            .get_matches_from(["ћирко", "-а", "српска"]);
+        assert!(matches.get_flag("ћирилица"));
+        assert_eq!(Some(&"српска".to_string()), matches.get_one("азбука"));
+        assert_eq!(
+            vec!["веб", "путање"],
+            matches
+                .get_many::<String>("прескачи")
+                .unwrap()
+                .collect::<Vec<_>>()
+        );
+        assert!(matches.get_many::<String>("не-прескачи").is_none());
+    }
+}
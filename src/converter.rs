@@ -0,0 +1,1095 @@
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::ops::Range;
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::Error;
+use crate::diff::{LossySpan, round_trip_losses};
+use crate::languages::{Language, Scheme};
+use crate::script::{Script, normalize_homoglyphs, split_words};
+use crate::skip::{SkipCategory, SkipRules};
+use crate::stats::Stats;
+use crate::typography::{QuoteStyle, apply_typography_with, normalize_quotes_with};
+
+/// Табела пресловљавања у једном смеру. Кључеви и вредности се чувају малим
+/// словима, а величина слова се примењује алгоритамски током конверзије.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Table {
+    map: HashMap<String, String>,
+    // Пресловљавања која важе само на почетку речи
+    initial: HashMap<String, String>,
+    // Пресловљавања која важе само на крају речи
+    final_: HashMap<String, String>,
+    // Дужина најдужег кључа у карактерима
+    max_key_len: usize,
+    // Секвенце у којима се двословна (вишесловна) пресловљавања не примењују
+    exceptions: HashSet<String>,
+    // Дужина најдужег изузетка у бајтовима
+    max_exception_len: usize,
+}
+
+impl Table {
+    pub(crate) fn insert(&mut self, from: &str, to: &str) {
+        self.max_key_len = self.max_key_len.max(from.chars().count());
+        self.map.insert(from.to_string(), to.to_string());
+    }
+
+    pub(crate) fn insert_initial(&mut self, from: &str, to: &str) {
+        self.max_key_len = self.max_key_len.max(from.chars().count());
+        self.initial.insert(from.to_string(), to.to_string());
+    }
+
+    pub(crate) fn insert_final(&mut self, from: &str, to: &str) {
+        self.max_key_len = self.max_key_len.max(from.chars().count());
+        self.final_.insert(from.to_string(), to.to_string());
+    }
+
+    pub(crate) fn insert_exception(&mut self, exception: &str) {
+        self.max_exception_len = self.max_exception_len.max(exception.len());
+        self.exceptions.insert(exception.to_string());
+    }
+
+    /// Додаје корисничка пресловљавања. Кључеви и вредности се своде на мала
+    /// слова.
+    fn extend_custom(&mut self, mappings: &HashMap<String, String>) -> Result<(), Error> {
+        for (from, to) in mappings {
+            if from.is_empty() {
+                return Err(Error::InvalidMapping(format!("\"\" = \"{to}\"")));
+            }
+            self.insert(&from.to_lowercase(), &to.to_lowercase());
+        }
+        Ok(())
+    }
+
+    /// Замењује слово `from` са `to` у свим резултатима пресловљавања.
+    /// Враћа `false` ако се слово не појављује ни у једном резултату.
+    fn replace_output(&mut self, from: char, to: &str) -> bool {
+        let mut found = false;
+        for converted in self
+            .map
+            .values_mut()
+            .chain(self.initial.values_mut())
+            .chain(self.final_.values_mut())
+        {
+            if converted.contains(from) {
+                *converted = converted.replace(from, to);
+                found = true;
+            }
+        }
+        found
+    }
+
+    /// Проналази најдуже пресловљавање које почиње на почетку `chars` и које
+    /// није дуже од `max_len` карактера. Враћа број конзумираних карактера и
+    /// резултат пресловљавања. На почетку, односно крају речи предност имају
+    /// пресловљавања дефинисана за почетак, односно крај речи.
+    fn longest_match(
+        &self,
+        chars: &[(usize, char)],
+        max_len: usize,
+        word_start: bool,
+    ) -> Option<(usize, &str)> {
+        let len = max_len.min(self.max_key_len).min(chars.len());
+        let mut key = String::new();
+        let mut ends = Vec::with_capacity(len);
+        for &(_, c) in &chars[..len] {
+            key.push(c.to_lowercase().next().unwrap());
+            ends.push(key.len());
+        }
+        (1..=len).rev().find_map(|n| {
+            let key = &key[..ends[n - 1]];
+            let word_end = chars.get(n).is_none_or(|&(_, c)| !c.is_alphabetic());
+            word_start
+                .then(|| self.initial.get(key))
+                .flatten()
+                .or_else(|| word_end.then(|| self.final_.get(key)).flatten())
+                .or_else(|| self.map.get(key))
+                .map(|to| (n, to.as_str()))
+        })
+    }
+
+    /// Враћа дужину изузетка у бајтовима ако изузетак почиње на почетку улаза.
+    fn find_exception(&self, input: &str) -> Option<usize> {
+        let check_len = self.max_exception_len.min(input.len());
+        (1..=check_len).rev().find(|&len| {
+            input
+                .get(..len)
+                .is_some_and(|substr| self.exceptions.contains(substr.to_lowercase().as_str()))
+        })
+    }
+}
+
+/// Корисничке табеле пресловљавања учитане из TOML фајла, нпр:
+///
+/// ```toml
+/// # Ако је `true` уграђене табеле се у потпуности замењују
+/// replace = false
+/// # Секвенце у којима се вишесловна пресловљавања на ћирилицу не примењују
+/// exceptions = ["nadživ"]
+///
+/// [cyr_to_lat]
+/// "ђ" = "dj"
+///
+/// [lat_to_cyr]
+/// "dj" = "ђ"
+/// ```
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CustomTables {
+    #[serde(default)]
+    replace: bool,
+    #[serde(default)]
+    exceptions: Vec<String>,
+    #[serde(default)]
+    cyr_to_lat: HashMap<String, String>,
+    #[serde(default)]
+    lat_to_cyr: HashMap<String, String>,
+}
+
+/// Део текста промењен конверзијом: опсег бајтова улаза и опсег бајтова излаза
+/// у који је пресловљен. Текст између делова се преписује непромењен.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Span {
+    pub input: Range<usize>,
+    pub output: Range<usize>,
+}
+
+/// Смер конверзије.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    ToLatin,
+    ToCyrillic,
+}
+
+impl Direction {
+    /// Аутоматска детекција смера конверзије. Уколико текст садржи ћирилицу
+    /// конвертује се у латиницу и обратно. Гледамо цео ћирилични блок јер неке
+    /// азбуке (нпр. руска) имају слова ван опсега `а-ш`.
+    pub fn detect(input: &str) -> Self {
+        if input.chars().any(|c| ('\u{400}'..='\u{4FF}').contains(&c)) {
+            Direction::ToLatin
+        } else {
+            Direction::ToCyrillic
+        }
+    }
+
+    /// Смер конверзије у писмо задато ознаком језика по BCP 47 (нпр.
+    /// `sr-Latn`). Враћа `None` ако ознака не садржи писмо.
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        tag.split(['-', '_']).skip(1).find_map(|subtag| {
+            match subtag.to_ascii_lowercase().as_str() {
+                "latn" => Some(Direction::ToLatin),
+                "cyrl" => Some(Direction::ToCyrillic),
+                _ => None,
+            }
+        })
+    }
+
+    /// Супротан смер конверзије.
+    pub fn reverse(self) -> Self {
+        match self {
+            Direction::ToLatin => Direction::ToCyrillic,
+            Direction::ToCyrillic => Direction::ToLatin,
+        }
+    }
+}
+
+/// Конвертор између ћирилице и латинице за одабрани језик.
+///
+/// Слободне функције [`crate::cyr_to_lat`] и [`crate::lat_to_cyr`] користе
+/// подразумевани конвертор за српски језик.
+#[derive(Debug, Clone)]
+pub struct Converter {
+    language: Language,
+    scheme: Scheme,
+    to_latin: Table,
+    to_cyrillic: Table,
+    // Поправка речи са помешаним писмима пре конверзије
+    homoglyphs: bool,
+    // Замена правих наводника типографским након конверзије
+    quotes: Option<QuoteStyle>,
+    // Типографска обрада након конверзије
+    typography: bool,
+    // Делови текста који се не пресловљавају
+    skip: SkipRules,
+}
+
+impl Default for Converter {
+    fn default() -> Self {
+        Converter::new(Language::default())
+    }
+}
+
+impl Converter {
+    /// Конвертор за дати језик са подразумеваним системом романизације.
+    pub fn new(language: Language) -> Self {
+        Converter::build(language, language.default_scheme())
+    }
+
+    /// Конвертор за дати језик и систем романизације.
+    pub fn with_scheme(language: Language, scheme: Scheme) -> Result<Self, Error> {
+        if !language.schemes().contains(&scheme) {
+            return Err(Error::UnsupportedScheme { language, scheme });
+        }
+        Ok(Converter::build(language, scheme))
+    }
+
+    fn build(language: Language, scheme: Scheme) -> Self {
+        let mut to_latin = Table::default();
+        let mut to_cyrillic = Table::default();
+        language.fill_tables(scheme, &mut to_latin, &mut to_cyrillic);
+        Converter {
+            language,
+            scheme,
+            to_latin,
+            to_cyrillic,
+            homoglyphs: false,
+            quotes: None,
+            typography: false,
+            skip: SkipRules::default(),
+        }
+    }
+
+    pub fn language(&self) -> Language {
+        self.language
+    }
+
+    pub fn scheme(&self) -> Scheme {
+        self.scheme
+    }
+
+    /// Учитава корисничке табеле пресловљавања из TOML текста (секције
+    /// `[cyr_to_lat]` и `[lat_to_cyr]`). Пресловљавања допуњују или
+    /// премошћавају уграђене табеле, осим ако је наведено `replace = true`
+    /// када их у потпуности замењују.
+    pub fn load_tables(&mut self, toml: &str) -> Result<(), Error> {
+        let custom: CustomTables = toml::from_str(toml)?;
+        if custom.replace {
+            self.to_latin = Table::default();
+            self.to_cyrillic = Table::default();
+        }
+        self.to_latin.extend_custom(&custom.cyr_to_lat)?;
+        self.to_cyrillic.extend_custom(&custom.lat_to_cyr)?;
+        for exception in &custom.exceptions {
+            self.to_cyrillic.insert_exception(&exception.to_lowercase());
+        }
+        Ok(())
+    }
+
+    /// Учитава корисничке табеле пресловљавања из TOML фајла. Погледати
+    /// [`Converter::load_tables`].
+    pub fn load_tables_file(&mut self, path: impl AsRef<Path>) -> Result<(), Error> {
+        self.load_tables(&fs::read_to_string(path)?)
+    }
+
+    /// Мења пресловљавање једног слова на латиницу. Ако је `from` ћирилично
+    /// слово мења се његово пресловљавање (нпр. `('џ', "dz")`), а ако је
+    /// латинично слово мења се у свим резултатима пресловљавања (нпр.
+    /// `('đ', "dj")`). Конверзија на ћирилицу се не мења; за то користити
+    /// [`Converter::load_tables`].
+    pub fn override_mapping(&mut self, from: char, to: &str) -> Result<(), Error> {
+        let from = from.to_lowercase().next().unwrap();
+        let to = to.to_lowercase();
+        let key = from.to_string();
+        if self.to_latin.map.contains_key(&key) {
+            self.to_latin.insert(&key, &to);
+            Ok(())
+        } else if self.to_latin.replace_output(from, &to) {
+            Ok(())
+        } else {
+            Err(Error::InvalidMapping(format!("{from}={to}")))
+        }
+    }
+
+    /// Додаје изузетак: секвенцу у којој се вишесловна пресловљавања на
+    /// ћирилицу не примењују (нпр. `nadživ`).
+    pub fn add_exception(&mut self, exception: &str) {
+        self.to_cyrillic.insert_exception(&exception.to_lowercase());
+    }
+
+    /// Укључује поправку речи са помешаним писмима (хомоглифа) пре
+    /// конверзије. Погледати [`crate::script::normalize_homoglyphs`].
+    pub fn set_homoglyph_normalization(&mut self, enabled: bool) {
+        self.homoglyphs = enabled;
+    }
+
+    /// Укључује замену правих наводника типографским након конверзије.
+    /// Погледати [`crate::typography::normalize_quotes`].
+    pub fn set_quote_style(&mut self, style: Option<QuoteStyle>) {
+        self.quotes = style;
+    }
+
+    /// Укључује типографску обраду (три тачке, црте, нераскидиви размаци)
+    /// након конверзије. Погледати [`crate::typography::apply_typography`].
+    pub fn set_typography(&mut self, enabled: bool) {
+        self.typography = enabled;
+    }
+
+    /// Укључује или искључује прескакање једне категорије делова текста
+    /// (веб адресе, путање...). Погледати [`SkipCategory::enabled_by_default`].
+    pub fn set_skip_category(&mut self, category: SkipCategory, enabled: bool) {
+        self.skip.set(category, enabled);
+    }
+
+    /// Поставља маркере у тексту између којих се конверзија не ради (нпр.
+    /// `("<!-- ćirko:off -->", "<!-- ćirko:on -->")`). Са `None` се маркери не
+    /// препознају. Подразумевани маркери су [`crate::skip::DEFAULT_MARKERS`].
+    pub fn set_skip_markers(&mut self, markers: Option<(&str, &str)>) {
+        self.skip.set_markers(markers);
+    }
+
+    /// Поставља листу мерних јединица које после броја остају непромењене
+    /// (категорија [`SkipCategory::Unit`]). Подразумевана листа је
+    /// [`crate::skip::DEFAULT_UNITS`].
+    pub fn set_units<S: AsRef<str>>(&mut self, units: &[S]) {
+        self.skip.set_units(units);
+    }
+
+    /// Поставља речи и изразе који се не конвертују (категорија
+    /// [`SkipCategory::Word`]), нпр. називе производа. Речи се препознају
+    /// само као целе речи и разликују се велика и мала слова.
+    pub fn set_skip_words<S: AsRef<str>>(&mut self, words: &[S]) {
+        self.skip.set_words(words);
+    }
+
+    /// Додаје регуларни израз за делове текста који се не конвертују
+    /// (категорија [`SkipCategory::Pattern`]), нпр. `ID-\d+[A-Z]+`. Израз се
+    /// примењује на почетку речи.
+    pub fn add_skip_pattern(&mut self, pattern: &str) -> Result<(), Error> {
+        self.skip.add_pattern(pattern)
+    }
+
+    /// Конверзија ћирилице на латиницу
+    pub fn to_latin(&self, input: &str) -> String {
+        self.convert(input, Direction::ToLatin)
+    }
+
+    /// Конверзија латинице на ћирилицу
+    pub fn to_cyrillic(&self, input: &str) -> String {
+        self.convert(input, Direction::ToCyrillic)
+    }
+
+    /// Обраде које се раде пре пресловљавања
+    fn preprocess<'a>(&self, input: &'a str) -> Cow<'a, str> {
+        if self.homoglyphs {
+            Cow::Owned(normalize_homoglyphs(input))
+        } else {
+            Cow::Borrowed(input)
+        }
+    }
+
+    /// Обраде које се раде након пресловљавања
+    fn postprocess(&self, output: String) -> String {
+        let output = match self.quotes {
+            Some(style) => normalize_quotes_with(&output, style, &self.skip),
+            None => output,
+        };
+        if self.typography {
+            apply_typography_with(&output, &self.skip)
+        } else {
+            output
+        }
+    }
+
+    /// Конверзија у задатом смеру
+    pub fn convert(&self, input: &str, direction: Direction) -> String {
+        self.convert_with_stats(input, direction, &mut Stats::default())
+    }
+
+    /// Конверзија у задатом смеру уз бележење статистике конверзије у
+    /// `stats`.
+    pub fn convert_with_stats(
+        &self,
+        input: &str,
+        direction: Direction,
+        stats: &mut Stats,
+    ) -> String {
+        let input = self.preprocess(input);
+        let (table, capacity) = match direction {
+            // Латинични облик може бити већи
+            Direction::ToLatin => (&self.to_latin, input.len() * 2),
+            Direction::ToCyrillic => (&self.to_cyrillic, input.len()),
+        };
+        self.postprocess(transliterate(
+            &input, table, &self.skip, capacity, stats, None,
+        ))
+    }
+
+    /// Конвертује текст у задатом смеру, а затим назад, и враћа речи које се
+    /// не враћају у изворни облик. Погледати [`crate::diff::round_trip_losses`].
+    pub fn round_trip(&self, input: &str, direction: Direction) -> Vec<LossySpan> {
+        let converted = self.convert(input, direction);
+        let restored = self.convert(&converted, direction.reverse());
+        round_trip_losses(input, &converted, &restored)
+    }
+
+    /// Конверзија у задатом смеру која уз резултат враћа и делове текста који
+    /// су промењени (нпр. за означавање измена у едитору). Поправка
+    /// хомоглифа и типографске обраде се не примењују.
+    pub fn convert_with_spans(&self, input: &str, direction: Direction) -> (String, Vec<Span>) {
+        let table = match direction {
+            Direction::ToLatin => &self.to_latin,
+            Direction::ToCyrillic => &self.to_cyrillic,
+        };
+        let mut spans = Vec::new();
+        let output = transliterate(
+            input,
+            table,
+            &self.skip,
+            input.len() * 2,
+            &mut Stats::default(),
+            Some(&mut spans),
+        );
+        (output, spans)
+    }
+
+    /// Речи резултата конверзије `output` у смеру `direction` које и даље
+    /// садрже слова писма са кога се конвертује (нпр. `w` и `q` код конверзије
+    /// на ћирилицу или руско `ы` код конверзије на латиницу). Делови текста
+    /// који се намерно не пресловљавају (веб адресе, изузете речи, делови
+    /// између маркера) се не узимају у обзир.
+    pub fn unconverted_words<'a>(&self, output: &'a str, direction: Direction) -> Vec<&'a str> {
+        let source = match direction {
+            Direction::ToLatin => Script::Cyrillic,
+            Direction::ToCyrillic => Script::Latin,
+        };
+        let mut words = Vec::new();
+        // Делови текста између делова који се прескачу
+        let mut push_words = |start: usize, end: usize| {
+            words.extend(
+                split_words(&output[start..end]).filter_map(|(word, is_word)| {
+                    (is_word && word.chars().any(|c| Script::of(c) == Some(source))).then_some(word)
+                }),
+            );
+        };
+        let (mut start, mut pos) = (0, 0);
+        while let Some(c) = output[pos..].chars().next() {
+            match self.skip.find_segment(output, pos) {
+                Some((len, _)) => {
+                    push_words(start, pos);
+                    pos += len;
+                    start = pos;
+                }
+                None => pos += c.len_utf8(),
+            }
+        }
+        push_words(start, output.len());
+        words
+    }
+
+    /// Проналази делове текста који се не пресловљавају на исти начин као при
+    /// конверзији, али без пресловљавања, и враћа укупну дужину тих делова у
+    /// бајтовима. Користи се за мерење удела ових провера у трајању
+    /// конверзије.
+    pub(crate) fn scan_skipped(&self, input: &str) -> usize {
+        let mut skipped = 0;
+        let mut pos = 0;
+        while let Some(c) = input[pos..].chars().next() {
+            match self.skip.find_segment(input, pos) {
+                Some((len, _)) => {
+                    skipped += len;
+                    pos += len;
+                }
+                None => pos += c.len_utf8(),
+            }
+        }
+        skipped
+    }
+
+    /// Дели текст на границама линија на делове од најмање `size` бајтова
+    /// који се могу конвертовати засебно (нпр. ради праћења напретка). Делови
+    /// текста искључени маркерима се не деле.
+    pub fn split_chunks<'a>(&self, input: &'a str, size: usize) -> Vec<&'a str> {
+        // Крај линије на којој је бајт `pos`
+        let line_end = |pos: usize| {
+            input.as_bytes()[pos.min(input.len())..]
+                .iter()
+                .position(|&b| b == b'\n')
+                .map_or(input.len(), |i| pos + i + 1)
+        };
+        let mut chunks = Vec::new();
+        let mut start = 0;
+        while start < input.len() {
+            let mut end = line_end(start + size);
+            if let Some((off, on)) = self.skip.markers() {
+                let mut pos = start;
+                while let Some(found) = input[pos..end].find(off) {
+                    let region = pos + found + off.len();
+                    match input[region..].find(on) {
+                        Some(i) => {
+                            pos = region + i + on.len();
+                            end = end.max(line_end(pos));
+                        }
+                        None => {
+                            end = input.len();
+                            break;
+                        }
+                    }
+                }
+            }
+            chunks.push(&input[start..end]);
+            start = end;
+        }
+        chunks
+    }
+}
+
+fn transliterate(
+    input: &str,
+    table: &Table,
+    skip: &SkipRules,
+    capacity: usize,
+    stats: &mut Stats,
+    mut spans: Option<&mut Vec<Span>>,
+) -> String {
+    let mut output = String::with_capacity(capacity);
+    let chars = input.char_indices().collect::<Vec<_>>();
+    stats.characters += chars.len();
+    let mut i = 0;
+    let mut exception_until = 0; // До ког бајта важи пронађени изузетак
+
+    while i < chars.len() {
+        let (pos, c) = chars[i];
+        if let Some((skip_bytes, category)) = skip.find_segment(input, pos) {
+            // Преузимамо текст који се прескаче без промене
+            stats.skip(category);
+            let skipped = &input[pos..pos + skip_bytes];
+            output.push_str(skipped);
+            i += skipped.chars().count();
+            continue;
+        }
+
+        // Унутар изузетка радимо нормалну карактер-по-карактер транслацију
+        let max_len = if pos < exception_until {
+            1
+        } else if let Some(len) = table.find_exception(&input[pos..]) {
+            exception_until = pos + len;
+            stats.exceptions += 1;
+            *stats
+                .applied_exceptions
+                .entry(input[pos..exception_until].to_lowercase())
+                .or_default() += 1;
+            1
+        } else {
+            usize::MAX
+        };
+
+        let word_start = i == 0 || !chars[i - 1].1.is_alphabetic();
+        match table.longest_match(&chars[i..], max_len, word_start) {
+            Some((len, converted)) => {
+                let lowercase = chars[i..i + len]
+                    .iter()
+                    .map(|&(_, c)| c.to_lowercase().next().unwrap());
+                let changed = !converted.chars().eq(lowercase);
+                if changed {
+                    stats.converted += len;
+                }
+                let start = output.len();
+                let rest_upper = rest_is_upper(&chars[i + 1..], len - 1);
+                push_cased(&mut output, converted, c.is_uppercase(), rest_upper);
+                i += len;
+                if changed && let Some(spans) = spans.as_deref_mut() {
+                    let end = chars.get(i).map_or(input.len(), |&(pos, _)| pos);
+                    // Суседни промењени делови се спајају
+                    match spans.last_mut() {
+                        Some(last) if last.input.end == pos && last.output.end == start => {
+                            last.input.end = end;
+                            last.output.end = output.len();
+                        }
+                        _ => spans.push(Span {
+                            input: pos..end,
+                            output: start..output.len(),
+                        }),
+                    }
+                }
+            }
+            None => {
+                output.push(c); // Ако није слово азбуке не конвертуј
+                i += 1;
+            }
+        }
+    }
+    output
+}
+
+/// Одређује да ли остатак вишесловног пресловљавања треба писати великим
+/// словима. Прво се гледају преостали карактери подударања а затим карактер
+/// који следи (нпр. `Љубав -> Ljubav`, али `ЉУБАВ -> LJUBAV`).
+fn rest_is_upper(chars: &[(usize, char)], matched: usize) -> bool {
+    chars
+        .iter()
+        .take(matched + 1)
+        .map(|&(_, c)| c)
+        .find(|c| c.is_alphabetic())
+        .is_some_and(|c| c.is_uppercase())
+}
+
+fn push_cased(output: &mut String, converted: &str, first_upper: bool, rest_upper: bool) {
+    for (idx, c) in converted.chars().enumerate() {
+        if (idx == 0 && first_upper) || (idx > 0 && rest_upper) {
+            output.extend(c.to_uppercase());
+        } else {
+            output.push(c);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_direction_from_tag() {
+        assert_eq!(Some(Direction::ToLatin), Direction::from_tag("sr-Latn"));
+        assert_eq!(
+            Some(Direction::ToCyrillic),
+            Direction::from_tag("sr-Cyrl-RS")
+        );
+        assert_eq!(Some(Direction::ToLatin), Direction::from_tag("sr_latn"));
+        assert_eq!(None, Direction::from_tag("sr"));
+        assert_eq!(None, Direction::from_tag("en-US"));
+    }
+
+    #[test]
+    fn test_montenegrin() {
+        let conv = Converter::new(Language::Montenegrin);
+        assert_eq!(
+            "Śekira i źenica",
+            conv.to_latin("С\u{301}екира и з\u{301}еница")
+        );
+        assert_eq!("ŚEKIRA", conv.to_latin("С\u{301}ЕКИРА"));
+        assert_eq!(
+            "С\u{301}екира и з\u{301}еница",
+            conv.to_cyrillic("Śekira i źenica")
+        );
+        // Комбинујући облик у латиници
+        assert_eq!(
+            "С\u{301}екира и з\u{301}еница",
+            conv.to_cyrillic("S\u{301}ekira i z\u{301}enica")
+        );
+        // Двословне секвенце и изузеци важе као у српском
+        assert_eq!("Љубав надживети", conv.to_cyrillic("Ljubav nadživeti"));
+    }
+
+    #[test]
+    fn test_russian_gost() {
+        let conv = Converter::new(Language::Russian);
+        assert_eq!("Shhuka i yozh", conv.to_latin("Щука и ёж"));
+        assert_eq!("SHHUKA", conv.to_latin("ЩУКА"));
+        assert_eq!("Sy'n s``el e`tot obed", conv.to_latin("Сын съел этот обед"));
+        assert_eq!(
+            "Сын съел этот обед",
+            conv.to_cyrillic("Sy'n s``el e`tot obed")
+        );
+        assert_eq!("Щука и ёж", conv.to_cyrillic("Shhuka i yozh"));
+        assert_eq!(
+            "Cirk, cy'gan, czar` i kuricza",
+            conv.to_latin("Цирк, цыган, царь и курица")
+        );
+        assert_eq!("CIRK", conv.to_latin("ЦИРК"));
+        assert_eq!(
+            "Цирк, цыган, царь и курица",
+            conv.to_cyrillic("Cirk, cy'gan, czar` i kuricza")
+        );
+    }
+
+    #[test]
+    fn test_russian_bgn_pcgn() {
+        let conv = Converter::with_scheme(Language::Russian, Scheme::BgnPcgn).unwrap();
+        assert_eq!("Yelena Khrushchëva", conv.to_latin("Елена Хрущёва"));
+        assert_eq!("moye, Tsar’, sov’yet", conv.to_latin("мое, Царь, совьет"));
+        assert_eq!("Tolstoy", conv.to_latin("Толстой"));
+        assert_eq!("Толстой", conv.to_cyrillic("Tolstoy"));
+        assert_eq!("Елена", conv.to_cyrillic("Yelena"));
+        assert_eq!("SHCHUKA", conv.to_latin("ЩУКА"));
+    }
+
+    #[test]
+    fn test_ukrainian_kmu_2010() {
+        let conv = Converter::new(Language::Ukrainian);
+        assert_eq!(
+            "Zghorany, Yizhakevych, Yurii",
+            conv.to_latin("Згорани, Їжакевич, Юрій")
+        );
+        assert_eq!(
+            "Mariana Hryhorivna Ivanytska",
+            conv.to_latin("Мар'яна Григорівна Іваницька")
+        );
+        assert_eq!(
+            "Koropie, Yenakiieve, Ganna",
+            conv.to_latin("Коропє, Єнакієве, Ґанна")
+        );
+        assert_eq!("KYIV", conv.to_latin("КИЇВ"));
+        assert_eq!("Харків", conv.to_cyrillic("Kharkiv"));
+    }
+
+    #[test]
+    fn test_bulgarian_streamlined() {
+        let conv = Converter::new(Language::Bulgarian);
+        assert_eq!("Istoria, Sofia", conv.to_latin("История, София"));
+        assert_eq!("Balgarski", conv.to_latin("Български"));
+        assert_eq!("Shtastie i yunak", conv.to_latin("Щастие и юнак"));
+        assert_eq!("Iliyan, Ilia", conv.to_latin("Илиян, Илия"));
+        assert_eq!("ЩАСТИЕ", conv.to_cyrillic("SHTASTIE"));
+        assert_eq!("София", conv.to_cyrillic("Sofia"));
+    }
+
+    #[test]
+    fn test_serbian_historical() {
+        let conv = Converter::new(Language::SerbianHistorical);
+        assert_eq!("Pesma o vremenu", conv.to_latin("Пѣсма о врѣмену"));
+        assert_eq!("Teodor i Dimitrije", conv.to_latin("Ѳеодоръ ї Димитрїе"));
+        assert_eq!("Ljubav", conv.to_latin("Льубавь"));
+        let conv = Converter::with_scheme(Language::SerbianHistorical, Scheme::Ijekavian).unwrap();
+        assert_eq!("Pjesma", conv.to_latin("Пѣсма"));
+        let conv =
+            Converter::with_scheme(Language::SerbianHistorical, Scheme::Etymological).unwrap();
+        assert_eq!("PĚSMA", conv.to_latin("ПѢСМА"));
+        // Конверзија на ћирилицу даје савремену азбуку
+        assert_eq!("Љубав", conv.to_cyrillic("Ljubav"));
+    }
+
+    #[test]
+    fn test_custom_tables() {
+        let mut conv = Converter::default();
+        conv.load_tables(
+            r#"
+            [cyr_to_lat]
+            "ђ" = "dj"
+            [lat_to_cyr]
+            "dj" = "ђ"
+            "#,
+        )
+        .unwrap();
+        assert_eq!("Djura DJURIĆ", conv.to_latin("Ђура ЂУРИЋ"));
+        assert_eq!("Ђура Ђурић", conv.to_cyrillic("Djura Đurić"));
+
+        // Потпуна замена табела
+        let mut conv = Converter::default();
+        conv.load_tables(
+            r#"
+            replace = true
+            [cyr_to_lat]
+            "ж" = "zh"
+            "#,
+        )
+        .unwrap();
+        assert_eq!("Zhаба", conv.to_latin("Жаба"));
+        assert_eq!("žaba", conv.to_cyrillic("žaba"));
+
+        assert!(conv.load_tables("[cyr_to_lat]\n\"\" = \"x\"").is_err());
+        assert!(conv.load_tables("[nepoznato]").is_err());
+    }
+
+    #[test]
+    fn test_override_mapping() {
+        let mut conv = Converter::default();
+        conv.override_mapping('đ', "dj").unwrap();
+        conv.override_mapping('Џ', "dz").unwrap();
+        assert_eq!("Djura i Dzeko, DJURA", conv.to_latin("Ђура и Џеко, ЂУРА"));
+        // Конверзија на ћирилицу остаје непромењена
+        assert_eq!("Ђура", conv.to_cyrillic("Đura"));
+        assert!(conv.override_mapping('w', "v").is_err());
+    }
+
+    #[test]
+    fn test_homoglyph_normalization() {
+        let mut conv = Converter::default();
+        conv.set_homoglyph_normalization(true);
+        // Ћирилично `о` у латиничној адреси
+        assert_eq!(
+            "Адреса: www.google.com",
+            conv.to_cyrillic("Adresa: www.gооgle.com")
+        );
+    }
+
+    #[test]
+    fn test_quote_style() {
+        let mut conv = Converter::default();
+        conv.set_quote_style(Some(QuoteStyle::Low));
+        assert_eq!(
+            "Рекао је „здраво”.",
+            conv.to_cyrillic("Rekao je \"zdravo\".")
+        );
+        conv.set_quote_style(Some(QuoteStyle::Angle));
+        assert_eq!("Rekao je »zdravo«.", conv.to_latin("Рекао је \"здраво\"."));
+    }
+
+    #[test]
+    fn test_typography() {
+        let mut conv = Converter::default();
+        conv.set_typography(true);
+        conv.set_quote_style(Some(QuoteStyle::Low));
+        assert_eq!(
+            "„Чекај…” – рекао је после 5\u{a0}km.",
+            conv.to_cyrillic("\"Čekaj...\" - rekao je posle 5 km.")
+        );
+    }
+
+    #[test]
+    fn test_skip_paths() {
+        let mut conv = Converter::default();
+        assert_eq!(
+            "Покрени /usr/local/bin/cirko или C:\\Users\\Igor\\cirko.exe",
+            conv.to_cyrillic("Pokreni /usr/local/bin/cirko ili C:\\Users\\Igor\\cirko.exe")
+        );
+        assert_eq!("ћирилица/латиница", conv.to_cyrillic("ćirilica/latinica"));
+        conv.set_skip_category(SkipCategory::Path, false);
+        assert_eq!("/уср/лоцал", conv.to_cyrillic("/usr/local"));
+    }
+
+    #[test]
+    fn test_skip_addresses_and_hashes() {
+        let conv = Converter::default();
+        assert_eq!(
+            "Адреса fe80::1, ууид 123e4567-e89b-12d3-a456-426614174000, комит a6d0d33",
+            conv.to_cyrillic(
+                "Adresa fe80::1, uuid 123e4567-e89b-12d3-a456-426614174000, komit a6d0d33"
+            )
+        );
+    }
+
+    #[test]
+    fn test_skip_mentions() {
+        let conv = Converter::default();
+        assert_eq!(
+            "Хвала @igordejanovic и @marko_m!",
+            conv.to_cyrillic("Hvala @igordejanovic i @marko_m!")
+        );
+    }
+
+    #[test]
+    fn test_skip_emoji_shortcodes() {
+        let conv = Converter::default();
+        assert_eq!(
+            "Свака част :thumbsup: :slightly_smiling_face:",
+            conv.to_cyrillic("Svaka čast :thumbsup: :slightly_smiling_face:")
+        );
+    }
+
+    #[test]
+    fn test_skip_publications() {
+        let conv = Converter::default();
+        assert_eq!(
+            "Видети doi:10.1000/182, ISBN 0-306-40615-X и arXiv:2101.00001.",
+            conv.to_cyrillic("Videti doi:10.1000/182, ISBN 0-306-40615-X i arXiv:2101.00001.")
+        );
+    }
+
+    #[test]
+    fn test_skip_template_placeholders() {
+        let mut conv = Converter::default();
+        let input = "Zdravo {{ user.name }}, imate %d poruka od %1$s u {0}.";
+        assert_eq!(
+            "Здраво {{ user.name }}, имате %d порука од %1$s у {0}.",
+            conv.to_cyrillic(input)
+        );
+        conv.set_skip_category(SkipCategory::Template, false);
+        assert_eq!("{{ име }}", conv.to_cyrillic("{{ ime }}"));
+    }
+
+    #[test]
+    fn test_skip_entities() {
+        let conv = Converter::default();
+        assert_eq!(
+            "Ђорђевић &amp; син, При&scaron;тина",
+            conv.to_cyrillic("Đorđević &amp; sin, Pri&scaron;tina")
+        );
+    }
+
+    #[test]
+    fn test_skip_code_identifiers() {
+        let mut conv = Converter::default();
+        let input = "Metoda getUserName vraća vrednost polja user_name.";
+        assert_eq!(
+            "Метода гетУсерНаме враћа вредност поља усер_наме.",
+            conv.to_cyrillic(input)
+        );
+        conv.set_skip_category(SkipCategory::Code, true);
+        assert_eq!(
+            "Метода getUserName враћа вредност поља user_name.",
+            conv.to_cyrillic(input)
+        );
+    }
+
+    #[test]
+    fn test_skip_words() {
+        let mut conv = Converter::default();
+        conv.set_skip_words(&["Cirko Studio", "iPhone"]);
+        assert_eq!(
+            "Cirko Studio ради на телефону iPhone, а ћирко на свему.",
+            conv.to_cyrillic("Cirko Studio radi na telefonu iPhone, a ćirko na svemu.")
+        );
+    }
+
+    #[test]
+    fn test_skip_patterns() {
+        let mut conv = Converter::default();
+        conv.add_skip_pattern(r"ID-\d+[A-Z]+").unwrap();
+        assert_eq!(
+            "Захтев ID-123AB је решен.",
+            conv.to_cyrillic("Zahtev ID-123AB je rešen.")
+        );
+        assert!(conv.add_skip_pattern("(").is_err());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let conv = Converter::default();
+        let losses = conv.round_trip("Жупан и\nподжупан.", Direction::ToLatin);
+        assert_eq!(
+            vec![LossySpan {
+                line: 2,
+                column: 1,
+                original: "поджупан".into(),
+                converted: "podžupan".into(),
+                restored: "поџупан".into(),
+            }],
+            losses
+        );
+        assert!(
+            conv.round_trip("podžupan", Direction::ToCyrillic)
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_skip_markers() {
+        let mut conv = Converter::default();
+        assert_eq!(
+            "Превод\n<!-- ћирко:искључи -->\nOriginal\n<!-- ћирко:укључи -->\nКрај",
+            conv.to_cyrillic(
+                "Prevod\n<!-- ћирко:искључи -->\nOriginal\n<!-- ћирко:укључи -->\nKraj"
+            )
+        );
+        conv.set_skip_markers(Some(("[[", "]]")));
+        assert_eq!("Ја [[ja]] ти", conv.to_cyrillic("Ja [[ja]] ti"));
+    }
+
+    #[test]
+    fn test_split_chunks() {
+        let conv = Converter::default();
+        assert_eq!(
+            vec!["prvi red\n", "drugi red\n", "treći"],
+            conv.split_chunks("prvi red\ndrugi red\ntreći", 5)
+        );
+        let input = "a\n<!-- ћирко:искључи -->\nb\nc\n<!-- ћирко:укључи --> d\ne\n";
+        assert_eq!(
+            vec![
+                "a\n<!-- ћирко:искључи -->\nb\nc\n<!-- ћирко:укључи --> d\n",
+                "e\n"
+            ],
+            conv.split_chunks(input, 3)
+        );
+    }
+
+    #[test]
+    fn test_skip_roman_numerals() {
+        let conv = Converter::default();
+        assert_eq!(
+            "У XIX веку је владао Карло V, а ви и ја смо у XXI.",
+            conv.to_cyrillic("U XIX veku je vladao Karlo V, a vi i ja smo u XXI.")
+        );
+        assert_eq!("МИ СМО ВИ", conv.to_cyrillic("MI SMO VI"));
+        assert_eq!(
+            "Да ли сте ВИ то урадили? Не, МИ нисмо.",
+            conv.to_cyrillic("Da li ste VI to uradili? Ne, MI nismo.")
+        );
+        assert_eq!(
+            "Луј VI и VI. разред у VI веку",
+            conv.to_cyrillic("Luj VI i VI. razred u VI veku")
+        );
+        assert_eq!(
+            "Том Цруисе, М. Илић. Витамин Ц и витамин Д.",
+            conv.to_cyrillic("Tom Cruise, M. Ilić. Vitamin C i vitamin D.")
+        );
+    }
+
+    #[test]
+    fn test_skip_units() {
+        let mut conv = Converter::default();
+        assert_eq!(
+            "Потрошња је 5 kWh при 100 km/h, а не 5 мачака.",
+            conv.to_cyrillic("Potrošnja je 5 kWh pri 100 km/h, a ne 5 mačaka.")
+        );
+        assert_eq!(
+            "Стигло је 3 с поруком, рођен 1990 г. у 7 х",
+            conv.to_cyrillic("Stiglo je 3 s porukom, rođen 1990 g. u 7 h")
+        );
+        conv.set_units(&["din"]);
+        assert_eq!(
+            "Кошта 100 din за 5 км",
+            conv.to_cyrillic("Košta 100 din za 5 km")
+        );
+    }
+
+    #[test]
+    fn test_unsupported_scheme() {
+        assert!(Converter::with_scheme(Language::Serbian, Scheme::Gost).is_err());
+    }
+
+    #[test]
+    fn test_serbian_ignores_montenegrin_letters() {
+        let conv = Converter::default();
+        assert_eq!("śекира", conv.to_cyrillic("śekira"));
+    }
+
+    #[test]
+    fn test_convert_with_stats() {
+        let mut conv = Converter::default();
+        conv.add_exception("nadživ");
+        let mut stats = Stats::default();
+        assert_eq!(
+            "Надживео 12 www.primer.rs",
+            conv.convert_with_stats(
+                "Nadživeo 12 www.primer.rs",
+                Direction::ToCyrillic,
+                &mut stats
+            )
+        );
+        assert_eq!(25, stats.characters);
+        assert_eq!(8, stats.converted);
+        assert_eq!(1, stats.exceptions);
+        assert_eq!(Some(&1), stats.applied_exceptions.get("nadživ"));
+        assert_eq!(Some(&1), stats.skipped.get(&SkipCategory::Web));
+    }
+
+    #[test]
+    fn test_unconverted_words() {
+        let conv = Converter::default();
+        let output = conv.to_cyrillic("Wi-Fi mreža, kviz i quiz na https://example.com/wiki");
+        assert_eq!(
+            vec!["Wи", "qуиз"],
+            conv.unconverted_words(&output, Direction::ToCyrillic)
+        );
+        let output = conv.to_latin("Щука и ђак");
+        assert_eq!(
+            vec!["Щuka"],
+            conv.unconverted_words(&output, Direction::ToLatin)
+        );
+    }
+
+    #[test]
+    fn test_convert_with_spans() {
+        let conv = Converter::default();
+        let (output, spans) =
+            conv.convert_with_spans("Njiva, 12 www.x.rs ž", Direction::ToCyrillic);
+        assert_eq!("Њива, 12 www.x.rs ж", output);
+        assert_eq!(
+            vec![
+                Span {
+                    input: 0..5,
+                    output: 0..8
+                },
+                Span {
+                    input: 19..21,
+                    output: 22..24
+                },
+            ],
+            spans
+        );
+    }
+}
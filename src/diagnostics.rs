@@ -0,0 +1,201 @@
+//! Поруке о грешкама, упозорења и обавештења о току конверзије, које се
+//! исписују на стандардни излаз за грешке. Обавештења се исписују само уз
+//! опцију `-в` (`-v`), а детаљна обавештења уз `-вв`. Са опцијом
+//! `--грешке-json` се свака порука исписује као JSON објекат у посебном реду,
+//! нпр.:
+//!
+//! ```json
+//! {"level":"warning","kind":"encoding","file":"stari.txt","message":"неисправни знакови у кодирању windows-1250 су замењени знаком „�”"}
+//! ```
+use std::fmt;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+
+use cirko::Error;
+use cirko::formats::json::json_string;
+
+use crate::messages::tr;
+
+/// Ниво поруке.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Level {
+    Error,
+    Warning,
+    /// Обавештење које се исписује уз `-в`.
+    Info,
+    /// Детаљно обавештење које се исписује уз `-вв`.
+    Debug,
+}
+
+impl Level {
+    fn name(self) -> &'static str {
+        match self {
+            Level::Error => "error",
+            Level::Warning => "warning",
+            Level::Info => "info",
+            Level::Debug => "debug",
+        }
+    }
+}
+
+/// Врста поруке, која омогућава програмима да поруке обраде без
+/// препознавања текста поруке.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    /// Неисправно задате опције.
+    Usage,
+    /// Грешка при читању, конверзији или упису фајла.
+    Io,
+    /// Синтаксна грешка у документу.
+    Syntax,
+    /// Текст се не може декодирати или записати у задатом кодирању.
+    Encoding,
+    /// Бинарни фајл који није конвертован.
+    Binary,
+    /// Примењен изузетак пресловљавања.
+    Exception,
+    /// Реч резултата која није пресловљена (`--строго`).
+    Unconverted,
+    /// Конвертован фајл.
+    Converted,
+}
+
+impl Kind {
+    /// Врста грешке библиотеке.
+    pub fn of(error: &Error) -> Self {
+        match error {
+            Error::Syntax { .. } => Kind::Syntax,
+            Error::Unencodable { .. } | Error::UnknownEncoding(_) => Kind::Encoding,
+            _ => Kind::Io,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Kind::Usage => "usage",
+            Kind::Io => "io",
+            Kind::Syntax => "syntax",
+            Kind::Encoding => "encoding",
+            Kind::Binary => "binary",
+            Kind::Exception => "exception",
+            Kind::Unconverted => "unconverted",
+            Kind::Converted => "converted",
+        }
+    }
+}
+
+static VERBOSITY: AtomicU8 = AtomicU8::new(0);
+static JSON: AtomicBool = AtomicBool::new(false);
+
+/// Поставља број понављања опције `-в` и да ли се поруке исписују у JSON
+/// облику.
+pub fn init(verbosity: u8, json: bool) {
+    VERBOSITY.store(verbosity, Ordering::Relaxed);
+    JSON.store(json, Ordering::Relaxed);
+}
+
+/// Да ли се поруке исписују у JSON облику.
+pub fn json() -> bool {
+    JSON.load(Ordering::Relaxed)
+}
+
+/// Исписује поруку нивоа `level` која се односи на фајл `file`. Порука се
+/// исписује на задатом језику порука.
+fn emit(level: Level, kind: Kind, file: Option<&Path>, message: impl fmt::Display) {
+    let verbosity = VERBOSITY.load(Ordering::Relaxed);
+    let visible = match level {
+        Level::Error | Level::Warning => true,
+        Level::Info => verbosity >= 1,
+        Level::Debug => verbosity >= 2,
+    };
+    if visible {
+        eprintln!(
+            "{}",
+            format_message(level, kind, file, &tr(message), json())
+        );
+    }
+}
+
+pub fn error(kind: Kind, file: Option<&Path>, message: impl fmt::Display) {
+    emit(Level::Error, kind, file, message);
+}
+
+pub fn warning(kind: Kind, file: Option<&Path>, message: impl fmt::Display) {
+    emit(Level::Warning, kind, file, message);
+}
+
+pub fn info(kind: Kind, file: Option<&Path>, message: impl fmt::Display) {
+    emit(Level::Info, kind, file, message);
+}
+
+pub fn debug(kind: Kind, file: Option<&Path>, message: impl fmt::Display) {
+    emit(Level::Debug, kind, file, message);
+}
+
+fn format_message(
+    level: Level,
+    kind: Kind,
+    file: Option<&Path>,
+    message: &str,
+    json: bool,
+) -> String {
+    let file = file.map(|file| file.to_string_lossy());
+    if json {
+        let file = file.as_deref().map_or("null".to_string(), json_string);
+        return format!(
+            "{{\"level\":\"{}\",\"kind\":\"{}\",\"file\":{file},\"message\":{}}}",
+            level.name(),
+            kind.name(),
+            json_string(message)
+        );
+    }
+    let prefix = match level {
+        Level::Warning => format!("{}: ", tr("упозорење")),
+        _ => String::new(),
+    };
+    match file {
+        Some(file) => format!("{prefix}{file}: {message}"),
+        None => format!("{prefix}{message}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_message() {
+        let file = Path::new("\"a\".txt");
+        assert_eq!(
+            "упозорење: \"a\".txt: кодирање windows-1250",
+            format_message(
+                Level::Warning,
+                Kind::Encoding,
+                Some(file),
+                "кодирање windows-1250",
+                false
+            )
+        );
+        assert_eq!(
+            r#"{"level":"warning","kind":"encoding","file":"\"a\".txt","message":"кодирање windows-1250"}"#,
+            format_message(
+                Level::Warning,
+                Kind::Encoding,
+                Some(file),
+                "кодирање windows-1250",
+                true
+            )
+        );
+        assert_eq!(
+            r#"{"level":"error","kind":"usage","file":null,"message":"a\nb"}"#,
+            format_message(Level::Error, Kind::Usage, None, "a\nb", true)
+        );
+        assert_eq!(
+            Kind::Syntax,
+            Kind::of(&Error::Syntax {
+                line: 1,
+                message: String::new()
+            })
+        );
+    }
+}
@@ -0,0 +1,382 @@
+//! Приказ измена насталих конверзијом у облику обједињеног (unified) диф-а.
+use std::collections::HashSet;
+
+// Број непромењених линија које се приказују око измена
+const CONTEXT: usize = 3;
+
+const RED: &str = "\x1b[31m";
+const GREEN: &str = "\x1b[32m";
+const CYAN: &str = "\x1b[36m";
+const BOLD: &str = "\x1b[1m";
+const RESET: &str = "\x1b[0m";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Op {
+    Equal(usize, usize),
+    Delete(usize),
+    Insert(usize),
+}
+
+/// Враћа обједињени диф линија текста `old` и `new`, или празан стринг ако
+/// нема измена. Ако је `color` тачно, линије се боје ANSI секвенцама.
+pub fn unified_diff(old: &str, new: &str, old_name: &str, new_name: &str, color: bool) -> String {
+    let old_lines: Vec<&str> = old.split_inclusive('\n').collect();
+    let new_lines: Vec<&str> = new.split_inclusive('\n').collect();
+    let ops = diff_lines(&old_lines, &new_lines);
+
+    // Групе измена са контекстом (опсези у листи операција)
+    let mut hunks: Vec<(usize, usize)> = Vec::new();
+    for (i, op) in ops.iter().enumerate() {
+        if matches!(op, Op::Equal(..)) {
+            continue;
+        }
+        let start = i.saturating_sub(CONTEXT);
+        let end = (i + CONTEXT + 1).min(ops.len());
+        match hunks.last_mut() {
+            Some(last) if start <= last.1 => last.1 = end,
+            _ => hunks.push((start, end)),
+        }
+    }
+    if hunks.is_empty() {
+        return String::new();
+    }
+
+    let paint = |code: &str, text: &str| {
+        if color {
+            format!("{code}{text}{RESET}")
+        } else {
+            text.to_string()
+        }
+    };
+    let mut output = paint(BOLD, &format!("--- a/{old_name}\n+++ b/{new_name}"));
+    output.push('\n');
+    for (start, end) in hunks {
+        let hunk = &ops[start..end];
+        // Позиција прве линије групе у старом и новом тексту
+        let (old_start, new_start) = ops[..start].iter().fold((0, 0), |(o, n), op| match op {
+            Op::Equal(..) => (o + 1, n + 1),
+            Op::Delete(_) => (o + 1, n),
+            Op::Insert(_) => (o, n + 1),
+        });
+        let old_count = hunk
+            .iter()
+            .filter(|op| !matches!(op, Op::Insert(_)))
+            .count();
+        let new_count = hunk
+            .iter()
+            .filter(|op| !matches!(op, Op::Delete(_)))
+            .count();
+        let range = |start: usize, count: usize| {
+            let start = if count == 0 { start } else { start + 1 };
+            format!("{start},{count}")
+        };
+        let header = format!(
+            "@@ -{} +{} @@",
+            range(old_start, old_count),
+            range(new_start, new_count)
+        );
+        output.push_str(&paint(CYAN, &header));
+        output.push('\n');
+        for op in hunk {
+            let (prefix, line, code) = match *op {
+                Op::Equal(i, _) => (' ', old_lines[i], ""),
+                Op::Delete(i) => ('-', old_lines[i], RED),
+                Op::Insert(j) => ('+', new_lines[j], GREEN),
+            };
+            let content = line.strip_suffix('\n').unwrap_or(line);
+            let content = format!("{prefix}{content}");
+            if code.is_empty() {
+                output.push_str(&content);
+            } else {
+                output.push_str(&paint(code, &content));
+            }
+            output.push('\n');
+            if !line.ends_with('\n') {
+                output.push_str("\\ No newline at end of file\n");
+            }
+        }
+    }
+    output
+}
+
+/// Реч која се повратном конверзијом не враћа у изворни облик (нпр. због
+/// двосмислених диграфа или страних слова).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LossySpan {
+    /// Редни број линије (од 1).
+    pub line: usize,
+    /// Редни број знака у линији (од 1).
+    pub column: usize,
+    pub original: String,
+    pub converted: String,
+    pub restored: String,
+}
+
+/// Упоређује изворни текст `original` са текстом `restored` добијеним
+/// повратном конверзијом текста `converted` и враћа речи које се разликују.
+/// Ако се речи неке линије не могу упарити (различит број речи), враћа се цела
+/// линија.
+pub fn round_trip_losses(original: &str, converted: &str, restored: &str) -> Vec<LossySpan> {
+    let mut losses = Vec::new();
+    let lines = original
+        .split_inclusive('\n')
+        .zip(converted.split_inclusive('\n'))
+        .zip(restored.split_inclusive('\n'));
+    for (i, ((original, converted), restored)) in lines.enumerate() {
+        if original == restored {
+            continue;
+        }
+        let (original_words, converted_words, restored_words) =
+            (words(original), words(converted), words(restored));
+        if original_words.len() != converted_words.len()
+            || original_words.len() != restored_words.len()
+        {
+            let line = |text: &str| text.trim_end_matches(['\r', '\n']).to_string();
+            losses.push(LossySpan {
+                line: i + 1,
+                column: 1,
+                original: line(original),
+                converted: line(converted),
+                restored: line(restored),
+            });
+            continue;
+        }
+        for ((&(offset, word), &(_, converted)), &(_, restored)) in original_words
+            .iter()
+            .zip(&converted_words)
+            .zip(&restored_words)
+        {
+            if word != restored {
+                losses.push(LossySpan {
+                    line: i + 1,
+                    column: original[..offset].chars().count() + 1,
+                    original: word.to_string(),
+                    converted: converted.to_string(),
+                    restored: restored.to_string(),
+                });
+            }
+        }
+    }
+    losses
+}
+
+/// Речи (низови слова и цифара) линије са позицијама у бајтовима.
+fn words(line: &str) -> Vec<(usize, &str)> {
+    let mut words = Vec::new();
+    let mut start = None;
+    for (i, c) in line.char_indices().chain([(line.len(), ' ')]) {
+        match (c.is_alphanumeric(), start) {
+            (true, None) => start = Some(i),
+            (false, Some(s)) => {
+                words.push((s, &line[s..i]));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    words
+}
+
+/// Враћа редне бројеве (од 1) и садржај линија текста `old` које у тексту
+/// `new` нису остале непромењене.
+pub fn changed_lines<'a>(old: &'a str, new: &str) -> Vec<(usize, &'a str)> {
+    let old_lines: Vec<&str> = old.split_inclusive('\n').collect();
+    let new_lines: Vec<&str> = new.split_inclusive('\n').collect();
+    diff_lines(&old_lines, &new_lines)
+        .into_iter()
+        .filter_map(|op| match op {
+            Op::Delete(i) => Some((i + 1, old_lines[i].trim_end_matches(['\r', '\n']))),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Одређује операције које линије `old` преводе у линије `new`.
+fn diff_lines(old: &[&str], new: &[&str]) -> Vec<Op> {
+    // Заједнички почетак и крај се не пореде алгоритмом
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let old_middle = &old[prefix..old.len() - suffix];
+    let new_middle = &new[prefix..new.len() - suffix];
+
+    // Линије које не постоје у другом тексту су сигурно измењене па се
+    // изостављају. Код пресловљеног текста то су скоро све линије, што знатно
+    // убрзава поређење.
+    let old_set: HashSet<&str> = old_middle.iter().copied().collect();
+    let new_set: HashSet<&str> = new_middle.iter().copied().collect();
+    let old_kept: Vec<usize> = (0..old_middle.len())
+        .filter(|&i| new_set.contains(old_middle[i]))
+        .collect();
+    let new_kept: Vec<usize> = (0..new_middle.len())
+        .filter(|&j| old_set.contains(new_middle[j]))
+        .collect();
+    let old_filtered: Vec<&str> = old_kept.iter().map(|&i| old_middle[i]).collect();
+    let new_filtered: Vec<&str> = new_kept.iter().map(|&j| new_middle[j]).collect();
+
+    let matches = (0..prefix)
+        .map(|i| (i, i))
+        .chain(
+            matching_lines(&old_filtered, &new_filtered)
+                .into_iter()
+                .map(|(i, j)| (prefix + old_kept[i], prefix + new_kept[j])),
+        )
+        .chain((0..suffix).map(|i| (old.len() - suffix + i, new.len() - suffix + i)));
+
+    let mut ops = Vec::with_capacity(old.len().max(new.len()));
+    let (mut i, mut j) = (0, 0);
+    for (old_index, new_index) in matches {
+        ops.extend((i..old_index).map(Op::Delete));
+        ops.extend((j..new_index).map(Op::Insert));
+        ops.push(Op::Equal(old_index, new_index));
+        (i, j) = (old_index + 1, new_index + 1);
+    }
+    ops.extend((i..old.len()).map(Op::Delete));
+    ops.extend((j..new.len()).map(Op::Insert));
+    ops
+}
+
+/// Парови индекса једнаких линија најдужег заједничког подниза (Мајерсов
+/// алгоритам).
+fn matching_lines(old: &[&str], new: &[&str]) -> Vec<(usize, usize)> {
+    let (n, m) = (old.len() as isize, new.len() as isize);
+    let offset = n + m + 1;
+    let index = |k: isize| (k + offset) as usize;
+    let mut v = vec![0isize; 2 * offset as usize + 1];
+    let mut trace = Vec::new();
+    'search: for d in 0..=(n + m) {
+        trace.push(v.clone());
+        for k in (-d..=d).step_by(2) {
+            let mut x = if k == -d || (k != d && v[index(k - 1)] < v[index(k + 1)]) {
+                v[index(k + 1)]
+            } else {
+                v[index(k - 1)] + 1
+            };
+            let mut y = x - k;
+            while x < n && y < m && old[x as usize] == new[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            v[index(k)] = x;
+            if x >= n && y >= m {
+                break 'search;
+            }
+        }
+    }
+
+    let mut pairs = Vec::new();
+    let (mut x, mut y) = (n, m);
+    for (d, v) in trace.iter().enumerate().rev() {
+        let d = d as isize;
+        let k = x - y;
+        let prev_k = if k == -d || (k != d && v[index(k - 1)] < v[index(k + 1)]) {
+            k + 1
+        } else {
+            k - 1
+        };
+        let prev_x = v[index(prev_k)];
+        let prev_y = prev_x - prev_k;
+        while x > prev_x && y > prev_y {
+            x -= 1;
+            y -= 1;
+            pairs.push((x as usize, y as usize));
+        }
+        x = prev_x;
+        y = prev_y;
+    }
+    pairs.reverse();
+    pairs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unified_diff() {
+        let old = "1\n2\n3\nZdravo\n5\n6\n7\n8\n9\n10\n11\nsvete";
+        let new = "1\n2\n3\nЗдраво\n5\n6\n7\n8\n9\n10\n11\nсвете";
+        let expected = "--- a/tekst.txt
++++ b/tekst.txt
+@@ -1,7 +1,7 @@
+ 1
+ 2
+ 3
+-Zdravo
++Здраво
+ 5
+ 6
+ 7
+@@ -9,4 +9,4 @@
+ 9
+ 10
+ 11
+-svete
+\\ No newline at end of file
++свете
+\\ No newline at end of file
+";
+        assert_eq!(
+            expected,
+            unified_diff(old, new, "tekst.txt", "tekst.txt", false)
+        );
+        assert_eq!("", unified_diff(old, old, "tekst.txt", "tekst.txt", false));
+
+        let diff = unified_diff("a\nb\n", "a\nx\ny\nb\n", "t", "t", true);
+        assert_eq!(
+            "\x1b[1m--- a/t\n+++ b/t\x1b[0m\n\x1b[36m@@ -1,2 +1,4 @@\x1b[0m\n a\n\x1b[32m+x\x1b[0m\n\x1b[32m+y\x1b[0m\n b\n",
+            diff
+        );
+    }
+
+    #[test]
+    fn test_changed_lines() {
+        let old = "Здраво\nsvete\r\n123\nkraj";
+        let new = "Здраво\nсвете\r\n123\nкрај";
+        assert_eq!(vec![(2, "svete"), (4, "kraj")], changed_lines(old, new));
+        assert!(changed_lines(new, new).is_empty());
+    }
+
+    #[test]
+    fn test_round_trip_losses() {
+        let losses = round_trip_losses(
+            "Добро јутро.\nнадживети Wagnera\n",
+            "Dobro jutro.\nnadživeti Wagnera\n",
+            "Добро јутро.\nнаџивети Wагнера\n",
+        );
+        assert_eq!(
+            vec![
+                LossySpan {
+                    line: 2,
+                    column: 1,
+                    original: "надживети".into(),
+                    converted: "nadživeti".into(),
+                    restored: "наџивети".into(),
+                },
+                LossySpan {
+                    line: 2,
+                    column: 11,
+                    original: "Wagnera".into(),
+                    converted: "Wagnera".into(),
+                    restored: "Wагнера".into(),
+                },
+            ],
+            losses
+        );
+        let losses = round_trip_losses("a b\n", "a b\n", "ab\n");
+        assert_eq!(
+            vec![LossySpan {
+                line: 1,
+                column: 1,
+                original: "a b".into(),
+                converted: "a b".into(),
+                restored: "ab".into(),
+            }],
+            losses
+        );
+    }
+}
@@ -0,0 +1,399 @@
+//! Декодирање и кодирање текста у старим кодним странама (windows-1250,
+//! windows-1251, ISO-8859-2, ISO-8859-5) и UTF-16, уз детекцију кодирања на
+//! основу садржаја.
+use std::fmt;
+use std::io;
+use std::str::FromStr;
+
+use once_cell::sync::Lazy;
+
+use crate::Error;
+
+// Знакови кодних страна Windows за бајтове од 0x80 до 0xFF
+static CP1250: Lazy<Vec<char>> = Lazy::new(|| {
+    "€\u{fffd}‚\u{fffd}„…†‡\u{fffd}‰Š‹ŚŤŽŹ\u{fffd}‘’“”•–—\u{fffd}™š›śťžź\u{a0}ˇ˘Ł¤Ą¦§¨©Ş«¬\u{ad}®Ż\
+     °±˛ł´µ¶·¸ąş»Ľ˝ľżŔÁÂĂÄĹĆÇČÉĘËĚÍÎĎĐŃŇÓÔŐÖ×ŘŮÚŰÜÝŢßŕáâăäĺćçčéęëěíîďđńňóôőö÷řůúűüýţ˙"
+        .chars()
+        .collect()
+});
+static CP1251: Lazy<Vec<char>> = Lazy::new(|| {
+    "ЂЃ‚ѓ„…†‡€‰Љ‹ЊЌЋЏђ‘’“”•–—\u{fffd}™љ›њќћџ\u{a0}ЎўЈ¤Ґ¦§Ё©Є«¬\u{ad}®Ї°±Ііґµ¶·ё№є»јЅѕї\
+     АБВГДЕЖЗИЙКЛМНОПРСТУФХЦЧШЩЪЫЬЭЮЯабвгдежзийклмнопрстуфхцчшщъыьэюя"
+        .chars()
+        .collect()
+});
+static CP1252: Lazy<Vec<char>> = Lazy::new(|| {
+    "€\u{fffd}‚ƒ„…†‡ˆ‰Š‹Œ\u{fffd}Ž\u{fffd}\u{fffd}‘’“”•–—˜™š›œ\u{fffd}žŸ\u{a0}¡¢£¤¥¦§¨©ª«¬\u{ad}®¯\
+     °±²³´µ¶·¸¹º»¼½¾¿ÀÁÂÃÄÅÆÇÈÉÊËÌÍÎÏÐÑÒÓÔÕÖ×ØÙÚÛÜÝÞßàáâãäåæçèéêëìíîïðñòóôõö÷øùúûüýþÿ"
+        .chars()
+        .collect()
+});
+
+// Знакови ISO-8859 кодних страна за бајтове од 0xA0 до 0xFF (бајтови од 0x80
+// до 0x9F су контролни знакови)
+static ISO_8859_2: Lazy<Vec<char>> = Lazy::new(|| {
+    "\u{a0}Ą˘Ł¤ĽŚ§¨ŠŞŤŹ\u{ad}ŽŻ°ą˛ł´ľśˇ¸šşťź˝žż\
+     ŔÁÂĂÄĹĆÇČÉĘËĚÍÎĎĐŃŇÓÔŐÖ×ŘŮÚŰÜÝŢßŕáâăäĺćçčéęëěíîďđńňóôőö÷řůúűüýţ˙"
+        .chars()
+        .collect()
+});
+static ISO_8859_5: Lazy<Vec<char>> = Lazy::new(|| {
+    "\u{a0}ЁЂЃЄЅІЇЈЉЊЋЌ\u{ad}ЎЏАБВГДЕЖЗИЙКЛМНОПРСТУФХЦЧШЩЪЫЬЭЮЯ\
+     абвгдежзийклмнопрстуфхцчшщъыьэюя№ёђѓєѕіїјљњћќ§ўџ"
+        .chars()
+        .collect()
+});
+
+/// Ознака редоследа бајтова (BOM) на почетку текста.
+pub const BOM: char = '\u{feff}';
+
+// Слова српске азбуке и абецеде ван ASCII опсега
+const SERBIAN_LOWERCASE: &str = "абвгдђежзијклљмнњопрстћуфхцчџшčćžšđ";
+const SERBIAN_UPPERCASE: &str = "АБВГДЂЕЖЗИЈКЛЉМНЊОПРСТЋУФХЦЧЏШČĆŽŠĐ";
+
+/// Кодирање текста.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    Utf8,
+    Utf16Le,
+    Utf16Be,
+    Windows1250,
+    Windows1251,
+    Windows1252,
+    Iso8859_2,
+    Iso8859_5,
+}
+
+impl Encoding {
+    /// Сва подржана кодирања.
+    pub const ALL: &'static [Encoding] = &[
+        Encoding::Utf8,
+        Encoding::Utf16Le,
+        Encoding::Utf16Be,
+        Encoding::Windows1250,
+        Encoding::Windows1251,
+        Encoding::Windows1252,
+        Encoding::Iso8859_2,
+        Encoding::Iso8859_5,
+    ];
+
+    // Једнобајтна кодирања међу којима се бира при детекцији, по приоритету
+    const DETECTED: &'static [Encoding] = &[
+        Encoding::Windows1250,
+        Encoding::Windows1251,
+        Encoding::Iso8859_2,
+        Encoding::Iso8859_5,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Encoding::Utf8 => "utf-8",
+            Encoding::Utf16Le => "utf-16le",
+            Encoding::Utf16Be => "utf-16be",
+            Encoding::Windows1250 => "windows-1250",
+            Encoding::Windows1251 => "windows-1251",
+            Encoding::Windows1252 => "windows-1252",
+            Encoding::Iso8859_2 => "iso-8859-2",
+            Encoding::Iso8859_5 => "iso-8859-5",
+        }
+    }
+
+    /// Број кодне стране једнобајтног кодирања.
+    fn code_page(self) -> Option<u32> {
+        match self {
+            Encoding::Windows1250 => Some(1250),
+            Encoding::Windows1251 => Some(1251),
+            Encoding::Windows1252 => Some(1252),
+            Encoding::Iso8859_2 => Some(28592),
+            Encoding::Iso8859_5 => Some(28595),
+            _ => None,
+        }
+    }
+
+    /// Детектује кодирање текста. Редом се проверавају BOM, нулти бајтови
+    /// (UTF-16) и исправност UTF-8 записа, а затим се бира једнобајтно
+    /// кодирање у коме декодирани текст највише личи на српски.
+    pub fn detect(bytes: &[u8]) -> Encoding {
+        if bytes.starts_with(&[0xef, 0xbb, 0xbf]) {
+            return Encoding::Utf8;
+        } else if bytes.starts_with(&[0xff, 0xfe]) {
+            return Encoding::Utf16Le;
+        } else if bytes.starts_with(&[0xfe, 0xff]) {
+            return Encoding::Utf16Be;
+        }
+        // ASCII знакови у UTF-16 запису имају нулти бајт
+        let zeros = |parity: usize| {
+            bytes
+                .iter()
+                .skip(parity)
+                .step_by(2)
+                .filter(|&&byte| byte == 0)
+                .count()
+        };
+        match (zeros(0), zeros(1)) {
+            (0, odd) if odd > 0 => return Encoding::Utf16Le,
+            (even, 0) if even > 0 => return Encoding::Utf16Be,
+            _ => {}
+        }
+        if std::str::from_utf8(bytes).is_ok() {
+            return Encoding::Utf8;
+        }
+        let mut best = (Encoding::Utf8, i64::MIN);
+        for &encoding in Encoding::DETECTED {
+            let score = serbian_score(encoding, bytes);
+            if score > best.1 {
+                best = (encoding, score);
+            }
+        }
+        best.0
+    }
+
+    /// Декодира текст. Бајтови који немају значење у једнобајтном кодирању и
+    /// неисправни UTF-16 знакови се замењују знаком `�`, док неисправан UTF-8
+    /// запис враћа грешку.
+    pub fn decode(self, bytes: &[u8]) -> Result<String, Error> {
+        let utf16 = |from_bytes: fn([u8; 2]) -> u16| {
+            let units = bytes
+                .chunks(2)
+                .map(|pair| from_bytes([pair[0], pair.get(1).copied().unwrap_or_default()]));
+            char::decode_utf16(units)
+                .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+                .collect()
+        };
+        Ok(match self {
+            Encoding::Utf8 => String::from_utf8(bytes.to_vec())
+                .map_err(|e| Error::Io(io::Error::new(io::ErrorKind::InvalidData, e)))?,
+            Encoding::Utf16Le => utf16(u16::from_le_bytes),
+            Encoding::Utf16Be => utf16(u16::from_be_bytes),
+            _ => {
+                let code_page = self.code_page().unwrap_or_default();
+                bytes
+                    .iter()
+                    .map(|&byte| {
+                        decode_byte(code_page, byte).unwrap_or(char::REPLACEMENT_CHARACTER)
+                    })
+                    .collect()
+            }
+        })
+    }
+
+    /// Кодира текст. Враћа грешку ако се неки знак не може записати у
+    /// једнобајтном кодирању.
+    pub fn encode(self, text: &str) -> Result<Vec<u8>, Error> {
+        Ok(match self {
+            Encoding::Utf8 => text.as_bytes().to_vec(),
+            Encoding::Utf16Le => text.encode_utf16().flat_map(u16::to_le_bytes).collect(),
+            Encoding::Utf16Be => text.encode_utf16().flat_map(u16::to_be_bytes).collect(),
+            _ => {
+                let code_page = self.code_page().unwrap_or_default();
+                text.chars()
+                    .map(|c| {
+                        encode_byte(code_page, c).ok_or(Error::Unencodable {
+                            character: c,
+                            encoding: self,
+                        })
+                    })
+                    .collect::<Result<_, _>>()?
+            }
+        })
+    }
+
+    /// Запис ознаке редоследа бајтова у овом кодирању (празан код
+    /// једнобајтних кодирања).
+    pub fn bom(self) -> &'static [u8] {
+        match self {
+            Encoding::Utf8 => &[0xef, 0xbb, 0xbf],
+            Encoding::Utf16Le => &[0xff, 0xfe],
+            Encoding::Utf16Be => &[0xfe, 0xff],
+            _ => &[],
+        }
+    }
+}
+
+impl fmt::Display for Encoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Encoding {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.to_lowercase();
+        let encoding = match name.as_str() {
+            "utf8" => Encoding::Utf8,
+            "cp1250" => Encoding::Windows1250,
+            "cp1251" => Encoding::Windows1251,
+            "cp1252" | "iso-8859-1" | "latin1" => Encoding::Windows1252,
+            "latin2" => Encoding::Iso8859_2,
+            _ => *Encoding::ALL
+                .iter()
+                .find(|encoding| encoding.name() == name)
+                .ok_or_else(|| Error::UnknownEncoding(s.to_string()))?,
+        };
+        Ok(encoding)
+    }
+}
+
+/// Да ли садржај фајла није текст. Бинарни садржај има нулте бајтове и на
+/// парним и на непарним позицијама, док их текст у UTF-16 запису има само на
+/// једним.
+pub fn is_binary(bytes: &[u8]) -> bool {
+    if bytes.starts_with(&[0xff, 0xfe]) || bytes.starts_with(&[0xfe, 0xff]) {
+        return false;
+    }
+    let zero = |parity: usize| bytes.iter().skip(parity).step_by(2).any(|&byte| byte == 0);
+    zero(0) && zero(1)
+}
+
+/// Колико декодирани текст личи на српски. Слова српске азбуке и абецеде
+/// повећавају оцену, а остали знакови ван ASCII опсега и ћирилична слова уз
+/// латиничка је смањују.
+fn serbian_score(encoding: Encoding, bytes: &[u8]) -> i64 {
+    let code_page = encoding.code_page().unwrap_or_default();
+    let chars: Vec<char> = bytes
+        .iter()
+        .map(|&byte| decode_byte(code_page, byte).unwrap_or(char::REPLACEMENT_CHARACTER))
+        .collect();
+    let mut score = 0;
+    for (i, &c) in chars.iter().enumerate() {
+        if c.is_ascii() {
+            continue;
+        }
+        score += if SERBIAN_LOWERCASE.contains(c) {
+            2
+        } else if SERBIAN_UPPERCASE.contains(c) {
+            1
+        } else {
+            -2
+        };
+        let cyrillic = ('\u{400}'..='\u{4ff}').contains(&c);
+        let latin_neighbour = [i.wrapping_sub(1), i + 1]
+            .iter()
+            .filter_map(|&j| chars.get(j))
+            .any(char::is_ascii_alphabetic);
+        if cyrillic && latin_neighbour {
+            score -= 4;
+        }
+    }
+    score
+}
+
+fn code_page_table(code_page: u32) -> Option<&'static [char]> {
+    match code_page {
+        1250 => Some(&CP1250),
+        1251 => Some(&CP1251),
+        1252 => Some(&CP1252),
+        28592 => Some(&ISO_8859_2),
+        28595 => Some(&ISO_8859_5),
+        _ => None,
+    }
+}
+
+/// Да ли је кодна страна ISO-8859 у којој су бајтови од 0x80 до 0x9F
+/// контролни знакови.
+fn is_iso(code_page: u32) -> bool {
+    (28591..=28599).contains(&code_page)
+}
+
+/// Знак бајта у кодној страни `code_page`.
+pub(crate) fn decode_byte(code_page: u32, byte: u8) -> Option<char> {
+    if byte.is_ascii() || is_iso(code_page) && byte < 0xa0 {
+        return Some(byte as char);
+    }
+    let offset = if is_iso(code_page) { 0xa0 } else { 0x80 };
+    code_page_table(code_page)?
+        .get(byte as usize - offset)
+        .copied()
+        .filter(|&c| c != char::REPLACEMENT_CHARACTER)
+}
+
+/// Бајт знака у кодној страни `code_page`.
+pub(crate) fn encode_byte(code_page: u32, c: char) -> Option<u8> {
+    if c.is_ascii() || is_iso(code_page) && ('\u{80}'..'\u{a0}').contains(&c) {
+        return Some(c as u8);
+    }
+    let offset = if is_iso(code_page) { 0xa0 } else { 0x80 };
+    let i = code_page_table(code_page)?.iter().position(|&t| t == c)?;
+    Some((offset + i) as u8)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_code_pages() {
+        assert_eq!(Some('Č'), decode_byte(1250, 0xc8));
+        assert_eq!(Some('ж'), decode_byte(1251, 0xe6));
+        assert_eq!(Some('š'), decode_byte(28592, 0xb9));
+        assert_eq!(Some('ђ'), decode_byte(28595, 0xf2));
+        assert_eq!(Some(0x9a), encode_byte(1250, 'š'));
+        assert_eq!(Some(0xbb), encode_byte(28595, 'Л'));
+        assert_eq!(None, encode_byte(1250, 'ж'));
+    }
+
+    #[test]
+    fn test_detect() {
+        let latin = "Čokančićem ću te, čokančićem ćeš me! Šta žele đaci?\n";
+        let cyrillic = "Чоканчићем ћу те, чоканчићем ћеш ме! Шта желе ђаци?\n";
+        for (encoding, text) in [
+            (Encoding::Windows1250, latin),
+            (Encoding::Windows1251, cyrillic),
+            (Encoding::Iso8859_2, latin),
+            (Encoding::Iso8859_5, cyrillic),
+        ] {
+            let code_page = encoding.code_page().unwrap();
+            let bytes: Vec<u8> = text
+                .chars()
+                .map(|c| encode_byte(code_page, c).unwrap())
+                .collect();
+            assert_eq!(encoding, Encoding::detect(&bytes));
+            assert_eq!(text, encoding.decode(&bytes).unwrap());
+        }
+
+        let utf16: Vec<u8> = cyrillic.encode_utf16().flat_map(u16::to_le_bytes).collect();
+        assert_eq!(Encoding::Utf16Le, Encoding::detect(&utf16));
+        assert_eq!(cyrillic, Encoding::Utf16Le.decode(&utf16).unwrap());
+        assert_eq!(Encoding::Utf8, Encoding::detect(cyrillic.as_bytes()));
+        assert!(Encoding::Utf8.decode(&[0xc8]).is_err());
+    }
+
+    #[test]
+    fn test_is_binary() {
+        assert!(is_binary(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR"));
+        assert!(!is_binary("Ћирко".as_bytes()));
+        let utf16: Vec<u8> = "Ћирко".encode_utf16().flat_map(u16::to_le_bytes).collect();
+        assert!(!is_binary(&utf16));
+        assert!(!is_binary(&[0xff, 0xfe, 0, 0, 0, 0]));
+    }
+
+    #[test]
+    fn test_encode() {
+        let text = "Ћирко — čiča";
+        for &encoding in Encoding::ALL {
+            if let Ok(bytes) = encoding.encode(text) {
+                assert_eq!(text, encoding.decode(&bytes).unwrap());
+            }
+        }
+        assert_eq!(
+            vec![0x8e, 0xe8, 0xf0, 0xea, 0xee],
+            Encoding::Windows1251.encode("Ћирко").unwrap()
+        );
+        assert_eq!(
+            vec![0x0d, 0x01, 0x61, 0x01],
+            Encoding::Utf16Le.encode("čš").unwrap()
+        );
+        for &encoding in Encoding::ALL {
+            let bom = encoding.encode(&BOM.to_string()).unwrap_or_default();
+            assert_eq!(bom, encoding.bom());
+        }
+        assert!(matches!(
+            Encoding::Windows1250.encode("Ћ"),
+            Err(Error::Unencodable {
+                character: 'Ћ', ..
+            })
+        ));
+    }
+}
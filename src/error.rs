@@ -0,0 +1,116 @@
+use std::path::PathBuf;
+use std::{fmt, io};
+
+use crate::encoding::Encoding;
+use crate::languages::{Language, Scheme};
+
+/// Грешке библиотеке.
+#[derive(Debug)]
+pub enum Error {
+    /// Систем романизације није подржан за дати језик.
+    UnsupportedScheme { language: Language, scheme: Scheme },
+    /// Непознат назив азбуке.
+    UnknownLanguage(String),
+    /// Непознат назив система романизације.
+    UnknownScheme(String),
+    /// Непозната категорија делова текста који се прескачу.
+    UnknownSkipCategory(String),
+    /// Непознат формат улазног текста.
+    UnknownFormat(String),
+    /// Непознат назив кодирања текста.
+    UnknownEncoding(String),
+    /// Непознат назив краја реда.
+    UnknownLineEnding(String),
+    /// Знак се не може записати у задатом кодирању.
+    Unencodable { character: char, encoding: Encoding },
+    /// Неисправан селектор (нпр. атрибута у XML документу).
+    InvalidSelector(String),
+    /// Неисправан шаблон путање (нпр. `*.md`) или регуларни израз.
+    InvalidPattern(String),
+    /// Неисправан опсег линија (нпр. `120-340`).
+    InvalidLineRange(String),
+    /// Више фајлова би након пресловљавања назива добило исту путању.
+    NameCollision(PathBuf),
+    /// Синтаксна грешка у документу.
+    Syntax { line: usize, message: String },
+    /// Неисправно пресловљавање у корисничкој табели.
+    InvalidMapping(String),
+    /// Грешка при читању фајла.
+    Io(io::Error),
+    /// Грешка у TOML фајлу.
+    Toml(toml::de::Error),
+    /// Грешка у ZIP архиви (EPUB...).
+    #[cfg(feature = "archives")]
+    Archive(zip::result::ZipError),
+    /// Грешка коју је вратила служба за конверзију.
+    Service(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::UnsupportedScheme { language, scheme } => {
+                write!(f, "систем „{scheme}” није подржан за {language} језик")
+            }
+            Error::UnknownLanguage(name) => write!(f, "непозната азбука „{name}”"),
+            Error::UnknownScheme(name) => write!(f, "непознат систем романизације „{name}”"),
+            Error::UnknownSkipCategory(name) => write!(f, "непозната категорија „{name}”"),
+            Error::UnknownFormat(name) => write!(f, "непознат формат „{name}”"),
+            Error::UnknownEncoding(name) => write!(f, "непознато кодирање „{name}”"),
+            Error::UnknownLineEnding(name) => write!(f, "непознат крај реда „{name}”"),
+            Error::Unencodable {
+                character,
+                encoding,
+            } => {
+                write!(
+                    f,
+                    "знак „{character}” се не може записати у кодирању {encoding}"
+                )
+            }
+            Error::InvalidSelector(selector) => write!(f, "неисправан селектор „{selector}”"),
+            Error::InvalidPattern(pattern) => write!(f, "неисправан шаблон „{pattern}”"),
+            Error::InvalidLineRange(range) => write!(f, "неисправан опсег линија „{range}”"),
+            Error::NameCollision(path) => {
+                write!(f, "више фајлова би добило назив „{}”", path.display())
+            }
+            Error::Syntax { line, message } => write!(f, "грешка у {line}. реду: {message}"),
+            Error::InvalidMapping(mapping) => write!(f, "неисправно пресловљавање „{mapping}”"),
+            Error::Io(e) => write!(f, "{e}"),
+            Error::Toml(e) => write!(f, "грешка у TOML фајлу: {e}"),
+            #[cfg(feature = "archives")]
+            Error::Archive(e) => write!(f, "грешка у ZIP архиви: {e}"),
+            Error::Service(message) => write!(f, "грешка службе: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(e) => Some(e),
+            Error::Toml(e) => Some(e),
+            #[cfg(feature = "archives")]
+            Error::Archive(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+impl From<toml::de::Error> for Error {
+    fn from(e: toml::de::Error) -> Self {
+        Error::Toml(e)
+    }
+}
+
+#[cfg(feature = "archives")]
+impl From<zip::result::ZipError> for Error {
+    fn from(e: zip::result::ZipError) -> Self {
+        Error::Archive(e)
+    }
+}
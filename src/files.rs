@@ -0,0 +1,621 @@
+//! Проналажење фајлова за конверзију у стаблу директоријума на основу шаблона
+//! путања (`*.md`, `target/**`) и правила из `.gitignore` фајлова, праћење
+//! њихових измена, пресловљавање назива фајлова и безбедан упис резултата.
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::SystemTime;
+
+use ignore::gitignore::Gitignore;
+use ignore::{Match, WalkBuilder};
+use regex::Regex;
+
+use crate::Error;
+use crate::formats::Format;
+
+/// Шаблон путање. `*` одговара било ком делу назива, `**` било ком броју
+/// директоријума, `?` једном знаку, `[abc]` једном од наведених знакова, а
+/// `{md,txt}` једној од наведених варијанти. Шаблон без `/` се пореди само са
+/// називом фајла.
+#[derive(Debug, Clone)]
+pub struct Glob {
+    regex: Regex,
+    name_only: bool,
+}
+
+impl FromStr for Glob {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || Error::InvalidPattern(s.to_string());
+        let pattern = s.trim_start_matches("./");
+        let mut regex = String::from("^");
+        let mut chars = pattern.chars().peekable();
+        let mut in_braces = false;
+        while let Some(c) = chars.next() {
+            match c {
+                '*' if chars.peek() == Some(&'*') => {
+                    chars.next();
+                    if chars.peek() == Some(&'/') {
+                        chars.next();
+                        regex.push_str("(?:.*/)?");
+                    } else {
+                        regex.push_str(".*");
+                    }
+                }
+                '*' => regex.push_str("[^/]*"),
+                '?' => regex.push_str("[^/]"),
+                '[' => {
+                    regex.push('[');
+                    loop {
+                        match chars.next().ok_or_else(invalid)? {
+                            ']' => break,
+                            '\\' => regex.push_str("\\\\"),
+                            '!' if regex.ends_with('[') => regex.push('^'),
+                            c => regex.push(c),
+                        }
+                    }
+                    regex.push(']');
+                }
+                '{' if !in_braces => {
+                    in_braces = true;
+                    regex.push_str("(?:");
+                }
+                ',' if in_braces => regex.push('|'),
+                '}' if in_braces => {
+                    in_braces = false;
+                    regex.push(')');
+                }
+                c => regex.push_str(&regex::escape(&c.to_string())),
+            }
+        }
+        if in_braces {
+            return Err(invalid());
+        }
+        regex.push('$');
+        Ok(Glob {
+            regex: Regex::new(&regex).map_err(|_| invalid())?,
+            name_only: !pattern.contains('/'),
+        })
+    }
+}
+
+impl Glob {
+    /// Да ли релативна путања `path` одговара шаблону.
+    pub fn matches(&self, path: &Path) -> bool {
+        let path = path
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        let path = if self.name_only {
+            path.rsplit('/').next().unwrap_or_default()
+        } else {
+            path.as_str()
+        };
+        self.regex.is_match(path)
+    }
+}
+
+/// Директоријуми који се подразумевано прескачу.
+const DEFAULT_IGNORE: &[&str] = &[".git", "target", "node_modules"];
+
+/// Начин обиласка директоријума. Подразумевано се прескачу скривени фајлови и
+/// директоријуми (чији назив почиње тачком), директоријуми `.git`, `target` и
+/// `node_modules` и фајлови наведени у `.gitignore` и `.ignore` фајловима
+/// директоријума и његових родитељских директоријума.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WalkOptions {
+    /// Обилазе се и скривени фајлови и директоријуми.
+    pub hidden: bool,
+    /// Не примењују се подразумевана правила ни `.gitignore` и `.ignore` фајлови.
+    pub no_ignore: bool,
+}
+
+/// Проналази фајлове у директоријуму `root` (рекурзивно) чије релативне путање
+/// одговарају неком од шаблона `include` (или свим ако није задат ниједан), а
+/// не одговарају ниједном шаблону `exclude`. Директоријуми који одговарају
+/// шаблону `exclude` или се игноришу према `options` се не обилазе. Враћа
+/// сортиране релативне путање.
+pub fn find_files(
+    root: &Path,
+    include: &[Glob],
+    exclude: &[Glob],
+    options: WalkOptions,
+) -> io::Result<Vec<PathBuf>> {
+    let root = &root.canonicalize()?;
+    let mut builder = WalkBuilder::new(root);
+    builder
+        .hidden(!options.hidden)
+        .parents(false)
+        .ignore(false)
+        .git_ignore(!options.no_ignore)
+        .git_global(false)
+        .git_exclude(!options.no_ignore)
+        .require_git(false);
+    // Правила из родитељских директоријума се примењују до корена git
+    // репозиторијума, редом од најудаљенијег
+    let mut outer = Vec::new();
+    if !options.no_ignore {
+        builder.add_custom_ignore_filename(".ignore");
+        let parents = root.ancestors().skip(1).collect::<Vec<_>>();
+        if !root.join(".git").exists()
+            && let Some(repository) = parents
+                .iter()
+                .position(|parent| parent.join(".git").exists())
+        {
+            for parent in parents[..=repository].iter().rev() {
+                for name in [".gitignore", ".ignore"] {
+                    outer.push(Gitignore::new(parent.join(name)).0);
+                }
+            }
+        }
+    }
+    let (walk_root, dir_exclude) = (root.to_path_buf(), exclude.to_vec());
+    builder.filter_entry(move |entry| {
+        let Ok(path) = entry.path().strip_prefix(&walk_root) else {
+            return true;
+        };
+        let is_dir = entry
+            .file_type()
+            .is_some_and(|file_type| file_type.is_dir());
+        let mut ignored = false;
+        for rules in &outer {
+            match rules.matched(entry.path(), is_dir) {
+                Match::Ignore(_) => ignored = true,
+                Match::Whitelist(_) => ignored = false,
+                Match::None => {}
+            }
+        }
+        if ignored || !is_dir {
+            return !ignored;
+        }
+        // `target/**` искључује и сам директоријум `target`
+        let pattern_path = path.join("");
+        let default_ignored =
+            !options.no_ignore && DEFAULT_IGNORE.iter().any(|name| entry.file_name() == *name);
+        !default_ignored
+            && !dir_exclude
+                .iter()
+                .any(|glob| glob.matches(path) || glob.matches(&pattern_path))
+    });
+
+    let mut files = Vec::new();
+    for entry in builder.build() {
+        let entry = match entry {
+            Ok(entry) => entry,
+            // Неисправни шаблони у фајловима за игнорисање се прескачу
+            Err(e) if e.io_error().is_none() => continue,
+            Err(e) => return Err(e.into_io_error().unwrap()),
+        };
+        let Ok(path) = entry.path().strip_prefix(root) else {
+            continue;
+        };
+        if entry.depth() == 0 || entry.file_type().is_none_or(|file_type| file_type.is_dir()) {
+            continue;
+        }
+        if (include.is_empty() || include.iter().any(|glob| glob.matches(path)))
+            && !exclude.iter().any(|glob| glob.matches(path))
+        {
+            files.push(path.to_path_buf());
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Да ли је фајл врсте `kind`, која је екстензија фајла (`md`, `.html`) или
+/// назив формата (`markdown`) који се одређује на основу екстензије.
+pub fn has_type(path: &Path, kind: &str) -> bool {
+    let kind = kind.trim_start_matches('.').to_lowercase();
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| extension.to_lowercase() == kind)
+        || Format::from_path(path).is_some_and(|format| format.name() == kind)
+}
+
+/// Чита листу путања фајлова (нпр. излаз команде `find`), по једну у реду или,
+/// ако је `null` тачно, раздвојене нул карактером (`find -print0`). Празне
+/// ставке се прескачу.
+pub fn read_file_list(mut reader: impl io::Read, null: bool) -> io::Result<Vec<PathBuf>> {
+    let mut list = Vec::new();
+    reader.read_to_end(&mut list)?;
+    let delimiter = if null { b'\0' } else { b'\n' };
+    Ok(list
+        .split(|&b| b == delimiter)
+        .map(|path| match path {
+            [path @ .., b'\r'] if !null => path,
+            path => path,
+        })
+        .filter(|path| !path.is_empty())
+        .map(path_from_bytes)
+        .collect())
+}
+
+#[cfg(unix)]
+fn path_from_bytes(bytes: &[u8]) -> PathBuf {
+    use std::os::unix::ffi::OsStrExt;
+    PathBuf::from(std::ffi::OsStr::from_bytes(bytes))
+}
+
+#[cfg(not(unix))]
+fn path_from_bytes(bytes: &[u8]) -> PathBuf {
+    PathBuf::from(String::from_utf8_lossy(bytes).into_owned())
+}
+
+/// Одређује преименовања фајлова и директоријума унутар `root` (рекурзивно)
+/// чији се називи мењају пресловљавањем функцијом `convert`. Екстензије
+/// фајлова се не пресловљавају, а скривени фајлови и директоријуми (`.git`) се
+/// прескачу. Преименовања су поређана тако да се садржај директоријума
+/// преименује пре самог директоријума. Враћа грешку ако би два фајла у истом
+/// директоријуму добила исти назив.
+pub fn plan_renames(
+    root: &Path,
+    convert: &impl Fn(&str) -> String,
+) -> Result<Vec<(PathBuf, PathBuf)>, Error> {
+    let mut renames = Vec::new();
+    let mut entries = fs::read_dir(root)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<io::Result<Vec<_>>>()?;
+    entries.sort();
+    let mut targets = HashSet::new();
+    for path in entries {
+        let name = match path.file_name().and_then(|name| name.to_str()) {
+            Some(name) if !name.starts_with('.') => name,
+            _ => {
+                targets.insert(path);
+                continue;
+            }
+        };
+        if fs::symlink_metadata(&path)?.is_dir() {
+            renames.extend(plan_renames(&path, convert)?);
+        }
+        let target = path.with_file_name(if path.is_file() {
+            convert_file_name(name, convert)
+        } else {
+            convert(name)
+        });
+        if !targets.insert(target.clone()) {
+            return Err(Error::NameCollision(target));
+        }
+        if target != path {
+            renames.push((path, target));
+        }
+    }
+    Ok(renames)
+}
+
+/// Пресловљава назив фајла функцијом `convert` не мењајући екстензију. Назив
+/// који почиње тачком (`.gitignore`) нема екстензију.
+pub fn convert_file_name(name: &str, convert: impl Fn(&str) -> String) -> String {
+    match name.rsplit_once('.') {
+        Some((stem, _)) if !stem.is_empty() => convert(stem) + &name[stem.len()..],
+        _ => convert(name),
+    }
+}
+
+/// Праћење измена фајлова на основу времена последње измене.
+#[derive(Debug, Default)]
+pub struct Watcher {
+    modified: HashMap<PathBuf, SystemTime>,
+}
+
+impl Watcher {
+    /// Враћа фајлове из `paths` који су додати или измењени од претходног
+    /// позива. При првом позиву враћа све фајлове. Фајлови који се не могу
+    /// прочитати (нпр. обрисани) се изостављају.
+    pub fn changed(&mut self, paths: Vec<PathBuf>) -> Vec<PathBuf> {
+        let mut modified = HashMap::with_capacity(paths.len());
+        let mut changed = Vec::new();
+        for path in paths {
+            let Ok(time) = fs::metadata(&path).and_then(|metadata| metadata.modified()) else {
+                continue;
+            };
+            if self.modified.get(&path) != Some(&time) {
+                changed.push(path.clone());
+            }
+            modified.insert(path, time);
+        }
+        self.modified = modified;
+        changed
+    }
+}
+
+/// Фајл који се уписује у привремени фајл у истом директоријуму и тек при
+/// позиву [`AtomicFile::commit`] замењује циљни фајл. Ако упис не успе или се
+/// прекине, постојећи фајл остаје неизмењен, а привремени фајл се брише.
+#[derive(Debug)]
+pub struct AtomicFile {
+    path: PathBuf,
+    temp: PathBuf,
+    file: Option<File>,
+}
+
+impl AtomicFile {
+    /// Отвара привремени фајл за упис у фајл `path`. Ако је `path` симболичка
+    /// веза, уписује се фајл на који она упућује.
+    pub fn create(path: &Path) -> io::Result<Self> {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+        let path = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+        let name = path
+            .file_name()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "путања није фајл"))?;
+        let mut temp_name = std::ffi::OsString::from(".");
+        temp_name.push(name);
+        temp_name.push(format!(
+            ".{}-{}.tmp",
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        let temp = path.with_file_name(temp_name);
+        let file = File::options().write(true).create_new(true).open(&temp)?;
+        Ok(AtomicFile {
+            path,
+            temp,
+            file: Some(file),
+        })
+    }
+
+    /// Замењује циљни фајл уписаним садржајем. Нови фајл задржава дозволе
+    /// постојећег фајла. Ако је задат фајл `source`, нови фајл добија његове
+    /// дозволе и време последње измене.
+    pub fn commit(mut self, source: Option<&Path>) -> io::Result<()> {
+        let file = self.file.take().expect("фајл је отворен до потврде уписа");
+        file.sync_all()?;
+        let original = source.unwrap_or(&self.path);
+        if let Ok(metadata) = fs::metadata(original) {
+            if source.is_some() {
+                file.set_modified(metadata.modified()?)?;
+            }
+            file.set_permissions(metadata.permissions())?;
+        }
+        drop(file);
+        fs::rename(&self.temp, &self.path)
+    }
+}
+
+impl Write for AtomicFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.file.as_mut().expect("фајл је отворен").write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.as_mut().expect("фајл је отворен").flush()
+    }
+}
+
+impl Drop for AtomicFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.temp);
+    }
+}
+
+/// Безбедно уписује `contents` у фајл `path` преко [`AtomicFile`].
+pub fn write_atomic(path: &Path, contents: &[u8], source: Option<&Path>) -> io::Result<()> {
+    let mut file = AtomicFile::create(path)?;
+    file.write_all(contents)?;
+    file.commit(source)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_glob() {
+        let glob = |pattern: &str| pattern.parse::<Glob>().unwrap();
+        assert!(glob("*.md").matches(Path::new("docs/uvod.md")));
+        assert!(!glob("*.md").matches(Path::new("docs/uvod.txt")));
+        assert!(glob("docs/*.{md,txt}").matches(Path::new("docs/uvod.txt")));
+        assert!(!glob("docs/*.md").matches(Path::new("docs/sr/uvod.md")));
+        assert!(glob("docs/**/*.md").matches(Path::new("docs/sr/uvod.md")));
+        assert!(glob("docs/**/*.md").matches(Path::new("docs/uvod.md")));
+        assert!(glob("target/**").matches(Path::new("target/debug/cirko")));
+        assert!(glob("poglavlje-[0-9].md").matches(Path::new("poglavlje-1.md")));
+        assert!("[abc".parse::<Glob>().is_err());
+    }
+
+    #[test]
+    fn test_find_files() {
+        let root = std::env::temp_dir().join(format!("cirko-files-{}", std::process::id()));
+        for file in [
+            "uvod.md",
+            "docs/poglavlje.md",
+            "docs/slika.png",
+            "target/izlaz.md",
+        ] {
+            let path = root.join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, "").unwrap();
+        }
+        let files = find_files(
+            &root,
+            &["*.md".parse().unwrap()],
+            &["target/**".parse().unwrap()],
+            WalkOptions::default(),
+        );
+        fs::remove_dir_all(&root).unwrap();
+        assert_eq!(
+            vec![PathBuf::from("docs/poglavlje.md"), PathBuf::from("uvod.md")],
+            files.unwrap()
+        );
+    }
+
+    #[test]
+    fn test_find_files_ignore() {
+        let root = std::env::temp_dir().join(format!("cirko-ignore-{}", std::process::id()));
+        for file in [
+            "uvod.md",
+            ".skriveno.md",
+            ".gitignore",
+            "docs/.ignore",
+            "docs/poglavlje.md",
+            "docs/nacrt.md",
+            "docs/stari/kraj.md",
+            "docs/kopija.bak.md",
+            "vazno.bak.md",
+            "node_modules/paket/README.md",
+            "target/izlaz.md",
+        ] {
+            let path = root.join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, "").unwrap();
+        }
+        fs::write(
+            root.join(".gitignore"),
+            "# komentar\nstari/\n*.bak.md\n!vazno.bak.md\n",
+        )
+        .unwrap();
+        fs::write(root.join("docs/.ignore"), "nacrt.md\n").unwrap();
+        let find = |hidden, no_ignore| {
+            find_files(
+                &root,
+                &["*.md".parse().unwrap()],
+                &[],
+                WalkOptions { hidden, no_ignore },
+            )
+            .unwrap()
+        };
+        let (ignored, hidden, all) = (find(false, false), find(true, false), find(true, true));
+        fs::remove_dir_all(&root).unwrap();
+        assert_eq!(
+            vec![
+                PathBuf::from("docs/poglavlje.md"),
+                PathBuf::from("uvod.md"),
+                PathBuf::from("vazno.bak.md")
+            ],
+            ignored
+        );
+        assert_eq!(
+            vec![
+                PathBuf::from(".skriveno.md"),
+                PathBuf::from("docs/poglavlje.md"),
+                PathBuf::from("uvod.md"),
+                PathBuf::from("vazno.bak.md")
+            ],
+            hidden
+        );
+        assert_eq!(9, all.len());
+    }
+
+    #[test]
+    fn test_has_type() {
+        assert!(has_type(Path::new("docs/uvod.MD"), "md"));
+        assert!(has_type(Path::new("docs/uvod.markdown"), "markdown"));
+        assert!(has_type(Path::new("index.html"), ".html"));
+        assert!(has_type(Path::new("beleške.txt"), "текст"));
+        assert!(!has_type(Path::new("slika.png"), "md"));
+        assert!(!has_type(Path::new("README"), "md"));
+    }
+
+    #[test]
+    fn test_read_file_list() {
+        let list = read_file_list("uvod.md\r\ndocs/prvo poglavlje.md\n\n".as_bytes(), false);
+        assert_eq!(
+            vec![
+                PathBuf::from("uvod.md"),
+                PathBuf::from("docs/prvo poglavlje.md")
+            ],
+            list.unwrap()
+        );
+        let list = read_file_list("./a\nb.md\0./c.md\0".as_bytes(), true);
+        assert_eq!(
+            vec![PathBuf::from("./a\nb.md"), PathBuf::from("./c.md")],
+            list.unwrap()
+        );
+    }
+
+    #[test]
+    fn test_plan_renames() {
+        let root = std::env::temp_dir().join(format!("cirko-rename-{}", std::process::id()));
+        for file in ["Slike/leto.jpg", "Slike/.skriveno", "Beleške.txt", "README"] {
+            let path = root.join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, "").unwrap();
+        }
+        let convert = |name: &str| name.replace("Slike", "Слике").replace("leto", "лето");
+        let renames = plan_renames(&root, &convert);
+        fs::write(root.join("Слике.txt"), "").unwrap();
+        fs::write(root.join("Slike.txt"), "").unwrap();
+        let collision = plan_renames(&root, &convert);
+        fs::remove_dir_all(&root).unwrap();
+        assert_eq!(
+            vec![
+                (root.join("Slike/leto.jpg"), root.join("Slike/лето.jpg")),
+                (root.join("Slike"), root.join("Слике")),
+            ],
+            renames.unwrap()
+        );
+        assert!(
+            matches!(collision, Err(Error::NameCollision(path)) if path == root.join("Слике.txt"))
+        );
+    }
+
+    #[test]
+    fn test_convert_file_name() {
+        let convert = |name: &str| name.to_uppercase();
+        assert_eq!("UVOD.md", convert_file_name("uvod.md", convert));
+        assert_eq!(".GITIGNORE", convert_file_name(".gitignore", convert));
+        assert_eq!("README", convert_file_name("readme", convert));
+    }
+
+    #[test]
+    fn test_watcher() {
+        let root = std::env::temp_dir().join(format!("cirko-watch-{}", std::process::id()));
+        fs::create_dir_all(&root).unwrap();
+        let (first, second) = (root.join("prvi.txt"), root.join("drugi.txt"));
+        fs::write(&first, "").unwrap();
+        let mut watcher = Watcher::default();
+        assert_eq!(vec![first.clone()], watcher.changed(vec![first.clone()]));
+        assert!(watcher.changed(vec![first.clone()]).is_empty());
+
+        fs::write(&second, "").unwrap();
+        let file = fs::File::options().write(true).open(&first).unwrap();
+        file.set_modified(SystemTime::UNIX_EPOCH).unwrap();
+        let changed = watcher.changed(vec![first.clone(), second.clone()]);
+        fs::remove_dir_all(&root).unwrap();
+        assert_eq!(vec![first, second], changed);
+    }
+
+    #[test]
+    fn test_write_atomic() {
+        let root = std::env::temp_dir().join(format!("cirko-atomic-{}", std::process::id()));
+        fs::create_dir_all(&root).unwrap();
+        let (source, target) = (root.join("izvor.txt"), root.join("cilj.txt"));
+        fs::write(&source, "Dobar dan").unwrap();
+        fs::write(&target, "stari").unwrap();
+
+        // Прекинут упис не мења постојећи фајл
+        let mut file = AtomicFile::create(&target).unwrap();
+        file.write_all(b"nepotpun").unwrap();
+        drop(file);
+        assert_eq!("stari", fs::read_to_string(&target).unwrap());
+
+        write_atomic(&target, "Добар дан".as_bytes(), None).unwrap();
+        assert_eq!("Добар дан", fs::read_to_string(&target).unwrap());
+
+        let modified = SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_000_000);
+        File::options()
+            .write(true)
+            .open(&source)
+            .unwrap()
+            .set_modified(modified)
+            .unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&source, fs::Permissions::from_mode(0o750)).unwrap();
+            write_atomic(&target, b"nov", Some(&source)).unwrap();
+            let metadata = fs::metadata(&target).unwrap();
+            assert_eq!(0o750, metadata.permissions().mode() & 0o777);
+            assert_eq!(modified, metadata.modified().unwrap());
+        }
+
+        // Остају само изворни и циљни фајл
+        assert_eq!(2, fs::read_dir(&root).unwrap().count());
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
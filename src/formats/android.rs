@@ -0,0 +1,98 @@
+//! Конверзија Android ресурса (`res/values/strings.xml`). Пресловљавају се
+//! вредности стрингова (`<string>`, `<plurals>`, `<string-array>`), док ознаке
+//! за замену (`%s`, `%1$d`), ескејп секвенце, референце на ентитете, елементи
+//! `<xliff:g>` и ресурси означени са `translatable="false"` остају непромењени.
+use super::convert_format_string;
+use super::xml::{Token, convert_text, tokenize};
+use crate::Error;
+
+// Ресурси чији се садржај пресловљава
+const STRING_RESOURCES: &[&str] = &["string", "plurals", "string-array"];
+
+/// Пресловљава стрингове у Android ресурсима функцијом `convert`.
+pub fn convert(input: &str, convert: impl Fn(&str) -> String) -> Result<String, Error> {
+    let convert = |text: &str| convert_format_string(text, &convert);
+    let mut output = String::with_capacity(input.len());
+    // Отворени елементи и да ли се њихов текст пресловљава
+    let mut stack: Vec<(&str, bool)> = Vec::new();
+    for token in tokenize(input)? {
+        match token {
+            // Референце на друге ресурсе (`@string/naziv`) се не мењају
+            Token::Text(text)
+                if stack.last().is_some_and(|&(_, is_text)| is_text)
+                    && !text.trim_start().starts_with(['@', '?']) =>
+            {
+                output.push_str(&convert_text(text, &convert))
+            }
+            Token::Text(raw) | Token::CData(raw) | Token::Markup(raw) => output.push_str(raw),
+            Token::StartTag(tag) => {
+                let is_text = match tag.name {
+                    name if STRING_RESOURCES.contains(&name) => {
+                        tag.attribute("translatable") != Some("false")
+                    }
+                    "xliff:g" => false,
+                    _ => stack.last().is_some_and(|&(_, is_text)| is_text),
+                };
+                output.push_str(tag.raw);
+                if !tag.self_closing {
+                    stack.push((tag.name, is_text));
+                }
+            }
+            Token::EndTag { name, raw } => {
+                if let Some(open) = stack.iter().rposition(|&(open, _)| open == name) {
+                    stack.truncate(open);
+                }
+                output.push_str(raw);
+            }
+        }
+    }
+    Ok(output)
+}
+
+/// Назив директоријума ресурса (нпр. `values-b+sr+Latn`) за ознаку језика
+/// `language` (нпр. `sr-Latn`).
+pub fn resource_directory(language: &str) -> String {
+    format!("values-b+{}", language.replace('-', "+"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lat_to_cyr;
+
+    #[test]
+    fn test_convert() {
+        let input = r#"<?xml version="1.0" encoding="utf-8"?>
+<resources xmlns:xliff="urn:oasis:names:tc:xliff:document:1.2">
+    <!-- Naslov -->
+    <string name="app_name" translatable="false">Kalendar</string>
+    <string name="welcome">Dobro došli, %1$s!\nImate %2$d poruka &amp; <b>obaveštenja</b>.</string>
+    <string name="countdown">Još <xliff:g id="time" example="5 days">%1$s</xliff:g> do kraja</string>
+    <string name="alias">@string/welcome</string>
+    <plurals name="files">
+        <item quantity="one">%d fajl</item>
+        <item quantity="other">%d fajlova</item>
+    </plurals>
+    <string-array name="days"><item>Ponedeljak</item><item>Utorak</item></string-array>
+</resources>"#;
+        let expected = r#"<?xml version="1.0" encoding="utf-8"?>
+<resources xmlns:xliff="urn:oasis:names:tc:xliff:document:1.2">
+    <!-- Naslov -->
+    <string name="app_name" translatable="false">Kalendar</string>
+    <string name="welcome">Добро дошли, %1$s!\nИмате %2$d порука &amp; <b>обавештења</b>.</string>
+    <string name="countdown">Још <xliff:g id="time" example="5 days">%1$s</xliff:g> до краја</string>
+    <string name="alias">@string/welcome</string>
+    <plurals name="files">
+        <item quantity="one">%d фајл</item>
+        <item quantity="other">%d фајлова</item>
+    </plurals>
+    <string-array name="days"><item>Понедељак</item><item>Уторак</item></string-array>
+</resources>"#;
+        assert_eq!(expected, convert(input, lat_to_cyr).unwrap());
+    }
+
+    #[test]
+    fn test_resource_directory() {
+        assert_eq!("values-b+sr+Latn", resource_directory("sr-Latn"));
+    }
+}
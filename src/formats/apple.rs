@@ -0,0 +1,145 @@
+//! Конверзија Apple датотека превода. У `.strings` датотекама пресловљавају се
+//! само вредности парова `"кључ" = "вредност";`, а у `.stringsdict` речницима
+//! вредности облика множине, док кључеви, коментари, ескејп секвенце и ознаке
+//! за замену (`%@`, `%1$d`, `%#@files@`) остају непромењени.
+use super::convert_format_string;
+use super::syntax_error;
+use super::xml::{Token, convert_text, tokenize};
+use crate::Error;
+
+// Кључеви речника чије вредности нису текст
+const CODE_KEYS: &[&str] = &["NSStringFormatSpecTypeKey", "NSStringFormatValueTypeKey"];
+
+/// Пресловљава вредности у `.strings` датотеци функцијом `convert`.
+pub fn convert_strings(input: &str, convert: impl Fn(&str) -> String) -> Result<String, Error> {
+    let mut output = String::with_capacity(input.len());
+    // Да ли је следећи стринг вредност (иза `=`)
+    let mut is_value = false;
+    let mut pos = 0;
+    while let Some(c) = input[pos..].chars().next() {
+        let rest = &input[pos..];
+        let len = match c {
+            '/' if rest.starts_with("/*") => rest
+                .find("*/")
+                .map(|end| end + 2)
+                .ok_or_else(|| syntax_error(input, pos, "незатворен коментар"))?,
+            '/' if rest.starts_with("//") => rest.find('\n').unwrap_or(rest.len()),
+            '"' => {
+                let end = string_end(rest)
+                    .ok_or_else(|| syntax_error(input, pos, "незатворен наводник"))?;
+                if is_value {
+                    output.push('"');
+                    output.push_str(&convert_format_string(&rest[1..end], &convert));
+                    output.push('"');
+                    pos += end + 1;
+                    is_value = false;
+                    continue;
+                }
+                end + 1
+            }
+            '=' => {
+                is_value = true;
+                1
+            }
+            ';' => {
+                is_value = false;
+                1
+            }
+            c => c.len_utf8(),
+        };
+        output.push_str(&rest[..len]);
+        pos += len;
+    }
+    Ok(output)
+}
+
+/// Позиција завршног наводника стринга који почиње наводником.
+fn string_end(input: &str) -> Option<usize> {
+    let mut escaped = false;
+    for (i, c) in input.char_indices().skip(1) {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '"' => return Some(i),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Пресловљава вредности у `.stringsdict` речнику функцијом `convert`.
+pub fn convert_stringsdict(input: &str, convert: impl Fn(&str) -> String) -> Result<String, Error> {
+    let convert = |text: &str| convert_format_string(text, &convert);
+    let mut output = String::with_capacity(input.len());
+    let mut element = None;
+    // Последњи кључ речника
+    let mut key = "";
+    for token in tokenize(input)? {
+        match token {
+            Token::Text(text) if element == Some("key") => {
+                key = text;
+                output.push_str(text);
+            }
+            Token::Text(text) if element == Some("string") && !CODE_KEYS.contains(&key) => {
+                output.push_str(&convert_text(text, &convert))
+            }
+            Token::Text(raw) | Token::CData(raw) | Token::Markup(raw) => output.push_str(raw),
+            Token::StartTag(tag) => {
+                element = (!tag.self_closing).then_some(tag.name);
+                output.push_str(tag.raw);
+            }
+            Token::EndTag { raw, .. } => {
+                element = None;
+                output.push_str(raw);
+            }
+        }
+    }
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lat_to_cyr;
+
+    #[test]
+    fn test_convert_strings() {
+        let input = r#"/* Naslov "glavnog" prozora */
+"welcome_title" = "Dobro došli, %@!";
+// Poruke
+"messages" = "Imate %1$d \"novih\" poruka\n";
+greeting = "Zdravo";
+"#;
+        let expected = r#"/* Naslov "glavnog" prozora */
+"welcome_title" = "Добро дошли, %@!";
+// Poruke
+"messages" = "Имате %1$d \"нових\" порука\n";
+greeting = "Здраво";
+"#;
+        assert_eq!(expected, convert_strings(input, lat_to_cyr).unwrap());
+        assert!(convert_strings(r#""a" = "b;"#, lat_to_cyr).is_err());
+    }
+
+    #[test]
+    fn test_convert_stringsdict() {
+        let input = r#"<plist version="1.0"><dict>
+<key>files</key><dict>
+<key>NSStringLocalizedFormatKey</key><string>Imate %#@files@</string>
+<key>files</key><dict>
+<key>NSStringFormatSpecTypeKey</key><string>NSStringPluralRuleType</string>
+<key>NSStringFormatValueTypeKey</key><string>d</string>
+<key>one</key><string>%d fajl</string>
+<key>other</key><string>%d fajlova</string>
+</dict></dict></dict></plist>"#;
+        let expected = r#"<plist version="1.0"><dict>
+<key>files</key><dict>
+<key>NSStringLocalizedFormatKey</key><string>Имате %#@files@</string>
+<key>files</key><dict>
+<key>NSStringFormatSpecTypeKey</key><string>NSStringPluralRuleType</string>
+<key>NSStringFormatValueTypeKey</key><string>d</string>
+<key>one</key><string>%d фајл</string>
+<key>other</key><string>%d фајлова</string>
+</dict></dict></dict></plist>"#;
+        assert_eq!(expected, convert_stringsdict(input, lat_to_cyr).unwrap());
+    }
+}
@@ -0,0 +1,157 @@
+//! Конверзија Flutter ARB (Application Resource Bundle) датотека. Пресловљава
+//! се текст порука, укључујући гране ICU израза множине и избора, док
+//! метаподаци (`@key`, `@@locale`), ознаке за замену (`{name}`), селектори
+//! (`one`, `=0`) и `#` у изразима множине остају непромењени.
+use super::json::{convert_escaped, transform};
+use super::xml::retag;
+use crate::Error;
+
+/// Пресловљава поруке функцијом `convert`. Ако је задата ознака језика
+/// `language` (нпр. `sr-Latn`) њоме се замењује ознака језика у `@@locale`
+/// (`sr` постаје `sr_Latn`).
+pub fn convert(
+    input: &str,
+    language: Option<&str>,
+    convert: impl Fn(&str) -> String,
+) -> Result<String, Error> {
+    transform(input, |string| match string.path {
+        _ if string.is_key => None,
+        ["@@locale"] => language
+            .and_then(|language| retag(string.raw, language))
+            .map(|tag| tag.replace('-', "_")),
+        [key] if !key.starts_with('@') => {
+            let mut output = String::with_capacity(string.raw.len());
+            let mut pos = 0;
+            convert_message(string.raw, &mut pos, false, &convert, &mut output);
+            // Вишак затворених заграда се преписује
+            output.push_str(&string.raw[pos..]);
+            Some(output)
+        }
+        _ => None,
+    })
+}
+
+/// Пресловљава ICU поруку од позиције `pos` до краја или затворене заграде
+/// која јој не припада.
+fn convert_message(
+    input: &str,
+    pos: &mut usize,
+    in_plural: bool,
+    convert: &impl Fn(&str) -> String,
+    output: &mut String,
+) {
+    let mut start = *pos;
+    while let Some(c) = input[*pos..].chars().next() {
+        match c {
+            '{' | '}' => {
+                output.push_str(&convert_escaped(&input[start..*pos], convert));
+                if c == '}' {
+                    return;
+                }
+                convert_argument(input, pos, in_plural, convert, output);
+                start = *pos;
+            }
+            '#' if in_plural => {
+                output.push_str(&convert_escaped(&input[start..*pos], convert));
+                output.push('#');
+                *pos += 1;
+                start = *pos;
+            }
+            c => *pos += c.len_utf8(),
+        }
+    }
+    output.push_str(&convert_escaped(&input[start..*pos], convert));
+}
+
+/// Пресловљава аргумент поруке (`{count, plural, one{...} other{...}}`) који
+/// почиње на позицији `pos`.
+fn convert_argument(
+    input: &str,
+    pos: &mut usize,
+    in_plural: bool,
+    convert: &impl Fn(&str) -> String,
+    output: &mut String,
+) {
+    let start = *pos;
+    let rest = &input[start..];
+    let mut parts = rest[1..].splitn(3, ',');
+    let name = parts.next().unwrap_or_default();
+    let kind = parts.next().map(str::trim);
+    let is_choice = matches!(kind, Some("plural" | "select" | "selectordinal"))
+        && !name.contains(['{', '}'])
+        && parts.next().is_some();
+    if !is_choice {
+        // Једноставан аргумент (`{name}`, `{date, date, short}`)
+        let mut depth = 0;
+        let end = rest
+            .char_indices()
+            .find(|&(_, c)| {
+                match c {
+                    '{' => depth += 1,
+                    '}' => depth -= 1,
+                    _ => {}
+                }
+                depth == 0
+            })
+            .map_or(rest.len(), |(i, _)| i + 1);
+        output.push_str(&rest[..end]);
+        *pos += end;
+        return;
+    }
+
+    // Селектори и гране израза множине и избора
+    let header = rest.match_indices(',').nth(1).map_or(0, |(i, _)| i + 1);
+    output.push_str(&rest[..header]);
+    *pos += header;
+    let in_plural = in_plural || kind != Some("select");
+    while let Some(c) = input[*pos..].chars().next() {
+        match c {
+            '}' => {
+                output.push('}');
+                *pos += 1;
+                return;
+            }
+            '{' => {
+                output.push('{');
+                *pos += 1;
+                convert_message(input, pos, in_plural, convert, output);
+                if input[*pos..].starts_with('}') {
+                    output.push('}');
+                    *pos += 1;
+                }
+            }
+            c => {
+                output.push(c);
+                *pos += c.len_utf8();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lat_to_cyr;
+
+    #[test]
+    fn test_convert() {
+        let input = r#"{
+  "@@locale": "sr",
+  "title": "Dobro došli, {name}!",
+  "@title": {"description": "Naslov početne strane", "placeholders": {"name": {"type": "String"}}},
+  "messages": "{count, plural, =0{Nema poruka} one{Imate # poruku} other{Imate {count} poruka}}",
+  "greeting": "{gender, select, female{Poštovana} male{Poštovani} other{Zdravo}}\n"
+}"#;
+        let expected = r#"{
+  "@@locale": "sr_Cyrl",
+  "title": "Добро дошли, {name}!",
+  "@title": {"description": "Naslov početne strane", "placeholders": {"name": {"type": "String"}}},
+  "messages": "{count, plural, =0{Нема порука} one{Имате # поруку} other{Имате {count} порука}}",
+  "greeting": "{gender, select, female{Поштована} male{Поштовани} other{Здраво}}\n"
+}"#;
+        assert_eq!(
+            expected,
+            convert(input, Some("sr-Cyrl"), lat_to_cyr).unwrap()
+        );
+    }
+}
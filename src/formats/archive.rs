@@ -0,0 +1,62 @@
+//! Измена фајлова у ZIP архивама (EPUB, XLSX...).
+use std::io::{self, Cursor, Read, Write};
+
+use zip::result::ZipError;
+use zip::write::SimpleFileOptions;
+use zip::{ZipArchive, ZipWriter};
+
+use crate::Error;
+
+/// Мења фајлове у ZIP архиви. Функција `replace` за сваки фајл добија назив и
+/// садржај и враћа нови садржај или `None` ако се фајл не мења. Редослед
+/// фајлова, начин компресије, време измене и дозволе остају непромењени.
+pub(crate) fn transform(
+    input: &[u8],
+    mut replace: impl FnMut(&str, &[u8]) -> Result<Option<Vec<u8>>, Error>,
+) -> Result<Vec<u8>, Error> {
+    let mut archive = ZipArchive::new(Cursor::new(input))?;
+    let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+    for i in 0..archive.len() {
+        let mut file = archive.by_index(i)?;
+        let name = file.name()?.into_owned();
+        let mut content = Vec::new();
+        file.read_to_end(&mut content)?;
+        match replace(&name, &content)? {
+            Some(content) => {
+                let mut options =
+                    SimpleFileOptions::default().compression_method(file.compression());
+                if let Some(time) = file.last_modified() {
+                    options = options.last_modified_time(time);
+                }
+                if let Some(mode) = file.unix_mode() {
+                    options = options.unix_permissions(mode);
+                }
+                writer.start_file(name, options)?;
+                writer.write_all(&content)?;
+            }
+            None => {
+                drop(file);
+                writer.raw_copy_file(archive.by_index_raw(i)?)?;
+            }
+        }
+    }
+    Ok(writer.finish()?.into_inner())
+}
+
+/// Садржај текстуалног фајла из архиве.
+pub(crate) fn text(content: &[u8]) -> Result<&str, Error> {
+    std::str::from_utf8(content).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e).into())
+}
+
+/// Чита текстуални фајл `name` из архиве, ако постоји.
+pub(crate) fn read(input: &[u8], name: &str) -> Result<Option<String>, Error> {
+    let mut archive = ZipArchive::new(Cursor::new(input))?;
+    let mut file = match archive.by_name(name) {
+        Ok(file) => file,
+        Err(ZipError::FileNotFound) => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    let mut content = Vec::new();
+    file.read_to_end(&mut content)?;
+    Ok(Some(text(&content)?.to_string()))
+}
@@ -0,0 +1,180 @@
+//! Конверзија AsciiDoc докумената. Пресловљавају се наслови и пасуси, док
+//! блокови кода, листинга и дословног текста (`----`, `....`), пропуштени
+//! блокови (`++++`), коментари, дефиниције атрибута (`:name: вредност`),
+//! атрибути блокова (`[source,python]`), мете макроа (`image::slika.png[]`,
+//! `link:https://primer.rs[текст]`), референце на атрибуте (`{name}`) и код у
+//! линији остају непромењени.
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+// Стилови блока чији се садржај не пресловљава
+const RAW_STYLES: &[&str] = &[
+    "source",
+    "listing",
+    "literal",
+    "pass",
+    "stem",
+    "latexmath",
+    "asciimath",
+];
+
+// Макрои чији се текст у заградама пресловљава
+const TEXT_MACROS: &[&str] = &["link", "xref", "mailto", "footnote", "http", "https", "ftp"];
+
+// Граничници блокова чији се садржај не пресловљава
+static RAW_DELIMITER: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^(-{4,}|\.{4,}|\+{4,}|/{4,}|```)").unwrap());
+
+// Дефиниције атрибута, атрибути блокова, макрои блокова и коментари
+static RAW_LINE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(:!?[\w-]+!?:(\s|$)|\[.*\]$|[a-z][\w-]*::\S*\[.*\]$|//([^/]|$))").unwrap()
+});
+
+// Наслов документа и одељка (`== Naslov`) или наслов блока (`.Naslov`)
+static TITLE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^(=+\s+|#+\s+|\.)[^.\s]").unwrap());
+
+// Код у линији, референце на атрибуте, унакрсне референце и макрои
+static INLINE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"`[^`\n]*`|\+\+\+.*?\+\+\+|\{[\w-]+\}|<<[^,>]*,?|\b([a-z][\w-]*):(?://)?[^\s\[]*\[([^\]]*)\]|https?://[^\s\[]+",
+    )
+    .unwrap()
+});
+
+/// Пресловљава наслове и пасусе AsciiDoc документа функцијом `convert`.
+pub fn convert(input: &str, convert: impl Fn(&str) -> String) -> String {
+    let mut output = String::with_capacity(input.len());
+    // Граничник отвореног блока који се не пресловљава
+    let mut delimiter: Option<&str> = None;
+    // Пасус иза атрибута `[source]` или увучен дословни пасус
+    let mut raw_paragraph = false;
+    let mut previous_blank = true;
+    for line in input.split_inclusive('\n') {
+        let content = line.trim_end();
+        let blank = content.is_empty();
+        if let Some(open) = delimiter {
+            if content == open || (open == "```" && content.starts_with(open)) {
+                delimiter = None;
+            }
+            output.push_str(line);
+        } else if let Some(open) = RAW_DELIMITER.find(content)
+            && (open.as_str() == "```" || open.end() == content.len())
+        {
+            delimiter = Some(open.as_str());
+            raw_paragraph = false;
+            output.push_str(line);
+        } else if blank {
+            raw_paragraph = false;
+            output.push_str(line);
+        } else if raw_paragraph || (previous_blank && line.starts_with([' ', '\t'])) {
+            raw_paragraph = true;
+            output.push_str(line);
+        } else if RAW_LINE.is_match(content) {
+            raw_paragraph = content
+                .strip_prefix('[')
+                .map(|style| {
+                    style
+                        .split([',', ']', '%', '#', '.'])
+                        .next()
+                        .unwrap_or_default()
+                })
+                .is_some_and(|style| RAW_STYLES.contains(&style));
+            output.push_str(line);
+        } else if let Some(title) = TITLE.captures(content) {
+            let prefix = title.get(1).unwrap().end();
+            output.push_str(&line[..prefix]);
+            output.push_str(&convert_inline(&line[prefix..], &convert));
+        } else {
+            output.push_str(&convert_inline(line, &convert));
+        }
+        previous_blank = blank;
+    }
+    output
+}
+
+/// Пресловљава текст линије изузев кода, атрибута и мета макроа.
+fn convert_inline(text: &str, convert: &impl Fn(&str) -> String) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut pos = 0;
+    for captures in INLINE.captures_iter(text) {
+        let protected = captures.get(0).unwrap();
+        output.push_str(&convert(&text[pos..protected.start()]));
+        match (captures.get(1), captures.get(2)) {
+            (Some(name), Some(label)) if TEXT_MACROS.contains(&name.as_str()) => {
+                // Додатни атрибути иза текста (`[текст, window=_blank]`)
+                let label_end = match label.as_str().split_once(',') {
+                    Some((text, rest)) if rest.contains('=') => label.start() + text.len(),
+                    _ => label.end(),
+                };
+                output.push_str(&text[protected.start()..label.start()]);
+                output.push_str(&convert(&text[label.start()..label_end]));
+                output.push_str(&text[label_end..protected.end()]);
+            }
+            _ => output.push_str(protected.as_str()),
+        }
+        pos = protected.end();
+    }
+    output.push_str(&convert(&text[pos..]));
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lat_to_cyr;
+
+    #[test]
+    fn test_convert() {
+        let input = "= Uputstvo
+:author: Petar Petrović
+:toc:
+
+== Instalacija
+
+.Primer
+Pokrenuti `cargo install` i pogledati link:https://primer.rs[sajt, window=_blank], {version} i <<uvod,uvod>>.
+
+image::slike/logo.png[Logo]
+
+[source,rust]
+----
+fn main() {}
+----
+
+[source]
+println!(\"zdravo\");
+
+// komentar
+
+  dosloven pasus
+
+Kraj.
+";
+        let expected = "= Упутство
+:author: Petar Petrović
+:toc:
+
+== Инсталација
+
+.Пример
+Покренути `cargo install` и погледати link:https://primer.rs[сајт, window=_blank], {version} и <<uvod,увод>>.
+
+image::slike/logo.png[Logo]
+
+[source,rust]
+----
+fn main() {}
+----
+
+[source]
+println!(\"zdravo\");
+
+// komentar
+
+  dosloven pasus
+
+Крај.
+";
+        assert_eq!(expected, convert(input, lat_to_cyr));
+    }
+}
@@ -0,0 +1,104 @@
+//! Конверзија ASS/SSA титлова у којој се пресловљава само поље `Text` линија
+//! `Dialogue`, док блокови за измену стила (`{\an8\pos(10,20)}`), посебни знаци
+//! (`\N`) и остале секције документа остају непромењени.
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+// Блокови за измену стила и посебни знаци (нови ред, нераскидиви размак)
+static OVERRIDE: Lazy<Regex> = Lazy::new(|| Regex::new(r"\{[^{}]*\}|\\[Nnh]").unwrap());
+
+// Подразумевани индекс поља `Text` ако секција нема линију `Format`
+const DEFAULT_TEXT_FIELD: usize = 9;
+
+/// Пресловљава текст дијалога функцијом `convert`.
+pub fn convert(input: &str, convert: impl Fn(&str) -> String) -> String {
+    let mut output = String::with_capacity(input.len());
+    let mut in_events = false;
+    let mut text_field = DEFAULT_TEXT_FIELD;
+    for line in input.split_inclusive('\n') {
+        let content = line.trim_end_matches(['\n', '\r']);
+        let trimmed = content.trim_start_matches('\u{feff}').trim_start();
+        if trimmed.starts_with('[') {
+            in_events = trimmed.trim_end().eq_ignore_ascii_case("[events]");
+        } else if in_events && let Some(fields) = trimmed.strip_prefix("Format:") {
+            text_field = fields
+                .split(',')
+                .position(|field| field.trim().eq_ignore_ascii_case("text"))
+                .unwrap_or(DEFAULT_TEXT_FIELD);
+        } else if in_events && let Some(fields) = trimmed.strip_prefix("Dialogue:") {
+            // Поље `Text` је последње и може садржати зарезе
+            let mut parts = fields.splitn(text_field + 1, ',');
+            let prefix_len = parts
+                .by_ref()
+                .take(text_field)
+                .map(|field| field.len() + 1)
+                .sum::<usize>();
+            if parts.next().is_some() {
+                let text_start = content.len() - fields.len() + prefix_len;
+                output.push_str(&content[..text_start]);
+                output.push_str(&convert_text(&content[text_start..], &convert));
+                output.push_str(&line[content.len()..]);
+                continue;
+            }
+        }
+        output.push_str(line);
+    }
+    output
+}
+
+/// Пресловљава текст дијалога изузев блокова за измену стила.
+fn convert_text(text: &str, convert: &impl Fn(&str) -> String) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut pos = 0;
+    for block in OVERRIDE.find_iter(text) {
+        output.push_str(&convert(&text[pos..block.start()]));
+        output.push_str(block.as_str());
+        pos = block.end();
+    }
+    output.push_str(&convert(&text[pos..]));
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lat_to_cyr;
+
+    #[test]
+    fn test_convert() {
+        let input = "[Script Info]
+Title: Moj film
+ScriptType: v4.00+
+
+[V4+ Styles]
+Format: Name, Fontname, Fontsize
+Style: Default,Arial,20
+
+[Events]
+Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text
+Dialogue: 0,0:00:01.00,0:00:03.00,Default,Ana,0,0,0,,{\\an8\\pos(10,20)}Dobro, jutro!\\NKako si?
+Comment: 0,0:00:04.00,0:00:05.00,Default,,0,0,0,,Komentar
+";
+        let expected = "[Script Info]
+Title: Moj film
+ScriptType: v4.00+
+
+[V4+ Styles]
+Format: Name, Fontname, Fontsize
+Style: Default,Arial,20
+
+[Events]
+Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text
+Dialogue: 0,0:00:01.00,0:00:03.00,Default,Ana,0,0,0,,{\\an8\\pos(10,20)}Добро, јутро!\\NКако си?
+Comment: 0,0:00:04.00,0:00:05.00,Default,,0,0,0,,Komentar
+";
+        assert_eq!(expected, convert(input, lat_to_cyr));
+    }
+
+    #[test]
+    fn test_text_field() {
+        let input = "[Events]\nFormat: Start, Text\nDialogue: 0:00:01.00,Zdravo, svete\n";
+        let expected = "[Events]\nFormat: Start, Text\nDialogue: 0:00:01.00,Здраво, свете\n";
+        assert_eq!(expected, convert(input, lat_to_cyr));
+    }
+}
@@ -0,0 +1,196 @@
+//! Конверзија BibTeX библиографија. Пресловљавају се само поља намењена
+//! читаоцу (наслов, аутори, издавач...), док кључеви цитата, остала поља
+//! (`url`, `doi`, `isbn`...), витичасте заграде које чувају велика слова и
+//! LaTeX команде унутар вредности остају непромењени.
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use super::latex::{self, group_len};
+
+// Поља која се пресловљавају
+const TEXT_FIELDS: &[&str] = &[
+    "title",
+    "subtitle",
+    "booktitle",
+    "author",
+    "editor",
+    "publisher",
+    "journal",
+    "series",
+    "address",
+    "school",
+    "institution",
+    "organization",
+    "howpublished",
+    "note",
+    "abstract",
+];
+
+// Ставке које немају поља (коментари, преамбула, скраћенице)
+const RAW_ENTRIES: &[&str] = &["comment", "preamble", "string"];
+
+// Раздвајач имена аутора и уредника
+static NAME_SEPARATOR: Lazy<Regex> = Lazy::new(|| Regex::new(r"\s+and\s+|\bothers\b").unwrap());
+
+/// Пресловљава поља намењена читаоцу функцијом `convert`.
+pub fn convert(input: &str, convert: impl Fn(&str) -> String) -> String {
+    let mut output = String::with_capacity(input.len());
+    let mut pos = 0;
+    while let Some(at) = input[pos..].find('@') {
+        let start = pos + at;
+        output.push_str(&input[pos..start]);
+        let rest = &input[start..];
+        let kind_len = rest[1..]
+            .find(|c: char| !c.is_ascii_alphanumeric())
+            .map_or(rest.len(), |i| i + 1);
+        let kind = rest[1..kind_len].to_lowercase();
+        let body = rest[kind_len..].trim_start();
+        let body_start = rest.len() - body.len();
+        let close = match body.chars().next() {
+            Some('{') => '}',
+            Some('(') => ')',
+            _ => {
+                output.push_str(&rest[..kind_len]);
+                pos = start + kind_len;
+                continue;
+            }
+        };
+        if RAW_ENTRIES.contains(&kind.as_str()) {
+            let open = if close == '}' { '{' } else { '(' };
+            let len = body_start + group_len(body, open, close);
+            output.push_str(&rest[..len]);
+            pos = start + len;
+            continue;
+        }
+
+        // Кључ цитата
+        let key_len = body.find([',', close]).map_or(body.len(), |i| i + 1);
+        output.push_str(&rest[..body_start + key_len]);
+        pos = start + body_start + key_len;
+        if body[..key_len].ends_with(close) {
+            continue;
+        }
+        pos = convert_fields(input, pos, close, &convert, &mut output);
+    }
+    output.push_str(&input[pos..]);
+    output
+}
+
+/// Пресловљава поља ставке од позиције `pos` до затворене заграде `close`.
+/// Враћа позицију иза ставке.
+fn convert_fields(
+    input: &str,
+    mut pos: usize,
+    close: char,
+    convert: &impl Fn(&str) -> String,
+    output: &mut String,
+) -> usize {
+    let mut field = String::new();
+    let mut field_start = pos;
+    while let Some(c) = input[pos..].chars().next() {
+        let rest = &input[pos..];
+        let len = match c {
+            c if c == close => {
+                output.push(c);
+                return pos + 1;
+            }
+            ',' => {
+                field_start = pos + 1;
+                1
+            }
+            '=' => {
+                field = input[field_start..pos].trim().to_lowercase();
+                1
+            }
+            '{' | '"' => {
+                let len = if c == '{' {
+                    group_len(rest, '{', '}')
+                } else {
+                    quoted_len(rest)
+                };
+                if TEXT_FIELDS.contains(&field.as_str()) && len >= 2 {
+                    let value = &rest[1..len - 1];
+                    output.push(c);
+                    if field == "author" || field == "editor" {
+                        output.push_str(&convert_names(value, convert));
+                    } else {
+                        output.push_str(&latex::convert(value, convert));
+                    }
+                    output.push_str(&rest[len - 1..len]);
+                    pos += len;
+                    continue;
+                }
+                len
+            }
+            c => c.len_utf8(),
+        };
+        output.push_str(&rest[..len]);
+        pos += len;
+    }
+    pos
+}
+
+/// Пресловљава имена аутора изузев раздвајача `and` и `others`.
+fn convert_names(value: &str, convert: &impl Fn(&str) -> String) -> String {
+    let mut output = String::with_capacity(value.len());
+    let mut pos = 0;
+    for separator in NAME_SEPARATOR.find_iter(value) {
+        output.push_str(&latex::convert(&value[pos..separator.start()], convert));
+        output.push_str(separator.as_str());
+        pos = separator.end();
+    }
+    output.push_str(&latex::convert(&value[pos..], convert));
+    output
+}
+
+/// Дужина вредности под наводницима (наводници унутар витичастих заграда не
+/// завршавају вредност).
+fn quoted_len(input: &str) -> usize {
+    let mut depth = 0;
+    for (i, c) in input.char_indices().skip(1) {
+        match c {
+            '{' => depth += 1,
+            '}' => depth -= 1,
+            '"' if depth == 0 => return i + 1,
+            _ => {}
+        }
+    }
+    input.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lat_to_cyr;
+
+    #[test]
+    fn test_convert() {
+        let input = r#"% Literatura
+@string{ns = "Novi Sad"}
+@Book{andric1945,
+  author    = {Ivo Andrić and Petar Petrović and others},
+  title     = "Na {Drini} {\'c}uprija",
+  publisher = {Prosveta},
+  address   = ns # { i Beograd},
+  year      = 1945,
+  url       = {https://primer.rs/knjiga},
+  doi       = {10.1000/182},
+}
+@comment{Napomena bez konverzije}
+"#;
+        let expected = r#"% Literatura
+@string{ns = "Novi Sad"}
+@Book{andric1945,
+  author    = {Иво Андрић and Петар Петровић and others},
+  title     = "На {Дрини} {\'ц}уприја",
+  publisher = {Просвета},
+  address   = ns # { и Београд},
+  year      = 1945,
+  url       = {https://primer.rs/knjiga},
+  doi       = {10.1000/182},
+}
+@comment{Napomena bez konverzije}
+"#;
+        assert_eq!(expected, convert(input, lat_to_cyr));
+    }
+}
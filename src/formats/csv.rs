@@ -0,0 +1,122 @@
+//! Конверзија CSV/TSV табела у којој се пресловљавају само изабране колоне,
+//! док граничници и наводници остају непромењени.
+use super::syntax_error;
+use crate::Error;
+
+/// Пресловљава поља табеле функцијом `convert`. Поља су одвојена знаком
+/// `delimiter` (`,` код CSV, таб код TSV), а пресловљавају се само колоне
+/// чији су индекси (почев од 0) у `columns`, односно све колоне ако
+/// `columns` није задато.
+pub fn convert(
+    input: &str,
+    delimiter: char,
+    columns: Option<&[usize]>,
+    convert: impl Fn(&str) -> String,
+) -> Result<String, Error> {
+    let mut output = String::with_capacity(input.len());
+    let mut column = 0;
+    let mut pos = 0;
+    while pos < input.len() {
+        let rest = &input[pos..];
+        let selected = columns.is_none_or(|columns| columns.contains(&column));
+        let len = if rest.starts_with('"') {
+            let len =
+                quoted_len(rest).ok_or_else(|| syntax_error(input, pos, "незатворен наводник"))?;
+            if selected {
+                output.push('"');
+                output.push_str(&convert_quoted(&rest[1..len - 1], &convert));
+                output.push('"');
+            } else {
+                output.push_str(&rest[..len]);
+            }
+            len
+        } else {
+            let len = rest.find([delimiter, '\n', '\r']).unwrap_or(rest.len());
+            if selected && len > 0 {
+                output.push_str(&convert(&rest[..len]));
+            } else {
+                output.push_str(&rest[..len]);
+            }
+            len
+        };
+        pos += len;
+
+        // Граничник или крај реда
+        let rest = &input[pos..];
+        let separator_len = match rest.chars().next() {
+            None => break,
+            Some(c) if c == delimiter => {
+                column += 1;
+                c.len_utf8()
+            }
+            Some('\r' | '\n') => {
+                column = 0;
+                if rest.starts_with("\r\n") { 2 } else { 1 }
+            }
+            Some(_) => return Err(syntax_error(input, pos, "неочекиван знак иза наводника")),
+        };
+        output.push_str(&rest[..separator_len]);
+        pos += separator_len;
+    }
+    Ok(output)
+}
+
+/// Дужина поља под наводницима заједно са наводницима.
+fn quoted_len(field: &str) -> Option<usize> {
+    let mut pos = 1;
+    loop {
+        pos += field[pos..].find('"')? + 1;
+        // `""` унутар поља је наводник
+        if !field[pos..].starts_with('"') {
+            return Some(pos);
+        }
+        pos += 1;
+    }
+}
+
+/// Пресловљава садржај поља под наводницима, осим удвојених наводника.
+fn convert_quoted(field: &str, convert: &impl Fn(&str) -> String) -> String {
+    field
+        .split("\"\"")
+        .map(convert)
+        .collect::<Vec<_>>()
+        .join("\"\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lat_to_cyr;
+
+    #[test]
+    fn test_convert() {
+        let input =
+            "ime,grad,opis\r\nIvo,Beograd,\"Pisac, \"\"Na Drini\"\"\nćuprija\"\n\"Vuk\",,Rečnik\n";
+        let expected =
+            "име,град,опис\r\nИво,Београд,\"Писац, \"\"На Дрини\"\"\nћуприја\"\n\"Вук\",,Речник\n";
+        assert_eq!(expected, convert(input, ',', None, lat_to_cyr).unwrap());
+    }
+
+    #[test]
+    fn test_convert_columns() {
+        assert_eq!(
+            "ivo\tБеоград\tnis\nIvo\t\"Ниш\"\tdom",
+            convert(
+                "ivo\tBeograd\tnis\nIvo\t\"Niš\"\tdom",
+                '\t',
+                Some(&[1]),
+                lat_to_cyr
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_syntax_errors() {
+        assert!(matches!(
+            convert("a,b\n\"c,d", ',', None, lat_to_cyr),
+            Err(Error::Syntax { line: 2, .. })
+        ));
+        assert!(convert("\"a\"b,c", ',', None, lat_to_cyr).is_err());
+    }
+}
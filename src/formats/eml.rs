@@ -0,0 +1,502 @@
+//! Конверзија порука електронске поште (EML/MIME). Пресловљавају се делови
+//! поруке типа `text/plain` и `text/html` и наслов поруке (`Subject`), који се
+//! поново кодира према RFC 2047. Остала заглавља, границе делова и прилози
+//! остају непромењени.
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use super::xml::{Content, convert_document, retag};
+use crate::Error;
+use crate::encoding::decode_byte;
+
+// Заглавља чија се вредност пресловљава
+const TEXT_HEADERS: &[&str] = &["subject", "thread-topic"];
+
+// Елементи HTML делова чији се садржај не пресловљава
+const CODE_ELEMENTS: &[&str] = &["script", "style"];
+
+const BASE64: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+// Кодирана реч у заглављу (`=?UTF-8?B?...?=`)
+static ENCODED_WORD: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"=\?([^?\s]+)\?([bBqQ])\?([^?\s]*)\?=").unwrap());
+
+/// Заглавље са непромењеним садржајем (укључујући настављене линије).
+struct Header<'a> {
+    name: &'a str,
+    raw: &'a str,
+}
+
+impl Header<'_> {
+    /// Вредност заглавља у једној линији.
+    fn value(&self) -> String {
+        let value = self.raw.split_once(':').map_or("", |(_, value)| value);
+        value.replace(['\r', '\n'], "").trim().to_string()
+    }
+}
+
+/// Пресловљава текстуалне делове и наслов поруке функцијом `convert`. Ако је
+/// задата ознака језика `language` (нпр. `sr-Latn`) њоме се замењују ознаке
+/// истог језика у заглављу `Content-Language` и HTML атрибутима `lang`.
+pub fn convert(
+    input: &str,
+    language: Option<&str>,
+    convert: impl Fn(&str) -> String,
+) -> Result<String, Error> {
+    let ending = if input.contains("\r\n") { "\r\n" } else { "\n" };
+    convert_entity(input, ending, language, &convert)
+}
+
+/// Пресловљава део поруке (заглавља и садржај).
+fn convert_entity(
+    input: &str,
+    ending: &str,
+    language: Option<&str>,
+    convert: &impl Fn(&str) -> String,
+) -> Result<String, Error> {
+    let (headers, separator, body) = split_entity(input);
+    let header = |name: &str| {
+        headers
+            .iter()
+            .find(|header| header.name.eq_ignore_ascii_case(name))
+            .map(Header::value)
+    };
+    let content_type = header("content-type").unwrap_or_else(|| "text/plain".to_string());
+    let mime = content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_lowercase();
+    let encoding = header("content-transfer-encoding")
+        .unwrap_or_default()
+        .to_lowercase();
+    let attachment = header("content-disposition")
+        .is_some_and(|disposition| disposition.to_lowercase().starts_with("attachment"));
+
+    // Садржај и измене заглавља `Content-Type` и `Content-Transfer-Encoding`
+    let mut new_type = None;
+    let mut new_encoding = None;
+    let body = match mime.as_str() {
+        _ if attachment => body.to_string(),
+        mime if mime.starts_with("multipart/") => match parameter(&content_type, "boundary") {
+            Some(boundary) => convert_multipart(body, &boundary, ending, language, convert)?,
+            None => body.to_string(),
+        },
+        "message/rfc822" => convert_entity(body, ending, language, convert)?,
+        "text/plain" | "text/html" => {
+            let charset = parameter(&content_type, "charset").unwrap_or_default();
+            match decode_body(body, &encoding, &charset) {
+                Some(text) => {
+                    let converted = if mime == "text/html" {
+                        convert_document(&text, language, convert, |stack| {
+                            if stack.iter().any(|element| CODE_ELEMENTS.contains(element)) {
+                                Content::Unchanged
+                            } else {
+                                Content::Text
+                            }
+                        })?
+                    } else {
+                        convert(&text)
+                    };
+                    if !converted.is_ascii() {
+                        if !charset.eq_ignore_ascii_case("utf-8") {
+                            new_type = Some(with_charset(&content_type, "utf-8"));
+                        }
+                        if matches!(encoding.as_str(), "" | "7bit") {
+                            new_encoding = Some("8bit");
+                        }
+                    }
+                    encode_body(&converted, &encoding, ending)
+                }
+                None => body.to_string(),
+            }
+        }
+        _ => body.to_string(),
+    };
+
+    let mut output = String::with_capacity(input.len());
+    for header in &headers {
+        let name = header.name.to_lowercase();
+        let value = if TEXT_HEADERS.contains(&name.as_str()) {
+            Some(encode_header(
+                &convert(&decode_header(&header.value())),
+                ending,
+            ))
+        } else if name == "content-language" {
+            language.and_then(|language| retag(&header.value(), language))
+        } else if name == "content-type" {
+            new_type.take()
+        } else if name == "content-transfer-encoding" {
+            new_encoding.take().map(str::to_string)
+        } else {
+            None
+        };
+        match value {
+            Some(value) => output.push_str(&format!("{}: {value}{ending}", header.name)),
+            None => output.push_str(header.raw),
+        }
+    }
+    if let Some(value) = new_type {
+        output.push_str(&format!("Content-Type: {value}{ending}"));
+    }
+    if let Some(value) = new_encoding {
+        output.push_str(&format!("Content-Transfer-Encoding: {value}{ending}"));
+    }
+    output.push_str(separator);
+    output.push_str(&body);
+    Ok(output)
+}
+
+/// Дели део поруке на заглавља, празну линију и садржај.
+fn split_entity(input: &str) -> (Vec<Header<'_>>, &str, &str) {
+    let mut headers: Vec<Header> = Vec::new();
+    let mut pos = 0;
+    for line in input.split_inclusive('\n') {
+        if line.trim_end().is_empty() {
+            return (headers, line, &input[pos + line.len()..]);
+        }
+        match headers.last_mut() {
+            // Настављена линија (folding)
+            Some(header) if line.starts_with([' ', '\t']) => {
+                header.raw = &input[pos - header.raw.len()..pos + line.len()];
+            }
+            _ => headers.push(Header {
+                name: line.split_once(':').map_or("", |(name, _)| name.trim()),
+                raw: line,
+            }),
+        }
+        pos += line.len();
+    }
+    (headers, "", "")
+}
+
+/// Вредност параметра заглавља (`boundary`, `charset`).
+fn parameter(value: &str, name: &str) -> Option<String> {
+    value.split(';').skip(1).find_map(|parameter| {
+        let (key, value) = parameter.split_once('=')?;
+        key.trim()
+            .eq_ignore_ascii_case(name)
+            .then(|| value.trim().trim_matches('"').to_string())
+    })
+}
+
+/// Замењује скуп знакова у вредности заглавља `Content-Type`.
+fn with_charset(content_type: &str, charset: &str) -> String {
+    let mut parts = content_type.split(';').map(str::trim);
+    let mut value = parts.next().unwrap_or_default().to_string();
+    value.push_str(&format!("; charset={charset}"));
+    for part in parts.filter(|part| {
+        !part
+            .split('=')
+            .next()
+            .is_some_and(|key| key.trim().eq_ignore_ascii_case("charset"))
+    }) {
+        value.push_str("; ");
+        value.push_str(part);
+    }
+    value
+}
+
+/// Пресловљава делове садржаја ограничене линијама `--boundary`. Увод и
+/// епилог остају непромењени.
+fn convert_multipart(
+    body: &str,
+    boundary: &str,
+    ending: &str,
+    language: Option<&str>,
+    convert: &impl Fn(&str) -> String,
+) -> Result<String, Error> {
+    let delimiter = format!("--{boundary}");
+    let mut output = String::with_capacity(body.len());
+    // Почетак дела који још није преписан
+    let mut part_start: Option<usize> = None;
+    let mut pos = 0;
+    for line in body.split_inclusive('\n') {
+        let trimmed = line.trim_end();
+        if trimmed == delimiter || trimmed == format!("{delimiter}--") {
+            match part_start {
+                Some(start) => {
+                    // Прелом линије испред границе припада граници
+                    let part = &body[start..pos];
+                    let content = part
+                        .strip_suffix("\r\n")
+                        .or_else(|| part.strip_suffix('\n'))
+                        .unwrap_or(part);
+                    output.push_str(&convert_entity(content, ending, language, convert)?);
+                    output.push_str(&part[content.len()..]);
+                }
+                None => output.push_str(&body[..pos]),
+            }
+            output.push_str(line);
+            part_start = (trimmed == delimiter).then_some(pos + line.len());
+            if part_start.is_none() {
+                output.push_str(&body[pos + line.len()..]);
+                return Ok(output);
+            }
+        }
+        pos += line.len();
+    }
+    // Порука без завршне границе
+    output.push_str(&body[part_start.unwrap_or(0)..]);
+    Ok(output)
+}
+
+/// Декодира садржај текстуалног дела. Враћа `None` ако кодирање или скуп
+/// знакова нису подржани.
+fn decode_body(body: &str, encoding: &str, charset: &str) -> Option<String> {
+    let bytes = match encoding {
+        "base64" => base64_decode(body)?,
+        "quoted-printable" => quoted_printable_decode(body, false),
+        "" | "7bit" | "8bit" | "binary" => return Some(body.to_string()),
+        _ => return None,
+    };
+    decode_charset(&bytes, charset)
+}
+
+/// Кодира садржај текстуалног дела у UTF-8 истим кодирањем као у оригиналу.
+fn encode_body(text: &str, encoding: &str, ending: &str) -> String {
+    match encoding {
+        "base64" => {
+            let encoded = base64_encode(text.as_bytes());
+            let mut output = String::with_capacity(encoded.len() + encoded.len() / 76 * 2);
+            for line in encoded.as_bytes().chunks(76) {
+                output.push_str(std::str::from_utf8(line).unwrap());
+                output.push_str(ending);
+            }
+            output
+        }
+        "quoted-printable" => quoted_printable_encode(text, ending),
+        _ => text.to_string(),
+    }
+}
+
+fn decode_charset(bytes: &[u8], charset: &str) -> Option<String> {
+    let code_page = match charset.to_lowercase().as_str() {
+        "" | "utf-8" | "utf8" | "us-ascii" => return String::from_utf8(bytes.to_vec()).ok(),
+        "windows-1250" | "cp1250" => 1250,
+        "windows-1251" | "cp1251" => 1251,
+        "windows-1252" | "cp1252" | "iso-8859-1" | "latin1" => 1252,
+        "iso-8859-2" | "latin2" => 28592,
+        "iso-8859-5" => 28595,
+        _ => return None,
+    };
+    bytes
+        .iter()
+        .map(|&byte| decode_byte(code_page, byte))
+        .collect()
+}
+
+/// Декодира кодиране речи у вредности заглавља (RFC 2047). Размаци између
+/// суседних кодираних речи се изостављају.
+fn decode_header(value: &str) -> String {
+    let mut output = String::with_capacity(value.len());
+    let mut pos = 0;
+    for word in ENCODED_WORD.captures_iter(value) {
+        let whole = word.get(0).unwrap();
+        let between = &value[pos..whole.start()];
+        if pos == 0 || !between.trim().is_empty() {
+            output.push_str(between);
+        }
+        let bytes = match &word[2] {
+            "b" | "B" => base64_decode(&word[3]),
+            _ => Some(quoted_printable_decode(&word[3], true)),
+        };
+        match bytes.and_then(|bytes| decode_charset(&bytes, &word[1])) {
+            Some(text) => output.push_str(&text),
+            None => output.push_str(whole.as_str()),
+        }
+        pos = whole.end();
+    }
+    output.push_str(&value[pos..]);
+    output
+}
+
+/// Кодира вредност заглавља у UTF-8 кодиране речи (RFC 2047) ако садржи
+/// не-ASCII знакове. Кодиране речи се настављају у новим линијама.
+fn encode_header(text: &str, ending: &str) -> String {
+    if text.is_ascii() {
+        return text.to_string();
+    }
+    // Кодирана реч може имати највише 75 знакова (45 бајтова у base64)
+    let mut words = Vec::new();
+    let mut chunk = String::new();
+    for c in text.chars() {
+        if chunk.len() + c.len_utf8() > 45 {
+            words.push(std::mem::take(&mut chunk));
+        }
+        chunk.push(c);
+    }
+    words.push(chunk);
+    words
+        .iter()
+        .map(|word| format!("=?UTF-8?B?{}?=", base64_encode(word.as_bytes())))
+        .collect::<Vec<_>>()
+        .join(&format!("{ending} "))
+}
+
+fn base64_encode(bytes: &[u8]) -> String {
+    let mut output = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, &byte)| n | (byte as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                output.push(BASE64[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                output.push('=');
+            }
+        }
+    }
+    output
+}
+
+fn base64_decode(input: &str) -> Option<Vec<u8>> {
+    let mut output = Vec::with_capacity(input.len() / 4 * 3);
+    let mut n = 0u32;
+    let mut bits = 0;
+    for byte in input.bytes() {
+        let value = match byte {
+            b'=' => break,
+            byte if byte.is_ascii_whitespace() => continue,
+            byte => BASE64.iter().position(|&b| b == byte)? as u32,
+        };
+        n = n << 6 | value;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            output.push((n >> bits) as u8);
+        }
+    }
+    Some(output)
+}
+
+/// Декодира quoted-printable садржај. У кодираним речима заглавља
+/// (`underscore`) `_` означава размак.
+fn quoted_printable_decode(input: &str, underscore: bool) -> Vec<u8> {
+    let bytes = input.as_bytes();
+    let mut output = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match bytes[i] {
+            b'=' if bytes[i + 1..].starts_with(b"\r\n") => i += 3,
+            b'=' if bytes[i + 1..].starts_with(b"\n") => i += 2,
+            b'=' if hex.is_some() => {
+                output.push(hex.unwrap());
+                i += 3;
+            }
+            b'_' if underscore => {
+                output.push(b' ');
+                i += 1;
+            }
+            byte => {
+                output.push(byte);
+                i += 1;
+            }
+        }
+    }
+    output
+}
+
+/// Кодира текст као quoted-printable са линијама од највише 76 знакова.
+fn quoted_printable_encode(text: &str, ending: &str) -> String {
+    let mut output = String::with_capacity(text.len() * 2);
+    for (i, line) in text.split('\n').enumerate() {
+        if i > 0 {
+            output.push_str(ending);
+        }
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        let mut width = 0;
+        for (j, &byte) in line.as_bytes().iter().enumerate() {
+            let last = j + 1 == line.len();
+            let encoded = match byte {
+                b' ' | b'\t' if !last => (byte as char).to_string(),
+                b'!'..=b'<' | b'>'..=b'~' => (byte as char).to_string(),
+                byte => format!("={byte:02X}"),
+            };
+            if width + encoded.len() > 75 {
+                output.push('=');
+                output.push_str(ending);
+                width = 0;
+            }
+            output.push_str(&encoded);
+            width += encoded.len();
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lat_to_cyr;
+
+    #[test]
+    fn test_convert() {
+        let input = "From: Petar <petar@primer.rs>
+Subject: =?UTF-8?Q?Pozdrav_iz_Beograda?=
+MIME-Version: 1.0
+Content-Type: multipart/mixed; boundary=\"granica\"
+
+Preambula.
+--granica
+Content-Type: text/plain; charset=us-ascii
+
+Dobar dan, Ana.
+--granica
+Content-Type: text/html; charset=utf-8
+Content-Transfer-Encoding: quoted-printable
+
+<p lang=3D\"sr-Latn\">Zdravo <b>svete</b></p><style>p {color: red}</style>
+--granica
+Content-Type: text/plain
+Content-Disposition: attachment; filename=\"beleska.txt\"
+Content-Transfer-Encoding: base64
+
+UHJpbG9n
+--granica--
+";
+        let output = convert(input, Some("sr-Cyrl"), lat_to_cyr).unwrap();
+        let subject = format!(
+            "Subject: =?UTF-8?B?{}?=\n",
+            base64_encode("Поздрав из Београда".as_bytes())
+        );
+        assert!(output.starts_with(&format!("From: Petar <petar@primer.rs>\n{subject}")));
+        assert!(output.contains(
+            "\n--granica
+Content-Type: text/plain; charset=utf-8
+Content-Transfer-Encoding: 8bit
+
+Добар дан, Ана.
+--granica\n"
+        ));
+        let html = output.split("quoted-printable\n\n").nth(1).unwrap();
+        let html = html.split("\n--granica").next().unwrap();
+        assert_eq!(
+            "<p lang=\"sr-Cyrl\">Здраво <b>свете</b></p><style>p {color: red}</style>",
+            String::from_utf8(quoted_printable_decode(html, false)).unwrap()
+        );
+        assert!(output.ends_with("\n\nUHJpbG9n\n--granica--\n"));
+    }
+
+    #[test]
+    fn test_codecs() {
+        for text in ["", "a", "ab", "abc", "Ћирко"] {
+            let encoded = base64_encode(text.as_bytes());
+            assert_eq!(text.as_bytes(), base64_decode(&encoded).unwrap());
+        }
+        assert_eq!("0LY=", base64_encode("ж".as_bytes()));
+        assert_eq!(
+            "Ана и Петар",
+            decode_header(
+                "=?utf-8?B?0JDQvdCw?= =?windows-1251?Q?_=E8_?= =?UTF-8?Q?=D0=9F=D0=B5=D1=82=D0=B0=D1=80?="
+            )
+        );
+    }
+}
@@ -0,0 +1,125 @@
+//! Конверзија EPUB књига. Пресловљава се текст XHTML докумената садржаја,
+//! садржај (NCX) и метаподаци књиге (наслов, аутор...), а ознаке језика се
+//! мењају тако да одговарају новом писму (нпр. `sr-Latn`).
+use super::archive;
+use super::xml::{Content, convert_document};
+use crate::Error;
+
+// Елементи XHTML докумената чији се садржај не пресловљава
+const CODE_ELEMENTS: &[&str] = &["script", "style"];
+
+// Метаподаци пакета (Dublin Core) чији се садржај пресловљава
+const TEXT_METADATA: &[&str] = &[
+    "dc:title",
+    "dc:creator",
+    "dc:contributor",
+    "dc:publisher",
+    "dc:subject",
+    "dc:description",
+    "dc:coverage",
+];
+
+/// Пресловљава EPUB књигу функцијом `convert`. Ако је задата ознака језика
+/// `language` (нпр. `sr-Latn`) њоме се замењују ознаке истог језика у
+/// `dc:language` и атрибутима `lang`/`xml:lang`.
+pub fn convert(
+    input: &[u8],
+    language: Option<&str>,
+    convert: impl Fn(&str) -> String,
+) -> Result<Vec<u8>, Error> {
+    archive::transform(input, |name, content| {
+        let extension = name.rsplit_once('.').map_or("", |(_, extension)| extension);
+        let content_of: fn(&[&str]) -> Content = match extension.to_lowercase().as_str() {
+            "xhtml" | "html" | "htm" => |stack| {
+                if stack.iter().any(|element| CODE_ELEMENTS.contains(element)) {
+                    Content::Unchanged
+                } else {
+                    Content::Text
+                }
+            },
+            "opf" => |stack| match stack.last() {
+                Some(&"dc:language") => Content::Language,
+                Some(element) if TEXT_METADATA.contains(element) => Content::Text,
+                _ => Content::Unchanged,
+            },
+            "ncx" => |_| Content::Text,
+            _ => return Ok(None),
+        };
+        let text = archive::text(content)?;
+        let converted =
+            convert_document(text, language, &convert, content_of).map_err(|e| match e {
+                Error::Syntax { line, message } => Error::Syntax {
+                    line,
+                    message: format!("{name}: {message}"),
+                },
+                e => e,
+            })?;
+        Ok(Some(converted.into_bytes()))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, Read, Write};
+
+    use zip::write::SimpleFileOptions;
+    use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+    use super::*;
+    use crate::lat_to_cyr;
+
+    #[test]
+    fn test_convert() {
+        let files = [
+            ("mimetype", "application/epub+zip"),
+            (
+                "OEBPS/content.opf",
+                "<metadata><dc:title>Na Drini</dc:title>\
+                 <dc:identifier>urn:isbn:1</dc:identifier>\
+                 <dc:language>sr</dc:language></metadata>",
+            ),
+            ("OEBPS/text.xhtml", "<p>Ćuprija</p>"),
+            ("OEBPS/style.css", "p { font: serif; }"),
+        ];
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, content) in files {
+            let options = SimpleFileOptions::default().compression_method(if name == "mimetype" {
+                CompressionMethod::Stored
+            } else {
+                CompressionMethod::Deflated
+            });
+            writer.start_file(name, options).unwrap();
+            writer.write_all(content.as_bytes()).unwrap();
+        }
+        let input = writer.finish().unwrap().into_inner();
+
+        let output = convert(&input, Some("sr-Cyrl"), lat_to_cyr).unwrap();
+        let mut archive = ZipArchive::new(Cursor::new(output)).unwrap();
+        let mut read = |i| {
+            let mut file = archive.by_index(i).unwrap();
+            let mut content = String::new();
+            file.read_to_string(&mut content).unwrap();
+            (
+                file.name().unwrap().to_string(),
+                file.compression(),
+                content,
+            )
+        };
+        assert_eq!(
+            (
+                "mimetype".to_string(),
+                CompressionMethod::Stored,
+                "application/epub+zip".to_string()
+            ),
+            read(0)
+        );
+        assert_eq!(
+            "<metadata><dc:title>На Дрини</dc:title>\
+             <dc:identifier>urn:isbn:1</dc:identifier>\
+             <dc:language>sr-Cyrl</dc:language></metadata>",
+            read(1).2
+        );
+        assert_eq!("<p>Ћуприја</p>", read(2).2);
+        assert_eq!("p { font: serif; }", read(3).2);
+    }
+}
@@ -0,0 +1,67 @@
+//! Конверзија FictionBook (FB2) књига. Пресловљава се текст књиге и подаци о
+//! књизи (`title-info`), док бинарни прилози (слике) и везе (`l:href`) остају
+//! непромењени.
+use super::xml::{Content, convert_document};
+use crate::Error;
+
+// Елементи података о књизи који се не пресловљавају
+const CODE_ELEMENTS: &[&str] = &["genre", "src-lang", "id"];
+
+/// Пресловљава FB2 књигу функцијом `convert`. Ако је задата ознака језика
+/// `language` (нпр. `sr-Latn`) њоме се замењује ознака истог језика у
+/// елементу `lang`.
+pub fn convert(
+    input: &str,
+    language: Option<&str>,
+    convert: impl Fn(&str) -> String,
+) -> Result<String, Error> {
+    convert_document(input, language, &convert, |stack| {
+        if stack.contains(&"binary") {
+            Content::Unchanged
+        } else if stack.contains(&"body") {
+            Content::Text
+        } else if stack.contains(&"title-info") {
+            match stack.last() {
+                Some(&"lang") => Content::Language,
+                Some(element) if CODE_ELEMENTS.contains(element) => Content::Unchanged,
+                _ => Content::Text,
+            }
+        } else {
+            Content::Unchanged
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lat_to_cyr;
+
+    #[test]
+    fn test_convert() {
+        let input = r##"<?xml version="1.0" encoding="UTF-8"?>
+<FictionBook xmlns="http://www.gribuser.ru/xml/fictionbook/2.0" xmlns:l="http://www.w3.org/1999/xlink">
+<description>
+<title-info><genre>prose_classic</genre><author><first-name>Ivo</first-name></author>
+<book-title>Na Drini ćuprija</book-title><lang>sr</lang></title-info>
+<document-info><program-used>Pisac</program-used></document-info>
+</description>
+<body><section><p>Most je <a l:href="#n1">star</a>.</p><image l:href="#most.jpg"/></section></body>
+<binary id="most.jpg" content-type="image/jpeg">LzlqLzRBQ</binary>
+</FictionBook>"##;
+        let expected = r##"<?xml version="1.0" encoding="UTF-8"?>
+<FictionBook xmlns="http://www.gribuser.ru/xml/fictionbook/2.0" xmlns:l="http://www.w3.org/1999/xlink">
+<description>
+<title-info><genre>prose_classic</genre><author><first-name>Иво</first-name></author>
+<book-title>На Дрини ћуприја</book-title><lang>sr-Cyrl</lang></title-info>
+<document-info><program-used>Pisac</program-used></document-info>
+</description>
+<body><section><p>Мост је <a l:href="#n1">стар</a>.</p><image l:href="#most.jpg"/></section></body>
+<binary id="most.jpg" content-type="image/jpeg">LzlqLzRBQ</binary>
+</FictionBook>"##;
+        assert_eq!(
+            expected,
+            convert(input, Some("sr-Cyrl"), lat_to_cyr).unwrap()
+        );
+    }
+}
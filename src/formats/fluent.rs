@@ -0,0 +1,171 @@
+//! Конверзија Fluent (`.ftl`) датотека. Пресловљава се текст вредности и
+//! атрибута порука, укључујући варијанте израза избора, док идентификатори,
+//! коментари, референце на променљиве и термине (`{ $user }`, `{ -brand }`),
+//! кључеви варијанти (`[one]`) и стринг литерали у изразима остају
+//! непромењени.
+
+/// Део вредности поруке који се обрађује.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    /// Израз унутар `{ ... }`.
+    Expression,
+    /// Варијанте израза избора (`{ $count -> ... }`).
+    Variants,
+}
+
+/// Пресловљава вредности порука функцијом `convert`.
+pub fn convert(input: &str, convert: impl Fn(&str) -> String) -> String {
+    let mut output = String::with_capacity(input.len());
+    // Текст који још није пресловљен
+    let mut text = String::new();
+    let mut stack: Vec<Mode> = Vec::new();
+    // Да ли се линије са увлачењем настављају вредност поруке
+    let mut in_message = false;
+    let mut line_start = true;
+    let mut pos = 0;
+    while pos < input.len() {
+        let rest = &input[pos..];
+        if line_start && stack.is_empty() {
+            output.push_str(&convert(&text));
+            text.clear();
+            let line = &rest[..rest.find('\n').map_or(rest.len(), |i| i + 1)];
+            let trimmed = line.trim_start_matches(' ');
+            let indent = line.len() - trimmed.len();
+            let len = if trimmed.trim().is_empty() {
+                line.len()
+            } else if indent == 0 {
+                // Порука или термин (`-brand = ...`), коментар или грешка
+                in_message = !line.starts_with('#') && line.contains('=');
+                match line.find('=') {
+                    Some(eq) if in_message => eq + 1,
+                    _ => line.len(),
+                }
+            } else if !in_message {
+                line.len()
+            } else if trimmed.starts_with('.')
+                && let Some(eq) = line.find('=')
+            {
+                // Атрибут поруке
+                eq + 1
+            } else {
+                indent
+            };
+            output.push_str(&line[..len]);
+            pos += len;
+            line_start = len == line.len();
+            continue;
+        }
+
+        let c = rest.chars().next().unwrap_or_default();
+        let mut len = c.len_utf8();
+        match stack.last() {
+            Some(Mode::Expression) => {
+                match c {
+                    '"' => len = string_len(rest),
+                    '{' => stack.push(Mode::Expression),
+                    '}' => {
+                        stack.pop();
+                    }
+                    '-' if rest.starts_with("->") => {
+                        stack.pop();
+                        stack.push(Mode::Variants);
+                        len = 2;
+                    }
+                    _ => {}
+                }
+                output.push_str(&rest[..len]);
+            }
+            mode => {
+                if mode == Some(&Mode::Variants) && line_start {
+                    line_start = false;
+                    // Кључ варијанте (`[one]`, `*[other]`)
+                    let trimmed = rest.trim_start_matches([' ', '\t', '\r']);
+                    let indent = rest.len() - trimmed.len();
+                    let key = trimmed
+                        .strip_prefix('*')
+                        .unwrap_or(trimmed)
+                        .starts_with('[')
+                        .then(|| trimmed.find([']', '\n']).map(|end| end + 1))
+                        .flatten();
+                    let prefix_len = indent + key.unwrap_or(0);
+                    if prefix_len > 0 {
+                        output.push_str(&rest[..prefix_len]);
+                        pos += prefix_len;
+                        continue;
+                    }
+                }
+                match c {
+                    '{' | '}' | '\n' => {
+                        output.push_str(&convert(&text));
+                        text.clear();
+                        output.push(c);
+                        match c {
+                            '{' => stack.push(Mode::Expression),
+                            '}' => {
+                                stack.pop();
+                            }
+                            _ => line_start = true,
+                        }
+                    }
+                    c => text.push(c),
+                }
+            }
+        }
+        pos += len;
+    }
+    output.push_str(&convert(&text));
+    output
+}
+
+/// Дужина стринг литерала који почиње наводником.
+fn string_len(input: &str) -> usize {
+    let mut escaped = false;
+    for (i, c) in input.char_indices().skip(1) {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '"' | '\n' => return i + 1,
+            _ => {}
+        }
+    }
+    input.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lat_to_cyr;
+
+    #[test]
+    fn test_convert() {
+        let input = r#"# Poruke aplikacije
+-brand-name = Kalendar
+welcome = Dobro došli, { $user }, u { -brand-name }!
+    .title = Naslov { "{" }
+emails =
+    { $count ->
+        [one] Imate jednu poruku.
+       *[other] Imate { NUMBER($count, style: "decimal") } poruka
+            u sandučetu.
+    }
+multiline =
+    Prva linija
+    druga linija
+"#;
+        let expected = r#"# Poruke aplikacije
+-brand-name = Календар
+welcome = Добро дошли, { $user }, у { -brand-name }!
+    .title = Наслов { "{" }
+emails =
+    { $count ->
+        [one] Имате једну поруку.
+       *[other] Имате { NUMBER($count, style: "decimal") } порука
+            у сандучету.
+    }
+multiline =
+    Прва линија
+    друга линија
+"#;
+        assert_eq!(expected, convert(input, lat_to_cyr));
+    }
+}
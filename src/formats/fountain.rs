@@ -0,0 +1,220 @@
+//! Конверзија сценарија у Fountain формату. Пресловљавају се радња, дијалози,
+//! имена ликова (уз очување великих слова) и опис места у заглављима сцена,
+//! док техничке ознаке заглавља (`INT.`, `EXT.`), бројеви сцена (`#1A#`),
+//! проширења имена (`(V.O.)`), прелази (`CUT TO:`), белешке (`[[...]]`) и
+//! избачени делови (`/* ... */`) остају непромењени.
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+// Поља насловне стране чија се вредност пресловљава
+const TEXT_KEYS: &[&str] = &["title", "credit", "author", "authors", "source"];
+
+// Заглавље сцене: ознака, опис места и број сцене
+static SCENE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^((?i:int\./ext|int/ext|i/e|int|ext|est)[. ]\s*|\.)(.*?)(\s*#[^#\s]+#)?(\s*)$")
+        .unwrap()
+});
+
+// Име лика са проширењима (`(V.O.)`) и ознаком двоструког дијалога (`^`)
+static CHARACTER: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^(\s*@?)([^(^]*?)((?:\s*\([^)]*\))*)(\s*\^)?(\s*)$").unwrap());
+
+// Белешке и избачени делови у линији
+static INLINE: Lazy<Regex> = Lazy::new(|| Regex::new(r"\[\[.*?\]\]|/\*.*?\*/").unwrap());
+
+/// Пресловљава текст сценарија функцијом `convert`.
+pub fn convert(input: &str, convert: impl Fn(&str) -> String) -> String {
+    let lines: Vec<&str> = input.split_inclusive('\n').collect();
+    let mut output = String::with_capacity(input.len());
+    let mut i = 0;
+
+    // Насловна страна (`Title: ...`) до прве празне линије
+    if lines.first().is_some_and(|line| is_title_key(line)) {
+        let mut convert_value = false;
+        while let Some(line) = lines.get(i) {
+            if line.trim().is_empty() {
+                break;
+            }
+            match line.split_once(':') {
+                Some((key, value)) if is_title_key(line) => {
+                    convert_value = TEXT_KEYS.contains(&key.trim().to_lowercase().as_str());
+                    output.push_str(key);
+                    output.push(':');
+                    output.push_str(&if convert_value {
+                        convert_inline(value, &convert)
+                    } else {
+                        value.to_string()
+                    });
+                }
+                _ if convert_value => output.push_str(&convert_inline(line, &convert)),
+                _ => output.push_str(line),
+            }
+            i += 1;
+        }
+    }
+
+    // Завршетак белешке или избаченог дела који се наставља у више линија
+    let mut raw_until: Option<&str> = None;
+    // Дијалог иза имена лика траје до празне линије
+    let mut in_dialogue = false;
+    while i < lines.len() {
+        let line = lines[i];
+        let trimmed = line.trim();
+        let previous_blank = i == 0 || lines[i - 1].trim().is_empty();
+        let next_blank = lines.get(i + 1).is_none_or(|next| next.trim().is_empty());
+        i += 1;
+
+        if let Some(end) = raw_until {
+            if trimmed.contains(end) {
+                raw_until = None;
+            }
+            output.push_str(line);
+            continue;
+        }
+        let unclosed = INLINE.replace_all(line, "");
+        if unclosed.contains("/*") {
+            raw_until = Some("*/");
+        } else if unclosed.contains("[[") {
+            raw_until = Some("]]");
+        }
+        if raw_until.is_some() {
+            output.push_str(line);
+            continue;
+        }
+
+        if trimmed.is_empty() {
+            in_dialogue = false;
+            output.push_str(line);
+        } else if in_dialogue {
+            output.push_str(&convert_inline(line, &convert));
+        } else if trimmed.starts_with("===") || is_transition(trimmed) {
+            output.push_str(line);
+        } else if previous_blank
+            && !trimmed.starts_with("..")
+            && let Some(scene) = SCENE.captures(line)
+        {
+            output.push_str(&scene[1]);
+            output.push_str(&convert_inline(&scene[2], &convert));
+            output.push_str(scene.get(3).map_or("", |number| number.as_str()));
+            output.push_str(&scene[4]);
+        } else if previous_blank
+            && !next_blank
+            && (trimmed.starts_with('@') || is_uppercase(trimmed))
+            && let Some(character) = CHARACTER.captures(line)
+        {
+            in_dialogue = true;
+            output.push_str(&character[1]);
+            output.push_str(&convert(&character[2]));
+            output.push_str(&character[3]);
+            output.push_str(character.get(4).map_or("", |dual| dual.as_str()));
+            output.push_str(&character[5]);
+        } else {
+            output.push_str(&convert_inline(line, &convert));
+        }
+    }
+    output
+}
+
+/// Да ли линија почиње пољем насловне стране (`Title:`).
+fn is_title_key(line: &str) -> bool {
+    line.split_once(':').is_some_and(|(key, _)| {
+        !key.is_empty()
+            && key.starts_with(|c: char| c.is_ascii_alphabetic())
+            && key.chars().all(|c| c.is_ascii_alphanumeric() || c == ' ')
+    })
+}
+
+/// Да ли линија садржи слова и сва су велика (имена ликова, прелази).
+fn is_uppercase(line: &str) -> bool {
+    let letters = line
+        .split('(')
+        .next()
+        .unwrap_or_default()
+        .chars()
+        .filter(|c| c.is_alphabetic());
+    let mut any = false;
+    for c in letters {
+        if c.is_lowercase() {
+            return false;
+        }
+        any = true;
+    }
+    any
+}
+
+/// Да ли је линија прелаз (`CUT TO:`, `FADE OUT.`).
+fn is_transition(line: &str) -> bool {
+    is_uppercase(line) && (line.ends_with("TO:") || line == "FADE OUT." || line == "FADE IN:")
+}
+
+/// Пресловљава текст линије изузев белешки и избачених делова.
+fn convert_inline(text: &str, convert: &impl Fn(&str) -> String) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut pos = 0;
+    for protected in INLINE.find_iter(text) {
+        output.push_str(&convert(&text[pos..protected.start()]));
+        output.push_str(protected.as_str());
+        pos = protected.end();
+    }
+    output.push_str(&convert(&text[pos..]));
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lat_to_cyr;
+
+    #[test]
+    fn test_convert() {
+        let input = "Title: Na Drini
+Author: Petar Petrović
+Draft date: 2024-01-15
+
+FADE IN:
+
+INT. KUĆA NA OBALI - NOĆ #1A#
+
+Ljubica sedi kraj prozora. [[proveriti rekvizite]]
+
+LJUBICA (V.O.)
+(tiho)
+Gde si bio?
+
+@McMillan ^
+Na reci.
+
+CUT TO:
+
+.SPOLJA, MOST
+
+/* izbačena
+scena */
+";
+        let expected = "Title: На Дрини
+Author: Петар Петровић
+Draft date: 2024-01-15
+
+FADE IN:
+
+INT. КУЋА НА ОБАЛИ - НОЋ #1A#
+
+Љубица седи крај прозора. [[proveriti rekvizite]]
+
+ЉУБИЦА (V.O.)
+(тихо)
+Где си био?
+
+@МцМиллан ^
+На реци.
+
+CUT TO:
+
+.СПОЉА, МОСТ
+
+/* izbačena
+scena */
+";
+        assert_eq!(expected, convert(input, lat_to_cyr));
+    }
+}
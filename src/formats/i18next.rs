@@ -0,0 +1,59 @@
+//! Конверзија i18next JSON пакета превода. Пресловљавају се вредности
+//! стрингова, док кључеви (укључујући суфиксе облика множине као што су
+//! `_plural` и `_few`), интерполације (`{{name}}`), угњеждени преводи
+//! (`$t(key)`) и ознаке компоненте `Trans` (`<1>`) остају непромењени.
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use super::json::{convert_escaped, transform};
+use crate::Error;
+
+// Интерполације, угњеждени преводи и ознаке компоненте `Trans`
+static PLACEHOLDER: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\{\{[^{}]*\}\}|\$t\([^()]*\)|</?\d+/?>").unwrap());
+
+/// Пресловљава вредности у пакету превода функцијом `convert`.
+pub fn convert(input: &str, convert: impl Fn(&str) -> String) -> Result<String, Error> {
+    transform(input, |string| {
+        if string.is_key {
+            return None;
+        }
+        let raw = string.raw;
+        let mut output = String::with_capacity(raw.len());
+        let mut pos = 0;
+        for placeholder in PLACEHOLDER.find_iter(raw) {
+            output.push_str(&convert_escaped(&raw[pos..placeholder.start()], &convert));
+            output.push_str(placeholder.as_str());
+            pos = placeholder.end();
+        }
+        output.push_str(&convert_escaped(&raw[pos..], &convert));
+        Some(output)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lat_to_cyr;
+
+    #[test]
+    fn test_convert() {
+        let input = r#"{
+  "welcome": "Dobro došli, {{name}}!",
+  "inbox": {
+    "message": "Imate {{count}} poruku",
+    "message_plural": "Imate {{count, number}} poruka",
+    "link": "Pogledaj $t(inbox.message, {\"count\": 1}) u <1>sandučetu</1>"
+  }
+}"#;
+        let expected = r#"{
+  "welcome": "Добро дошли, {{name}}!",
+  "inbox": {
+    "message": "Имате {{count}} поруку",
+    "message_plural": "Имате {{count, number}} порука",
+    "link": "Погледај $t(inbox.message, {\"count\": 1}) у <1>сандучету</1>"
+  }
+}"#;
+        assert_eq!(expected, convert(input, lat_to_cyr).unwrap());
+    }
+}
@@ -0,0 +1,183 @@
+//! Конверзија iCalendar календара (`.ics`). Пресловљавају се наслови, описи и
+//! места догађаја (`SUMMARY`, `DESCRIPTION`, `LOCATION`...), док остала
+//! својства, параметри и escape секвенце (`\n`, `\,`) остају непромењени.
+//! Пресловљене вредности се поново преламају на 75 бајтова.
+use super::json::convert_escaped;
+use super::xml::retag;
+
+// Својства чија се вредност пресловљава
+const TEXT_PROPERTIES: &[&str] = &[
+    "SUMMARY",
+    "DESCRIPTION",
+    "LOCATION",
+    "COMMENT",
+    "X-WR-CALNAME",
+    "X-WR-CALDESC",
+];
+
+/// Пресловљава вредности текстуалних својстава функцијом `convert`. Ако је
+/// задата ознака језика `language` (нпр. `sr-Latn`) њоме се замењује ознака
+/// истог језика у параметру `LANGUAGE` пресловљених својстава.
+pub fn convert(input: &str, language: Option<&str>, convert: impl Fn(&str) -> String) -> String {
+    convert_properties(input, TEXT_PROPERTIES, language, &convert)
+}
+
+/// Пресловљава вредности својстава `properties` у садржају у коме су
+/// својства записана у облику `NAME;PARAM=...:вредност` (iCalendar, vCard).
+/// Дуге линије се настављају у наредним линијама које почињу размаком.
+pub(crate) fn convert_properties(
+    input: &str,
+    properties: &[&str],
+    language: Option<&str>,
+    convert: &impl Fn(&str) -> String,
+) -> String {
+    let mut output = String::with_capacity(input.len());
+    let mut lines = input.split_inclusive('\n').peekable();
+    while let Some(first) = lines.next() {
+        // Логичка линија са настављеним линијама
+        let mut physical = vec![first];
+        while let Some(next) = lines.peek()
+            && next.starts_with([' ', '\t'])
+        {
+            physical.push(next);
+            lines.next();
+        }
+        let last = physical[physical.len() - 1];
+        let ending = &last[last.trim_end_matches(['\r', '\n']).len()..];
+        let logical = physical
+            .iter()
+            .enumerate()
+            .map(|(i, line)| {
+                let line = line.trim_end_matches(['\r', '\n']);
+                if i > 0 { &line[1..] } else { line }
+            })
+            .collect::<String>();
+        match convert_property(&logical, properties, language, convert) {
+            Some(converted) => output.push_str(&fold(&converted, ending)),
+            None => physical.iter().for_each(|line| output.push_str(line)),
+        }
+    }
+    output
+}
+
+/// Пресловљава вредност својства ако је наведено у `properties`.
+fn convert_property(
+    line: &str,
+    properties: &[&str],
+    language: Option<&str>,
+    convert: &impl Fn(&str) -> String,
+) -> Option<String> {
+    // Двотачка унутар параметра под наводницима не завршава назив
+    let mut quoted = false;
+    let colon = line.find(|c| {
+        if c == '"' {
+            quoted = !quoted;
+        }
+        c == ':' && !quoted
+    })?;
+    let mut parameters = split_parameters(&line[..colon]);
+    // Назив својства може имати префикс групе (`item1.ORG`)
+    let name = parameters[0].rsplit('.').next().unwrap_or_default();
+    if !properties
+        .iter()
+        .any(|property| property.eq_ignore_ascii_case(name))
+        || parameters
+            .iter()
+            .any(|parameter| parameter_name(parameter) == "ENCODING")
+    {
+        return None;
+    }
+    for parameter in &mut parameters[1..] {
+        if parameter_name(parameter) == "LANGUAGE"
+            && let Some((key, tag)) = parameter.split_once('=')
+            && let Some(tag) = language.and_then(|language| retag(tag, language))
+        {
+            *parameter = format!("{key}={tag}");
+        }
+    }
+    Some(format!(
+        "{}:{}",
+        parameters.join(";"),
+        convert_escaped(&line[colon + 1..], convert)
+    ))
+}
+
+/// Дели назив и параметре својства (`;` унутар наводника не раздваја).
+fn split_parameters(head: &str) -> Vec<String> {
+    let mut parameters = vec![String::new()];
+    let mut quoted = false;
+    for c in head.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            ';' if !quoted => {
+                parameters.push(String::new());
+                continue;
+            }
+            _ => {}
+        }
+        parameters.last_mut().unwrap().push(c);
+    }
+    parameters
+}
+
+fn parameter_name(parameter: &str) -> String {
+    parameter
+        .split('=')
+        .next()
+        .unwrap_or_default()
+        .to_uppercase()
+}
+
+/// Прелама линију на делове од највише 75 бајтова. Наредни делови почињу
+/// размаком.
+fn fold(line: &str, ending: &str) -> String {
+    let mut output = String::with_capacity(line.len() + ending.len());
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > 75 {
+            output.push_str(ending);
+            output.push(' ');
+            width = 1;
+        }
+        output.push(c);
+        width += c.len_utf8();
+    }
+    output.push_str(ending);
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lat_to_cyr;
+
+    #[test]
+    fn test_convert() {
+        let input = "BEGIN:VCALENDAR\r
+X-WR-CALNAME:Predavanja\r
+BEGIN:VEVENT\r
+UID:predavanje-1@primer.rs\r
+SUMMARY;LANGUAGE=sr-Latn:Predavanje o Tesli\r
+LOCATION:Beograd\\, Narodni muzej\r
+DESCRIPTION:Prvi red\\nDrugi red sa veoma dugim opisom koji se nastavlja u\r
+  narednoj liniji\r
+URL:https://primer.rs/predavanje\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+        let expected = "BEGIN:VCALENDAR\r
+X-WR-CALNAME:Предавања\r
+BEGIN:VEVENT\r
+UID:predavanje-1@primer.rs\r
+SUMMARY;LANGUAGE=sr-Cyrl:Предавање о Тесли\r
+LOCATION:Београд\\, Народни музеј\r
+DESCRIPTION:Први ред\\nДруги ред са веома дугим \r
+ описом који се наставља у наредној линиј\r
+ и\r
+URL:https://primer.rs/predavanje\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+        assert_eq!(expected, convert(input, Some("sr-Cyrl"), lat_to_cyr));
+    }
+}
@@ -0,0 +1,169 @@
+//! Конверзија Jupyter свезака (`.ipynb`). Пресловљава се садржај markdown
+//! ћелија (као Markdown документ) и опционо текстуални излази ћелија кода, док
+//! код, метаподаци, бројачи извршавања и остали излази остају непромењени.
+use std::collections::VecDeque;
+
+use super::json::{convert_escaped, transform};
+use super::markdown;
+use crate::Error;
+
+/// Пресловљава markdown ћелије функцијом `convert`. Ако је `outputs` тачно
+/// пресловљавају се и текстуални излази ћелија кода (`stream`, `text/plain`).
+pub fn convert(
+    input: &str,
+    outputs: bool,
+    convert: impl Fn(&str) -> String,
+) -> Result<String, Error> {
+    // Извор ћелије може бити подељен у више стрингова (по један за сваку
+    // линију), па се у првом пролазу прикупљају извори и типови ћелија
+    let mut types = Vec::new();
+    let mut sources: Vec<Vec<String>> = Vec::new();
+    transform(input, |string| {
+        match (string.is_key, string.path) {
+            (true, ["cells"]) if string.raw == "source" => sources.push(Vec::new()),
+            (false, ["cells", "source"]) => {
+                if let Some(source) = sources.last_mut() {
+                    source.push(string.raw.to_string());
+                }
+            }
+            (false, ["cells", "cell_type"]) => types.push(string.raw.to_string()),
+            _ => {}
+        }
+        None
+    })?;
+
+    let mut replacements = VecDeque::new();
+    for (kind, source) in types.iter().zip(&sources) {
+        if kind == "markdown" {
+            replacements.extend(convert_source(source, &convert).into_iter().map(Some));
+        } else {
+            replacements.extend(source.iter().map(|_| None));
+        }
+    }
+
+    transform(input, |string| match (string.is_key, string.path) {
+        (false, ["cells", "source"]) => replacements.pop_front().flatten(),
+        (false, ["cells", "outputs", "text"] | ["cells", "outputs", "data", "text/plain"])
+            if outputs =>
+        {
+            Some(convert_escaped(string.raw, &convert))
+        }
+        _ => None,
+    })
+}
+
+/// Пресловљава извор markdown ћелије као целину и дели резултат на стрингове
+/// са истим бројем линија као у извору.
+fn convert_source(source: &[String], convert: &impl Fn(&str) -> String) -> Vec<String> {
+    let text = source
+        .iter()
+        .map(|raw| unescape_newlines(raw))
+        .collect::<String>();
+    let converted = markdown::convert(&text, |text| convert_escaped(text, convert));
+    let mut lines = converted.split_inclusive('\n');
+    let mut output = Vec::with_capacity(source.len());
+    for (i, raw) in source.iter().enumerate() {
+        let part = if i + 1 == source.len() {
+            lines.by_ref().collect::<String>()
+        } else {
+            let count = unescape_newlines(raw).matches('\n').count();
+            lines.by_ref().take(count).collect::<String>()
+        };
+        output.push(part.replace('\n', "\\n"));
+    }
+    output
+}
+
+/// Замењује `\n` escape секвенце у JSON стрингу правим преломима линија, док
+/// остале escape секвенце остају непромењене.
+fn unescape_newlines(raw: &str) -> String {
+    let mut output = String::with_capacity(raw.len());
+    let mut chars = raw.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            output.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => output.push('\n'),
+            Some(next) => {
+                output.push(c);
+                output.push(next);
+            }
+            None => output.push(c),
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lat_to_cyr;
+
+    #[test]
+    fn test_convert() {
+        let input = r##"{
+ "cells": [
+  {
+   "cell_type": "markdown",
+   "metadata": {},
+   "source": [
+    "# Analiza \"podataka\"\n",
+    "\n",
+    "```python\n",
+    "import pandas\n",
+    "```\n",
+    "Kraj"
+   ]
+  },
+  {
+   "cell_type": "code",
+   "execution_count": 1,
+   "metadata": {},
+   "outputs": [
+    {"name": "stdout", "output_type": "stream", "text": ["Zdravo\n"]}
+   ],
+   "source": ["print(\"Zdravo\")"]
+  }
+ ],
+ "metadata": {"kernelspec": {"language": "python", "name": "python3"}},
+ "nbformat": 4,
+ "nbformat_minor": 5
+}"##;
+        let expected = r##"{
+ "cells": [
+  {
+   "cell_type": "markdown",
+   "metadata": {},
+   "source": [
+    "# Анализа \"података\"\n",
+    "\n",
+    "```python\n",
+    "import pandas\n",
+    "```\n",
+    "Крај"
+   ]
+  },
+  {
+   "cell_type": "code",
+   "execution_count": 1,
+   "metadata": {},
+   "outputs": [
+    {"name": "stdout", "output_type": "stream", "text": ["Здраво\n"]}
+   ],
+   "source": ["print(\"Zdravo\")"]
+  }
+ ],
+ "metadata": {"kernelspec": {"language": "python", "name": "python3"}},
+ "nbformat": 4,
+ "nbformat_minor": 5
+}"##;
+        assert_eq!(expected, convert(input, true, lat_to_cyr).unwrap());
+        assert!(
+            convert(input, false, lat_to_cyr)
+                .unwrap()
+                .contains("[\"Zdravo\\n\"]")
+        );
+    }
+}
@@ -0,0 +1,346 @@
+//! Конверзија JSON докумената у којој се пресловљавају само вредности
+//! стрингова (и опционо кључеви), док бројеви, escape секвенце и форматирање
+//! остају непромењени. Слова записана секвенцама `\uXXXX` се пресловљавају и
+//! поново записују као escape секвенце.
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use super::syntax_error;
+use crate::Error;
+
+static NUMBER: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^-?(0|[1-9][0-9]*)(\.[0-9]+)?([eE][+-]?[0-9]+)?").unwrap());
+
+// Escape секвенце (`\n`, `\u0436`). Секвенце `\uXXXX` за слова се пресловљавају.
+static ESCAPE: Lazy<Regex> = Lazy::new(|| Regex::new(r#"\\(u[0-9a-fA-F]{4}|.)"#).unwrap());
+
+/// Стринг у документу који се може заменити.
+pub(crate) struct JsonString<'a> {
+    /// Садржај стринга са escape секвенцама, без наводника.
+    pub(crate) raw: &'a str,
+    pub(crate) is_key: bool,
+    /// Кључеви објеката до стринга (без кључа самог стринга).
+    pub(crate) path: &'a [&'a str],
+}
+
+struct Parser<'a, F> {
+    input: &'a str,
+    pos: usize,
+    output: String,
+    path: Vec<&'a str>,
+    replace: F,
+}
+
+/// Пролази кроз JSON документ и мења стрингове за које `replace` врати нови
+/// садржај (са escape секвенцама, без наводника). Остатак документа се
+/// преписује непромењен.
+pub(crate) fn transform<F>(input: &str, replace: F) -> Result<String, Error>
+where
+    F: FnMut(&JsonString) -> Option<String>,
+{
+    let mut parser = Parser {
+        input,
+        pos: 0,
+        output: String::with_capacity(input.len()),
+        path: Vec::new(),
+        replace,
+    };
+    parser.whitespace();
+    parser.value()?;
+    parser.whitespace();
+    if parser.pos < input.len() {
+        return Err(parser.error("неочекиван садржај након краја документа"));
+    }
+    Ok(parser.output)
+}
+
+impl<'a, F> Parser<'a, F>
+where
+    F: FnMut(&JsonString) -> Option<String>,
+{
+    fn error(&self, message: &str) -> Error {
+        syntax_error(self.input, self.pos, message)
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.input.as_bytes().get(self.pos).copied()
+    }
+
+    fn copy(&mut self, len: usize) {
+        self.output.push_str(&self.input[self.pos..self.pos + len]);
+        self.pos += len;
+    }
+
+    fn whitespace(&mut self) {
+        let rest = &self.input[self.pos..];
+        let len = rest.len() - rest.trim_start_matches([' ', '\t', '\n', '\r']).len();
+        self.copy(len);
+    }
+
+    fn expect(&mut self, c: u8, message: &str) -> Result<(), Error> {
+        if self.peek() == Some(c) {
+            self.copy(1);
+            Ok(())
+        } else {
+            Err(self.error(message))
+        }
+    }
+
+    fn value(&mut self) -> Result<(), Error> {
+        match self.peek() {
+            Some(b'{') => self.object(),
+            Some(b'[') => self.array(),
+            Some(b'"') => self.string(false).map(|_| ()),
+            Some(_) => {
+                let rest = &self.input[self.pos..];
+                let len = ["true", "false", "null"]
+                    .into_iter()
+                    .find(|literal| rest.starts_with(literal))
+                    .map(str::len)
+                    .or_else(|| NUMBER.find(rest).map(|m| m.end()))
+                    .ok_or_else(|| self.error("неочекиван знак"))?;
+                self.copy(len);
+                Ok(())
+            }
+            None => Err(self.error("неочекиван крај документа")),
+        }
+    }
+
+    fn object(&mut self) -> Result<(), Error> {
+        self.copy(1);
+        self.whitespace();
+        if self.peek() == Some(b'}') {
+            self.copy(1);
+            return Ok(());
+        }
+        loop {
+            if self.peek() != Some(b'"') {
+                return Err(self.error("очекиван кључ"));
+            }
+            let key = self.string(true)?;
+            self.whitespace();
+            self.expect(b':', "очекивано `:`")?;
+            self.whitespace();
+            self.path.push(key);
+            self.value()?;
+            self.path.pop();
+            self.whitespace();
+            if self.peek() == Some(b',') {
+                self.copy(1);
+                self.whitespace();
+            } else {
+                return self.expect(b'}', "очекивано `,` или `}`");
+            }
+        }
+    }
+
+    fn array(&mut self) -> Result<(), Error> {
+        self.copy(1);
+        self.whitespace();
+        if self.peek() == Some(b']') {
+            self.copy(1);
+            return Ok(());
+        }
+        loop {
+            self.value()?;
+            self.whitespace();
+            if self.peek() == Some(b',') {
+                self.copy(1);
+                self.whitespace();
+            } else {
+                break;
+            }
+        }
+        self.expect(b']', "очекивано `,` или `]`")
+    }
+
+    /// Обрађује стринг и враћа његов садржај.
+    fn string(&mut self, is_key: bool) -> Result<&'a str, Error> {
+        let input = self.input;
+        let start = self.pos + 1;
+        let mut escaped = false;
+        let len = input[start..]
+            .find(|c| {
+                let end = c == '"' && !escaped;
+                escaped = c == '\\' && !escaped;
+                end
+            })
+            .ok_or_else(|| self.error("незатворен стринг"))?;
+        let raw = &input[start..start + len];
+
+        let path = &self.path;
+        let replaced = (self.replace)(&JsonString { raw, is_key, path });
+        self.output.push('"');
+        self.output.push_str(replaced.as_deref().unwrap_or(raw));
+        self.output.push('"');
+        self.pos = start + len + 1;
+        Ok(raw)
+    }
+}
+
+/// JSON стринг са наводницима и escape секвенцама.
+pub fn json_string(text: &str) -> String {
+    let mut output = String::with_capacity(text.len() + 2);
+    output.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => output.push_str("\\\""),
+            '\\' => output.push_str("\\\\"),
+            '\n' => output.push_str("\\n"),
+            '\r' => output.push_str("\\r"),
+            '\t' => output.push_str("\\t"),
+            c if (c as u32) < 0x20 => output.push_str(&format!("\\u{:04x}", c as u32)),
+            c => output.push(c),
+        }
+    }
+    output.push('"');
+    output
+}
+
+/// Пресловљава садржај стринга са escape секвенцама. Слова записана
+/// секвенцама `\uXXXX` се декодирају и пресловљавају заједно са околним
+/// текстом, а не-ASCII слова речи која садрже таква слова се у резултату
+/// поново записују као `\uXXXX`. Остале escape секвенце остају непромењене.
+pub(crate) fn convert_escaped(raw: &str, convert: &impl Fn(&str) -> String) -> String {
+    let mut output = String::with_capacity(raw.len());
+    // Знакови текста и да ли су записани escape секвенцом
+    let mut text = Vec::new();
+    let flush = |text: &mut Vec<(char, bool)>, output: &mut String| {
+        for (run, escaped) in escaped_runs(text) {
+            for c in convert(&run).chars() {
+                if escaped && c.is_alphabetic() && !c.is_ascii() {
+                    for unit in c.encode_utf16(&mut [0; 2]) {
+                        output.push_str(&format!("\\u{unit:04x}"));
+                    }
+                } else {
+                    output.push(c);
+                }
+            }
+        }
+        text.clear();
+    };
+    let mut pos = 0;
+    for escape in ESCAPE.find_iter(raw) {
+        text.extend(raw[pos..escape.start()].chars().map(|c| (c, false)));
+        pos = escape.end();
+        // Декодирају се само слова, да би знаци попут `\u0022` остали
+        // escape секвенце
+        let decoded = escape
+            .as_str()
+            .strip_prefix("\\u")
+            .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+            .and_then(char::from_u32)
+            .filter(|c| c.is_alphabetic());
+        if let Some(c) = decoded {
+            text.push((c, true));
+        } else {
+            flush(&mut text, &mut output);
+            output.push_str(escape.as_str());
+        }
+    }
+    text.extend(raw[pos..].chars().map(|c| (c, false)));
+    flush(&mut text, &mut output);
+    output
+}
+
+/// Дели текст на делове који се пресловљавају заједно: узастопне речи (са
+/// знаковима иза њих) које садрже слова записана escape секвенцама, односно
+/// које их не садрже.
+fn escaped_runs(text: &[(char, bool)]) -> Vec<(String, bool)> {
+    let mut runs: Vec<(String, bool)> = Vec::new();
+    let mut start = 0;
+    while start < text.len() {
+        let word_end = text[start..]
+            .iter()
+            .position(|(c, _)| !c.is_alphanumeric())
+            .map_or(text.len(), |len| start + len);
+        let end = text[word_end..]
+            .iter()
+            .position(|(c, _)| c.is_alphanumeric())
+            .map_or(text.len(), |len| word_end + len);
+        let escaped = text[start..word_end].iter().any(|&(_, escaped)| escaped);
+        let part = text[start..end].iter().map(|&(c, _)| c);
+        match runs.last_mut() {
+            Some((run, run_escaped)) if *run_escaped == escaped => run.extend(part),
+            _ => runs.push((part.collect(), escaped)),
+        }
+        start = end;
+    }
+    runs
+}
+
+/// Пресловљава вредности стрингова функцијом `convert`. Кључеви се
+/// пресловљавају само ако је задат регуларни израз `keys` који их
+/// (делимично) подудара.
+pub fn convert(
+    input: &str,
+    keys: Option<&Regex>,
+    convert: impl Fn(&str) -> String,
+) -> Result<String, Error> {
+    transform(input, |string| {
+        let convert_key = keys.is_some_and(|keys| keys.is_match(string.raw));
+        (!string.is_key || convert_key).then(|| convert_escaped(string.raw, &convert))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{cyr_to_lat, lat_to_cyr};
+
+    #[test]
+    fn test_convert() {
+        let input = r#"{
+  "naslov": "Ljubav\nижivot",
+  "ocena" : 4.5e1, "tagovi": ["reč", true, null],
+  "autor": {"ime": "Ivo \"Andrić\""}
+}"#;
+        let expected = r#"{
+  "naslov": "Љубав\nиживот",
+  "ocena" : 4.5e1, "tagovi": ["реч", true, null],
+  "autor": {"ime": "Иво \"Андрић\""}
+}"#;
+        assert_eq!(expected, convert(input, None, lat_to_cyr).unwrap());
+    }
+
+    #[test]
+    fn test_unicode_escapes() {
+        assert_eq!(
+            r#"["\u041d\u0435\u0436\u043d\u043e", "\u0022Ниш\u0022"]"#,
+            convert(r#"["Ne\u017eno", "\u0022Niš\u0022"]"#, None, lat_to_cyr).unwrap()
+        );
+        assert_eq!(
+            r#"["\u0161uma", "\ud83d\ude00 Ljubav"]"#,
+            convert(r#"["\u0448ума", "\ud83d\ude00 Љубав"]"#, None, cyr_to_lat).unwrap()
+        );
+        assert_eq!(
+            r#"["Ниш и \u0436\u0438\u0432\u043e\u0442\nчарапа"]"#,
+            convert(r#"["Niš i \u017eivot\nčarapa"]"#, None, lat_to_cyr).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_convert_keys() {
+        let keys = Regex::new("^naslov").unwrap();
+        assert_eq!(
+            r#"{"наслов_1": "Књига", "ime": "Иво"}"#,
+            convert(
+                r#"{"naslov_1": "Knjiga", "ime": "Ivo"}"#,
+                Some(&keys),
+                lat_to_cyr
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_syntax_errors() {
+        assert!(matches!(
+            convert("{\n\"a\": }", None, lat_to_cyr),
+            Err(Error::Syntax { line: 2, .. })
+        ));
+        assert!(convert(r#"{"a": "b"#, None, lat_to_cyr).is_err());
+        assert!(convert(r#"["a",]"#, None, lat_to_cyr).is_err());
+        assert!(convert(r#"{"a": 1} x"#, None, lat_to_cyr).is_err());
+    }
+}
@@ -0,0 +1,109 @@
+//! Конверзија JSONL токова (један JSON документ по реду). Ток се обрађује ред
+//! по ред, па се и велики фајлови пресловљавају уз сталну потрошњу меморије.
+use std::io::{BufRead, Write};
+use std::str::FromStr;
+
+use super::json::{convert_escaped, transform};
+use crate::Error;
+
+/// Поље JSON објекта задато путањом кључева одвојених тачком (нпр.
+/// `autor.ime`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Field {
+    keys: Vec<String>,
+}
+
+impl Field {
+    /// Да ли се стринг на путањи `path` налази у пољу (директно или унутар
+    /// низова и објеката).
+    fn contains(&self, path: &[&str]) -> bool {
+        path.len() >= self.keys.len() && self.keys.iter().zip(path).all(|(key, k)| key == k)
+    }
+}
+
+impl FromStr for Field {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let keys = s.split('.').map(str::to_string).collect::<Vec<_>>();
+        if keys.iter().any(String::is_empty) {
+            return Err(Error::InvalidSelector(s.to_string()));
+        }
+        Ok(Field { keys })
+    }
+}
+
+/// Чита JSONL ток из `reader`, пресловљава вредности стрингова функцијом
+/// `convert` и уписује резултат у `writer`. Ако је листа `fields` празна
+/// пресловљавају се сви стрингови, а иначе само они у задатим пољима. Кључеви
+/// се не пресловљавају.
+pub fn convert<R: BufRead, W: Write>(
+    mut reader: R,
+    mut writer: W,
+    fields: &[Field],
+    convert: impl Fn(&str) -> String,
+) -> Result<(), Error> {
+    let mut line = String::new();
+    let mut number = 0;
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            break;
+        }
+        number += 1;
+        let content = line.trim_end_matches(['\n', '\r']);
+        if content.trim().is_empty() {
+            writer.write_all(line.as_bytes())?;
+            continue;
+        }
+        let converted = transform(content, |string| {
+            let selected =
+                fields.is_empty() || fields.iter().any(|field| field.contains(string.path));
+            (!string.is_key && selected).then(|| convert_escaped(string.raw, &convert))
+        })
+        .map_err(|e| match e {
+            Error::Syntax { message, .. } => Error::Syntax {
+                line: number,
+                message,
+            },
+            e => e,
+        })?;
+        writer.write_all(converted.as_bytes())?;
+        writer.write_all(&line.as_bytes()[content.len()..])?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lat_to_cyr;
+
+    #[test]
+    fn test_convert() {
+        let input = r#"{"naslov": "Knjiga", "autor": {"ime": "Ivo", "grad": "Beograd"}}
+
+{"naslov": "Rečnik", "autor": {"ime": "Vuk"}, "tagovi": ["jezik", {"ime": "Ana"}]}
+"#;
+        let expected = r#"{"naslov": "Књига", "autor": {"ime": "Иво", "grad": "Beograd"}}
+
+{"naslov": "Речник", "autor": {"ime": "Вук"}, "tagovi": ["језик", {"ime": "Ана"}]}
+"#;
+        let fields = ["naslov", "autor.ime", "tagovi"].map(|field| field.parse::<Field>().unwrap());
+        let mut output = Vec::new();
+        convert(input.as_bytes(), &mut output, &fields, lat_to_cyr).unwrap();
+        assert_eq!(expected, String::from_utf8(output).unwrap());
+
+        let mut output = Vec::new();
+        convert("[\"a\"]\r\n\"b\"".as_bytes(), &mut output, &[], lat_to_cyr).unwrap();
+        assert_eq!("[\"а\"]\r\n\"б\"", String::from_utf8(output).unwrap());
+    }
+
+    #[test]
+    fn test_syntax_errors() {
+        let result = convert("{}\n{\"a\": }\n".as_bytes(), Vec::new(), &[], lat_to_cyr);
+        assert!(matches!(result, Err(Error::Syntax { line: 2, .. })));
+        assert!("autor..ime".parse::<Field>().is_err());
+    }
+}
@@ -0,0 +1,304 @@
+//! Конверзија LaTeX докумената. Пресловљава се текст документа, укључујући
+//! аргументе команди за наслове, фусноте и истицање, док називи команди,
+//! коментари, математички режим (`$...$`, `\[...\]`, окружења `equation`,
+//! `align`...), ознаке и референце (`\label`, `\ref`, `\cite`), адресе и
+//! вербатим окружења остају непромењени.
+
+// Команде чији се аргументи не пресловљавају
+const RAW_ARGUMENTS: &[&str] = &[
+    "label",
+    "ref",
+    "eqref",
+    "pageref",
+    "autoref",
+    "nameref",
+    "cref",
+    "Cref",
+    "cite",
+    "citep",
+    "citet",
+    "citeauthor",
+    "citeyear",
+    "nocite",
+    "parencite",
+    "textcite",
+    "autocite",
+    "footcite",
+    "url",
+    "nolinkurl",
+    "path",
+    "texttt",
+    "lstinline",
+    "includegraphics",
+    "input",
+    "include",
+    "includeonly",
+    "usepackage",
+    "RequirePackage",
+    "documentclass",
+    "bibliography",
+    "bibliographystyle",
+    "addbibresource",
+    "graphicspath",
+    "newcommand",
+    "renewcommand",
+    "providecommand",
+    "newenvironment",
+    "renewenvironment",
+    "setlength",
+    "addtolength",
+    "setcounter",
+    "addtocounter",
+    "hspace",
+    "vspace",
+    "color",
+    "pagestyle",
+    "thispagestyle",
+    "pagenumbering",
+    "hypersetup",
+    "lstset",
+    "selectlanguage",
+    "begin",
+    "end",
+];
+
+// Команде чији се само први аргумент не пресловљава (`\href{url}{текст}`)
+const FIRST_RAW_ARGUMENT: &[&str] = &["href", "textcolor", "colorbox", "foreignlanguage"];
+
+// Окружења чији се садржај не пресловљава
+const RAW_ENVIRONMENTS: &[&str] = &[
+    "equation",
+    "equation*",
+    "align",
+    "align*",
+    "alignat",
+    "alignat*",
+    "gather",
+    "gather*",
+    "multline",
+    "multline*",
+    "flalign",
+    "flalign*",
+    "eqnarray",
+    "eqnarray*",
+    "math",
+    "displaymath",
+    "verbatim",
+    "verbatim*",
+    "Verbatim",
+    "lstlisting",
+    "minted",
+    "comment",
+    "tikzpicture",
+    "filecontents",
+];
+
+struct Parser<'a, F> {
+    input: &'a str,
+    pos: usize,
+    output: String,
+    // Почетак текста који још није пресловљен
+    text_start: usize,
+    convert: F,
+}
+
+impl<F: Fn(&str) -> String> Parser<'_, F> {
+    fn rest(&self) -> &str {
+        &self.input[self.pos..]
+    }
+
+    /// Преписује `len` бајтова непромењено.
+    fn raw(&mut self, len: usize) {
+        let text = &self.input[self.text_start..self.pos];
+        if !text.is_empty() {
+            self.output.push_str(&(self.convert)(text));
+        }
+        let end = (self.pos + len).min(self.input.len());
+        self.output.push_str(&self.input[self.pos..end]);
+        self.pos = end;
+        self.text_start = end;
+    }
+
+    /// Преписује непромењено све до `end` (укључујући) или до краја.
+    fn raw_until(&mut self, start: usize, end: &str) {
+        let len = self.rest()[start..]
+            .find(end)
+            .map_or(self.rest().len(), |i| start + i + end.len());
+        self.raw(len);
+    }
+
+    /// Преписује непромењене опционе (`[...]`) и обавезне (`{...}`)
+    /// аргументе, највише `count` обавезних.
+    fn raw_arguments(&mut self, count: usize) {
+        let mut count = count;
+        loop {
+            let rest = self.rest();
+            let len = if rest.starts_with('*') {
+                1
+            } else if rest.starts_with('[') {
+                group_len(rest, '[', ']')
+            } else if count > 0 && rest.starts_with('{') {
+                count -= 1;
+                group_len(rest, '{', '}')
+            } else {
+                return;
+            };
+            self.raw(len);
+        }
+    }
+
+    fn parse(&mut self) {
+        while let Some(c) = self.rest().chars().next() {
+            let rest = self.rest();
+            match c {
+                '%' => self.raw(rest.find('\n').unwrap_or(rest.len())),
+                '$' if rest.starts_with("$$") => self.raw_until(2, "$$"),
+                '$' => self.raw(math_len(rest)),
+                '\\' => self.command(),
+                c => self.pos += c.len_utf8(),
+            }
+        }
+        self.raw(0);
+    }
+
+    fn command(&mut self) {
+        let rest = self.rest();
+        let name_len = rest[1..]
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(rest.len() - 1);
+        if name_len == 0 {
+            // Контролни симбол (`\%`, `\\`, `\[`)
+            match rest[1..].chars().next() {
+                Some('[') => self.raw_until(2, "\\]"),
+                Some('(') => self.raw_until(2, "\\)"),
+                Some('\\') => {
+                    self.raw(2);
+                    self.raw_arguments(0);
+                }
+                Some(c) => self.raw(1 + c.len_utf8()),
+                None => self.raw(1),
+            }
+            return;
+        }
+
+        let name = &rest[1..1 + name_len];
+        if name == "verb" {
+            // `\verb|...|` са било којим граничником
+            let delimiter = rest[5..].trim_start_matches('*').chars().next();
+            let start = rest.len() - rest[5..].trim_start_matches('*').len();
+            match delimiter {
+                Some(d) => self.raw_until(start + d.len_utf8(), &d.to_string()),
+                None => self.raw(rest.len()),
+            }
+        } else if name == "begin"
+            && let Some(environment) = rest[6..]
+                .strip_prefix('{')
+                .and_then(|rest| rest.split_once('}'))
+                .map(|(environment, _)| environment)
+            && RAW_ENVIRONMENTS.contains(&environment)
+        {
+            let end = format!("\\end{{{environment}}}");
+            self.raw_until(0, &end);
+        } else if RAW_ARGUMENTS.contains(&name) {
+            self.raw(1 + name_len);
+            self.raw_arguments(usize::MAX);
+        } else if FIRST_RAW_ARGUMENT.contains(&name) {
+            self.raw(1 + name_len);
+            self.raw_arguments(1);
+        } else {
+            self.raw(1 + name_len);
+        }
+    }
+}
+
+/// Дужина групе ограничене заградама `open` и `close` (са угњежденим
+/// групама).
+pub(crate) fn group_len(input: &str, open: char, close: char) -> usize {
+    let mut depth = 0;
+    let mut escaped = false;
+    for (i, c) in input.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            c if c == open => depth += 1,
+            c if c == close => {
+                depth -= 1;
+                if depth == 0 {
+                    return i + 1;
+                }
+            }
+            _ => {}
+        }
+    }
+    input.len()
+}
+
+/// Дужина математике у линији (`$...$`).
+pub(crate) fn math_len(input: &str) -> usize {
+    let mut escaped = false;
+    for (i, c) in input.char_indices().skip(1) {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '$' => return i + 1,
+            _ => {}
+        }
+    }
+    input.len()
+}
+
+/// Пресловљава текст LaTeX документа функцијом `convert`.
+pub fn convert(input: &str, convert: impl Fn(&str) -> String) -> String {
+    let mut parser = Parser {
+        input,
+        pos: 0,
+        output: String::with_capacity(input.len()),
+        text_start: 0,
+        convert,
+    };
+    parser.parse();
+    parser.output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lat_to_cyr;
+
+    #[test]
+    fn test_convert() {
+        let input = r#"\documentclass[12pt,a4paper]{article}
+\usepackage[serbian]{babel}
+\begin{document}
+\section{Uvod}\label{sec:uvod}
+Ovo je \textbf{važan} tekst\footnote{Videti \cite[str.~5]{knuth84}.} sa formulom $a+b$
+i referencom na sliku~\ref{fig:most}. % komentar
+\begin{equation}
+  E = mc^2 \text{gde je}
+\end{equation}
+\[ x \over y \] \\[2mm]
+Adresa: \href{https://primer.rs}{sajt}, kod \verb|foo bar|.
+\begin{verbatim}
+  neki kod
+\end{verbatim}
+\end{document}
+"#;
+        let expected = r#"\documentclass[12pt,a4paper]{article}
+\usepackage[serbian]{babel}
+\begin{document}
+\section{Увод}\label{sec:uvod}
+Ово је \textbf{важан} текст\footnote{Видети \cite[str.~5]{knuth84}.} са формулом $a+b$
+и референцом на слику~\ref{fig:most}. % komentar
+\begin{equation}
+  E = mc^2 \text{gde je}
+\end{equation}
+\[ x \over y \] \\[2mm]
+Адреса: \href{https://primer.rs}{сајт}, код \verb|foo bar|.
+\begin{verbatim}
+  neki kod
+\end{verbatim}
+\end{document}
+"#;
+        assert_eq!(expected, convert(input, lat_to_cyr));
+    }
+}
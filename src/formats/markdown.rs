@@ -0,0 +1,359 @@
+//! Конверзија Markdown докумената. YAML заглавље (front matter) се пресловљава
+//! као YAML документ, а у телу документа се не мењају блокови кода, код у
+//! линији, адресе веза и слика, аутоматске везе и HTML ознаке.
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use super::{xml, yaml};
+
+// Аутоматске везе (`<https://primer.rs>`, `<ime@primer.rs>`)
+static AUTOLINK: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"^<([a-zA-Z][a-zA-Z0-9+.-]{1,31}:[^<>\s]*|[\w.!#$%&'*+/=?^`{|}~-]+@[a-zA-Z0-9.-]+)>",
+    )
+    .unwrap()
+});
+
+// HTML ознаке са атрибутима без вредности или без наводника (`<input
+// disabled>`, `<td colspan=2>`), које XML парсер не прихвата
+static HTML_TAG: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^</?[a-zA-Z][a-zA-Z0-9-]*(\s[^<>]*)?/?>").unwrap());
+
+// Дефиниција референце на везу (`[1]: dokumenti/vodic.html "Naslov"`) до
+// почетка адресе
+static REFERENCE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^ {0,3}\[[^\]]+\]:[ \t]*\n?[ \t]*").unwrap());
+
+/// Пресловљава YAML заглавље и тело Markdown документа функцијом `convert`.
+pub fn convert(input: &str, convert: impl Fn(&str) -> String) -> String {
+    let (front_matter, body) = split_front_matter(input);
+    let mut output = yaml::convert(front_matter, &convert);
+    output.push_str(&convert_body(body, &convert));
+    output
+}
+
+/// Дели документ на YAML заглавље (између линија `---`) и тело.
+fn split_front_matter(input: &str) -> (&str, &str) {
+    let mut lines = input.split_inclusive('\n');
+    let mut len = match lines.next() {
+        Some(line) if line.trim_end() == "---" => line.len(),
+        _ => return ("", input),
+    };
+    for line in lines {
+        len += line.len();
+        if matches!(line.trim_end(), "---" | "...") {
+            return input.split_at(len);
+        }
+    }
+    ("", input)
+}
+
+pub(crate) fn convert_body(body: &str, convert: &impl Fn(&str) -> String) -> String {
+    let mut output = String::with_capacity(body.len());
+    // Почетак текста који још није пресловљен
+    let mut text_start = 0;
+    // Ограда отвореног блока кода (нпр. ```)
+    let mut fence: Option<&str> = None;
+    // Да ли је отворен блок кода увучен за четири размака или таб
+    let mut indented_code = false;
+    // Да ли је претходна линија празна, односно део листе
+    let mut after_blank = true;
+    let mut in_list = false;
+    let mut pos = 0;
+
+    for line in body.split_inclusive('\n') {
+        let line_end = pos + line.len();
+        let blank = line.trim().is_empty();
+        let indented = line.starts_with("    ") || line.starts_with('\t');
+        if fence.is_none() && !blank {
+            if indented && !in_list && (indented_code || after_blank) {
+                // Увучен блок кода почиње иза празне линије, а не у листи
+                if !indented_code {
+                    output.push_str(&convert_inline(&body[text_start..pos], convert));
+                    text_start = pos;
+                    indented_code = true;
+                }
+            } else if !indented {
+                if indented_code {
+                    output.push_str(&body[text_start..pos]);
+                    text_start = pos;
+                    indented_code = false;
+                }
+                // Листа траје до првог неувученог пасуса иза празне линије
+                in_list = is_list_item(line) || in_list && !after_blank;
+            }
+        }
+        after_blank = blank;
+        if indented_code {
+            pos = line_end;
+            continue;
+        }
+        match (fence, fence_marker(line)) {
+            (None, Some((marker, _))) => {
+                output.push_str(&convert_inline(&body[text_start..pos], convert));
+                text_start = pos;
+                fence = Some(marker);
+            }
+            // Затварајућа ограда је од истог знака и бар исте дужине
+            (Some(open), Some((marker, rest)))
+                if marker.starts_with(open) && rest.trim().is_empty() =>
+            {
+                output.push_str(&body[text_start..line_end]);
+                text_start = line_end;
+                fence = None;
+            }
+            _ => {}
+        }
+        pos = line_end;
+    }
+    if fence.is_some() || indented_code {
+        // Незатворен блок кода траје до краја документа
+        output.push_str(&body[text_start..]);
+    } else {
+        output.push_str(&convert_inline(&body[text_start..], convert));
+    }
+    output
+}
+
+/// Да ли линија почиње ставку листе (`- `, `* `, `+ `, `1. ` или `1) `).
+fn is_list_item(line: &str) -> bool {
+    let trimmed = line.trim_start_matches(' ');
+    if line.len() - trimmed.len() > 3 {
+        return false;
+    }
+    let marker = trimmed.trim_start_matches(|c: char| c.is_ascii_digit());
+    let marker = match trimmed.len() - marker.len() {
+        0 => marker.strip_prefix(['-', '*', '+']),
+        1..=9 => marker.strip_prefix(['.', ')']),
+        _ => None,
+    };
+    marker.is_some_and(|rest| rest.starts_with([' ', '\t']) || rest.trim().is_empty())
+}
+
+/// Ограда блока кода (```` ``` ```` или `~~~`) на почетку линије и остатак
+/// линије иза ње.
+fn fence_marker(line: &str) -> Option<(&str, &str)> {
+    let trimmed = line.trim_start_matches(' ');
+    if line.len() - trimmed.len() > 3 {
+        return None;
+    }
+    let c = trimmed.chars().next().filter(|&c| c == '`' || c == '~')?;
+    let len = trimmed.len() - trimmed.trim_start_matches(c).len();
+    (len >= 3).then(|| trimmed.split_at(len))
+}
+
+/// Пресловљава текст изузев кода у линији (`` `код` ``), адреса веза и
+/// слика (`[uvod](poglavlje/uvod.md)`), адреса у дефиницијама референци,
+/// аутоматских веза и HTML ознака.
+fn convert_inline(text: &str, convert: &impl Fn(&str) -> String) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut converted_until = 0;
+    let mut pos = 0;
+    while pos < text.len() {
+        let rest = &text[pos..];
+        // Део текста који се не пресловљава, као почетак и крај у односу на
+        // `pos`
+        let skipped = match rest.as_bytes()[0] {
+            b'`' => code_span_len(rest).map(|len| (0, len)),
+            b']' if rest.starts_with("](") => Some((2, 2 + destination_len(&rest[2..], true))),
+            b'<' => AUTOLINK
+                .find(rest)
+                .map(|m| m.end())
+                .or_else(|| xml::markup_len(rest))
+                .or_else(|| HTML_TAG.find(rest).map(|m| m.end()))
+                .map(|len| (0, len)),
+            b'[' if text[..pos].trim_end_matches(' ').ends_with('\n')
+                || text[..pos].trim_matches(' ').is_empty() =>
+            {
+                REFERENCE.find(rest).map(|m| {
+                    let start = m.end();
+                    (start, start + destination_len(&rest[start..], false))
+                })
+            }
+            _ => None,
+        };
+        match skipped {
+            Some((start, end)) if end > start => {
+                output.push_str(&convert(&text[converted_until..pos + start]));
+                output.push_str(&text[pos + start..pos + end]);
+                converted_until = pos + end;
+                pos += end;
+            }
+            // Низ знакова `` ` `` без затварајућег низа се не проверава поново
+            _ if rest.starts_with('`') => {
+                pos += rest.len() - rest.trim_start_matches('`').len();
+            }
+            _ => pos += rest.chars().next().map_or(1, char::len_utf8),
+        }
+    }
+    output.push_str(&convert(&text[converted_until..]));
+    output
+}
+
+/// Дужина кода у линији на почетку текста, заједно са граничницима.
+fn code_span_len(text: &str) -> Option<usize> {
+    let ticks = text.len() - text.trim_start_matches('`').len();
+    let delimiter = &text[..ticks];
+    // Затварајући низ мора бити исте дужине
+    text[ticks..]
+        .match_indices(delimiter)
+        .map(|(i, _)| ticks + i)
+        .find(|&i| !text[i + ticks..].starts_with('`') && !text[..i].ends_with('`'))
+        .map(|end| end + ticks)
+}
+
+/// Дужина адресе везе или слике на почетку текста: `<...>` или низ без
+/// размака у коме су заграде уравнотежене. Адреса везе у линији се завршава
+/// затвореном заградом (`inline`).
+fn destination_len(text: &str, inline: bool) -> usize {
+    if text.starts_with('<') {
+        return text.find(['>', '\n']).map_or(0, |i| i + 1);
+    }
+    let mut depth = 0;
+    for (i, c) in text.char_indices() {
+        match c {
+            c if c.is_whitespace() => return i,
+            '(' => depth += 1,
+            ')' if depth == 0 && inline => return i,
+            ')' => depth -= 1,
+            _ => {}
+        }
+    }
+    text.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lat_to_cyr;
+
+    #[test]
+    fn test_convert() {
+        let input = "---
+naslov: Zdravo svete
+tagovi: [rust]
+---
+# Zdravo
+
+Pozovi `cirko -h` ili ``a ` b``.
+
+```rust
+let x = \"ne menjaj\";
+```
+
+~~~~
+```
+i ovo je kod
+~~~~
+Kraj.
+";
+        let expected = "---
+naslov: Здраво свете
+tagovi: [руст]
+---
+# Здраво
+
+Позови `cirko -h` или ``a ` b``.
+
+```rust
+let x = \"ne menjaj\";
+```
+
+~~~~
+```
+i ovo je kod
+~~~~
+Крај.
+";
+        assert_eq!(expected, convert(input, lat_to_cyr));
+    }
+
+    #[test]
+    fn test_indented_code_blocks() {
+        let input = "Primer:
+
+    let x = \"ne menjaj\";
+
+\tcirko -l ulaz.txt
+Kraj.
+
+- Stavka
+    nastavak stavke
+";
+        let expected = "Пример:
+
+    let x = \"ne menjaj\";
+
+\tcirko -l ulaz.txt
+Крај.
+
+- Ставка
+    наставак ставке
+";
+        assert_eq!(expected, convert(input, lat_to_cyr));
+        // Увучена линија у пасусу није блок кода
+        assert_eq!(
+            "Први ред\n    други ред\n",
+            convert("Prvi red\n    drugi red\n", lat_to_cyr)
+        );
+    }
+
+    #[test]
+    fn test_links_and_images() {
+        let convert = |text| convert_body(text, &lat_to_cyr);
+        assert_eq!(
+            "Видети [увод](poglavlje/uvod.md \"Увод\").",
+            convert("Videti [uvod](poglavlje/uvod.md \"Uvod\").")
+        );
+        assert_eq!(
+            "![слика](slike/most(1).png) и [везу](<putanja sa razmakom.md>)",
+            convert("![slika](slike/most(1).png) i [vezu](<putanja sa razmakom.md>)")
+        );
+    }
+
+    #[test]
+    fn test_reference_definitions() {
+        assert_eq!(
+            "Видети [водич][1].\n\n[1]: dokumenti/vodic.html \"Водич\"\n",
+            convert_body(
+                "Videti [vodič][1].\n\n[1]: dokumenti/vodic.html \"Vodič\"\n",
+                &lat_to_cyr
+            )
+        );
+    }
+
+    #[test]
+    fn test_autolinks() {
+        assert_eq!(
+            "Сајт <https://primer.rs/uvod> и <ime@primer.rs>",
+            convert_body(
+                "Sajt <https://primer.rs/uvod> i <ime@primer.rs>",
+                &lat_to_cyr
+            )
+        );
+    }
+
+    #[test]
+    fn test_html() {
+        let convert = |text| convert_body(text, &lat_to_cyr);
+        assert_eq!(
+            "<span class=\"x\">Текст</span> <!-- komentar -->",
+            convert("<span class=\"x\">Tekst</span> <!-- komentar -->")
+        );
+        assert_eq!(
+            "<table><tr><td colspan=2>Ћелија</td></tr></table>",
+            convert("<table><tr><td colspan=2>Ćelija</td></tr></table>")
+        );
+        assert_eq!("<input disabled> а < б", convert("<input disabled> a < b"));
+    }
+
+    #[test]
+    fn test_split_front_matter() {
+        assert_eq!(
+            ("---\na: b\n---\n", "x"),
+            split_front_matter("---\na: b\n---\nx")
+        );
+        assert_eq!(("", "---\na: b"), split_front_matter("---\na: b"));
+        assert_eq!(("", "x\n---\n"), split_front_matter("x\n---\n"));
+    }
+}
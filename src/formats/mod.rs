@@ -0,0 +1,411 @@
+//! Конверзија структурираних формата у којима се пресловљава само текст, док
+//! структура документа остаје непромењена.
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use crate::Error;
+
+pub mod android;
+pub mod apple;
+pub mod arb;
+#[cfg(feature = "archives")]
+mod archive;
+pub mod asciidoc;
+pub mod ass;
+pub mod bibtex;
+pub mod csv;
+pub mod eml;
+#[cfg(feature = "archives")]
+pub mod epub;
+pub mod fb2;
+pub mod fluent;
+pub mod fountain;
+pub mod i18next;
+pub mod ics;
+pub mod ipynb;
+pub mod json;
+pub mod jsonl;
+pub mod latex;
+pub mod markdown;
+pub mod org;
+pub mod pandoc;
+pub mod po;
+#[cfg(feature = "archives")]
+pub mod pptx;
+pub mod properties;
+pub mod resx;
+pub mod rst;
+pub mod rtf;
+pub mod site;
+pub mod sql;
+pub mod srt;
+#[cfg(feature = "archives")]
+pub mod targz;
+pub mod ts;
+pub mod typst;
+pub mod vcard;
+pub mod vtt;
+pub mod xliff;
+#[cfg(feature = "archives")]
+pub mod xlsx;
+pub mod xml;
+pub mod yaml;
+#[cfg(feature = "archives")]
+pub mod zip;
+
+// Ескејп секвенце (`\n`, `\u00e9`), printf/Python ознаке за замену (`%s`,
+// `%1$d`, `%(name)s`) и променљиве Apple речника (`%#@files@`)
+static FORMAT_PLACEHOLDER: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"\\[uU][0-9a-fA-F]{4}|\\.|%#@[^@]*@|%(\d+\$)?[-+#0]*(\*|\d+)?(\.(\*|\d+))?(hh|ll|[hlLqjzt])?[diouxXeEfFgGaAcspn@%]|%\([^)]*\)[a-zA-Z]",
+    )
+    .unwrap()
+});
+
+/// Формат улазног текста.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Format {
+    /// Обичан текст који се у целости пресловљава.
+    #[default]
+    Text,
+    /// XML документ. Погледати [`xml::convert`].
+    Xml,
+    /// JSON документ. Погледати [`json::convert`].
+    Json,
+    /// Ток JSON докумената, један по реду. Погледати [`jsonl::convert`].
+    Jsonl,
+    /// YAML документ. Погледати [`yaml::convert`].
+    Yaml,
+    /// Markdown документ са YAML заглављем. Погледати [`markdown::convert`].
+    Markdown,
+    /// CSV табела. Погледати [`csv::convert`].
+    Csv,
+    /// Табела са пољима одвојеним табом. Погледати [`csv::convert`].
+    Tsv,
+    /// SRT титлови. Погледати [`srt::convert`].
+    Srt,
+    /// WebVTT титлови. Погледати [`vtt::convert`].
+    Vtt,
+    /// ASS/SSA титлови. Погледати [`ass::convert`].
+    Ass,
+    /// EPUB књига. Погледати [`epub::convert`].
+    #[cfg(feature = "archives")]
+    Epub,
+    /// FictionBook (FB2) књига. Погледати [`fb2::convert`].
+    Fb2,
+    /// XLSX табела. Погледати [`xlsx::convert`].
+    #[cfg(feature = "archives")]
+    Xlsx,
+    /// PPTX презентација. Погледати [`pptx::convert`].
+    #[cfg(feature = "archives")]
+    Pptx,
+    /// RTF документ. Погледати [`rtf::convert`].
+    Rtf,
+    /// gettext PO/POT каталог. Погледати [`po::convert`].
+    Po,
+    /// XLIFF датотека са преводима. Погледати [`xliff::convert`].
+    Xliff,
+    /// Qt Linguist датотека са преводима. Погледати [`ts::convert`].
+    Ts,
+    /// Android ресурси са стринговима. Погледати [`android::convert`].
+    Android,
+    /// Apple `.strings` датотека. Погледати [`apple::convert_strings`].
+    Strings,
+    /// Apple `.stringsdict` речник. Погледати [`apple::convert_stringsdict`].
+    Stringsdict,
+    /// Java `.properties` датотека. Погледати [`properties::convert`].
+    Properties,
+    /// .NET RESX ресурси. Погледати [`resx::convert`].
+    Resx,
+    /// Fluent датотека са преводима. Погледати [`fluent::convert`].
+    Fluent,
+    /// i18next JSON пакет превода. Погледати [`i18next::convert`].
+    I18next,
+    /// Flutter ARB датотека. Погледати [`arb::convert`].
+    Arb,
+    /// LaTeX документ. Погледати [`latex::convert`].
+    Latex,
+    /// BibTeX библиографија. Погледати [`bibtex::convert`].
+    Bibtex,
+    /// Emacs Org документ. Погледати [`org::convert`].
+    Org,
+    /// reStructuredText документ. Погледати [`rst::convert`].
+    Rst,
+    /// AsciiDoc документ. Погледати [`asciidoc::convert`].
+    Asciidoc,
+    /// Typst документ. Погледати [`typst::convert`].
+    Typst,
+    /// Pandoc AST у JSON облику. Погледати [`pandoc::convert`].
+    Pandoc,
+    /// Jupyter свеска. Погледати [`ipynb::convert`].
+    Ipynb,
+    /// Порука електронске поште (EML/MIME). Погледати [`eml::convert`].
+    Eml,
+    /// iCalendar календар. Погледати [`ics::convert`].
+    Ics,
+    /// Електронска визит карта (vCard). Погледати [`vcard::convert`].
+    Vcard,
+    /// SQL скрипта или дамп базе. Погледати [`sql::convert`].
+    Sql,
+    /// Сценарио у Fountain формату. Погледати [`fountain::convert`].
+    Fountain,
+    /// Директоријум садржаја статичког сајта. Погледати [`site::convert`].
+    Site,
+    /// ZIP архива са документима. Погледати [`zip::convert`].
+    #[cfg(feature = "archives")]
+    Zip,
+    /// `.tar.gz` архива са документима. Погледати [`targz::convert`].
+    #[cfg(feature = "archives")]
+    TarGz,
+}
+
+impl Format {
+    /// Сви подржани формати.
+    pub const ALL: &'static [Format] = &[
+        Format::Text,
+        Format::Xml,
+        Format::Json,
+        Format::Jsonl,
+        Format::Yaml,
+        Format::Markdown,
+        Format::Csv,
+        Format::Tsv,
+        Format::Srt,
+        Format::Vtt,
+        Format::Ass,
+        #[cfg(feature = "archives")]
+        Format::Epub,
+        Format::Fb2,
+        #[cfg(feature = "archives")]
+        Format::Xlsx,
+        #[cfg(feature = "archives")]
+        Format::Pptx,
+        Format::Rtf,
+        Format::Po,
+        Format::Xliff,
+        Format::Ts,
+        Format::Android,
+        Format::Strings,
+        Format::Stringsdict,
+        Format::Properties,
+        Format::Resx,
+        Format::Fluent,
+        Format::I18next,
+        Format::Arb,
+        Format::Latex,
+        Format::Bibtex,
+        Format::Org,
+        Format::Rst,
+        Format::Asciidoc,
+        Format::Typst,
+        Format::Pandoc,
+        Format::Ipynb,
+        Format::Eml,
+        Format::Ics,
+        Format::Vcard,
+        Format::Sql,
+        Format::Fountain,
+        Format::Site,
+        #[cfg(feature = "archives")]
+        Format::Zip,
+        #[cfg(feature = "archives")]
+        Format::TarGz,
+    ];
+
+    /// Назив формата (нпр. `xml`).
+    pub fn name(self) -> &'static str {
+        match self {
+            Format::Text => "текст",
+            Format::Xml => "xml",
+            Format::Json => "json",
+            Format::Jsonl => "jsonl",
+            Format::Yaml => "yaml",
+            Format::Markdown => "markdown",
+            Format::Csv => "csv",
+            Format::Tsv => "tsv",
+            Format::Srt => "srt",
+            Format::Vtt => "vtt",
+            Format::Ass => "ass",
+            #[cfg(feature = "archives")]
+            Format::Epub => "epub",
+            Format::Fb2 => "fb2",
+            #[cfg(feature = "archives")]
+            Format::Xlsx => "xlsx",
+            #[cfg(feature = "archives")]
+            Format::Pptx => "pptx",
+            Format::Rtf => "rtf",
+            Format::Po => "po",
+            Format::Xliff => "xliff",
+            Format::Ts => "ts",
+            Format::Android => "android",
+            Format::Strings => "strings",
+            Format::Stringsdict => "stringsdict",
+            Format::Properties => "properties",
+            Format::Resx => "resx",
+            Format::Fluent => "fluent",
+            Format::I18next => "i18next",
+            Format::Arb => "arb",
+            Format::Latex => "latex",
+            Format::Bibtex => "bibtex",
+            Format::Org => "org",
+            Format::Rst => "rst",
+            Format::Asciidoc => "asciidoc",
+            Format::Typst => "typst",
+            Format::Pandoc => "pandoc",
+            Format::Ipynb => "ipynb",
+            Format::Eml => "eml",
+            Format::Ics => "ics",
+            Format::Vcard => "vcard",
+            Format::Sql => "sql",
+            Format::Fountain => "fountain",
+            Format::Site => "site",
+            #[cfg(feature = "archives")]
+            Format::Zip => "zip",
+            #[cfg(feature = "archives")]
+            Format::TarGz => "tar.gz",
+        }
+    }
+
+    /// Формат фајла на основу екстензије (нпр. `пост.md`). Екстензија `.ts` се
+    /// не препознаје јер се чешће користи за TypeScript него за Qt Linguist.
+    pub fn from_path(path: &Path) -> Option<Format> {
+        let extension = path.extension()?.to_str()?.to_lowercase();
+        #[cfg(feature = "archives")]
+        let is_tar = |path: &Path| {
+            path.file_stem()
+                .and_then(|stem| Path::new(stem).extension())
+                .is_some_and(|extension| extension.eq_ignore_ascii_case("tar"))
+        };
+        let format = match extension.as_str() {
+            "txt" | "text" => Format::Text,
+            "xml" | "xhtml" | "svg" => Format::Xml,
+            "json" => Format::Json,
+            "jsonl" | "ndjson" => Format::Jsonl,
+            "yaml" | "yml" => Format::Yaml,
+            "md" | "markdown" => Format::Markdown,
+            "csv" => Format::Csv,
+            "tsv" => Format::Tsv,
+            "srt" => Format::Srt,
+            "vtt" => Format::Vtt,
+            "ass" | "ssa" => Format::Ass,
+            #[cfg(feature = "archives")]
+            "epub" => Format::Epub,
+            "fb2" => Format::Fb2,
+            #[cfg(feature = "archives")]
+            "xlsx" => Format::Xlsx,
+            #[cfg(feature = "archives")]
+            "pptx" => Format::Pptx,
+            "rtf" => Format::Rtf,
+            "po" | "pot" => Format::Po,
+            "xlf" | "xliff" => Format::Xliff,
+            "strings" => Format::Strings,
+            "stringsdict" => Format::Stringsdict,
+            "properties" => Format::Properties,
+            "resx" => Format::Resx,
+            "ftl" => Format::Fluent,
+            "arb" => Format::Arb,
+            "tex" => Format::Latex,
+            "bib" => Format::Bibtex,
+            "org" => Format::Org,
+            "rst" => Format::Rst,
+            "adoc" | "asciidoc" => Format::Asciidoc,
+            "typ" => Format::Typst,
+            "ipynb" => Format::Ipynb,
+            "eml" => Format::Eml,
+            "ics" => Format::Ics,
+            "vcf" | "vcard" => Format::Vcard,
+            "sql" => Format::Sql,
+            "fountain" => Format::Fountain,
+            #[cfg(feature = "archives")]
+            "zip" => Format::Zip,
+            #[cfg(feature = "archives")]
+            "tgz" => Format::TarGz,
+            #[cfg(feature = "archives")]
+            "gz" if is_tar(path) => Format::TarGz,
+            _ => return None,
+        };
+        Some(format)
+    }
+
+    /// Да ли је формат бинарни (нпр. ZIP архива), па се улаз не чита као текст.
+    pub fn is_binary(self) -> bool {
+        match self {
+            #[cfg(feature = "archives")]
+            Format::Epub | Format::Xlsx | Format::Pptx | Format::Zip | Format::TarGz => true,
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Format {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Format::ALL
+            .iter()
+            .find(|format| format.name() == s)
+            .copied()
+            .ok_or_else(|| Error::UnknownFormat(s.to_string()))
+    }
+}
+
+/// Грешка у синтакси документа на датој позицији (у бајтовима).
+pub(crate) fn syntax_error(input: &str, offset: usize, message: &str) -> Error {
+    Error::Syntax {
+        line: input[..offset].matches('\n').count() + 1,
+        message: message.to_string(),
+    }
+}
+
+/// Пресловљава стринг из датотеке превода изузев ескејп секвенци и ознака за
+/// замену.
+pub(crate) fn convert_format_string(text: &str, convert: &impl Fn(&str) -> String) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut pos = 0;
+    for placeholder in FORMAT_PLACEHOLDER.find_iter(text) {
+        output.push_str(&convert(&text[pos..placeholder.start()]));
+        output.push_str(placeholder.as_str());
+        pos = placeholder.end();
+    }
+    output.push_str(&convert(&text[pos..]));
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_path() {
+        assert_eq!(
+            Some(Format::Markdown),
+            Format::from_path(Path::new("пост.md"))
+        );
+        assert_eq!(
+            Some(Format::Po),
+            Format::from_path(Path::new("locale/sr.POT"))
+        );
+        assert_eq!(
+            Some(Format::Yaml),
+            Format::from_path(Path::new("_config.yml"))
+        );
+        #[cfg(feature = "archives")]
+        assert_eq!(
+            Some(Format::TarGz),
+            Format::from_path(Path::new("knjige.tar.gz"))
+        );
+        assert_eq!(None, Format::from_path(Path::new("dnevnik.txt.gz")));
+        assert_eq!(None, Format::from_path(Path::new("app.ts")));
+        assert_eq!(None, Format::from_path(Path::new("README")));
+    }
+}
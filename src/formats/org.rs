@@ -0,0 +1,140 @@
+//! Конверзија Emacs Org докумената. Пресловљавају се наслови и текст, док
+//! блокови кода и примера (`#+BEGIN_SRC`), фиоке (`:PROPERTIES:`), подешавања
+//! (`#+OPTIONS:`), коментари, одредишта веза, код у линији (`~код~`,
+//! `=дословно=`), временске ознаке, кључне речи задатака и ознаке наслова
+//! остају непромењени.
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+// Блокови чији се садржај не пресловљава
+const RAW_BLOCKS: &[&str] = &["src", "example", "export", "comment"];
+
+// Подешавања чија се вредност пресловљава
+const TEXT_KEYWORDS: &[&str] = &["title", "subtitle", "author", "description", "caption"];
+
+// Наслов: звездице, кључна реч задатка, приоритет, текст и ознаке
+static HEADING: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(\*+[ \t]+(?:(?:TODO|DONE)[ \t]+)?(?:\[#[A-Z0-9]\][ \t]+)?)(.*?)([ \t]+:[\w@#%:]+:)?([ \t]*\r?\n?)$")
+        .unwrap()
+});
+
+// Одредишта веза, код у линији и временске ознаке
+static INLINE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r#"\[\[[^\]]*\]\[?|(?:^|[\s(\[{'"])(~[^\s~](?:[^\n~]*?[^\s~])?~|=[^\s=](?:[^\n=]*?[^\s=])?=)|src_\w+(?:\[[^\]]*\])?\{[^}]*\}|[<\[]\d{4}-\d{2}-\d{2}[^>\]\n]*[>\]]"#,
+    )
+    .unwrap()
+});
+
+/// Пресловљава наслове и текст Org документа функцијом `convert`.
+pub fn convert(input: &str, convert: impl Fn(&str) -> String) -> String {
+    let mut output = String::with_capacity(input.len());
+    // Ознака краја отвореног блока (`#+end_src`)
+    let mut block_end: Option<String> = None;
+    let mut in_drawer = false;
+    for line in input.split_inclusive('\n') {
+        let trimmed = line.trim();
+        let lower = trimmed.to_lowercase();
+        if let Some(end) = &block_end {
+            if lower.starts_with(end.as_str()) {
+                block_end = None;
+            }
+            output.push_str(line);
+        } else if in_drawer {
+            in_drawer = !lower.eq(":end:");
+            output.push_str(line);
+        } else if let Some(name) = lower.strip_prefix("#+begin_") {
+            let name = name.split_whitespace().next().unwrap_or_default();
+            if RAW_BLOCKS.contains(&name) {
+                block_end = Some(format!("#+end_{name}"));
+            }
+            output.push_str(line);
+        } else if trimmed.len() > 2
+            && trimmed.starts_with(':')
+            && trimmed.ends_with(':')
+            && !trimmed.contains(char::is_whitespace)
+        {
+            // Фиока (`:PROPERTIES:` ... `:END:`)
+            in_drawer = true;
+            output.push_str(line);
+        } else if lower.starts_with("#+") {
+            match line.split_once(':') {
+                Some((keyword, value))
+                    if TEXT_KEYWORDS.contains(&keyword.trim()[2..].to_lowercase().as_str()) =>
+                {
+                    output.push_str(keyword);
+                    output.push(':');
+                    output.push_str(&convert_inline(value, &convert));
+                }
+                _ => output.push_str(line),
+            }
+        } else if trimmed == "#"
+            || trimmed.starts_with("# ")
+            || trimmed == ":"
+            || trimmed.starts_with(": ")
+        {
+            // Коментари и линије фиксне ширине
+            output.push_str(line);
+        } else if let Some(heading) = HEADING.captures(line) {
+            output.push_str(&heading[1]);
+            output.push_str(&convert_inline(&heading[2], &convert));
+            output.push_str(heading.get(3).map_or("", |tags| tags.as_str()));
+            output.push_str(&heading[4]);
+        } else {
+            output.push_str(&convert_inline(line, &convert));
+        }
+    }
+    output
+}
+
+/// Пресловљава текст линије изузев одредишта веза, кода и временских ознака.
+fn convert_inline(text: &str, convert: &impl Fn(&str) -> String) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut pos = 0;
+    for captures in INLINE.captures_iter(text) {
+        // Код у линији се подудара заједно са знаком испред
+        let protected = captures.get(1).unwrap_or_else(|| captures.get(0).unwrap());
+        output.push_str(&convert(&text[pos..protected.start()]));
+        output.push_str(protected.as_str());
+        pos = protected.end();
+    }
+    output.push_str(&convert(&text[pos..]));
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lat_to_cyr;
+
+    #[test]
+    fn test_convert() {
+        let input = "#+TITLE: Beleške
+#+OPTIONS: toc:nil
+* TODO [#A] Prvi zadatak :posao:hitno:
+  :PROPERTIES:
+  :CUSTOM_ID: prvi
+  :END:
+Pogledati [[https://primer.rs][sajt]] i [[file:beleske.org]], pozvati ~make test~ pre <2024-01-15 Mon>.
+# komentar
+#+BEGIN_SRC python
+print(\"zdravo\")
+#+END_SRC
+Kraj.
+";
+        let expected = "#+TITLE: Белешке
+#+OPTIONS: toc:nil
+* TODO [#A] Први задатак :posao:hitno:
+  :PROPERTIES:
+  :CUSTOM_ID: prvi
+  :END:
+Погледати [[https://primer.rs][сајт]] и [[file:beleske.org]], позвати ~make test~ пре <2024-01-15 Mon>.
+# komentar
+#+BEGIN_SRC python
+print(\"zdravo\")
+#+END_SRC
+Крај.
+";
+        assert_eq!(expected, convert(input, lat_to_cyr));
+    }
+}
@@ -0,0 +1,69 @@
+//! Конверзија Pandoc AST-а у JSON облику (`pandoc -t json`), тако да се Ћирко
+//! може користити као Pandoc филтер. Пресловљава се само садржај текстуалних
+//! чворова (`Str`, `MetaString`), док код (`Code`, `CodeBlock`), математика,
+//! сирови блокови, адресе веза, атрибути и структура документа остају
+//! непромењени. Ознака језика из метаподатака (`lang`) одређује смер
+//! конверзије кад се Ћирко користи као филтер.
+use super::json::{convert_escaped, transform};
+use crate::Error;
+
+// Чворови чији се садржај пресловљава
+const TEXT_NODES: &[&str] = &["Str", "MetaString"];
+
+/// Пресловљава текстуалне чворове Pandoc документа функцијом `convert`.
+/// Ознака језика у метаподацима (`lang`) остаје непромењена.
+pub fn convert(input: &str, convert: impl Fn(&str) -> String) -> Result<String, Error> {
+    // Тип последњег чвора (`"t"`) чији садржај (`"c"`) још није обрађен
+    let mut node: Option<String> = None;
+    transform(input, |string| match string.path.last() {
+        _ if string.is_key || string.path.starts_with(&["meta", "lang"]) => None,
+        Some(&"t") => {
+            node = Some(string.raw.to_string());
+            None
+        }
+        Some(&"c") => node
+            .take()
+            .filter(|node| TEXT_NODES.contains(&node.as_str()))
+            .map(|_| convert_escaped(string.raw, &convert)),
+        _ => None,
+    })
+}
+
+/// Ознака језика документа из метаподатка `lang` (нпр. `sr-Latn`).
+pub fn language(input: &str) -> Result<Option<String>, Error> {
+    let mut language: Option<String> = None;
+    transform(input, |string| {
+        if !string.is_key
+            && string.path.starts_with(&["meta", "lang"])
+            && string.path.last() == Some(&"c")
+        {
+            language.get_or_insert_default().push_str(string.raw);
+        }
+        None
+    })?;
+    Ok(language)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lat_to_cyr;
+
+    #[test]
+    fn test_convert() {
+        let input = r#"{"pandoc-api-version":[1,23,1],"meta":{"title":{"t":"MetaInlines","c":[{"t":"Str","c":"Naslov"}]}},"blocks":[{"t":"Para","c":[{"t":"Str","c":"Pogledati"},{"t":"Space"},{"t":"Link","c":[["",[],[]],[{"t":"Emph","c":[{"t":"Str","c":"sajt"}]}],["https://primer.rs",""]]},{"t":"Space"},{"t":"Code","c":[["",[],[]],"cargo build"]},{"t":"Math","c":[{"t":"InlineMath"},"x+y"]}]},{"t":"CodeBlock","c":[["",["rust"],[]],"fn main() {}"]}]}"#;
+        let expected = r#"{"pandoc-api-version":[1,23,1],"meta":{"title":{"t":"MetaInlines","c":[{"t":"Str","c":"Наслов"}]}},"blocks":[{"t":"Para","c":[{"t":"Str","c":"Погледати"},{"t":"Space"},{"t":"Link","c":[["",[],[]],[{"t":"Emph","c":[{"t":"Str","c":"сајт"}]}],["https://primer.rs",""]]},{"t":"Space"},{"t":"Code","c":[["",[],[]],"cargo build"]},{"t":"Math","c":[{"t":"InlineMath"},"x+y"]}]},{"t":"CodeBlock","c":[["",["rust"],[]],"fn main() {}"]}]}"#;
+        assert_eq!(expected, convert(input, lat_to_cyr).unwrap());
+    }
+
+    #[test]
+    fn test_language() {
+        let input = r#"{"pandoc-api-version":[1,23,1],"meta":{"lang":{"t":"MetaInlines","c":[{"t":"Str","c":"sr-Latn"}]}},"blocks":[]}"#;
+        assert_eq!(Some("sr-Latn".into()), language(input).unwrap());
+        assert_eq!(input, convert(input, lat_to_cyr).unwrap());
+        let input = r#"{"pandoc-api-version":[1,23,1],"meta":{"lang":{"t":"MetaString","c":"sr-Cyrl"}},"blocks":[]}"#;
+        assert_eq!(Some("sr-Cyrl".into()), language(input).unwrap());
+        let input = r#"{"pandoc-api-version":[1,23,1],"meta":{"title":{"t":"MetaString","c":"sr"}},"blocks":[]}"#;
+        assert_eq!(None, language(input).unwrap());
+    }
+}
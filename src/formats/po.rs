@@ -0,0 +1,143 @@
+//! Конверзија gettext PO/POT каталога. Пресловљавају се само преводи (`msgstr`
+//! и облици множине `msgstr[N]`), док изворни текст (`msgid`), контекст,
+//! коментари и заставице остају непромењени. Ознака језика у заглављу каталога
+//! (`Language:`) се мења тако да одговара новом писму (нпр. `sr@latin`).
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use super::convert_format_string;
+
+// Ознака језика у заглављу каталога
+static LANGUAGE: Lazy<Regex> = Lazy::new(|| Regex::new(r#"^Language:[^\\"]*"#).unwrap());
+
+/// Поље записа каталога.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Context,
+    Id,
+    Translation,
+    Other,
+}
+
+/// Пресловљава преводе у каталогу функцијом `convert`. Ако је задата ознака
+/// локала `language` (нпр. `sr@latin`) њоме се замењује ознака језика у
+/// заглављу каталога.
+pub fn convert(input: &str, language: Option<&str>, convert: impl Fn(&str) -> String) -> String {
+    let mut output = String::with_capacity(input.len());
+    let mut field = Field::Other;
+    // Да ли је текући запис заглавље каталога (празан `msgid` без контекста)
+    let mut header = false;
+    for line in input.split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        let string = line
+            .find('"')
+            .zip(line.rfind('"'))
+            .filter(|(start, end)| start < end)
+            .map(|(start, end)| (start + 1, end));
+        if trimmed.starts_with('"') {
+            // Наставак стринга претходног поља
+        } else if trimmed.starts_with("msgctxt") {
+            field = Field::Context;
+        } else if trimmed.starts_with("msgid_plural") {
+            field = Field::Other;
+        } else if trimmed.starts_with("msgid") {
+            header = field != Field::Context;
+            field = Field::Id;
+        } else if trimmed.starts_with("msgstr") {
+            field = Field::Translation;
+        } else {
+            field = Field::Other;
+        }
+
+        let Some((start, end)) = string else {
+            output.push_str(line);
+            continue;
+        };
+        let content = &line[start..end];
+        match field {
+            Field::Id if !content.is_empty() => header = false,
+            Field::Translation if header => {
+                if let Some(language) = language {
+                    output.push_str(&line[..start]);
+                    output.push_str(&LANGUAGE.replace(content, format!("Language: {language}")));
+                    output.push_str(&line[end..]);
+                    continue;
+                }
+            }
+            Field::Translation => {
+                output.push_str(&line[..start]);
+                output.push_str(&convert_format_string(content, &convert));
+                output.push_str(&line[end..]);
+                continue;
+            }
+            _ => {}
+        }
+        output.push_str(line);
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cyr_to_lat;
+
+    #[test]
+    fn test_convert() {
+        let input = r#"# Превод програма
+msgid ""
+msgstr ""
+"Project-Id-Version: program 1.0\n"
+"Language: sr\n"
+"Plural-Forms: nplurals=3; plural=(n%10==1 && n%100!=11 ? 0 : n%10>=2 && n%10<=4 && (n%100<10 || n%100>=20) ? 1 : 2);\n"
+
+#. Наслов прозора
+#: src/main.c:10
+#, c-format
+msgid "Hello, %s!"
+msgstr "Здраво, %s!\n"
+
+msgctxt "menu"
+msgid ""
+"Open "
+"file"
+msgstr ""
+"Отвори "
+"датотеку"
+
+msgid "%d file"
+msgid_plural "%d files"
+msgstr[0] "%d датотека"
+msgstr[1] "%1$d датотеке"
+msgstr[2] "\"%(count)s\" датотека"
+"#;
+        let expected = r#"# Превод програма
+msgid ""
+msgstr ""
+"Project-Id-Version: program 1.0\n"
+"Language: sr@latin\n"
+"Plural-Forms: nplurals=3; plural=(n%10==1 && n%100!=11 ? 0 : n%10>=2 && n%10<=4 && (n%100<10 || n%100>=20) ? 1 : 2);\n"
+
+#. Наслов прозора
+#: src/main.c:10
+#, c-format
+msgid "Hello, %s!"
+msgstr "Zdravo, %s!\n"
+
+msgctxt "menu"
+msgid ""
+"Open "
+"file"
+msgstr ""
+"Otvori "
+"datoteku"
+
+msgid "%d file"
+msgid_plural "%d files"
+msgstr[0] "%d datoteka"
+msgstr[1] "%1$d datoteke"
+msgstr[2] "\"%(count)s\" datoteka"
+"#;
+        assert_eq!(expected, convert(input, Some("sr@latin"), cyr_to_lat));
+    }
+}
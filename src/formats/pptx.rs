@@ -0,0 +1,97 @@
+//! Конверзија PPTX презентација. Пресловљава се текст слајдова, белешки,
+//! мастера и распореда слајдова, а ознаке језика (`lang`) се мењају тако да
+//! одговарају новом писму.
+use super::archive;
+use super::xml::{Content, convert_document};
+use crate::Error;
+
+// Делови презентације у којима се пресловљава текст
+const TEXT_PARTS: &[&str] = &[
+    "ppt/slides/",
+    "ppt/notesSlides/",
+    "ppt/slideMasters/",
+    "ppt/slideLayouts/",
+    "ppt/notesMasters/",
+];
+
+/// Пресловљава PPTX презентацију функцијом `convert`. Ако је задата ознака
+/// језика `language` (нпр. `sr-Latn`) њоме се замењују ознаке истог језика у
+/// атрибутима `lang`.
+pub fn convert(
+    input: &[u8],
+    language: Option<&str>,
+    convert: impl Fn(&str) -> String,
+) -> Result<Vec<u8>, Error> {
+    archive::transform(input, |name, content| {
+        let is_text_part = TEXT_PARTS.iter().any(|part| {
+            name.strip_prefix(part)
+                .is_some_and(|file| !file.contains('/') && file.ends_with(".xml"))
+        });
+        if !is_text_part {
+            return Ok(None);
+        }
+        // Текст је само у елементима `a:t` (text run)
+        let converted = convert_document(archive::text(content)?, language, &convert, |stack| {
+            if stack.last() == Some(&"a:t") {
+                Content::Text
+            } else {
+                Content::Unchanged
+            }
+        })?;
+        Ok(Some(converted.into_bytes()))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, Read, Write};
+
+    use zip::write::SimpleFileOptions;
+    use zip::{ZipArchive, ZipWriter};
+
+    use super::*;
+    use crate::lat_to_cyr;
+
+    #[test]
+    fn test_convert() {
+        let slide = r#"<p:sld><p:cSld><p:spTree><p:sp><p:nvSpPr><p:cNvPr id="2" name="Naslov 1"/></p:nvSpPr>
+<p:txBody><a:p><a:r><a:rPr lang="sr-Latn-RS"/><a:t>Dobar dan</a:t></a:r></a:p></p:txBody></p:sp></p:spTree></p:cSld></p:sld>"#;
+        let files = [
+            ("ppt/slides/slide1.xml", slide),
+            ("ppt/slides/_rels/slide1.xml.rels", "<Relationships/>"),
+            ("ppt/notesSlides/notesSlide1.xml", "<a:t>Beleška</a:t>"),
+            ("docProps/app.xml", "<a:t>Ne menjaj</a:t>"),
+        ];
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, content) in files {
+            writer
+                .start_file(name, SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(content.as_bytes()).unwrap();
+        }
+        let input = writer.finish().unwrap().into_inner();
+
+        let output = convert(&input, Some("sr-Cyrl"), lat_to_cyr).unwrap();
+        let mut archive = ZipArchive::new(Cursor::new(output)).unwrap();
+        let mut read = |name| {
+            let mut content = String::new();
+            archive
+                .by_name(name)
+                .unwrap()
+                .read_to_string(&mut content)
+                .unwrap();
+            content
+        };
+        assert_eq!(
+            slide
+                .replace("Dobar dan", "Добар дан")
+                .replace("sr-Latn-RS", "sr-Cyrl-RS"),
+            read("ppt/slides/slide1.xml")
+        );
+        assert_eq!(
+            "<a:t>Белешка</a:t>",
+            read("ppt/notesSlides/notesSlide1.xml")
+        );
+        assert_eq!("<a:t>Ne menjaj</a:t>", read("docProps/app.xml"));
+    }
+}
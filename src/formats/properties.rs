@@ -0,0 +1,152 @@
+//! Конверзија Java `.properties` датотека. Пресловљавају се само вредности, док
+//! кључеви, коментари, наставци линија и ознаке за замену (`{0}`) остају
+//! непромењени. Знакови записани ескејп секвенцама (`\u0436`) се декодирају пре
+//! пресловљавања, а ако их вредност садржи, пресловљени знакови који нису ASCII
+//! се такође записују ескејп секвенцама.
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+// Наставци линија, ескејп секвенце и ознаке за замену MessageFormat-а
+static ESCAPE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\\\r?\n[ \t\x0C]*|\\u[0-9a-fA-F]{4}|\\(?s:.)|\{[^{}]*\}").unwrap());
+
+/// Пресловљава вредности функцијом `convert`.
+pub fn convert(input: &str, convert: impl Fn(&str) -> String) -> String {
+    let mut output = String::with_capacity(input.len());
+    let mut lines = input.split_inclusive('\n');
+    let mut pos = 0;
+    while let Some(line) = lines.next() {
+        let start = pos;
+        pos += line.len();
+        let trimmed = line.trim_start_matches([' ', '\t', '\x0C']);
+        if trimmed.trim_end().is_empty() || trimmed.starts_with(['#', '!']) {
+            output.push_str(line);
+            continue;
+        }
+
+        // Логичка линија се наставља ако се физичка завршава непарним бројем
+        // обрнутих косих црта
+        let mut physical = line;
+        while is_continued(physical)
+            && let Some(next) = lines.next()
+        {
+            pos += next.len();
+            physical = next;
+        }
+        let logical = &input[start..pos];
+        let value_start = value_start(logical);
+        let value_end = logical
+            .trim_end_matches(['\n', '\r'])
+            .len()
+            .max(value_start);
+        output.push_str(&logical[..value_start]);
+        output.push_str(&convert_value(&logical[value_start..value_end], &convert));
+        output.push_str(&logical[value_end..]);
+    }
+    output
+}
+
+/// Да ли се линија наставља у следећој.
+fn is_continued(line: &str) -> bool {
+    let content = line.trim_end_matches(['\n', '\r']);
+    (content.len() - content.trim_end_matches('\\').len()) % 2 == 1
+}
+
+/// Почетак вредности у логичкој линији (иза кључа и раздвајача).
+fn value_start(line: &str) -> usize {
+    let key_start = line.len() - line.trim_start_matches([' ', '\t', '\x0C']).len();
+    let mut chars = line[key_start..].char_indices();
+    let mut key_end = line.len();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' => {
+                chars.next();
+            }
+            '=' | ':' | ' ' | '\t' | '\x0C' | '\r' | '\n' => {
+                key_end = key_start + i;
+                break;
+            }
+            _ => {}
+        }
+    }
+    let rest = &line[key_end..];
+    let mut separator = rest.trim_start_matches([' ', '\t', '\x0C']);
+    if let Some(after) = separator.strip_prefix(['=', ':']) {
+        separator = after.trim_start_matches([' ', '\t', '\x0C']);
+    }
+    line.len() - separator.len()
+}
+
+/// Пресловљава вредност изузев наставака линија, ескејп секвенци и ознака за
+/// замену.
+fn convert_value(value: &str, convert: &impl Fn(&str) -> String) -> String {
+    let mut output = String::with_capacity(value.len());
+    let mut escaped = false;
+    let mut text = String::new();
+    let flush = |text: &mut String, output: &mut String, escaped: bool| {
+        for c in convert(text).chars() {
+            if escaped && !c.is_ascii() {
+                for unit in c.encode_utf16(&mut [0; 2]) {
+                    output.push_str(&format!("\\u{unit:04x}"));
+                }
+            } else {
+                output.push(c);
+            }
+        }
+        text.clear();
+    };
+    let mut pos = 0;
+    for escape in ESCAPE.find_iter(value) {
+        text.push_str(&value[pos..escape.start()]);
+        pos = escape.end();
+        let decoded = escape
+            .as_str()
+            .strip_prefix("\\u")
+            .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+            .and_then(char::from_u32);
+        if let Some(c) = decoded {
+            text.push(c);
+            escaped = true;
+        } else {
+            flush(&mut text, &mut output, escaped);
+            output.push_str(escape.as_str());
+        }
+    }
+    text.push_str(&value[pos..]);
+    flush(&mut text, &mut output, escaped);
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{cyr_to_lat, lat_to_cyr};
+
+    #[test]
+    fn test_convert() {
+        let input = "# Poruke aplikacije\n\
+                     app.title = Kalendar\n\
+                     greeting: Zdravo, {0}!\n\
+                     multi.line = Prva linija \\\n    druga linija\n\
+                     key\\ with\\=escapes Vrednost\\tsa tabom\n\
+                     empty=\n";
+        let expected = "# Poruke aplikacije\n\
+                        app.title = Календар\n\
+                        greeting: Здраво, {0}!\n\
+                        multi.line = Прва линија \\\n    друга линија\n\
+                        key\\ with\\=escapes Вредност\\tса табом\n\
+                        empty=\n";
+        assert_eq!(expected, convert(input, lat_to_cyr));
+    }
+
+    #[test]
+    fn test_convert_escapes() {
+        let input = "title=\\u0417\\u0434\\u0440\\u0430\\u0432\\u043e\n";
+        assert_eq!("title=Zdravo\n", convert(input, cyr_to_lat));
+        let input = "title=\\u0160uma\n";
+        assert_eq!(
+            "title=\\u0428\\u0443\\u043c\\u0430\n",
+            convert(input, lat_to_cyr)
+        );
+    }
+}
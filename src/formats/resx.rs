@@ -0,0 +1,99 @@
+//! Конверзија .NET RESX ресурса. Пресловљавају се вредности (`<value>`)
+//! стринг ресурса, док бинарни и остали ресурси (са атрибутом `type` или
+//! `mimetype`), коментари, заглавља и XSD шема остају непромењени. Ознаке за
+//! замену (`{0}`, `{0:N2}`) се не пресловљавају.
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use super::xml::{Token, convert_text, tokenize};
+use crate::Error;
+
+// Ознаке за замену у сложеном форматирању (`{0}`, `{1:N2}`)
+static PLACEHOLDER: Lazy<Regex> = Lazy::new(|| Regex::new(r"\{[^{}]*\}").unwrap());
+
+/// Пресловљава стринг ресурсе функцијом `convert`.
+pub fn convert(input: &str, convert: impl Fn(&str) -> String) -> Result<String, Error> {
+    let convert = |text: &str| {
+        let mut output = String::with_capacity(text.len());
+        let mut pos = 0;
+        for placeholder in PLACEHOLDER.find_iter(text) {
+            output.push_str(&convert(&text[pos..placeholder.start()]));
+            output.push_str(placeholder.as_str());
+            pos = placeholder.end();
+        }
+        output.push_str(&convert(&text[pos..]));
+        output
+    };
+    let mut output = String::with_capacity(input.len());
+    // Да ли је текући `<data>` стринг ресурс
+    let mut is_string = false;
+    let mut stack: Vec<&str> = Vec::new();
+    for token in tokenize(input)? {
+        match token {
+            Token::Text(text) if is_string && stack.ends_with(&["data", "value"]) => {
+                output.push_str(&convert_text(text, &convert))
+            }
+            Token::Text(raw) | Token::CData(raw) | Token::Markup(raw) => output.push_str(raw),
+            Token::StartTag(tag) => {
+                if tag.name == "data" {
+                    // Ресурси дизајнера форми (`>>button1.Name`) садрже називе типова
+                    is_string = tag
+                        .attribute("type")
+                        .is_none_or(|t| t.starts_with("System.String"))
+                        && tag.attribute("mimetype").is_none()
+                        && !tag.attribute("name").is_some_and(|name| {
+                            name.starts_with(">>") || name.starts_with("&gt;&gt;")
+                        });
+                }
+                output.push_str(tag.raw);
+                if !tag.self_closing {
+                    stack.push(tag.name);
+                }
+            }
+            Token::EndTag { name, raw } => {
+                if let Some(open) = stack.iter().rposition(|&open| open == name) {
+                    stack.truncate(open);
+                }
+                output.push_str(raw);
+            }
+        }
+    }
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lat_to_cyr;
+
+    #[test]
+    fn test_convert() {
+        let input = r#"<?xml version="1.0" encoding="utf-8"?>
+<root>
+  <xsd:schema id="root" xmlns=""><xsd:element name="root" msdata:IsDataSet="true"/></xsd:schema>
+  <resheader name="resmimetype"><value>text/microsoft-resx</value></resheader>
+  <data name="Greeting" xml:space="preserve">
+    <value>Zdravo, {0}! Imate {1:N0} poruka.</value>
+    <comment>Pozdravna poruka</comment>
+  </data>
+  <data name="&gt;&gt;button1.Type" xml:space="preserve"><value>System.Windows.Forms.Button</value></data>
+  <data name="Logo" type="System.Drawing.Bitmap, System.Drawing" mimetype="application/x-microsoft.net.object.bytearray.base64">
+    <value>iVBORw0KGgo=</value>
+  </data>
+</root>"#;
+        let expected = r#"<?xml version="1.0" encoding="utf-8"?>
+<root>
+  <xsd:schema id="root" xmlns=""><xsd:element name="root" msdata:IsDataSet="true"/></xsd:schema>
+  <resheader name="resmimetype"><value>text/microsoft-resx</value></resheader>
+  <data name="Greeting" xml:space="preserve">
+    <value>Здраво, {0}! Имате {1:N0} порука.</value>
+    <comment>Pozdravna poruka</comment>
+  </data>
+  <data name="&gt;&gt;button1.Type" xml:space="preserve"><value>System.Windows.Forms.Button</value></data>
+  <data name="Logo" type="System.Drawing.Bitmap, System.Drawing" mimetype="application/x-microsoft.net.object.bytearray.base64">
+    <value>iVBORw0KGgo=</value>
+  </data>
+</root>"#;
+        assert_eq!(expected, convert(input, lat_to_cyr).unwrap());
+    }
+}
@@ -0,0 +1,343 @@
+//! Конверзија reStructuredText докумената. Пресловљава се текст документа,
+//! док директиве, литерални блокови (иза `::`), коментари, одредишта веза,
+//! код у линији (``` ``код`` ```), улоге (`:ref:`), замене (`|име|`) и адресе
+//! остају непромењени. Подвлаке наслова и ширине ћелија табела се прилагођавају
+//! дужини пресловљеног текста.
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+// Директиве чији се садржај не пресловљава
+const RAW_DIRECTIVES: &[&str] = &[
+    "code",
+    "code-block",
+    "sourcecode",
+    "literalinclude",
+    "include",
+    "math",
+    "raw",
+    "highlight",
+    "toctree",
+    "index",
+    "only",
+    "graphviz",
+    "digraph",
+    "mermaid",
+    "doctest",
+    "testcode",
+    "testoutput",
+    "automodule",
+    "autoclass",
+    "autofunction",
+    "automethod",
+    "ipython",
+    "jupyter-execute",
+];
+
+// Директиве чији је аргумент текст (наслов)
+const TEXT_ARGUMENTS: &[&str] = &[
+    "note",
+    "warning",
+    "tip",
+    "hint",
+    "important",
+    "caution",
+    "danger",
+    "error",
+    "attention",
+    "admonition",
+    "seealso",
+    "topic",
+    "sidebar",
+    "rubric",
+    "contents",
+    "table",
+    "csv-table",
+    "list-table",
+];
+
+// Опције директива чија је вредност текст
+const TEXT_OPTIONS: &[&str] = &["alt", "caption", "header"];
+
+static DIRECTIVE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^(\.\.\s+(?:\|[^|]+\|\s+)?([\w:.+-]+)::)(.*)$").unwrap());
+
+static OPTION: Lazy<Regex> = Lazy::new(|| Regex::new(r"^(\s*:([\w-]+):)(.*)$").unwrap());
+
+// Код у линији, улоге, референце, замене, фусноте и адресе
+static INLINE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"``[^`]+``|:[\w:+.-]+:`[^`]*`|`[^`]*`(:[\w:+.-]+:|__?)?|\|[^|\s][^|]*\|_{0,2}|\[[\w#*.-]+\]_|\b[[:alnum:]]+(?:[-.][[:alnum:]]+)*__?\b|https?://[^\s<>]+",
+    )
+    .unwrap()
+});
+
+// Текст везе и одредиште (`Naslov <https://primer.rs>`)
+static LINK: Lazy<Regex> = Lazy::new(|| Regex::new(r"^([^`]*`)(.*?)(\s*<[^<>]*>`.*)$").unwrap());
+
+/// Пресловљава текст документа функцијом `convert`.
+pub fn convert(input: &str, convert: impl Fn(&str) -> String) -> String {
+    let lines: Vec<&str> = input.split_inclusive('\n').collect();
+    let mut output = String::with_capacity(input.len());
+    // Увлачење линије иза које следи блок који се не пресловљава
+    let mut raw_indent: Option<usize> = None;
+    // Увлачење директиве иза које следе опције
+    let mut options_indent: Option<usize> = None;
+    let mut in_table = false;
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i];
+        let content = line.trim_end_matches(['\n', '\r']);
+        let ending = &line[content.len()..];
+        let trimmed = content.trim_start();
+        let indent = content.len() - trimmed.len();
+        i += 1;
+
+        if trimmed.is_empty() {
+            options_indent = None;
+            in_table = false;
+            output.push_str(line);
+            continue;
+        }
+        if let Some(base) = raw_indent {
+            if indent > base {
+                output.push_str(line);
+                continue;
+            }
+            raw_indent = None;
+        }
+        if let Some(base) = options_indent {
+            if indent > base
+                && let Some(option) = OPTION.captures(content)
+            {
+                output.push_str(&option[1]);
+                if TEXT_OPTIONS.contains(&&option[2]) {
+                    output.push_str(&convert_inline(&option[3], &convert));
+                } else {
+                    output.push_str(&option[3]);
+                }
+                output.push_str(ending);
+                continue;
+            }
+            options_indent = None;
+        }
+
+        if trimmed == ".." || trimmed.starts_with(".. ") {
+            if let Some(directive) = DIRECTIVE.captures(trimmed) {
+                output.push_str(&content[..indent]);
+                output.push_str(&directive[1]);
+                if RAW_DIRECTIVES.contains(&&directive[2]) || trimmed.starts_with(".. |") {
+                    output.push_str(&directive[3]);
+                    raw_indent = Some(indent);
+                } else {
+                    if TEXT_ARGUMENTS.contains(&&directive[2]) {
+                        output.push_str(&convert_inline(&directive[3], &convert));
+                    } else {
+                        output.push_str(&directive[3]);
+                    }
+                    options_indent = Some(indent);
+                }
+                output.push_str(ending);
+            } else if let Some(label_end) = trimmed
+                .strip_prefix(".. [")
+                .and_then(|label| label.find(']'))
+            {
+                // Фуснота или цитат
+                let text_start = indent + label_end + 5;
+                output.push_str(&content[..text_start]);
+                output.push_str(&convert_inline(&content[text_start..], &convert));
+                output.push_str(ending);
+            } else {
+                // Коментар или одредиште везе
+                output.push_str(line);
+                raw_indent = Some(indent);
+            }
+            continue;
+        }
+        if trimmed.starts_with("__ ") {
+            output.push_str(line);
+            continue;
+        }
+        if trimmed.starts_with('+') && trimmed.ends_with('+') && trimmed.len() > 1 {
+            in_table = true;
+            output.push_str(line);
+            continue;
+        }
+        if in_table && trimmed.starts_with('|') {
+            output.push_str(&content[..indent]);
+            output.push_str(&convert_table_row(trimmed, &convert));
+            output.push_str(ending);
+            continue;
+        }
+
+        // Наслов са подвлаком (и опционо надвлаком)
+        let overline = is_adornment(content)
+            && lines
+                .get(i + 1)
+                .is_some_and(|line| is_adornment(line.trim_end()));
+        let title = if overline { lines.get(i) } else { Some(&line) };
+        let underline = if overline {
+            lines.get(i + 1)
+        } else {
+            lines.get(i)
+        };
+        if let (Some(title), Some(underline)) = (title, underline)
+            && !is_adornment(title.trim_end())
+            && is_adornment(underline.trim_end())
+            && !(overline && content.trim_end() != underline.trim_end())
+        {
+            let title_content = title.trim_end_matches(['\n', '\r']);
+            let converted = convert_inline(title_content, &convert);
+            let width = converted.trim_end().chars().count();
+            let old_width = title_content.trim_end().chars().count();
+            let adjust = |adornment: &str| {
+                let adornment_content = adornment.trim_end_matches(['\n', '\r']);
+                let len = adornment_content.chars().count();
+                let c = adornment_content.chars().next().unwrap_or('=');
+                let len = if len == old_width || len < width {
+                    width
+                } else {
+                    len
+                };
+                format!(
+                    "{}{}",
+                    c.to_string().repeat(len),
+                    &adornment[adornment_content.len()..]
+                )
+            };
+            if overline {
+                output.push_str(&adjust(line));
+                i += 1;
+            }
+            output.push_str(&converted);
+            output.push_str(&title[title_content.len()..]);
+            output.push_str(&adjust(underline));
+            i += 1;
+            continue;
+        }
+
+        output.push_str(&convert_inline(content, &convert));
+        output.push_str(ending);
+        // Литерални блок следи иза пасуса који се завршава са `::`
+        if trimmed.ends_with("::") {
+            raw_indent = Some(indent);
+        }
+    }
+    output
+}
+
+/// Да ли је линија подвлака или надвлака наслова (`=====`).
+fn is_adornment(line: &str) -> bool {
+    let mut chars = line.chars();
+    match chars.next() {
+        Some(c) if c.is_ascii_punctuation() => {
+            line.chars().count() >= 2 && chars.all(|other| other == c)
+        }
+        _ => false,
+    }
+}
+
+/// Пресловљава ћелије реда табеле задржавајући њихову ширину ако садржај
+/// ћелије има довољно размака на крају.
+fn convert_table_row(row: &str, convert: &impl Fn(&str) -> String) -> String {
+    row.split('|')
+        .map(|cell| {
+            let converted = convert_inline(cell, convert);
+            let width = cell.chars().count();
+            let content = converted.trim_end();
+            let content_width = content.chars().count();
+            if content_width <= width && converted.chars().count() != width {
+                format!("{content}{}", " ".repeat(width - content_width))
+            } else {
+                converted
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("|")
+}
+
+/// Пресловљава текст линије изузев кода, улога, референци и адреса. Текст веза
+/// (`` `Naslov <https://primer.rs>`_ ``) се пресловљава.
+fn convert_inline(text: &str, convert: &impl Fn(&str) -> String) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut pos = 0;
+    for protected in INLINE.find_iter(text) {
+        output.push_str(&convert(&text[pos..protected.start()]));
+        match LINK.captures(protected.as_str()) {
+            Some(link) if !protected.as_str().starts_with("``") => {
+                output.push_str(&link[1]);
+                output.push_str(&convert(&link[2]));
+                output.push_str(&link[3]);
+            }
+            _ => output.push_str(protected.as_str()),
+        }
+        pos = protected.end();
+    }
+    output.push_str(&convert(&text[pos..]));
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{cyr_to_lat, lat_to_cyr};
+
+    #[test]
+    fn test_convert() {
+        let input = "Uvod
+====
+
+Pogledati ``make html``, :ref:`Instalacija <install>` i `sajt <https://primer.rs>`_.
+Zamena |ime| i referenca install_.
+
+.. _install:
+
+.. note:: Važna napomena
+   :class: upozorenje
+
+   Tekst napomene.
+
+.. code-block:: python
+   :caption: Primer
+
+   print(\"zdravo\")
+
+Primer koda::
+
+   ls -la
+
+.. komentar koji se ne menja
+";
+        let expected = "Увод
+====
+
+Погледати ``make html``, :ref:`Инсталација <install>` и `сајт <https://primer.rs>`_.
+Замена |ime| и референца install_.
+
+.. _install:
+
+.. note:: Важна напомена
+   :class: upozorenje
+
+   Текст напомене.
+
+.. code-block:: python
+   :caption: Primer
+
+   print(\"zdravo\")
+
+Пример кода::
+
+   ls -la
+
+.. komentar koji se ne menja
+";
+        assert_eq!(expected, convert(input, lat_to_cyr));
+    }
+
+    #[test]
+    fn test_adjust_widths() {
+        let input = "=====\nЉубав\n=====\n\n+-------+----+\n| Њива  | 1  |\n+-------+----+\n";
+        let expected = "======\nLjubav\n======\n\n+-------+----+\n| Njiva | 1  |\n+-------+----+\n";
+        assert_eq!(expected, convert(input, cyr_to_lat));
+    }
+}
@@ -0,0 +1,295 @@
+//! Конверзија RTF докумената. Пресловљава се само текст документа, док
+//! контролне речи, табеле фонтова и боја, метаподаци, слике и инструкције
+//! поља остају непромењени. Текст записан бајтовима кодне стране (`\'e8`) или
+//! Unicode ознакама (`\u1078?`) се декодира пре пресловљавања.
+use std::collections::HashMap;
+
+use super::syntax_error;
+use crate::encoding::{decode_byte, encode_byte};
+use crate::error::Error;
+
+// Одредишта чији се садржај не пресловљава
+const SKIPPED_DESTINATIONS: &[&str] = &[
+    "fonttbl",
+    "colortbl",
+    "stylesheet",
+    "listtable",
+    "listoverridetable",
+    "info",
+    "pict",
+    "object",
+    "fldinst",
+    "themedata",
+    "colorschememapping",
+    "datastore",
+    "latentstyles",
+    "xmlnstbl",
+    "rsidtbl",
+    "filetbl",
+    "revtbl",
+    "generator",
+];
+
+/// Кодна страна за скуп знакова фонта (`\fcharset`).
+fn charset_code_page(charset: i32) -> Option<u32> {
+    match charset {
+        0 => Some(1252),
+        204 => Some(1251),
+        238 => Some(1250),
+        _ => None,
+    }
+}
+
+/// Део RTF документа.
+enum Token<'a> {
+    GroupStart,
+    GroupEnd,
+    ControlWord {
+        name: &'a str,
+        parameter: Option<i32>,
+    },
+    /// Бајт у кодној страни документа (`\'e8`).
+    Hex(u8),
+    /// Контролни симбол (`\~`, `\*`, `\{`).
+    Symbol(char),
+    Text(char),
+    Newline,
+}
+
+/// Стање групе (`{...}`).
+#[derive(Debug, Clone)]
+struct Group {
+    /// Садржај групе се не пресловљава.
+    skip: bool,
+    font_table: bool,
+    /// Број заменских знакова иза `\u` (`\uc`).
+    unicode_skip: usize,
+    code_page: u32,
+}
+
+/// Пресловљава текст RTF документа функцијом `convert`.
+pub fn convert(input: &str, convert: impl Fn(&str) -> String) -> Result<String, Error> {
+    let mut output = String::with_capacity(input.len());
+    // Текст који још није пресловљен
+    let mut text = String::new();
+    let mut group = Group {
+        skip: false,
+        font_table: false,
+        unicode_skip: 1,
+        code_page: 1252,
+    };
+    let mut stack = Vec::new();
+    let mut default_code_page = 1252;
+    let mut fonts = HashMap::new();
+    let mut font_definition = None;
+    // Да ли је токен први у групи (одредиште групе)
+    let mut group_start = false;
+    // Број заменских знакова иза `\u` који се још прескачу
+    let mut fallback = 0;
+    // Да ли се иза последње контролне речи мора додати размак
+    let mut needs_delimiter = false;
+
+    let mut pos = 0;
+    while pos < input.len() {
+        let (token, len) = next_token(&input[pos..]);
+        let raw = &input[pos..pos + len];
+        if raw == "\\" {
+            return Err(syntax_error(input, pos, "неочекиван крај документа"));
+        }
+        pos += len;
+
+        // Текст документа (и заменски знакови иза `\u` који се изостављају)
+        if !group.skip {
+            let decoded = match token {
+                Token::Text(c) | Token::Symbol(c @ ('\\' | '{' | '}')) => Some(c),
+                Token::Hex(byte) => decode_byte(group.code_page, byte),
+                Token::ControlWord {
+                    name: "u",
+                    parameter: Some(parameter),
+                } => char::from_u32(parameter as u16 as u32),
+                _ => None,
+            };
+            if let Some(c) = decoded {
+                if fallback > 0 {
+                    fallback -= 1;
+                } else {
+                    text.push(c);
+                }
+                if matches!(token, Token::ControlWord { .. }) {
+                    fallback = group.unicode_skip;
+                }
+                group_start = false;
+                continue;
+            }
+        }
+
+        if !text.is_empty() {
+            let converted = encode_text(&convert(&text), &group);
+            if needs_delimiter {
+                output.push(' ');
+            }
+            output.push_str(&converted);
+            text.clear();
+        }
+        output.push_str(raw);
+        needs_delimiter = false;
+        fallback = 0;
+
+        match token {
+            Token::GroupStart => {
+                stack.push(group.clone());
+                group_start = true;
+                continue;
+            }
+            Token::GroupEnd => {
+                if let Some(parent) = stack.pop() {
+                    group = parent;
+                }
+            }
+            Token::Symbol('*') if group_start => group.skip = true,
+            Token::ControlWord { name, parameter } => {
+                needs_delimiter = !raw.ends_with(' ');
+                if group_start && SKIPPED_DESTINATIONS.contains(&name) {
+                    group.skip = true;
+                    group.font_table = name == "fonttbl";
+                }
+                match (name, parameter) {
+                    ("ansicpg", Some(code_page)) => {
+                        default_code_page = code_page as u32;
+                        group.code_page = default_code_page;
+                    }
+                    ("uc", Some(count)) => group.unicode_skip = count.max(0) as usize,
+                    ("f", Some(font)) if group.font_table => font_definition = Some(font),
+                    ("fcharset", Some(charset)) if group.font_table => {
+                        if let (Some(font), Some(code_page)) =
+                            (font_definition, charset_code_page(charset))
+                        {
+                            fonts.insert(font, code_page);
+                        }
+                    }
+                    ("f", Some(font)) => {
+                        group.code_page = fonts.get(&font).copied().unwrap_or(default_code_page);
+                    }
+                    ("bin", Some(length)) => {
+                        // Бинарни подаци се преписују непромењени
+                        let mut end = (pos + length.max(0) as usize).min(input.len());
+                        while !input.is_char_boundary(end) {
+                            end += 1;
+                        }
+                        output.push_str(&input[pos..end]);
+                        pos = end;
+                    }
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+        group_start = false;
+    }
+    if !text.is_empty() {
+        if needs_delimiter {
+            output.push(' ');
+        }
+        output.push_str(&encode_text(&convert(&text), &group));
+    }
+    Ok(output)
+}
+
+/// Записује текст у RTF облику. Знакови којих нема у кодној страни групе се
+/// записују Unicode ознакама (`\u1078?`).
+fn encode_text(text: &str, group: &Group) -> String {
+    let mut output = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' | '{' | '}' => {
+                output.push('\\');
+                output.push(c);
+            }
+            c if c.is_ascii() => output.push(c),
+            c => match encode_byte(group.code_page, c) {
+                Some(byte) => output.push_str(&format!("\\'{byte:02x}")),
+                None => {
+                    for unit in c.encode_utf16(&mut [0; 2]) {
+                        output.push_str(&format!("\\u{}", *unit as i16));
+                        if group.unicode_skip == 0 {
+                            output.push(' ');
+                        }
+                        output.push_str(&"?".repeat(group.unicode_skip));
+                    }
+                }
+            },
+        }
+    }
+    output
+}
+
+/// Следећи токен и његова дужина у бајтовима.
+fn next_token(input: &str) -> (Token<'_>, usize) {
+    let mut chars = input.chars();
+    let c = chars.next().unwrap_or_default();
+    match c {
+        '{' => (Token::GroupStart, 1),
+        '}' => (Token::GroupEnd, 1),
+        '\r' | '\n' => (Token::Newline, 1),
+        '\\' => {
+            let rest = &input[1..];
+            let name_len = rest
+                .find(|c: char| !c.is_ascii_alphabetic())
+                .unwrap_or(rest.len());
+            if name_len > 0 {
+                let name = &rest[..name_len];
+                let after = &rest[name_len..];
+                let digits = after
+                    .char_indices()
+                    .find(|&(i, c)| !(c.is_ascii_digit() || (i == 0 && c == '-')))
+                    .map_or(after.len(), |(i, _)| i);
+                let parameter = after[..digits].parse().ok();
+                let delimiter = usize::from(after[digits..].starts_with(' '));
+                let len = 1 + name_len + digits + delimiter;
+                (Token::ControlWord { name, parameter }, len)
+            } else if let Some(hex) = rest.strip_prefix('\'')
+                && let Some(byte) = hex
+                    .get(..2)
+                    .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+            {
+                (Token::Hex(byte), 4)
+            } else if let Some(symbol) = rest.chars().next() {
+                (Token::Symbol(symbol), 1 + symbol.len_utf8())
+            } else {
+                // Контролни знак на крају улаза
+                (Token::Symbol('\\'), 1)
+            }
+        }
+        c => (Token::Text(c), c.len_utf8()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{cyr_to_lat, lat_to_cyr};
+
+    #[test]
+    fn test_convert_to_latin() {
+        let input = r"{\rtf1\ansi\ansicpg1251{\fonttbl{\f0\fcharset204 Times;}}{\info{\title \'cd\'e0\'f1\'eb\'ee\'e2}}\f0\'c4\'ee\'e1\'e0\'f0 \b\'e4\'e0\'ed\b0  \u1078?\par}";
+        let expected = r"{\rtf1\ansi\ansicpg1251{\fonttbl{\f0\fcharset204 Times;}}{\info{\title \'cd\'e0\'f1\'eb\'ee\'e2}}\f0 Dobar \b dan\b0  \u382?\par}";
+        assert_eq!(expected, convert(input, cyr_to_lat).unwrap());
+    }
+
+    #[test]
+    fn test_convert_to_cyrillic() {
+        let input = r#"{\rtf1\ansi\ansicpg1250{\fonttbl{\f0\fcharset238 Arial;}}\f0 \'c8a\'9aa \{x\}{\field{\*\fldinst HYPERLINK "http://primer.rs"}{\fldrslt Link}}}"#;
+        let expected = r#"{\rtf1\ansi\ansicpg1250{\fonttbl{\f0\fcharset238 Arial;}}\f0 \u1063?\u1072?\u1096?\u1072? \{x\}{\field{\*\fldinst HYPERLINK "http://primer.rs"}{\fldrslt \u1051?\u1080?\u1085?\u1082?}}}"#;
+        assert_eq!(expected, convert(input, lat_to_cyr).unwrap());
+    }
+
+    #[test]
+    fn test_truncated_document() {
+        assert!(matches!(
+            convert("{\\rtf1 \\'e8a\\", lat_to_cyr),
+            Err(Error::Syntax { line: 1, .. })
+        ));
+        assert!(convert("{\\rtf1\n\\", lat_to_cyr).is_err());
+        assert!(convert("{\\rtf1 \\'e", lat_to_cyr).is_ok());
+    }
+}
@@ -0,0 +1,255 @@
+//! Конверзија садржаја статичких сајтова (Hugo, Jekyll, Zola). Markdown
+//! стране у директоријуму садржаја се пресловљавају у паралелни директоријум,
+//! а остали фајлови (слике, стилови) се копирају непромењени. У заглављу стране
+//! (YAML између `---` или TOML између `+++`) пресловљавају се само изабрана
+//! поља (нпр. `title`, `description`), а ознаке језика (`lang: sr`) се мењају
+//! тако да одговарају новом писму.
+use std::fs;
+use std::path::Path;
+
+use super::json::convert_escaped;
+use super::markdown::convert_body;
+use super::xml::retag;
+use super::yaml;
+use crate::Error;
+
+// Поља заглавља са ознаком језика
+const LANGUAGE_FIELDS: &[&str] = &["lang", "language", "locale"];
+
+// Екстензије Markdown страна
+const PAGE_EXTENSIONS: &[&str] = &["md", "markdown"];
+
+/// Пресловљава стране из директоријума `input` функцијом `convert` и уписује
+/// их у директоријум `output` са истом структуром. У заглављима страна се
+/// пресловљавају поља `fields`, а ако је задата ознака језика `language` (нпр.
+/// `sr-Latn`) њоме се замењују ознаке истог језика.
+pub fn convert(
+    input: &Path,
+    output: &Path,
+    fields: &[&str],
+    language: Option<&str>,
+    convert: impl Fn(&str) -> String,
+) -> Result<(), Error> {
+    let skip = output.canonicalize().ok();
+    convert_directory(input, output, skip.as_deref(), fields, language, &convert)
+}
+
+fn convert_directory(
+    input: &Path,
+    output: &Path,
+    skip: Option<&Path>,
+    fields: &[&str],
+    language: Option<&str>,
+    convert: &impl Fn(&str) -> String,
+) -> Result<(), Error> {
+    fs::create_dir_all(output).map_err(Error::Io)?;
+    let mut entries = fs::read_dir(input)
+        .and_then(|entries| entries.collect::<Result<Vec<_>, _>>())
+        .map_err(Error::Io)?;
+    entries.sort_by_key(|entry| entry.file_name());
+    for entry in entries {
+        let path = entry.path();
+        let target = output.join(entry.file_name());
+        if path.is_dir() {
+            // Излазни директоријум унутар улазног се не обрађује
+            if skip.is_some_and(|skip| path.canonicalize().is_ok_and(|path| path == skip)) {
+                continue;
+            }
+            convert_directory(&path, &target, skip, fields, language, convert)?;
+        } else if path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .is_some_and(|extension| PAGE_EXTENSIONS.contains(&extension.to_lowercase().as_str()))
+        {
+            let page = fs::read_to_string(&path).map_err(Error::Io)?;
+            fs::write(&target, convert_page(&page, fields, language, convert))
+                .map_err(Error::Io)?;
+        } else {
+            fs::copy(&path, &target).map_err(Error::Io)?;
+        }
+    }
+    Ok(())
+}
+
+/// Пресловљава Markdown страну са заглављем у коме се пресловљавају само поља
+/// `fields`.
+pub fn convert_page(
+    input: &str,
+    fields: &[&str],
+    language: Option<&str>,
+    convert: &impl Fn(&str) -> String,
+) -> String {
+    let first = match input.split_inclusive('\n').next() {
+        Some(line) if matches!(line.trim_end(), "---" | "+++") => line,
+        _ => return convert_body(input, convert),
+    };
+    let marker = first.trim_end();
+    let first = first.len();
+    let mut pos = first;
+    let mut end = None;
+    for line in input[first..].split_inclusive('\n') {
+        if line.trim_end() == marker {
+            end = Some((pos, pos + line.len()));
+            break;
+        }
+        pos += line.len();
+    }
+    let Some((close, body_start)) = end else {
+        return convert_body(input, convert);
+    };
+
+    let front = &input[first..close];
+    let mut output = String::with_capacity(input.len());
+    output.push_str(&input[..first]);
+    if marker == "---" {
+        output.push_str(&convert_yaml(front, fields, language, convert));
+    } else {
+        output.push_str(&convert_toml(front, fields, language, convert));
+    }
+    output.push_str(&input[close..body_start]);
+    output.push_str(&convert_body(&input[body_start..], convert));
+    output
+}
+
+/// Пресловљава изабрана поља највишег нивоа YAML заглавља.
+fn convert_yaml(
+    front: &str,
+    fields: &[&str],
+    language: Option<&str>,
+    convert: &impl Fn(&str) -> String,
+) -> String {
+    let mut output = String::with_capacity(front.len());
+    // Поље највишег нивоа коме припадају увучене линије
+    let mut field = String::new();
+    for line in front.split_inclusive('\n') {
+        if !line.starts_with([' ', '\t', '\r', '\n', '#', '-'])
+            && let Some((key, _)) = line.split_once(':')
+        {
+            field = key.trim().trim_matches(['"', '\'']).to_string();
+        }
+        let field = field.as_str();
+        if fields.contains(&field) {
+            output.push_str(&yaml::convert(line, convert));
+        } else if LANGUAGE_FIELDS.contains(&field)
+            && let Some((key, value)) = line.split_once(':')
+            && let Some(value) = retag_value(value, language)
+        {
+            output.push_str(key);
+            output.push(':');
+            output.push_str(&value);
+        } else {
+            output.push_str(line);
+        }
+    }
+    output
+}
+
+/// Пресловљава изабрана поља највишег нивоа TOML заглавља (пре прве табеле).
+fn convert_toml(
+    front: &str,
+    fields: &[&str],
+    language: Option<&str>,
+    convert: &impl Fn(&str) -> String,
+) -> String {
+    let mut output = String::with_capacity(front.len());
+    let mut in_table = false;
+    for line in front.split_inclusive('\n') {
+        in_table = in_table || line.trim_start().starts_with('[');
+        let field = line.split_once('=').filter(|_| !in_table);
+        match field {
+            Some((key, value)) if fields.contains(&key.trim()) => {
+                output.push_str(key);
+                output.push('=');
+                let content = value.trim_start();
+                output.push_str(&value[..value.len() - content.len()]);
+                match content.chars().next() {
+                    Some(quote @ ('"' | '\'')) => {
+                        let end = content[1..].rfind(quote).map_or(content.len(), |i| i + 1);
+                        output.push(quote);
+                        if quote == '"' {
+                            output.push_str(&convert_escaped(&content[1..end], convert));
+                        } else {
+                            output.push_str(&convert(&content[1..end]));
+                        }
+                        output.push_str(&content[end..]);
+                    }
+                    _ => output.push_str(content),
+                }
+            }
+            Some((key, value)) if LANGUAGE_FIELDS.contains(&key.trim()) => {
+                output.push_str(key);
+                output.push('=');
+                output.push_str(&retag_value(value, language).unwrap_or_else(|| value.to_string()));
+            }
+            _ => output.push_str(line),
+        }
+    }
+    output
+}
+
+/// Замењује ознаку језика у вредности поља (са или без наводника).
+fn retag_value(value: &str, language: Option<&str>) -> Option<String> {
+    let tag = value.trim().trim_matches(['"', '\'']);
+    let new_tag = retag(tag, language?)?;
+    Some(value.replacen(tag, &new_tag, 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lat_to_cyr;
+
+    #[test]
+    fn test_convert_page() {
+        let fields = ["title", "description"];
+        let input = "---
+title: Na Drini ćuprija
+description: |
+  Roman o mostu
+slug: na-drini
+lang: sr-Latn
+tags: [roman, istorija]
+---
+# Uvod
+
+Tekst `kod`.
+";
+        let expected = "---
+title: На Дрини ћуприја
+description: |
+  Роман о мосту
+slug: na-drini
+lang: sr-Cyrl
+tags: [roman, istorija]
+---
+# Увод
+
+Текст `kod`.
+";
+        assert_eq!(
+            expected,
+            convert_page(input, &fields, Some("sr-Cyrl"), &lat_to_cyr)
+        );
+
+        let input = "+++
+title = \"Prva strana\"
+lang = \"sr\"
+[extra]
+title = \"Ostaje\"
++++
+Tekst.
+";
+        let expected = "+++
+title = \"Прва страна\"
+lang = \"sr-Cyrl\"
+[extra]
+title = \"Ostaje\"
++++
+Текст.
+";
+        assert_eq!(
+            expected,
+            convert_page(input, &fields, Some("sr-Cyrl"), &lat_to_cyr)
+        );
+    }
+}
@@ -0,0 +1,172 @@
+//! Конверзија SQL скрипти и дампова базе. Пресловљава се само садржај
+//! стринг литерала (`'...'`), укључујући податке `COPY ... FROM stdin` блокова
+//! (PostgreSQL), док кључне речи, идентификатори (`"name"`, `` `name` ``),
+//! бројеви, коментари, хексадецимални литерали (`X'..'`), тела функција
+//! (`$$...$$`) и escape секвенце остају непромењени.
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use super::json::convert_escaped;
+
+// Ознака почетка стринга под доларима (`$$`, `$body$`)
+static DOLLAR_QUOTE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^\$([A-Za-z_]\w*)?\$").unwrap());
+
+/// Пресловљава садржај стринг литерала функцијом `convert`. Обрнута коса
+/// црта у стринговима се сматра escape знаком (MySQL), осим ако скрипта
+/// садржи `standard_conforming_strings = on` (PostgreSQL), када важи само у
+/// `E'...'` стринговима.
+pub fn convert(input: &str, convert: impl Fn(&str) -> String) -> String {
+    let backslash_escapes = !input.contains("standard_conforming_strings = on");
+    let bytes = input.as_bytes();
+    let mut output = String::with_capacity(input.len());
+    // Почетак дела који још није преписан
+    let mut start = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        let rest = &input[i..];
+        match bytes[i] {
+            b'\'' => {
+                let prefix = i.checked_sub(1).map(|j| bytes[j].to_ascii_uppercase());
+                let is_word = |j: usize| bytes[j].is_ascii_alphanumeric() || bytes[j] == b'_';
+                let prefixed = i >= 2 && is_word(i - 2);
+                let escapes = backslash_escapes || (prefix == Some(b'E') && !prefixed);
+                let len = string_len(rest, escapes);
+                // Хексадецимални и бинарни литерали (`X'4142'`) се не мењају
+                let binary = matches!(prefix, Some(b'X' | b'B')) && !prefixed;
+                if !binary {
+                    // Незатворен стринг траје до краја улаза
+                    let closed = len >= 2 && bytes[i + len - 1] == b'\'';
+                    let content = &input[i + 1..i + len - usize::from(closed)];
+                    output.push_str(&input[start..i + 1]);
+                    if escapes {
+                        output.push_str(&convert_escaped(content, &convert));
+                    } else {
+                        output.push_str(&convert(content));
+                    }
+                    start = i + 1 + content.len();
+                }
+                i += len;
+            }
+            b'"' => i += rest[1..].find('"').map_or(rest.len(), |end| end + 2),
+            b'`' => i += rest[1..].find('`').map_or(rest.len(), |end| end + 2),
+            b'-' if rest.starts_with("--") => i += rest.find('\n').unwrap_or(rest.len()),
+            b'/' if rest.starts_with("/*") => {
+                i += rest[2..].find("*/").map_or(rest.len(), |end| end + 4);
+            }
+            b'$' if let Some(tag) = DOLLAR_QUOTE.find(rest) => {
+                let tag = tag.as_str();
+                i += rest[tag.len()..]
+                    .find(tag)
+                    .map_or(rest.len(), |end| end + 2 * tag.len());
+            }
+            b';' => {
+                i += 1;
+                let line_start = input[..i].rfind('\n').map_or(0, |j| j + 1);
+                let statement = input[line_start..i - 1].trim().to_uppercase();
+                if statement.starts_with("COPY ") && statement.ends_with("FROM STDIN") {
+                    let data_start = input[i..].find('\n').map_or(input.len(), |j| i + j + 1);
+                    output.push_str(&input[start..data_start]);
+                    i = convert_copy_data(input, data_start, &convert, &mut output);
+                    start = i;
+                }
+            }
+            _ => i += rest.chars().next().map_or(1, char::len_utf8),
+        }
+    }
+    output.push_str(&input[start..]);
+    output
+}
+
+/// Дужина стринг литерала са наводницима. Удвојени наводници (`''`) и,
+/// ако је `escapes` тачно, наводници иза обрнуте косе црте не завршавају
+/// стринг.
+fn string_len(input: &str, escapes: bool) -> usize {
+    let bytes = input.as_bytes();
+    let mut i = 1;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' if escapes => i += 2,
+            b'\'' if bytes.get(i + 1) == Some(&b'\'') => i += 2,
+            b'\'' => return i + 1,
+            _ => i += 1,
+        }
+    }
+    input.len()
+}
+
+/// Пресловљава податке `COPY` блока од позиције `start` до линије `\.`.
+/// Вредности су одвојене табулаторима, а `\N` означава `NULL`. Враћа
+/// позицију иза блока.
+fn convert_copy_data(
+    input: &str,
+    start: usize,
+    convert: &impl Fn(&str) -> String,
+    output: &mut String,
+) -> usize {
+    let mut pos = start;
+    for line in input[start..].split_inclusive('\n') {
+        pos += line.len();
+        let content = line.trim_end_matches(['\r', '\n']);
+        if content == "\\." {
+            output.push_str(line);
+            return pos;
+        }
+        let fields = content
+            .split('\t')
+            .map(|field| match field {
+                "\\N" => field.to_string(),
+                field => convert_escaped(field, convert),
+            })
+            .collect::<Vec<_>>();
+        output.push_str(&fields.join("\t"));
+        output.push_str(&line[content.len()..]);
+    }
+    pos
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lat_to_cyr;
+
+    #[test]
+    fn test_convert() {
+        let input = r#"-- Dump baze
+CREATE TABLE `knjige` (`naslov` varchar(100) DEFAULT 'Bez naslova');
+INSERT INTO "knjige" VALUES (1, 'Na Drini ćuprija', 'Ivo \'Andrić\'\n', X'4142', 'Đak''s');
+/* komentar */
+CREATE FUNCTION f() RETURNS text AS $$ SELECT 'tekst' $$ LANGUAGE sql;
+COPY public.autori (id, ime, grad) FROM stdin;
+1	Ivo Andrić	\N
+2	Meša\tSelimović	Tuzla
+\.
+SELECT 'Kraj';
+"#;
+        let expected = r#"-- Dump baze
+CREATE TABLE `knjige` (`naslov` varchar(100) DEFAULT 'Без наслова');
+INSERT INTO "knjige" VALUES (1, 'На Дрини ћуприја', 'Иво \'Андрић\'\n', X'4142', 'Ђак''с');
+/* komentar */
+CREATE FUNCTION f() RETURNS text AS $$ SELECT 'tekst' $$ LANGUAGE sql;
+COPY public.autori (id, ime, grad) FROM stdin;
+1	Иво Андрић	\N
+2	Меша\tСелимовић	Тузла
+\.
+SELECT 'Крај';
+"#;
+        assert_eq!(expected, convert(input, lat_to_cyr));
+    }
+
+    #[test]
+    fn test_non_ascii_outside_strings() {
+        assert_eq!(
+            "CREATE TABLE књиге (наслов text DEFAULT 'Наслов');",
+            convert(
+                "CREATE TABLE књиге (наслов text DEFAULT 'Naslov');",
+                lat_to_cyr
+            )
+        );
+        assert_eq!("SELECT 'ж", convert("SELECT 'ž", lat_to_cyr));
+        assert_eq!("SELECT 'Ђак", convert("SELECT 'Đak", lat_to_cyr));
+        assert_eq!("SELECT '", convert("SELECT '", lat_to_cyr));
+    }
+}
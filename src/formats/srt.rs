@@ -0,0 +1,75 @@
+//! Конверзија SRT титлова у којој се пресловљава само текст титла, док редни
+//! бројеви, време приказивања и ознаке форматирања (`<i>`, `{\an8}`) остају
+//! непромењени.
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+static TIMING: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^\d+:\d\d:\d\d[,.]\d+\s*-->\s*\d+:\d\d:\d\d[,.]\d+").unwrap());
+
+// Ознаке форматирања (`<i>`, `</font>`, `{\an8}`)
+static TAG: Lazy<Regex> = Lazy::new(|| Regex::new(r"<[^<>]*>|\{\\[^{}]*\}").unwrap());
+
+/// Пресловљава текст титлова функцијом `convert`.
+pub fn convert(input: &str, convert: impl Fn(&str) -> String) -> String {
+    let mut output = String::with_capacity(input.len());
+    // Да ли следи редни број титла
+    let mut cue_start = true;
+    for line in input.split_inclusive('\n') {
+        let content = line.trim_end_matches(['\n', '\r']);
+        let trimmed = content.trim_start_matches('\u{feff}').trim();
+        if trimmed.is_empty()
+            || (cue_start && trimmed.bytes().all(|b| b.is_ascii_digit()))
+            || TIMING.is_match(trimmed)
+        {
+            output.push_str(line);
+        } else {
+            output.push_str(&convert_tagged(content, &convert));
+            output.push_str(&line[content.len()..]);
+        }
+        cue_start = trimmed.is_empty();
+    }
+    output
+}
+
+/// Пресловљава текст изузев ознака форматирања.
+pub(crate) fn convert_tagged(text: &str, convert: &impl Fn(&str) -> String) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut pos = 0;
+    for tag in TAG.find_iter(text) {
+        output.push_str(&convert(&text[pos..tag.start()]));
+        output.push_str(tag.as_str());
+        pos = tag.end();
+    }
+    output.push_str(&convert(&text[pos..]));
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lat_to_cyr;
+
+    #[test]
+    fn test_convert() {
+        let input = "\u{feff}1\r
+00:00:01,000 --> 00:00:03,500\r
+<i>Dobro jutro!</i>\r
+\r
+2
+00:00:04,000 --> 00:00:06,000 X1:10 X2:200
+{\\an8}<font color=\"#ff0000\">Koliko?</font>
+42
+";
+        let expected = "\u{feff}1\r
+00:00:01,000 --> 00:00:03,500\r
+<i>Добро јутро!</i>\r
+\r
+2
+00:00:04,000 --> 00:00:06,000 X1:10 X2:200
+{\\an8}<font color=\"#ff0000\">Колико?</font>
+42
+";
+        assert_eq!(expected, convert(input, lat_to_cyr));
+    }
+}
@@ -0,0 +1,222 @@
+//! Конверзија фајлова у `.tar.gz` архивама. Заглавља ставки се не мењају осим
+//! величине и контролне суме измењених фајлова, а остале ставке (директоријуми,
+//! линкови, PAX заглавља...) се преписују непромењене.
+use std::io::{self, Read, Write};
+
+use flate2::Compression;
+use flate2::read::MultiGzDecoder;
+use flate2::write::GzEncoder;
+
+use crate::Error;
+
+const BLOCK: usize = 512;
+
+/// Пресловљава фајлове у `.tar.gz` архиви. Функција `convert` за сваки фајл
+/// добија путању и садржај и враћа нови садржај или `None` ако се фајл не
+/// мења.
+pub fn convert(
+    input: &[u8],
+    mut convert: impl FnMut(&str, &[u8]) -> Result<Option<Vec<u8>>, Error>,
+) -> Result<Vec<u8>, Error> {
+    let mut tar = Vec::new();
+    MultiGzDecoder::new(input).read_to_end(&mut tar)?;
+    let mut output = Vec::with_capacity(tar.len());
+    let mut pos = 0;
+    // Путања следеће ставке задата GNU (`L`) или PAX (`x`) заглављем
+    let mut long_name: Option<String> = None;
+    while pos + BLOCK <= tar.len() {
+        let header = &tar[pos..pos + BLOCK];
+        if header.iter().all(|&b| b == 0) {
+            // Крај архиве
+            break;
+        }
+        let size = entry_size(header)?;
+        let start = pos + BLOCK;
+        let end = start + size;
+        let next = start + size.div_ceil(BLOCK) * BLOCK;
+        if next > tar.len() {
+            return Err(invalid("ставка прелази крај архиве"));
+        }
+        let content = &tar[start..end];
+        let converted = match header[156] {
+            b'L' => {
+                long_name = Some(field(content));
+                None
+            }
+            b'x' => {
+                long_name = pax_path(content).or(long_name);
+                None
+            }
+            // Обичан фајл
+            b'0' | b'\0' | b'7' => {
+                let name = long_name.take().unwrap_or_else(|| entry_name(header));
+                convert(&name, content)?
+            }
+            _ => {
+                long_name = None;
+                None
+            }
+        };
+        match converted {
+            Some(content) => {
+                let mut header: [u8; BLOCK] = header.try_into().unwrap();
+                set_size(&mut header, content.len())?;
+                output.extend_from_slice(&header);
+                output.extend_from_slice(&content);
+                output.resize(output.len().div_ceil(BLOCK) * BLOCK, 0);
+            }
+            None => output.extend_from_slice(&tar[pos..next]),
+        }
+        pos = next;
+    }
+    // Крај архиве (два празна блока) и евентуални остатак се преписују
+    output.extend_from_slice(&tar[pos.min(tar.len())..]);
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(&output)?;
+    Ok(encoder.finish()?)
+}
+
+fn invalid(message: &str) -> Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("неисправна tar архива: {message}"),
+    )
+    .into()
+}
+
+/// Текстуално поље заглавља завршено нулом.
+fn field(bytes: &[u8]) -> String {
+    let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..end]).into_owned()
+}
+
+/// Путања ставке, са префиксом код USTAR заглавља.
+fn entry_name(header: &[u8]) -> String {
+    let name = field(&header[..100]);
+    let prefix = field(&header[345..500]);
+    if &header[257..262] == b"ustar" && !prefix.is_empty() {
+        format!("{prefix}/{name}")
+    } else {
+        name
+    }
+}
+
+/// Путања (`path`) из PAX заглавља чији су записи облика `<дужина> кључ=вредност\n`.
+fn pax_path(content: &[u8]) -> Option<String> {
+    let mut rest = content;
+    while let Some(space) = rest.iter().position(|&b| b == b' ') {
+        let len: usize = std::str::from_utf8(&rest[..space]).ok()?.parse().ok()?;
+        let record = rest.get(space + 1..len)?;
+        if let Some(path) = record.strip_prefix(b"path=") {
+            return Some(String::from_utf8_lossy(path.strip_suffix(b"\n")?).into_owned());
+        }
+        rest = &rest[len..];
+    }
+    None
+}
+
+/// Величина садржаја ставке (октални запис или GNU бинарни запис за велике
+/// фајлове).
+fn entry_size(header: &[u8]) -> Result<usize, Error> {
+    let size = &header[124..136];
+    if size[0] & 0x80 != 0 {
+        return Ok(size[1..]
+            .iter()
+            .fold(0, |size, &b| (size << 8) | usize::from(b)));
+    }
+    let size = field(size);
+    let size = size.trim_matches(|c: char| c == ' ' || c == '\0');
+    if size.is_empty() {
+        return Ok(0);
+    }
+    usize::from_str_radix(size, 8).map_err(|_| invalid("неисправна величина ставке"))
+}
+
+/// Уписује нову величину ставке у заглавље и поново рачуна контролну суму.
+fn set_size(header: &mut [u8; BLOCK], size: usize) -> Result<(), Error> {
+    let size = format!("{size:011o}\0");
+    if size.len() != 12 {
+        return Err(invalid("фајл је превелик"));
+    }
+    header[124..136].copy_from_slice(size.as_bytes());
+    header[148..156].fill(b' ');
+    let checksum: u32 = header.iter().map(|&b| u32::from(b)).sum();
+    header[148..156].copy_from_slice(format!("{checksum:06o}\0 ").as_bytes());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header(name: &str, size: usize, typeflag: u8) -> [u8; BLOCK] {
+        let mut header = [0; BLOCK];
+        header[..name.len()].copy_from_slice(name.as_bytes());
+        header[100..108].copy_from_slice(b"0000644\0");
+        header[156] = typeflag;
+        header[257..263].copy_from_slice(b"ustar\0");
+        header[263..265].copy_from_slice(b"00");
+        set_size(&mut header, size).unwrap();
+        header
+    }
+
+    fn archive(entries: &[(&str, &[u8], u8)]) -> Vec<u8> {
+        let mut tar = Vec::new();
+        for (name, content, typeflag) in entries {
+            tar.extend_from_slice(&header(name, content.len(), *typeflag));
+            tar.extend_from_slice(content);
+            tar.resize(tar.len().div_ceil(BLOCK) * BLOCK, 0);
+        }
+        tar.extend_from_slice(&[0; 2 * BLOCK]);
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&tar).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    #[test]
+    fn test_convert() {
+        let long_name = format!("{}/pesma.txt", "d".repeat(100));
+        let input = archive(&[
+            ("tekstovi/", b"", b'5'),
+            ("tekstovi/uvod.txt", b"Zdravo", b'0'),
+            ("slika.png", b"PNG", b'0'),
+            ("././@LongLink", long_name.as_bytes(), b'L'),
+            ("pesma.txt", b"Pesma", b'0'),
+        ]);
+        let mut names = Vec::new();
+        let output = convert(&input, |name, content| {
+            names.push(name.to_string());
+            Ok(name
+                .ends_with(".txt")
+                .then(|| String::from_utf8_lossy(content).to_uppercase().into_bytes()))
+        })
+        .unwrap();
+        assert_eq!(
+            vec!["tekstovi/uvod.txt", "slika.png", long_name.as_str()],
+            names
+        );
+        let expected = archive(&[
+            ("tekstovi/", b"", b'5'),
+            ("tekstovi/uvod.txt", b"ZDRAVO", b'0'),
+            ("slika.png", b"PNG", b'0'),
+            ("././@LongLink", long_name.as_bytes(), b'L'),
+            ("pesma.txt", b"PESMA", b'0'),
+        ]);
+        let unpack = |archive: &[u8]| {
+            let mut tar = Vec::new();
+            MultiGzDecoder::new(archive).read_to_end(&mut tar).unwrap();
+            tar
+        };
+        assert_eq!(unpack(&expected), unpack(&output));
+        assert!(convert(b"nije arhiva", |_, _| Ok(None)).is_err());
+    }
+
+    #[test]
+    fn test_pax_path() {
+        assert_eq!(
+            Some("dugačka/putanja.md".to_string()),
+            pax_path("20 mtime=1700000000\n28 path=dugačka/putanja.md\n".as_bytes())
+        );
+        assert_eq!(None, pax_path(b"20 mtime=1700000000\n"));
+    }
+}
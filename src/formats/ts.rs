@@ -0,0 +1,88 @@
+//! Конверзија Qt Linguist (`.ts`) датотека. Пресловљава се само текст превода
+//! (`<translation>`, укључујући облике множине `<numerusform>`), док изворни
+//! текст, коментари и ознаке за замену (`%1`, `%n`, `%L1`) остају непромењени.
+use once_cell::sync::Lazy;
+use regex::{Captures, Regex};
+
+use super::xml::{Content, convert_document, retag};
+use crate::Error;
+
+// Ознаке за замену у Qt стринговима
+static PLACEHOLDER: Lazy<Regex> = Lazy::new(|| Regex::new(r"%L?(\d+|n)").unwrap());
+
+// Ознака језика превода у коренском елементу
+static LANGUAGE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"(<TS\b[^>]*\slanguage=")([^"]*)""#).unwrap());
+
+/// Пресловљава преводе у `.ts` датотеци функцијом `convert`. Ако је задата
+/// ознака језика `language` (нпр. `sr-Latn`) њоме се замењује ознака језика
+/// превода (`sr_RS` постаје `sr_Latn_RS`).
+pub fn convert(
+    input: &str,
+    language: Option<&str>,
+    convert: impl Fn(&str) -> String,
+) -> Result<String, Error> {
+    let convert = |text: &str| {
+        let mut output = String::with_capacity(text.len());
+        let mut pos = 0;
+        for placeholder in PLACEHOLDER.find_iter(text) {
+            output.push_str(&convert(&text[pos..placeholder.start()]));
+            output.push_str(placeholder.as_str());
+            pos = placeholder.end();
+        }
+        output.push_str(&convert(&text[pos..]));
+        output
+    };
+    let output = convert_document(input, None, &convert, |stack| {
+        if stack.contains(&"translation") {
+            Content::Text
+        } else {
+            Content::Unchanged
+        }
+    })?;
+    let Some(language) = language else {
+        return Ok(output);
+    };
+    Ok(LANGUAGE
+        .replace(&output, |captures: &Captures| {
+            let tag = retag(&captures[2], language).map(|tag| tag.replace('-', "_"));
+            format!(
+                "{}{}\"",
+                &captures[1],
+                tag.as_deref().unwrap_or(&captures[2])
+            )
+        })
+        .into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lat_to_cyr;
+
+    #[test]
+    fn test_convert() {
+        let input = r#"<?xml version="1.0" encoding="utf-8"?>
+<!DOCTYPE TS>
+<TS version="2.1" language="sr_RS" sourcelanguage="en">
+<context><name>MainWindow</name>
+<message><location filename="main.cpp" line="10"/><source>Open %1</source>
+<comment>Meni</comment><translation>Otvori %1 (%L2)</translation></message>
+<message numerus="yes"><source>%n file(s)</source>
+<translation type="unfinished"><numerusform>%n datoteka</numerusform><numerusform>%n datoteke</numerusform></translation></message>
+</context></TS>"#;
+        let expected = r#"<?xml version="1.0" encoding="utf-8"?>
+<!DOCTYPE TS>
+<TS version="2.1" language="sr_Cyrl_RS" sourcelanguage="en">
+<context><name>MainWindow</name>
+<message><location filename="main.cpp" line="10"/><source>Open %1</source>
+<comment>Meni</comment><translation>Отвори %1 (%L2)</translation></message>
+<message numerus="yes"><source>%n file(s)</source>
+<translation type="unfinished"><numerusform>%n датотека</numerusform><numerusform>%n датотеке</numerusform></translation></message>
+</context></TS>"#;
+        assert_eq!(
+            expected,
+            convert(input, Some("sr-Cyrl"), lat_to_cyr).unwrap()
+        );
+    }
+}
@@ -0,0 +1,254 @@
+//! Конверзија Typst докумената. Пресловљава се текст у режиму ознака
+//! (markup), укључујући блокове садржаја (`[...]`) унутар позива функција, док
+//! изрази (`#set`, `#let`, `#func(...)`), ниске у коду, математички режим
+//! (`$...$`), код (`` `код` ``), коментари, ознаке (`<label>`), референце
+//! (`@label`) и адресе остају непромењени.
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use super::latex::math_len;
+
+// Кључне речи израза који трају до краја линије
+const KEYWORDS: &[&str] = &[
+    "let", "set", "show", "import", "include", "if", "for", "while", "return", "context",
+];
+
+// Ознаке, референце и адресе
+static REFERENCE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^(<[\w:.-]+>|@[\w:.-]+|https?://[^\s\[\]()<>]+)").unwrap());
+
+struct Parser<'a, F> {
+    input: &'a str,
+    pos: usize,
+    output: String,
+    // Почетак текста или кода који још није преписан
+    text_start: usize,
+    convert: F,
+}
+
+impl<F: Fn(&str) -> String> Parser<'_, F> {
+    fn rest(&self) -> &str {
+        &self.input[self.pos..]
+    }
+
+    /// Пресловљава текст до тренутне позиције.
+    fn text(&mut self) {
+        let text = &self.input[self.text_start..self.pos];
+        if !text.is_empty() {
+            self.output.push_str(&(self.convert)(text));
+        }
+        self.text_start = self.pos;
+    }
+
+    /// Преписује непромењено све до тренутне позиције.
+    fn raw(&mut self) {
+        self.output.push_str(&self.input[self.text_start..self.pos]);
+        self.text_start = self.pos;
+    }
+
+    /// Преписује непромењено `len` бајтова у режиму ознака.
+    fn skip(&mut self, len: usize) {
+        self.text();
+        self.pos = (self.pos + len).min(self.input.len());
+        self.raw();
+    }
+
+    /// Дужина од почетка до `end` (укључујући) од позиције `start` или до
+    /// краја.
+    fn len_until(&self, start: usize, end: &str) -> usize {
+        let rest = self.rest();
+        rest[start.min(rest.len())..]
+            .find(end)
+            .map_or(rest.len(), |i| start + i + end.len())
+    }
+
+    /// Режим ознака. Ако је `nested` режим се завршава на затвореној угластој
+    /// загради блока садржаја.
+    fn markup(&mut self, nested: bool) {
+        let mut depth = 0usize;
+        while let Some(c) = self.rest().chars().next() {
+            let rest = self.rest();
+            match c {
+                ']' if nested && depth == 0 => break,
+                '[' => {
+                    depth += 1;
+                    self.pos += 1;
+                }
+                ']' => {
+                    depth = depth.saturating_sub(1);
+                    self.pos += 1;
+                }
+                '\\' => self.skip(1 + rest[1..].chars().next().map_or(0, char::len_utf8)),
+                '/' if rest.starts_with("//") => self.skip(rest.find('\n').unwrap_or(rest.len())),
+                '/' if rest.starts_with("/*") => self.skip(self.len_until(2, "*/")),
+                '`' if rest.starts_with("```") => self.skip(self.len_until(3, "```")),
+                '`' => self.skip(self.len_until(1, "`")),
+                '$' => self.skip(math_len(rest)),
+                '#' if rest[1..].starts_with(|c: char| {
+                    c.is_alphabetic() || matches!(c, '_' | '(' | '{' | '[')
+                }) =>
+                {
+                    self.text();
+                    self.pos += 1;
+                    self.expression();
+                }
+                _ => match REFERENCE.find(rest) {
+                    Some(reference) => self.skip(reference.end()),
+                    None => self.pos += c.len_utf8(),
+                },
+            }
+        }
+        self.text();
+    }
+
+    /// Израз иза `#`: позив функције са аргументима и блоковима садржаја,
+    /// приступ пољима или израз са кључном речи до краја линије.
+    fn expression(&mut self) {
+        let rest = self.rest();
+        let name_len = rest
+            .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '-'))
+            .unwrap_or(rest.len());
+        if KEYWORDS.contains(&&rest[..name_len]) {
+            self.statement();
+            self.raw();
+            return;
+        }
+        self.pos += name_len;
+        loop {
+            let rest = self.rest();
+            match rest.chars().next() {
+                Some('(') => {
+                    self.pos += 1;
+                    self.code(')');
+                }
+                Some('{') => {
+                    self.pos += 1;
+                    self.code('}');
+                }
+                Some('[') => self.content(),
+                Some('.') if rest[1..].starts_with(char::is_alphabetic) => {
+                    self.pos += rest[1..]
+                        .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '-'))
+                        .map_or(rest.len(), |i| i + 1);
+                }
+                _ => break,
+            }
+        }
+        self.raw();
+    }
+
+    /// Израз са кључном речи (`#set text(...)`) до краја линије или `;`.
+    fn statement(&mut self) {
+        while let Some(c) = self.rest().chars().next() {
+            if c == '\n' || c == ';' {
+                return;
+            }
+            self.code_token(c);
+        }
+    }
+
+    /// Код до затворене заграде `close` (укључујући).
+    fn code(&mut self, close: char) {
+        while let Some(c) = self.rest().chars().next() {
+            if c == close {
+                self.pos += 1;
+                return;
+            }
+            self.code_token(c);
+        }
+    }
+
+    /// Прескаче један елемент кода који почиње знаком `c`.
+    fn code_token(&mut self, c: char) {
+        let rest = self.rest();
+        match c {
+            '(' => {
+                self.pos += 1;
+                self.code(')');
+            }
+            '{' => {
+                self.pos += 1;
+                self.code('}');
+            }
+            '[' => self.content(),
+            '"' => self.pos += string_len(rest),
+            '/' if rest.starts_with("//") => self.pos += rest.find('\n').unwrap_or(rest.len()),
+            '/' if rest.starts_with("/*") => self.pos += self.len_until(2, "*/"),
+            c => self.pos += c.len_utf8(),
+        }
+    }
+
+    /// Блок садржаја (`[...]`) унутар кода.
+    fn content(&mut self) {
+        self.pos += 1;
+        self.raw();
+        self.markup(true);
+        if self.rest().starts_with(']') {
+            self.pos += 1;
+        }
+    }
+}
+
+/// Дужина ниске под наводницима.
+fn string_len(input: &str) -> usize {
+    let mut escaped = false;
+    for (i, c) in input.char_indices().skip(1) {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '"' => return i + 1,
+            _ => {}
+        }
+    }
+    input.len()
+}
+
+/// Пресловљава текст Typst документа функцијом `convert`.
+pub fn convert(input: &str, convert: impl Fn(&str) -> String) -> String {
+    let mut parser = Parser {
+        input,
+        pos: 0,
+        output: String::with_capacity(input.len()),
+        text_start: 0,
+        convert,
+    };
+    parser.markup(false);
+    parser.output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lat_to_cyr;
+
+    #[test]
+    fn test_convert() {
+        let input = r#"#set page(paper: "a4")
+#set text(lang: "sr")
+= Uvod <uvod>
+
+Ovo je *važan* tekst sa formulom $a + b$ i kodom `cargo build`.
+Pogledati @uvod i https://primer.rs, #link("https://primer.rs")[sajt].
+#figure(image("most.png"), caption: [Slika mosta])
+// komentar
+#let naslov = "Naslov"
+```rust
+fn main() {}
+```
+"#;
+        let expected = r#"#set page(paper: "a4")
+#set text(lang: "sr")
+= Увод <uvod>
+
+Ово је *важан* текст са формулом $a + b$ и кодом `cargo build`.
+Погледати @uvod и https://primer.rs, #link("https://primer.rs")[сајт].
+#figure(image("most.png"), caption: [Слика моста])
+// komentar
+#let naslov = "Naslov"
+```rust
+fn main() {}
+```
+"#;
+        assert_eq!(expected, convert(input, lat_to_cyr));
+    }
+}
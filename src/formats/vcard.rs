@@ -0,0 +1,50 @@
+//! Конверзија електронских визит карти (vCard, `.vcf`). Пресловљавају се
+//! имена, организације, звања, адресе и белешке (`FN`, `N`, `ORG`, `TITLE`,
+//! `NOTE`...), док остала својства (телефони, адресе е-поште, фотографије),
+//! параметри и escape секвенце остају непромењени.
+use super::ics::convert_properties;
+
+// Својства чија се вредност пресловљава
+const TEXT_PROPERTIES: &[&str] = &[
+    "FN", "N", "NICKNAME", "ORG", "TITLE", "ROLE", "ADR", "LABEL", "NOTE",
+];
+
+/// Пресловљава вредности текстуалних својстава функцијом `convert`. Ако је
+/// задата ознака језика `language` (нпр. `sr-Latn`) њоме се замењује ознака
+/// истог језика у параметру `LANGUAGE` пресловљених својстава.
+pub fn convert(input: &str, language: Option<&str>, convert: impl Fn(&str) -> String) -> String {
+    convert_properties(input, TEXT_PROPERTIES, language, &convert)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lat_to_cyr;
+
+    #[test]
+    fn test_convert() {
+        let input = "BEGIN:VCARD
+VERSION:4.0
+FN:Petar Petrović
+N:Petrović;Petar;;dr;
+item1.ORG;TYPE=work:Univerzitet u Novom Sadu;Fakultet tehničkih
+  nauka
+TITLE;LANGUAGE=sr-Latn:Profesor
+EMAIL:petar@primer.rs
+NOTE;CHARSET=UTF-8;ENCODING=QUOTED-PRINTABLE:Napomena
+END:VCARD
+";
+        let expected = "BEGIN:VCARD
+VERSION:4.0
+FN:Петар Петровић
+N:Петровић;Петар;;др;
+item1.ORG;TYPE=work:Универзитет у Новом Саду;Факу
+ лтет техничких наука
+TITLE;LANGUAGE=sr-Cyrl:Професор
+EMAIL:petar@primer.rs
+NOTE;CHARSET=UTF-8;ENCODING=QUOTED-PRINTABLE:Napomena
+END:VCARD
+";
+        assert_eq!(expected, convert(input, Some("sr-Cyrl"), lat_to_cyr));
+    }
+}
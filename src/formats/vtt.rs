@@ -0,0 +1,99 @@
+//! Конверзија WebVTT титлова у којој се пресловљава само текст титла, док
+//! заглавље, идентификатори, време приказивања, подешавања, коментари и ознаке
+//! (`<v Ана>`, `<c.zuta>`, `<i>`) остају непромењени.
+use super::srt::convert_tagged;
+use super::xml::convert_text;
+
+enum State {
+    /// Почетак блока (после празне линије).
+    BlockStart,
+    /// Блок који се не мења (заглавље, `NOTE`, `STYLE`, `REGION`).
+    Skip,
+    /// Идентификатор титла након кога следи време приказивања.
+    Timing,
+    /// Текст титла.
+    Payload,
+}
+
+/// Пресловљава текст титлова функцијом `convert`.
+pub fn convert(input: &str, convert: impl Fn(&str) -> String) -> String {
+    let mut output = String::with_capacity(input.len());
+    // Заглавље `WEBVTT` се прескаче до прве празне линије
+    let mut state = State::Skip;
+    for line in input.split_inclusive('\n') {
+        let content = line.trim_end_matches(['\n', '\r']);
+        if content.trim().is_empty() {
+            output.push_str(line);
+            state = State::BlockStart;
+            continue;
+        }
+        state = match state {
+            State::BlockStart if content.contains("-->") => State::Payload,
+            State::BlockStart
+                if ["NOTE", "STYLE", "REGION"].iter().any(|keyword| {
+                    content
+                        .strip_prefix(keyword)
+                        .is_some_and(|rest| rest.is_empty() || rest.starts_with([' ', '\t']))
+                }) =>
+            {
+                State::Skip
+            }
+            State::BlockStart => State::Timing,
+            State::Timing => State::Payload,
+            State::Skip => State::Skip,
+            State::Payload => {
+                let converted = convert_tagged(content, &|text: &str| convert_text(text, &convert));
+                output.push_str(&converted);
+                output.push_str(&line[content.len()..]);
+                continue;
+            }
+        };
+        output.push_str(line);
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lat_to_cyr;
+
+    #[test]
+    fn test_convert() {
+        let input = "WEBVTT - Film
+Kind: captions
+
+NOTE Ovo je komentar
+koji se ne menja
+
+STYLE
+::cue(.zuta) { color: yellow; }
+
+uvod
+00:01.000 --> 00:04.000 align:start line:0
+<v Ana>Dobro jutro!</v>
+Kako si &amp; <c.zuta>šta</c> radiš?
+
+00:05.000 --> 00:06.000
+<i>Kraj</i> <00:05.500>filma
+";
+        let expected = "WEBVTT - Film
+Kind: captions
+
+NOTE Ovo je komentar
+koji se ne menja
+
+STYLE
+::cue(.zuta) { color: yellow; }
+
+uvod
+00:01.000 --> 00:04.000 align:start line:0
+<v Ana>Добро јутро!</v>
+Како си &amp; <c.zuta>шта</c> радиш?
+
+00:05.000 --> 00:06.000
+<i>Крај</i> <00:05.500>филма
+";
+        assert_eq!(expected, convert(input, lat_to_cyr));
+    }
+}
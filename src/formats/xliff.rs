@@ -0,0 +1,63 @@
+//! Конверзија XLIFF 1.2 и 2.0 датотека. Пресловљава се само текст превода
+//! (`<target>`), док изворни текст, напомене и ознаке за замену (`<x/>`,
+//! `<ph>`...) остају непромењени. Ознака циљног језика (`target-language`,
+//! `trgLang`) се мења тако да одговара новом писму.
+use super::xml::{Content, convert_document};
+use crate::Error;
+
+// Елементи превода чији је садржај изворни код, а не текст
+const CODE_ELEMENTS: &[&str] = &["ph", "bpt", "ept", "it"];
+
+/// Пресловљава преводе у XLIFF датотеци функцијом `convert`. Ако је задата
+/// ознака језика `language` (нпр. `sr-Latn`) њоме се замењују ознаке циљног
+/// језика.
+pub fn convert(
+    input: &str,
+    language: Option<&str>,
+    convert: impl Fn(&str) -> String,
+) -> Result<String, Error> {
+    convert_document(input, language, &convert, |stack| {
+        if stack.contains(&"target") && !stack.iter().any(|element| CODE_ELEMENTS.contains(element))
+        {
+            Content::Text
+        } else {
+            Content::Unchanged
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lat_to_cyr;
+
+    #[test]
+    fn test_convert_1_2() {
+        let input = r#"<xliff version="1.2"><file source-language="en" target-language="sr-Latn-RS" datatype="plaintext">
+<body><trans-unit id="1"><source>Hello <x id="1"/> world</source>
+<target>Zdravo <x id="1"/> svete <ph id="2">%s</ph> <g id="3">danas</g></target>
+<note>Pozdrav</note></trans-unit></body></file></xliff>"#;
+        let expected = r#"<xliff version="1.2"><file source-language="en" target-language="sr-Cyrl-RS" datatype="plaintext">
+<body><trans-unit id="1"><source>Hello <x id="1"/> world</source>
+<target>Здраво <x id="1"/> свете <ph id="2">%s</ph> <g id="3">данас</g></target>
+<note>Pozdrav</note></trans-unit></body></file></xliff>"#;
+        assert_eq!(
+            expected,
+            convert(input, Some("sr-Cyrl"), lat_to_cyr).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_convert_2_0() {
+        let input = r#"<xliff version="2.0" srcLang="en" trgLang="sr"><file id="f1"><unit id="u1">
+<segment><source>Open <ph id="1"/></source><target>Otvori <ph id="1"/><pc id="2">fajl</pc></target></segment>
+</unit></file></xliff>"#;
+        let expected = r#"<xliff version="2.0" srcLang="en" trgLang="sr-Cyrl"><file id="f1"><unit id="u1">
+<segment><source>Open <ph id="1"/></source><target>Отвори <ph id="1"/><pc id="2">фајл</pc></target></segment>
+</unit></file></xliff>"#;
+        assert_eq!(
+            expected,
+            convert(input, Some("sr-Cyrl"), lat_to_cyr).unwrap()
+        );
+    }
+}
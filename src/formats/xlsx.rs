@@ -0,0 +1,416 @@
+//! Конверзија XLSX табела. Пресловљавају се дељени стрингови
+//! (`xl/sharedStrings.xml`) и стрингови унети у ћелије (`inlineStr`), опционо
+//! само у изабраним листовима и колонама, док формуле, имена опсега и бројеви
+//! остају непромењени.
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+use super::archive;
+use super::xml::{Token, convert_text, tokenize};
+use crate::Error;
+
+const WORKBOOK: &str = "xl/workbook.xml";
+const WORKBOOK_RELATIONSHIPS: &str = "xl/_rels/workbook.xml.rels";
+const SHARED_STRINGS: &str = "xl/sharedStrings.xml";
+
+/// Дељени стрингови који се пресловљавају.
+enum Shared {
+    All,
+    Selected {
+        /// Индекси стрингова који се пресловљавају.
+        converted: HashSet<usize>,
+        /// Стрингови који се користе и у ћелијама које се не пресловљавају.
+        /// Пресловљене копије се додају на крај листе, са датим индексима.
+        copies: BTreeMap<usize, usize>,
+    },
+}
+
+impl Shared {
+    fn is_converted(&self, index: usize) -> bool {
+        match self {
+            Shared::All => true,
+            Shared::Selected { converted, .. } => converted.contains(&index),
+        }
+    }
+
+    fn copy_count(&self) -> usize {
+        match self {
+            Shared::All => 0,
+            Shared::Selected { copies, .. } => copies.len(),
+        }
+    }
+
+    fn copy(&self, index: usize) -> Option<usize> {
+        match self {
+            Shared::All => None,
+            Shared::Selected { copies, .. } => copies.get(&index).copied(),
+        }
+    }
+}
+
+/// Пресловљава XLSX табелу функцијом `convert`. Ако је задата листа `sheets`
+/// пресловљавају се само листови са тим називима, а ако је задата листа
+/// `columns` само колоне са тим индексима (почев од 0, тј. колоне `A`).
+pub fn convert(
+    input: &[u8],
+    sheets: Option<&[String]>,
+    columns: Option<&[usize]>,
+    convert: impl Fn(&str) -> String,
+) -> Result<Vec<u8>, Error> {
+    let worksheets = worksheets(input)?;
+    let selected_sheets = match sheets {
+        Some(sheets) => sheets
+            .iter()
+            .map(|sheet| {
+                worksheets
+                    .get(sheet)
+                    .map(String::as_str)
+                    .ok_or_else(|| Error::InvalidSelector(sheet.clone()))
+            })
+            .collect::<Result<HashSet<_>, _>>()?,
+        None => worksheets.values().map(String::as_str).collect(),
+    };
+    let is_selected_column =
+        |column: usize| columns.is_none_or(|columns| columns.contains(&column));
+
+    let shared = if sheets.is_none() && columns.is_none() {
+        Shared::All
+    } else {
+        // Дељени стрингови из изабраних и осталих ћелија
+        let mut selected = BTreeSet::new();
+        let mut unselected = HashSet::new();
+        for path in worksheets.values() {
+            let Some(sheet) = archive::read(input, path)? else {
+                continue;
+            };
+            let is_selected_sheet = selected_sheets.contains(path.as_str());
+            let is_selected = |column| is_selected_sheet && is_selected_column(column);
+            convert_sheet(
+                &sheet,
+                is_selected,
+                &str::to_string,
+                |index, selected_cell| {
+                    if selected_cell {
+                        selected.insert(index);
+                    } else {
+                        unselected.insert(index);
+                    }
+                    None
+                },
+            )?;
+        }
+        let count = match archive::read(input, SHARED_STRINGS)? {
+            Some(strings) => tokenize(&strings)?
+                .iter()
+                .filter(
+                    |token| matches!(token, Token::StartTag(tag) if local_name(tag.name) == "si"),
+                )
+                .count(),
+            None => 0,
+        };
+        let (copied, converted): (Vec<_>, Vec<_>) = selected
+            .into_iter()
+            .partition(|index| unselected.contains(index));
+        Shared::Selected {
+            converted: converted.into_iter().collect(),
+            copies: copied.into_iter().zip(count..).collect(),
+        }
+    };
+
+    archive::transform(input, |name, content| {
+        let converted = if name == SHARED_STRINGS {
+            convert_shared_strings(archive::text(content)?, &shared, &convert)?
+        } else if selected_sheets.contains(name) {
+            convert_sheet(
+                archive::text(content)?,
+                is_selected_column,
+                &convert,
+                |index, selected| selected.then(|| shared.copy(index)).flatten(),
+            )?
+        } else {
+            return Ok(None);
+        };
+        Ok(Some(converted.into_bytes()))
+    })
+}
+
+/// Листови радне свеске: називи и путање до фајлова у архиви.
+fn worksheets(input: &[u8]) -> Result<HashMap<String, String>, Error> {
+    let (Some(workbook), Some(relationships)) = (
+        archive::read(input, WORKBOOK)?,
+        archive::read(input, WORKBOOK_RELATIONSHIPS)?,
+    ) else {
+        return Ok(HashMap::new());
+    };
+    let mut targets = HashMap::new();
+    for token in tokenize(&relationships)? {
+        if let Token::StartTag(tag) = token
+            && let (Some(id), Some(target)) = (tag.attribute("Id"), tag.attribute("Target"))
+        {
+            let path = match target.strip_prefix('/') {
+                Some(path) => path.to_string(),
+                None => format!("xl/{target}"),
+            };
+            targets.insert(id, path);
+        }
+    }
+    let mut worksheets = HashMap::new();
+    for token in tokenize(&workbook)? {
+        if let Token::StartTag(tag) = token
+            && local_name(tag.name) == "sheet"
+            && let Some(name) = tag.attribute("name")
+            && let Some(id) = tag.attributes.iter().find(|a| a.name.ends_with(":id"))
+            && let Some(path) = targets.get(id.value)
+        {
+            worksheets.insert(name.to_string(), path.clone());
+        }
+    }
+    Ok(worksheets)
+}
+
+/// Пресловљава стрингове унете у ћелије листа у колонама за које
+/// `is_selected` врати `true`. За сваку ћелију са дељеним стрингом позива се
+/// `shared` са индексом стринга и податком да ли је ћелија изабрана, а она
+/// враћа нови индекс стринга ако се мења.
+fn convert_sheet(
+    input: &str,
+    is_selected: impl Fn(usize) -> bool,
+    convert: &impl Fn(&str) -> String,
+    mut shared: impl FnMut(usize, bool) -> Option<usize>,
+) -> Result<String, Error> {
+    let mut output = String::with_capacity(input.len());
+    let mut stack: Vec<&str> = Vec::new();
+    let mut column = None;
+    // Да ли је текућа ћелија изабрана и да ли садржи дељени стринг
+    let mut cell = None;
+    for token in tokenize(input)? {
+        match token {
+            Token::Text(text) => match (cell, stack.last()) {
+                (Some((selected, true)), Some(&"v")) => {
+                    match text.trim().parse().ok().and_then(|i| shared(i, selected)) {
+                        Some(index) => output.push_str(&index.to_string()),
+                        None => output.push_str(text),
+                    }
+                }
+                (Some((true, false)), Some(&"t")) if stack.contains(&"is") => {
+                    output.push_str(&convert_text(text, convert));
+                }
+                _ => output.push_str(text),
+            },
+            Token::CData(raw) | Token::Markup(raw) => output.push_str(raw),
+            Token::StartTag(tag) => {
+                match local_name(tag.name) {
+                    "row" => column = None,
+                    "c" => {
+                        let current = tag
+                            .attribute("r")
+                            .and_then(column_index)
+                            .unwrap_or(column.map_or(0, |column| column + 1));
+                        column = Some(current);
+                        cell = (!tag.self_closing)
+                            .then(|| (is_selected(current), tag.attribute("t") == Some("s")));
+                    }
+                    _ => {}
+                }
+                if !tag.self_closing {
+                    stack.push(local_name(tag.name));
+                }
+                output.push_str(tag.raw);
+            }
+            Token::EndTag { name, raw } => {
+                if let Some(open) = stack.iter().rposition(|&open| open == local_name(name)) {
+                    stack.truncate(open);
+                }
+                if local_name(name) == "c" {
+                    cell = None;
+                }
+                output.push_str(raw);
+            }
+        }
+    }
+    Ok(output)
+}
+
+/// Пресловљава дељене стрингове и додаје пресловљене копије стрингова који
+/// се користе и у ћелијама које се не пресловљавају.
+fn convert_shared_strings(
+    input: &str,
+    shared: &Shared,
+    convert: &impl Fn(&str) -> String,
+) -> Result<String, Error> {
+    let mut output = String::with_capacity(input.len());
+    let mut stack: Vec<&str> = Vec::new();
+    let mut index = 0;
+    // Изворни и пресловљени садржај текућег стринга
+    let mut string: Option<(String, String)> = None;
+    let mut copies = Vec::new();
+    for token in tokenize(input)? {
+        let (raw, converted) = match &token {
+            Token::Text(text) if stack.last() == Some(&"t") && !stack.contains(&"rPh") => {
+                (*text, convert_text(text, convert))
+            }
+            Token::Text(raw) | Token::CData(raw) | Token::Markup(raw) => (*raw, raw.to_string()),
+            Token::StartTag(tag) if local_name(tag.name) == "sst" => {
+                let tag_with_count = tag.with_attributes(|attribute| {
+                    let count = attribute.value.parse::<usize>().ok()?;
+                    (attribute.name == "uniqueCount")
+                        .then(|| (count + shared.copy_count()).to_string())
+                });
+                (tag.raw, tag_with_count)
+            }
+            Token::StartTag(tag) => (tag.raw, tag.raw.to_string()),
+            Token::EndTag { name, raw } if local_name(name) == "sst" => {
+                (*raw, copies.concat() + *raw)
+            }
+            Token::EndTag { raw, .. } => (*raw, raw.to_string()),
+        };
+        if let Token::StartTag(tag) = &token
+            && !tag.self_closing
+        {
+            stack.push(local_name(tag.name));
+            if local_name(tag.name) == "si" {
+                string = Some(Default::default());
+            }
+        }
+        match &mut string {
+            Some((original, converted_string)) => {
+                original.push_str(raw);
+                converted_string.push_str(&converted);
+            }
+            None => output.push_str(&converted),
+        }
+        match token {
+            Token::StartTag(tag) if tag.self_closing && local_name(tag.name) == "si" => index += 1,
+            Token::EndTag { name, .. } => {
+                let name = local_name(name);
+                if let Some(open) = stack.iter().rposition(|&open| open == name) {
+                    stack.truncate(open);
+                }
+                if name == "si"
+                    && let Some((original, converted)) = string.take()
+                {
+                    if shared.copy(index).is_some() {
+                        copies.push(converted.clone());
+                    }
+                    if shared.is_converted(index) {
+                        output.push_str(&converted);
+                    } else {
+                        output.push_str(&original);
+                    }
+                    index += 1;
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(output)
+}
+
+/// Назив елемента без префикса простора имена.
+fn local_name(name: &str) -> &str {
+    name.rsplit_once(':').map_or(name, |(_, local)| local)
+}
+
+/// Индекс колоне (почев од 0) из ознаке ћелије (нпр. `B3`).
+fn column_index(reference: &str) -> Option<usize> {
+    let letters = reference
+        .chars()
+        .take_while(char::is_ascii_alphabetic)
+        .map(|c| c.to_ascii_uppercase() as usize - 'A' as usize + 1)
+        .collect::<Vec<_>>();
+    let column = letters
+        .into_iter()
+        .fold(0, |column, letter| column * 26 + letter);
+    column.checked_sub(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, Read, Write};
+
+    use zip::write::SimpleFileOptions;
+    use zip::{ZipArchive, ZipWriter};
+
+    use super::*;
+    use crate::lat_to_cyr;
+
+    fn workbook(sheet: &str) -> Vec<u8> {
+        let files = [
+            (
+                WORKBOOK,
+                r#"<workbook xmlns:r="r"><sheets><sheet name="Lista" sheetId="1" r:id="rId1"/></sheets>
+<definedNames><definedName name="Ime">Lista!$A$1</definedName></definedNames></workbook>"#,
+            ),
+            (
+                WORKBOOK_RELATIONSHIPS,
+                r#"<Relationships><Relationship Id="rId1" Target="worksheets/sheet1.xml"/></Relationships>"#,
+            ),
+            (
+                SHARED_STRINGS,
+                r#"<sst count="4" uniqueCount="3"><si><t>Ivo</t></si><si><r><rPr/><t>Niš</t></r><rPh><t>x</t></rPh></si><si><t>Beograd</t></si></sst>"#,
+            ),
+            ("xl/worksheets/sheet1.xml", sheet),
+        ];
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, content) in files {
+            writer
+                .start_file(name, SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(content.as_bytes()).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    fn read(input: Vec<u8>, name: &str) -> String {
+        let mut archive = ZipArchive::new(Cursor::new(input)).unwrap();
+        let mut content = String::new();
+        archive
+            .by_name(name)
+            .unwrap()
+            .read_to_string(&mut content)
+            .unwrap();
+        content
+    }
+
+    const SHEET: &str = r#"<worksheet><sheetData><row r="1"><c r="A1" t="s"><v>0</v></c><c r="B1" t="s"><v>1</v></c><c r="C1" t="inlineStr"><is><t>Reč</t></is></c></row><row r="2"><c r="A2" t="s"><v>2</v></c><c r="B2" t="s"><v>0</v></c><c r="C2"><f>CONCAT(A1,"Ivo")</f><v>1</v></c></row></sheetData></worksheet>"#;
+
+    #[test]
+    fn test_convert() {
+        let output = convert(&workbook(SHEET), None, None, lat_to_cyr).unwrap();
+        assert_eq!(
+            r#"<sst count="4" uniqueCount="3"><si><t>Иво</t></si><si><r><rPr/><t>Ниш</t></r><rPh><t>x</t></rPh></si><si><t>Београд</t></si></sst>"#,
+            read(output.clone(), SHARED_STRINGS)
+        );
+        assert_eq!(
+            SHEET.replace("Reč", "Реч"),
+            read(output.clone(), "xl/worksheets/sheet1.xml")
+        );
+        assert!(read(output, WORKBOOK).contains("<definedName name=\"Ime\">Lista!$A$1"));
+    }
+
+    #[test]
+    fn test_convert_columns() {
+        // Колона B: стринг 1 се пресловљава, а стринг 0 се користи и у
+        // колони A па се додаје пресловљена копија
+        let sheets = ["Lista".to_string()];
+        let output = convert(&workbook(SHEET), Some(&sheets), Some(&[1]), lat_to_cyr).unwrap();
+        assert_eq!(
+            r#"<sst count="4" uniqueCount="4"><si><t>Ivo</t></si><si><r><rPr/><t>Ниш</t></r><rPh><t>x</t></rPh></si><si><t>Beograd</t></si><si><t>Иво</t></si></sst>"#,
+            read(output.clone(), SHARED_STRINGS)
+        );
+        assert_eq!(
+            SHEET.replace(r#"<c r="B2" t="s"><v>0</v>"#, r#"<c r="B2" t="s"><v>3</v>"#),
+            read(output, "xl/worksheets/sheet1.xml")
+        );
+        assert!(matches!(
+            convert(&workbook(SHEET), Some(&["X".to_string()]), None, lat_to_cyr),
+            Err(Error::InvalidSelector(_))
+        ));
+    }
+
+    #[test]
+    fn test_column_index() {
+        assert_eq!(Some(0), column_index("A1"));
+        assert_eq!(Some(27), column_index("AB12"));
+        assert_eq!(None, column_index("12"));
+    }
+}
@@ -0,0 +1,605 @@
+//! Конверзија XML докумената (DocBook, TEI...). Пресловљава се текст елемената
+//! и изабрани атрибути, док структура, CDATA секције, коментари и инструкције
+//! обраде остају непромењени.
+use std::str::FromStr;
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use super::syntax_error;
+use crate::Error;
+
+// Атрибути који се пресловљавају у [`convert_document`]
+const TEXT_ATTRIBUTES: &[&str] = &["alt", "title"];
+
+// Атрибути са ознаком језика који се мењају у [`convert_document`]
+const LANGUAGE_ATTRIBUTES: &[&str] = &["lang", "xml:lang", "target-language", "trgLang"];
+
+// Референце на ентитете (`&amp;`, `&#1078;`)
+static ENTITY: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"&(#[0-9]+|#x[0-9a-fA-F]+|[\w.-]+);").unwrap());
+
+// Именовани HTML ентитети за слова латинице која се користе у српском
+const LETTER_ENTITIES: &[(&str, char)] = &[
+    ("Ccaron", 'Č'),
+    ("ccaron", 'č'),
+    ("Cacute", 'Ć'),
+    ("cacute", 'ć'),
+    ("Dstrok", 'Đ'),
+    ("dstrok", 'đ'),
+    ("Scaron", 'Š'),
+    ("scaron", 'š'),
+    ("Zcaron", 'Ž'),
+    ("zcaron", 'ž'),
+];
+
+/// Део XML документа.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Token<'a> {
+    Text(&'a str),
+    /// `<![CDATA[...]]>` заједно са ознакама почетка и краја.
+    CData(&'a str),
+    /// Коментари, инструкције обраде и декларације (`<!DOCTYPE ...>`).
+    Markup(&'a str),
+    StartTag(Tag<'a>),
+    EndTag {
+        name: &'a str,
+        raw: &'a str,
+    },
+}
+
+/// Почетна ознака елемента.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Tag<'a> {
+    pub(crate) name: &'a str,
+    pub(crate) raw: &'a str,
+    pub(crate) attributes: Vec<Attribute<'a>>,
+    pub(crate) self_closing: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Attribute<'a> {
+    pub(crate) name: &'a str,
+    pub(crate) value: &'a str,
+    // Почетак вредности у бајтовима унутар ознаке
+    offset: usize,
+}
+
+impl<'a> Tag<'a> {
+    /// Вредност атрибута `name`.
+    pub(crate) fn attribute(&self, name: &str) -> Option<&'a str> {
+        self.attributes
+            .iter()
+            .find(|attribute| attribute.name == name)
+            .map(|attribute| attribute.value)
+    }
+
+    /// Враћа ознаку у којој су вредности атрибута за које `convert` врати
+    /// `Some` замењене. Остатак ознаке (размаци, наводници) се не мења.
+    pub(crate) fn with_attributes(&self, convert: impl Fn(&Attribute) -> Option<String>) -> String {
+        let mut output = String::with_capacity(self.raw.len());
+        let mut pos = 0;
+        for attribute in &self.attributes {
+            if let Some(value) = convert(attribute) {
+                output.push_str(&self.raw[pos..attribute.offset]);
+                output.push_str(&value);
+                pos = attribute.offset + attribute.value.len();
+            }
+        }
+        output.push_str(&self.raw[pos..]);
+        output
+    }
+}
+
+/// Дели XML документ на делове.
+pub(crate) fn tokenize(input: &str) -> Result<Vec<Token<'_>>, Error> {
+    let mut tokens = Vec::new();
+    let mut pos = 0;
+    while pos < input.len() {
+        let (token, len) = next_token(input, pos)?;
+        tokens.push(token);
+        pos += len;
+    }
+    Ok(tokens)
+}
+
+/// Дужина ознаке елемента, коментара, CDATA секције или декларације на
+/// почетку текста, или `None` ако текст не почиње исправном ознаком (нпр. HTML
+/// ознаке у Markdown документу).
+pub(crate) fn markup_len(input: &str) -> Option<usize> {
+    match next_token(input, 0).ok()? {
+        (Token::Text(_), _) => None,
+        (Token::EndTag { name, .. }, _) if name.is_empty() || !name.chars().all(is_name_char) => {
+            None
+        }
+        (_, len) => Some(len),
+    }
+}
+
+/// Део документа који почиње на бајту `pos` и његова дужина.
+fn next_token(input: &str, pos: usize) -> Result<(Token<'_>, usize), Error> {
+    let rest = &input[pos..];
+    Ok(if rest.starts_with("<!--") {
+        let len = find_end(input, pos, "-->", "незатворен коментар")?;
+        (Token::Markup(&rest[..len]), len)
+    } else if rest.starts_with("<![CDATA[") {
+        let len = find_end(input, pos, "]]>", "незатворена CDATA секција")?;
+        (Token::CData(&rest[..len]), len)
+    } else if rest.starts_with("<?") {
+        let len = find_end(input, pos, "?>", "незатворена инструкција обраде")?;
+        (Token::Markup(&rest[..len]), len)
+    } else if rest.starts_with("<!") {
+        let len = declaration_len(rest)
+            .ok_or_else(|| syntax_error(input, pos, "незатворена декларација"))?;
+        (Token::Markup(&rest[..len]), len)
+    } else if rest.starts_with("</") {
+        let len = find_end(input, pos, ">", "незатворена ознака")?;
+        let raw = &rest[..len];
+        let name = raw[2..len - 1].trim();
+        (Token::EndTag { name, raw }, len)
+    } else if rest.starts_with('<') && rest[1..].starts_with(is_name_char) {
+        let tag = parse_tag(rest)
+            .map_err(|(offset, message)| syntax_error(input, pos + offset, message))?;
+        let len = tag.raw.len();
+        (Token::StartTag(tag), len)
+    } else {
+        // Текст до следеће ознаке (усамљено `<` се толерише као текст)
+        let first = rest.chars().next().map_or(0, char::len_utf8);
+        let len = rest[first..].find('<').map_or(rest.len(), |i| i + first);
+        (Token::Text(&rest[..len]), len)
+    })
+}
+
+fn is_name_char(c: char) -> bool {
+    c.is_alphanumeric() || "_:.-".contains(c)
+}
+
+fn find_end(input: &str, pos: usize, end: &str, message: &str) -> Result<usize, Error> {
+    input[pos..]
+        .find(end)
+        .map(|i| i + end.len())
+        .ok_or_else(|| syntax_error(input, pos, message))
+}
+
+// Декларација може садржати унутрашњи подскуп у угластим заградама
+// (`<!DOCTYPE doc [<!ENTITY ...>]>`)
+fn declaration_len(input: &str) -> Option<usize> {
+    let mut depth = 0;
+    let mut quote = None;
+    for (i, c) in input.char_indices().skip(2) {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '[' | '<') => depth += 1,
+            (None, ']') => depth -= 1,
+            (None, '>') if depth == 0 => return Some(i + 1),
+            (None, '>') => depth -= 1,
+            _ => {}
+        }
+    }
+    None
+}
+
+fn parse_tag(input: &str) -> Result<Tag<'_>, (usize, &'static str)> {
+    let name_len = input[1..]
+        .find(|c| !is_name_char(c))
+        .ok_or((0, "незатворена ознака"))?
+        + 1;
+    let mut attributes = Vec::new();
+    let mut pos = name_len;
+    loop {
+        pos += input[pos..].len() - input[pos..].trim_start().len();
+        let rest = &input[pos..];
+        if rest.starts_with("/>") || rest.starts_with('>') {
+            let self_closing = rest.starts_with('/');
+            let len = pos + if self_closing { 2 } else { 1 };
+            return Ok(Tag {
+                name: &input[1..name_len],
+                raw: &input[..len],
+                attributes,
+                self_closing,
+            });
+        }
+        let attr_len = rest
+            .find(|c| !is_name_char(c))
+            .ok_or((pos, "незатворена ознака"))?;
+        if attr_len == 0 {
+            return Err((pos, "неисправан атрибут"));
+        }
+        let name = &rest[..attr_len];
+        pos += attr_len;
+        pos += input[pos..].len() - input[pos..].trim_start().len();
+        if !input[pos..].starts_with('=') {
+            return Err((pos, "недостаје вредност атрибута"));
+        }
+        pos += 1;
+        pos += input[pos..].len() - input[pos..].trim_start().len();
+        let quote = input[pos..]
+            .chars()
+            .next()
+            .filter(|&c| c == '"' || c == '\'')
+            .ok_or((pos, "вредност атрибута мора бити под наводницима"))?;
+        let value_len = input[pos + 1..]
+            .find(quote)
+            .ok_or((pos, "незатворена вредност атрибута"))?;
+        attributes.push(Attribute {
+            name,
+            value: &input[pos + 1..pos + 1 + value_len],
+            offset: pos + 1,
+        });
+        pos += value_len + 2;
+    }
+}
+
+/// Слово на које упућује референца на ентитет (`&scaron;`, `&#353;`,
+/// `&#x161;`).
+fn decode_letter(entity: &str) -> Option<char> {
+    let name = entity.strip_prefix('&')?.strip_suffix(';')?;
+    let letter = match name.strip_prefix('#') {
+        Some(code) => match code.strip_prefix('x') {
+            Some(hex) => u32::from_str_radix(hex, 16).ok(),
+            None => code.parse().ok(),
+        }
+        .and_then(char::from_u32),
+        None => LETTER_ENTITIES
+            .iter()
+            .find(|(entity, _)| *entity == name)
+            .map(|&(_, letter)| letter),
+    }?;
+    letter.is_alphabetic().then_some(letter)
+}
+
+/// Пресловљава текст у коме могу бити референце на ентитете. Ентитети који
+/// упућују на слова (`&scaron;`, `&#1096;`) се декодирају и пресловљавају
+/// заједно са околним текстом, а остали (`&amp;`, `&nbsp;`) остају
+/// непромењени. Пресловљена слова се у резултату записују као обични знаци.
+pub(crate) fn convert_text(text: &str, convert: &impl Fn(&str) -> String) -> String {
+    let mut output = String::with_capacity(text.len());
+    // Текст до следећег ентитета који не упућује на слово
+    let mut run = String::new();
+    let mut pos = 0;
+    for entity in ENTITY.find_iter(text) {
+        run.push_str(&text[pos..entity.start()]);
+        match decode_letter(entity.as_str()) {
+            Some(letter) => run.push(letter),
+            None => {
+                output.push_str(&convert(&run));
+                output.push_str(entity.as_str());
+                run.clear();
+            }
+        }
+        pos = entity.end();
+    }
+    run.push_str(&text[pos..]);
+    output.push_str(&convert(&run));
+    output
+}
+
+/// Селектор атрибута који се пресловљава: `@title` (атрибут било ког
+/// елемента), `img/@alt` или `figure/img/@alt` (атрибут елемента унутар
+/// задатих предака). `*` одговара било ком елементу.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AttributeSelector {
+    path: Vec<String>,
+    attribute: String,
+}
+
+impl AttributeSelector {
+    /// Да ли селектор одговара атрибуту последњег елемента на стеку отворених
+    /// елемената.
+    fn matches(&self, stack: &[&str], attribute: &str) -> bool {
+        self.attribute == attribute
+            && self.path.len() <= stack.len()
+            && self
+                .path
+                .iter()
+                .rev()
+                .zip(stack.iter().rev())
+                .all(|(selector, name)| selector == "*" || selector == name)
+    }
+}
+
+impl FromStr for AttributeSelector {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || Error::InvalidSelector(s.to_string());
+        let (path, attribute) = s.rsplit_once('@').ok_or_else(invalid)?;
+        let path = match path.trim_start_matches('/') {
+            "" => "",
+            path => path.strip_suffix('/').ok_or_else(invalid)?,
+        };
+        if attribute.is_empty() || !attribute.chars().all(is_name_char) {
+            return Err(invalid());
+        }
+        let path = path
+            .split('/')
+            .filter(|name| !name.is_empty())
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+        if path
+            .iter()
+            .any(|name| name != "*" && !name.chars().all(is_name_char))
+        {
+            return Err(invalid());
+        }
+        Ok(AttributeSelector {
+            path,
+            attribute: attribute.to_string(),
+        })
+    }
+}
+
+/// Пресловљава текст елемената и вредности атрибута који одговарају неком од
+/// селектора функцијом `convert`. CDATA секције, коментари, инструкције обраде
+/// и ентитети који не упућују на слова се не мењају, а ентитети за слова се
+/// пресловљавају и записују као обични знаци.
+pub fn convert(
+    input: &str,
+    attributes: &[AttributeSelector],
+    convert: impl Fn(&str) -> String,
+) -> Result<String, Error> {
+    let mut output = String::with_capacity(input.len());
+    let mut stack: Vec<&str> = Vec::new();
+    for token in tokenize(input)? {
+        match token {
+            Token::Text(text) => output.push_str(&convert_text(text, &convert)),
+            Token::CData(raw) | Token::Markup(raw) => output.push_str(raw),
+            Token::StartTag(tag) => {
+                stack.push(tag.name);
+                output.push_str(&tag.with_attributes(|attribute| {
+                    attributes
+                        .iter()
+                        .any(|selector| selector.matches(&stack, attribute.name))
+                        .then(|| convert_text(attribute.value, &convert))
+                }));
+                if tag.self_closing {
+                    stack.pop();
+                }
+            }
+            Token::EndTag { name, raw } => {
+                if let Some(open) = stack.iter().rposition(|&open| open == name) {
+                    stack.truncate(open);
+                }
+                output.push_str(raw);
+            }
+        }
+    }
+    Ok(output)
+}
+
+/// Обрада садржаја елемента у [`convert_document`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Content {
+    /// Текст (и атрибути `alt` и `title`) се пресловљава.
+    Text,
+    /// Текст је ознака језика која се мења (нпр. `dc:language`).
+    Language,
+    Unchanged,
+}
+
+/// Пресловљава XML документ (EPUB, FB2) у коме `content_of` на основу
+/// отворених елемената одређује обраду садржаја. Ако је задата ознака језика
+/// `language` (нпр. `sr-Latn`) њоме се замењују ознаке истог језика, као и
+/// вредности атрибута `lang`/`xml:lang` и циљног језика XLIFF датотека.
+pub(crate) fn convert_document(
+    input: &str,
+    language: Option<&str>,
+    convert: &impl Fn(&str) -> String,
+    content_of: impl Fn(&[&str]) -> Content,
+) -> Result<String, Error> {
+    let mut output = String::with_capacity(input.len());
+    let mut stack: Vec<&str> = Vec::new();
+    for token in tokenize(input)? {
+        match token {
+            Token::Text(text) => match content_of(&stack) {
+                Content::Text => output.push_str(&convert_text(text, convert)),
+                Content::Language => {
+                    let tag = text.trim();
+                    match language.and_then(|language| retag(tag, language)) {
+                        Some(new_tag) => output.push_str(&text.replace(tag, &new_tag)),
+                        None => output.push_str(text),
+                    }
+                }
+                Content::Unchanged => output.push_str(text),
+            },
+            Token::CData(raw) | Token::Markup(raw) => output.push_str(raw),
+            Token::StartTag(tag) => {
+                stack.push(tag.name);
+                let is_text = content_of(&stack) == Content::Text;
+                output.push_str(&tag.with_attributes(|attribute| match attribute.name {
+                    name if LANGUAGE_ATTRIBUTES.contains(&name) => {
+                        language.and_then(|language| retag(attribute.value, language))
+                    }
+                    name if is_text && TEXT_ATTRIBUTES.contains(&name) => {
+                        Some(convert_text(attribute.value, convert))
+                    }
+                    _ => None,
+                }));
+                if tag.self_closing {
+                    stack.pop();
+                }
+            }
+            Token::EndTag { name, raw } => {
+                if let Some(open) = stack.iter().rposition(|&open| open == name) {
+                    stack.truncate(open);
+                }
+                output.push_str(raw);
+            }
+        }
+    }
+    Ok(output)
+}
+
+/// Замењује ознаку језика `tag` (нпр. `sr-Latn-RS`) ознаком `language` (нпр.
+/// `sr-Cyrl`) ако означавају исти језик. Ознака региона се задржава
+/// (`sr-Cyrl-RS`).
+pub(crate) fn retag(tag: &str, language: &str) -> Option<String> {
+    let mut subtags = tag.split(['-', '_']);
+    let primary = subtags.next()?;
+    if !language
+        .split('-')
+        .next()
+        .is_some_and(|language| language.eq_ignore_ascii_case(primary))
+    {
+        return None;
+    }
+    // Ознака писма има четири слова
+    let rest = subtags
+        .filter(|subtag| !(subtag.len() == 4 && subtag.chars().all(|c| c.is_ascii_alphabetic())))
+        .collect::<Vec<_>>();
+    Some(
+        [language]
+            .into_iter()
+            .chain(rest)
+            .collect::<Vec<_>>()
+            .join("-"),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{cyr_to_lat, lat_to_cyr};
+
+    fn selectors(selectors: &[&str]) -> Vec<AttributeSelector> {
+        selectors.iter().map(|s| s.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn test_tokenize() {
+        let tokens =
+            tokenize("<?xml version=\"1.0\"?><a x='1' y = \"2\"/>t&amp;<!-- k --></a>").unwrap();
+        assert_eq!(5, tokens.len());
+        assert_eq!(Token::Markup("<?xml version=\"1.0\"?>"), tokens[0]);
+        let Token::StartTag(tag) = &tokens[1] else {
+            panic!("очекивана почетна ознака");
+        };
+        assert_eq!(("a", true), (tag.name, tag.self_closing));
+        assert_eq!(
+            vec![("x", "1"), ("y", "2")],
+            tag.attributes
+                .iter()
+                .map(|a| (a.name, a.value))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(Token::Text("t&amp;"), tokens[2]);
+        assert_eq!(
+            Token::EndTag {
+                name: "a",
+                raw: "</a>"
+            },
+            tokens[4]
+        );
+    }
+
+    #[test]
+    fn test_syntax_errors() {
+        assert!(matches!(
+            tokenize("<a>\n<!-- коментар"),
+            Err(Error::Syntax { line: 2, .. })
+        ));
+        assert!(tokenize("<a x=1>").is_err());
+        assert!(tokenize("<a x").is_err());
+    }
+
+    #[test]
+    fn test_selectors() {
+        assert!("@title".parse::<AttributeSelector>().is_ok());
+        assert!("//figure/img/@alt".parse::<AttributeSelector>().is_ok());
+        assert!("title".parse::<AttributeSelector>().is_err());
+        assert!("img@alt".parse::<AttributeSelector>().is_err());
+        assert!("img/@".parse::<AttributeSelector>().is_err());
+    }
+
+    #[test]
+    fn test_retag() {
+        assert_eq!(Some("sr-Cyrl".into()), retag("sr", "sr-Cyrl"));
+        assert_eq!(Some("sr-Latn-RS".into()), retag("sr-Cyrl-RS", "sr-Latn"));
+        assert_eq!(Some("sr-Latn-ME".into()), retag("sr_ME", "sr-Latn"));
+        assert_eq!(None, retag("en-US", "sr-Latn"));
+    }
+
+    #[test]
+    fn test_convert_document() {
+        let input = r#"<html xmlns="http://www.w3.org/1999/xhtml" xml:lang="sr" lang="sr-Latn-RS">
+<head><title>Na Drini</title><style>p { font: serif; }</style></head>
+<body><p>Ivo &amp; <span lang="en">Andrić</span></p><img alt="Most" src="most.jpg"/></body>
+</html>"#;
+        let expected = r#"<html xmlns="http://www.w3.org/1999/xhtml" xml:lang="sr-Cyrl" lang="sr-Cyrl-RS">
+<head><title>На Дрини</title><style>p { font: serif; }</style></head>
+<body><p>Иво &amp; <span lang="en">Андрић</span></p><img alt="Мост" src="most.jpg"/></body>
+</html>"#;
+        let content_of = |stack: &[&str]| {
+            if stack.contains(&"style") {
+                Content::Unchanged
+            } else {
+                Content::Text
+            }
+        };
+        assert_eq!(
+            expected,
+            convert_document(input, Some("sr-Cyrl"), &lat_to_cyr, content_of).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_convert_text() {
+        assert_eq!(
+            "Приштина &amp; Ниш",
+            convert_text("Pri&scaron;tina &amp; Niš", &lat_to_cyr)
+        );
+        assert_eq!("Нежно", convert_text("Ne&#382;no", &lat_to_cyr));
+        assert_eq!(
+            "šuma&nbsp;i reka",
+            convert_text("&#x448;ума&nbsp;и река", &cyr_to_lat)
+        );
+        // Ентитети који не упућују на слова и непознати ентитети
+        assert_eq!(
+            "&#8230; &autor;",
+            convert_text("&#8230; &autor;", &lat_to_cyr)
+        );
+    }
+
+    #[test]
+    fn test_convert() {
+        let input = r#"<?xml version="1.0"?>
+<!DOCTYPE book [<!ENTITY autor "Vuk">]>
+<book lang="sr" title="Knjiga">
+  <!-- komentar -->
+  <para>Ljubav &amp; &autor; <emphasis>ćuprija</emphasis></para>
+  <figure><img alt="Slika" src="slika.png"/></figure>
+  <img alt="Druga"/>
+  <code><![CDATA[if a < b]]></code>
+</book>"#;
+        let expected = r#"<?xml version="1.0"?>
+<!DOCTYPE book [<!ENTITY autor "Vuk">]>
+<book lang="sr" title="Књига">
+  <!-- komentar -->
+  <para>Љубав &amp; &autor; <emphasis>ћуприја</emphasis></para>
+  <figure><img alt="Слика" src="slika.png"/></figure>
+  <img alt="Druga"/>
+  <code><![CDATA[if a < b]]></code>
+</book>"#;
+        assert_eq!(
+            expected,
+            convert(
+                input,
+                &selectors(&["book/@title", "figure/img/@alt"]),
+                lat_to_cyr
+            )
+            .unwrap()
+        );
+        // Селектор без путање одговара атрибуту било ког елемента
+        assert_eq!(
+            "<img alt=\"Слика\"/><p alt='Текст'/>",
+            convert(
+                "<img alt=\"Slika\"/><p alt='Tekst'/>",
+                &selectors(&["@alt"]),
+                lat_to_cyr
+            )
+            .unwrap()
+        );
+    }
+}
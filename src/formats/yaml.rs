@@ -0,0 +1,276 @@
+//! Конверзија YAML докумената у којој се пресловљавају само скаларне вредности
+//! стрингова, док кључеви, сидра (`&a`), псеудоними (`*a`), ознаке типова
+//! (`!!str`) и коментари остају непромењени.
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use super::json::convert_escaped;
+
+// Скалари који нису стрингови (бројеви, логичке вредности, датуми...)
+static NON_STRING: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?ix)^(
+            true|false|yes|no|y|n|on|off|null|~
+            |[-+]?(\d[\d_]*)?\.?\d[\d_]*([eE][-+]?\d+)?
+            |[-+]?\.(inf|nan)
+            |0x[0-9a-f]+|0o[0-7]+
+            |\d{4}-\d\d-\d\d([Tt\s].*)?
+        )$",
+    )
+    .unwrap()
+});
+
+/// Пресловљава вредности скалара YAML документа функцијом `convert`.
+pub fn convert(input: &str, convert: impl Fn(&str) -> String) -> String {
+    let mut output = String::with_capacity(input.len());
+    // Увлачење линије која започиње блок скалар (`опис: |`)
+    let mut block_scalar: Option<usize> = None;
+
+    for line in input.split_inclusive('\n') {
+        let content = line.trim_end_matches(['\n', '\r']);
+        let ending = &line[content.len()..];
+        let indent = content.len() - content.trim_start_matches([' ', '\t']).len();
+        let trimmed = &content[indent..];
+
+        if let Some(parent) = block_scalar {
+            if trimmed.is_empty() || indent > parent {
+                output.push_str(&content[..indent]);
+                output.push_str(&convert(trimmed));
+                output.push_str(ending);
+                continue;
+            }
+            block_scalar = None;
+        }
+
+        if trimmed.is_empty()
+            || trimmed.starts_with('#')
+            || trimmed.starts_with('%')
+            || content.starts_with("---")
+            || content.starts_with("...")
+        {
+            output.push_str(line);
+            continue;
+        }
+
+        // Ознаке елемената низа (`- `) и кључ се не мењају
+        let mut value_start = indent;
+        while content[value_start..].starts_with("- ") || &content[value_start..] == "-" {
+            value_start += 1;
+            value_start += content[value_start..].len() - content[value_start..].trim_start().len();
+        }
+        // Сложени кључ (`? kljuc`) се не мења, а вредност је у линији `: `
+        if content[value_start..].starts_with("? ") || &content[value_start..] == "?" {
+            output.push_str(line);
+            continue;
+        }
+        if let Some(key_len) = key_len(&content[value_start..]) {
+            value_start += key_len;
+            value_start += content[value_start..].len() - content[value_start..].trim_start().len();
+        }
+        let value = &content[value_start..];
+        if value.starts_with(['|', '>']) {
+            block_scalar = Some(indent);
+        }
+        output.push_str(&content[..value_start]);
+        output.push_str(&convert_value(value, &convert));
+        output.push_str(ending);
+    }
+    output
+}
+
+/// Дужина кључа заједно са `:` ако линија почиње кључем.
+fn key_len(line: &str) -> Option<usize> {
+    let key_end = match line.chars().next()? {
+        quote @ ('"' | '\'') => quoted_len(line, quote)?,
+        '[' | '{' | '#' | '&' | '*' | '!' | '|' | '>' => return None,
+        _ => {
+            let comment = line.find(" #").unwrap_or(line.len());
+            line[..comment]
+                .match_indices(':')
+                .map(|(i, _)| i)
+                .find(|&i| line[i + 1..].is_empty() || line[i + 1..].starts_with([' ', '\t']))?
+        }
+    };
+    line[key_end..]
+        .trim_start()
+        .strip_prefix(':')
+        .filter(|rest| rest.is_empty() || rest.starts_with([' ', '\t']))
+        .map(|rest| line.len() - rest.len())
+}
+
+/// Дужина скалара под наводницима заједно са наводницима.
+fn quoted_len(text: &str, quote: char) -> Option<usize> {
+    let mut chars = text.char_indices().skip(1).peekable();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' if quote == '"' => {
+                chars.next();
+            }
+            // `''` унутар једноструких наводника
+            '\'' if quote == '\'' && chars.peek().is_some_and(|&(_, next)| next == '\'') => {
+                chars.next();
+            }
+            c if c == quote => return Some(i + 1),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Пресловљава вредност (скалар или колекцију у линији) са евентуалним
+/// коментаром на крају.
+fn convert_value(value: &str, convert: &impl Fn(&str) -> String) -> String {
+    // Сидра, псеудоними и ознаке типова
+    let mut start = 0;
+    while value[start..].starts_with(['&', '*', '!']) {
+        start += value[start..]
+            .find([' ', '\t'])
+            .unwrap_or(value.len() - start);
+        start += value[start..].len() - value[start..].trim_start().len();
+    }
+    let (properties, scalar) = value.split_at(start);
+
+    let converted = match scalar.chars().next() {
+        None | Some('|' | '>' | '#') => scalar.to_string(),
+        Some(quote @ ('"' | '\'')) => {
+            // Скалар под наводницима се може наставити у следећој линији
+            let len = quoted_len(scalar, quote).unwrap_or(scalar.len());
+            convert_escaped(&scalar[..len], convert) + &scalar[len..]
+        }
+        Some('[' | '{') => convert_flow(scalar, convert),
+        Some(_) => {
+            let len = scalar.find(" #").unwrap_or(scalar.len());
+            let plain = scalar[..len].trim_end();
+            if NON_STRING.is_match(plain) {
+                scalar.to_string()
+            } else {
+                convert(plain) + &scalar[plain.len()..]
+            }
+        }
+    };
+    format!("{properties}{converted}")
+}
+
+/// Пресловљава скаларе колекције у линији (`[a, b]`, `{кључ: вредност}`).
+fn convert_flow(flow: &str, convert: &impl Fn(&str) -> String) -> String {
+    let mut output = String::with_capacity(flow.len());
+    // Да ли су отворене колекције мапе
+    let mut stack: Vec<bool> = Vec::new();
+    let mut segment_start = 0;
+    let mut pos = 0;
+    while let Some(c) = flow[pos..].chars().next() {
+        if c == '"' || c == '\'' {
+            pos += quoted_len(&flow[pos..], c).unwrap_or(flow.len() - pos);
+            continue;
+        }
+        pos += c.len_utf8();
+        if !"[]{},".contains(c) {
+            continue;
+        }
+        let in_map = stack.last() == Some(&true);
+        output.push_str(&convert_flow_item(
+            &flow[segment_start..pos - 1],
+            in_map,
+            convert,
+        ));
+        output.push(c);
+        segment_start = pos;
+        match c {
+            '[' => stack.push(false),
+            '{' => stack.push(true),
+            ']' | '}' => {
+                stack.pop();
+            }
+            _ => {}
+        }
+        if stack.is_empty() {
+            // Иза колекције може бити само коментар
+            break;
+        }
+    }
+    output.push_str(&flow[segment_start..]);
+    output
+}
+
+/// Пресловљава један елемент колекције у линији. Код мапа се кључ не мења.
+fn convert_flow_item(item: &str, in_map: bool, convert: &impl Fn(&str) -> String) -> String {
+    let trimmed = item.trim();
+    if trimmed.is_empty() {
+        return item.to_string();
+    }
+    let start = item.len() - item.trim_start().len();
+    let end = start + trimmed.len();
+    let key_len = in_map.then(|| key_len(trimmed)).flatten().unwrap_or(0);
+    let key_len = key_len + trimmed[key_len..].len() - trimmed[key_len..].trim_start().len();
+    format!(
+        "{}{}{}",
+        &item[..start + key_len],
+        convert_value(&trimmed[key_len..], convert),
+        &item[end..]
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lat_to_cyr;
+
+    #[test]
+    fn test_convert() {
+        let input = r#"# Podešavanja sajta
+naslov: Moj blog  # komentar
+opis: "Beleške o \"Rustu\"\n"
+autor: &autor 'Ivo Andrić'
+urednik: *autor
+tip: !!str Knjiga
+broj: 42
+aktivan: yes
+datum: 2024-01-15
+tagovi: [programiranje, "Rust", {ime: Vuk}]
+meni:
+  - ime: Početna
+    url: /
+  - O meni
+sadržaj: |
+  Prva linija.
+  # nije komentar
+kraj: Kraj
+"#;
+        let expected = r#"# Podešavanja sajta
+naslov: Мој блог  # komentar
+opis: "Белешке о \"Русту\"\n"
+autor: &autor 'Иво Андрић'
+urednik: *autor
+tip: !!str Књига
+broj: 42
+aktivan: yes
+datum: 2024-01-15
+tagovi: [програмирање, "Руст", {ime: Вук}]
+meni:
+  - ime: Почетна
+    url: /
+  - О мени
+sadržaj: |
+  Прва линија.
+  # није коментар
+kraj: Крај
+"#;
+        assert_eq!(expected, convert(input, lat_to_cyr));
+    }
+
+    #[test]
+    fn test_complex_keys() {
+        let input = "? slozen kljuc\n: vrednost\n- ? [a, b]\n  : Lista\n";
+        let expected = "? slozen kljuc\n: вредност\n- ? [a, b]\n  : Листа\n";
+        assert_eq!(expected, convert(input, lat_to_cyr));
+    }
+
+    #[test]
+    fn test_key_len() {
+        assert_eq!(Some(7), key_len("naslov: x"));
+        assert_eq!(Some(7), key_len("\"a: b\": x"));
+        assert_eq!(Some(4), key_len("url:"));
+        assert_eq!(None, key_len("http://primer.rs"));
+        assert_eq!(None, key_len("Tekst # a: b"));
+    }
+}
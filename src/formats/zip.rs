@@ -0,0 +1,56 @@
+//! Конверзија фајлова у ZIP архивама. Фајлови који се не мењају се преписују
+//! без поновне компресије.
+use super::archive;
+use crate::Error;
+
+/// Пресловљава фајлове у ZIP архиви. Функција `convert` за сваки фајл добија
+/// путању и садржај и враћа нови садржај или `None` ако се фајл не мења.
+pub fn convert(
+    input: &[u8],
+    convert: impl FnMut(&str, &[u8]) -> Result<Option<Vec<u8>>, Error>,
+) -> Result<Vec<u8>, Error> {
+    archive::transform(input, convert)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, Read, Write};
+
+    use zip::write::SimpleFileOptions;
+    use zip::{ZipArchive, ZipWriter};
+
+    use super::*;
+    use crate::lat_to_cyr;
+
+    #[test]
+    fn test_convert() {
+        let files = [("tekstovi/uvod.txt", "Dobar dan"), ("slika.png", "PNG")];
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, content) in files {
+            writer
+                .start_file(name, SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(content.as_bytes()).unwrap();
+        }
+        let input = writer.finish().unwrap().into_inner();
+
+        let output = convert(&input, |name, content| {
+            Ok(name
+                .ends_with(".txt")
+                .then(|| lat_to_cyr(&String::from_utf8_lossy(content)).into_bytes()))
+        })
+        .unwrap();
+        let mut archive = ZipArchive::new(Cursor::new(output)).unwrap();
+        let mut read = |name| {
+            let mut content = String::new();
+            archive
+                .by_name(name)
+                .unwrap()
+                .read_to_string(&mut content)
+                .unwrap();
+            content
+        };
+        assert_eq!("Добар дан", read("tekstovi/uvod.txt"));
+        assert_eq!("PNG", read("slika.png"));
+    }
+}
@@ -0,0 +1,83 @@
+use phf::phf_map;
+
+use crate::converter::Table;
+
+// Поједностављени систем (Streamlined System) прописан Законом о
+// транслитерацији Бугарске из 2009. године.
+static STREAMLINED_CYR_TO_LAT: phf::Map<char, &'static str> = phf_map! {
+    'а' => "a",
+    'б' => "b",
+    'в' => "v",
+    'г' => "g",
+    'д' => "d",
+    'е' => "e",
+    'ж' => "zh",
+    'з' => "z",
+    'и' => "i",
+    'й' => "y",
+    'к' => "k",
+    'л' => "l",
+    'м' => "m",
+    'н' => "n",
+    'о' => "o",
+    'п' => "p",
+    'р' => "r",
+    'с' => "s",
+    'т' => "t",
+    'у' => "u",
+    'ф' => "f",
+    'х' => "h",
+    'ц' => "ts",
+    'ч' => "ch",
+    'ш' => "sh",
+    'щ' => "sht",
+    'ъ' => "a",
+    'ь' => "y",
+    'ю' => "yu",
+    'я' => "ya",
+};
+
+// Обрнута табела је приближна јер систем није повратан (нпр. `a` може бити
+// `а` или `ъ`, а `y` може бити `й` или `ь`).
+static STREAMLINED_LAT_TO_CYR: phf::Map<&'static str, char> = phf_map! {
+    "a" => 'а',
+    "b" => 'б',
+    "v" => 'в',
+    "g" => 'г',
+    "d" => 'д',
+    "e" => 'е',
+    "zh" => 'ж',
+    "z" => 'з',
+    "i" => 'и',
+    "y" => 'й',
+    "k" => 'к',
+    "l" => 'л',
+    "m" => 'м',
+    "n" => 'н',
+    "o" => 'о',
+    "p" => 'п',
+    "r" => 'р',
+    "s" => 'с',
+    "t" => 'т',
+    "u" => 'у',
+    "f" => 'ф',
+    "h" => 'х',
+    "ts" => 'ц',
+    "ch" => 'ч',
+    "sh" => 'ш',
+    "sht" => 'щ',
+    "yu" => 'ю',
+    "ya" => 'я',
+};
+
+pub(super) fn fill_streamlined_tables(to_latin: &mut Table, to_cyrillic: &mut Table) {
+    for (cyr, lat) in STREAMLINED_CYR_TO_LAT.entries() {
+        to_latin.insert(&cyr.to_string(), lat);
+    }
+    for (lat, cyr) in STREAMLINED_LAT_TO_CYR.entries() {
+        to_cyrillic.insert(lat, &cyr.to_string());
+    }
+    // Завршетак `-ия` се пише као `-ia` (`България -> Bulgaria`)
+    to_latin.insert_final("ия", "ia");
+    to_cyrillic.insert_final("ia", "ия");
+}
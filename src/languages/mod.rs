@@ -0,0 +1,190 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::converter::Table;
+use crate::{Direction, Error};
+
+mod bulgarian;
+mod russian;
+mod serbian;
+mod ukrainian;
+
+/// Језик чија се ћирилица пресловљава.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Language {
+    /// Српска ћирилица и латиница (подразумевано).
+    #[default]
+    Serbian,
+    /// Српска азбука проширена црногорским словима `С́`/`З́` (`Ś`/`Ź`).
+    Montenegrin,
+    /// Руска ћирилица.
+    Russian,
+    /// Украјинска ћирилица.
+    Ukrainian,
+    /// Бугарска ћирилица.
+    Bulgarian,
+    /// Српски текстови писани предвуковском (славеносрпском) ортографијом
+    /// (`ѣ`, `ы`, `ъ`, `ї`...). Конверзија на ћирилицу даје савремену азбуку.
+    SerbianHistorical,
+}
+
+/// Систем романизације (пресловљавања на латиницу).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Scheme {
+    /// Гајева латиница (српски и црногорски).
+    Gaj,
+    /// ГОСТ 7.79-2000, систем Б (руски). Повратан систем.
+    Gost,
+    /// BGN/PCGN 1947 (руски). Конверзија на ћирилицу је приближна.
+    BgnPcgn,
+    /// Званична украјинска романизација из 2010. године (КМУ бр. 55).
+    Kmu2010,
+    /// Званични бугарски поједностављени систем (Streamlined System) из 2009.
+    Streamlined,
+    /// Предвуковски текстови, јат (`ѣ`) се чита екавски (`e`).
+    Ekavian,
+    /// Предвуковски текстови, јат (`ѣ`) се чита ијекавски (`je`).
+    Ijekavian,
+    /// Предвуковски текстови, јат (`ѣ`) се пише као `ě`.
+    Etymological,
+}
+
+impl Language {
+    /// Системи романизације подржани за дати језик. Први је подразумевани.
+    pub fn schemes(self) -> &'static [Scheme] {
+        match self {
+            Language::Serbian | Language::Montenegrin => &[Scheme::Gaj],
+            Language::Russian => &[Scheme::Gost, Scheme::BgnPcgn],
+            Language::Ukrainian => &[Scheme::Kmu2010],
+            Language::Bulgarian => &[Scheme::Streamlined],
+            Language::SerbianHistorical => {
+                &[Scheme::Ekavian, Scheme::Ijekavian, Scheme::Etymological]
+            }
+        }
+    }
+
+    pub fn default_scheme(self) -> Scheme {
+        self.schemes()[0]
+    }
+
+    /// Ознака језика и писма по BCP 47 (нпр. `sr-Latn`) за текст пресловљен у
+    /// смеру `direction`.
+    pub fn tag(self, direction: Direction) -> String {
+        let script = match direction {
+            Direction::ToLatin => "Latn",
+            Direction::ToCyrillic => "Cyrl",
+        };
+        format!("{}-{script}", self.code())
+    }
+
+    /// Ознака локала по конвенцији gettext-а (нпр. `sr@latin`) за текст
+    /// пресловљен у смеру `direction`.
+    pub fn locale(self, direction: Direction) -> String {
+        match direction {
+            Direction::ToLatin => format!("{}@latin", self.code()),
+            Direction::ToCyrillic => self.code().to_string(),
+        }
+    }
+
+    /// Ознака језика по ISO 639.
+    fn code(self) -> &'static str {
+        match self {
+            Language::Serbian | Language::SerbianHistorical => "sr",
+            Language::Montenegrin => "cnr",
+            Language::Russian => "ru",
+            Language::Ukrainian => "uk",
+            Language::Bulgarian => "bg",
+        }
+    }
+
+    /// Попуњава табеле пресловљавања за дати језик и систем. Сва
+    /// пресловљавања се дефинишу само малим словима.
+    pub(crate) fn fill_tables(self, scheme: Scheme, to_latin: &mut Table, to_cyrillic: &mut Table) {
+        match (self, scheme) {
+            (Language::Serbian, _) => serbian::fill_tables(to_latin, to_cyrillic),
+            (Language::Montenegrin, _) => {
+                serbian::fill_tables(to_latin, to_cyrillic);
+                serbian::fill_montenegrin_tables(to_latin, to_cyrillic);
+            }
+            (Language::Russian, Scheme::BgnPcgn) => {
+                russian::fill_bgn_pcgn_tables(to_latin, to_cyrillic)
+            }
+            (Language::Russian, _) => russian::fill_gost_tables(to_latin, to_cyrillic),
+            (Language::Ukrainian, _) => ukrainian::fill_kmu_2010_tables(to_latin, to_cyrillic),
+            (Language::Bulgarian, _) => bulgarian::fill_streamlined_tables(to_latin, to_cyrillic),
+            (Language::SerbianHistorical, scheme) => {
+                let yat = match scheme {
+                    Scheme::Ijekavian => "je",
+                    Scheme::Etymological => "ě",
+                    _ => "e",
+                };
+                serbian::fill_tables(to_latin, to_cyrillic);
+                serbian::fill_historical_tables(yat, to_latin);
+            }
+        }
+    }
+}
+
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Language::Serbian => "српски",
+            Language::Montenegrin => "црногорски",
+            Language::Russian => "руски",
+            Language::Ukrainian => "украјински",
+            Language::Bulgarian => "бугарски",
+            Language::SerbianHistorical => "предвуковски српски",
+        })
+    }
+}
+
+impl fmt::Display for Scheme {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Scheme::Gaj => "Гајева латиница",
+            Scheme::Gost => "ГОСТ 7.79-2000",
+            Scheme::BgnPcgn => "BGN/PCGN",
+            Scheme::Kmu2010 => "КМУ 2010",
+            Scheme::Streamlined => "Streamlined System",
+            Scheme::Ekavian => "екавско читање јата",
+            Scheme::Ijekavian => "ијекавско читање јата",
+            Scheme::Etymological => "етимолошки јат",
+        })
+    }
+}
+
+impl FromStr for Language {
+    type Err = Error;
+
+    /// Прихвата назив азбуке (`српска`, `руска`...) или ознаку језика (`sr`,
+    /// `ru`...).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "српска" | "sr" => Ok(Language::Serbian),
+            "црногорска" | "cnr" => Ok(Language::Montenegrin),
+            "руска" | "ru" => Ok(Language::Russian),
+            "украјинска" | "uk" => Ok(Language::Ukrainian),
+            "бугарска" | "bg" => Ok(Language::Bulgarian),
+            "предвуковска" | "sr-pre-vuk" => Ok(Language::SerbianHistorical),
+            _ => Err(Error::UnknownLanguage(s.to_string())),
+        }
+    }
+}
+
+impl FromStr for Scheme {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "gaj" => Ok(Scheme::Gaj),
+            "gost" => Ok(Scheme::Gost),
+            "bgn-pcgn" => Ok(Scheme::BgnPcgn),
+            "kmu-2010" => Ok(Scheme::Kmu2010),
+            "streamlined" => Ok(Scheme::Streamlined),
+            "ekavski" => Ok(Scheme::Ekavian),
+            "ijekavski" => Ok(Scheme::Ijekavian),
+            "etimoloski" => Ok(Scheme::Etymological),
+            _ => Err(Error::UnknownScheme(s.to_string())),
+        }
+    }
+}
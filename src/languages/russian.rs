@@ -0,0 +1,185 @@
+use phf::phf_map;
+
+use crate::converter::Table;
+
+// ГОСТ 7.79-2000, систем Б. Систем је повратан па се обрнута табела добија
+// директно из ове, уз неколико алтернативних облика. Слово `ц` се испред `е`,
+// `и`, `ы` и `й` пише као `c`, што се решава у `fill_gost_tables`.
+static GOST_CYR_TO_LAT: phf::Map<char, &'static str> = phf_map! {
+    'а' => "a",
+    'б' => "b",
+    'в' => "v",
+    'г' => "g",
+    'д' => "d",
+    'е' => "e",
+    'ё' => "yo",
+    'ж' => "zh",
+    'з' => "z",
+    'и' => "i",
+    'й' => "j",
+    'к' => "k",
+    'л' => "l",
+    'м' => "m",
+    'н' => "n",
+    'о' => "o",
+    'п' => "p",
+    'р' => "r",
+    'с' => "s",
+    'т' => "t",
+    'у' => "u",
+    'ф' => "f",
+    'х' => "x",
+    'ц' => "cz",
+    'ч' => "ch",
+    'ш' => "sh",
+    'щ' => "shh",
+    'ъ' => "``",
+    'ы' => "y'",
+    'ь' => "`",
+    'э' => "e`",
+    'ю' => "yu",
+    'я' => "ya",
+};
+
+static GOST_LAT_TO_CYR_EXTRA: phf::Map<&'static str, char> = phf_map! {
+    "c" => 'ц',
+    "y" => 'ы',
+};
+
+// BGN/PCGN 1947. Слова `е` и `ё` се на почетку речи и после самогласника,
+// `й`, `ъ` и `ь` пишу као `ye`/`yë` што се решава у `fill_bgn_pcgn_tables`.
+static BGN_PCGN_CYR_TO_LAT: phf::Map<char, &'static str> = phf_map! {
+    'а' => "a",
+    'б' => "b",
+    'в' => "v",
+    'г' => "g",
+    'д' => "d",
+    'е' => "e",
+    'ё' => "ë",
+    'ж' => "zh",
+    'з' => "z",
+    'и' => "i",
+    'й' => "y",
+    'к' => "k",
+    'л' => "l",
+    'м' => "m",
+    'н' => "n",
+    'о' => "o",
+    'п' => "p",
+    'р' => "r",
+    'с' => "s",
+    'т' => "t",
+    'у' => "u",
+    'ф' => "f",
+    'х' => "kh",
+    'ц' => "ts",
+    'ч' => "ch",
+    'ш' => "sh",
+    'щ' => "shch",
+    'ъ' => "”",
+    'ы' => "y",
+    'ь' => "’",
+    'э' => "e",
+    'ю' => "yu",
+    'я' => "ya",
+};
+
+// Секвенце које би се иначе прочитале као двословна слова раздвајају се тачком
+// на средини реда.
+static BGN_PCGN_SEPARATED: phf::Map<&'static str, &'static str> = phf_map! {
+    "тс" => "t·s",
+    "шч" => "sh·ch",
+};
+
+static BGN_PCGN_LAT_TO_CYR: phf::Map<&'static str, char> = phf_map! {
+    "a" => 'а',
+    "b" => 'б',
+    "v" => 'в',
+    "g" => 'г',
+    "d" => 'д',
+    "e" => 'е',
+    "ye" => 'е',
+    "ë" => 'ё',
+    "yë" => 'ё',
+    "zh" => 'ж',
+    "z" => 'з',
+    "i" => 'и',
+    "k" => 'к',
+    "l" => 'л',
+    "m" => 'м',
+    "n" => 'н',
+    "o" => 'о',
+    "p" => 'п',
+    "r" => 'р',
+    "s" => 'с',
+    "t" => 'т',
+    "u" => 'у',
+    "f" => 'ф',
+    "kh" => 'х',
+    "ts" => 'ц',
+    "ch" => 'ч',
+    "sh" => 'ш',
+    "shch" => 'щ',
+    "”" => 'ъ',
+    "y" => 'ы',
+    "’" => 'ь',
+    "yu" => 'ю',
+    "ya" => 'я',
+};
+
+const VOWELS: &[char] = &['а', 'е', 'ё', 'и', 'о', 'у', 'ы', 'э', 'ю', 'я'];
+
+// Самогласници у латиници и њихови ћирилични парњаци за обрнуту табелу
+const LAT_VOWELS: &[(&str, char)] = &[
+    ("a", 'а'),
+    ("e", 'е'),
+    ("i", 'и'),
+    ("o", 'о'),
+    ("u", 'у'),
+    ("y", 'ы'),
+];
+
+pub(super) fn fill_gost_tables(to_latin: &mut Table, to_cyrillic: &mut Table) {
+    for (cyr, lat) in GOST_CYR_TO_LAT.entries() {
+        to_latin.insert(&cyr.to_string(), lat);
+        to_cyrillic.insert(lat, &cyr.to_string());
+    }
+    for (lat, cyr) in GOST_LAT_TO_CYR_EXTRA.entries() {
+        to_cyrillic.insert(lat, &cyr.to_string());
+    }
+
+    // `ц` испред `е`, `и`, `ы` и `й` (нпр. `цирк` → `cirk`)
+    for next in ['е', 'и', 'ы', 'й'] {
+        let next_lat = GOST_CYR_TO_LAT[&next];
+        to_latin.insert(&format!("ц{next}"), &format!("c{next_lat}"));
+    }
+}
+
+pub(super) fn fill_bgn_pcgn_tables(to_latin: &mut Table, to_cyrillic: &mut Table) {
+    for (cyr, lat) in BGN_PCGN_CYR_TO_LAT.entries() {
+        to_latin.insert(&cyr.to_string(), lat);
+    }
+    for (cyr, lat) in BGN_PCGN_SEPARATED.entries() {
+        to_latin.insert(cyr, lat);
+    }
+    for (lat, cyr) in BGN_PCGN_LAT_TO_CYR.entries() {
+        to_cyrillic.insert(lat, &cyr.to_string());
+    }
+
+    // `е`/`ё` на почетку речи и после самогласника, `й`, `ъ` и `ь`
+    to_latin.insert_initial("е", "ye");
+    to_latin.insert_initial("ё", "yë");
+    for &prev in VOWELS.iter().chain(&['й', 'ъ', 'ь']) {
+        let prev_lat = BGN_PCGN_CYR_TO_LAT[&prev];
+        to_latin.insert(&format!("{prev}е"), &format!("{prev_lat}ye"));
+        to_latin.insert(&format!("{prev}ё"), &format!("{prev_lat}yë"));
+    }
+
+    // `y` после самогласника је најчешће `й` (нпр. `-ый`, `-ой`, `-ай`)
+    for &(lat, cyr) in LAT_VOWELS {
+        to_cyrillic.insert(&format!("{lat}y"), &format!("{cyr}й"));
+        for (y_lat, y_cyr) in [("ye", 'е'), ("yë", 'ё'), ("yu", 'ю'), ("ya", 'я')] {
+            to_cyrillic.insert(&format!("{lat}{y_lat}"), &format!("{cyr}{y_cyr}"));
+        }
+    }
+}
@@ -0,0 +1,169 @@
+use phf::{phf_map, phf_set};
+
+use crate::converter::Table;
+
+static CYR_TO_LAT: phf::Map<char, &'static str> = phf_map! {
+    'а' => "a",
+    'б' => "b",
+    'в' => "v",
+    'г' => "g",
+    'д' => "d",
+    'ђ' => "đ",
+    'е' => "e",
+    'ж' => "ž",
+    'з' => "z",
+    'и' => "i",
+    'ј' => "j",
+    'к' => "k",
+    'л' => "l",
+    'љ' => "lj",
+    'м' => "m",
+    'н' => "n",
+    'њ' => "nj",
+    'о' => "o",
+    'п' => "p",
+    'р' => "r",
+    'с' => "s",
+    'т' => "t",
+    'ћ' => "ć",
+    'у' => "u",
+    'ф' => "f",
+    'х' => "h",
+    'ц' => "c",
+    'ч' => "č",
+    'џ' => "dž",
+    'ш' => "š",
+};
+
+static LAT_TO_CYR: phf::Map<&'static str, char> = phf_map! {
+    "a" => 'а',
+    "b" => 'б',
+    "v" => 'в',
+    "g" => 'г',
+    "d" => 'д',
+    "đ" => 'ђ',
+    "e" => 'е',
+    "ž" => 'ж',
+    "z" => 'з',
+    "i" => 'и',
+    "j" => 'ј',
+    "k" => 'к',
+    "l" => 'л',
+    "m" => 'м',
+    "n" => 'н',
+    "o" => 'о',
+    "p" => 'п',
+    "r" => 'р',
+    "s" => 'с',
+    "t" => 'т',
+    "ć" => 'ћ',
+    "u" => 'у',
+    "f" => 'ф',
+    "h" => 'х',
+    "c" => 'ц',
+    "č" => 'ч',
+    "š" => 'ш',
+
+    // Мапирање двословних секвенци
+    "dž" => 'џ',
+    "lj" => 'љ',
+    "nj" => 'њ',
+};
+
+// Изузеци преузети из OOOTranslit екстензије за Либре Офис: https://extensions.libreoffice.org/en/extensions/show/oootranslit
+static EXCEPTIONS: phf::Set<&'static str> = phf_set! {
+    "tanjug",
+    "adžive",
+    "nadže",
+    "odžive",
+    "odžvaka",
+    "odžuri",
+    "džubori",
+    "onjugacij",
+    "njukcij",
+    "njekcij",
+    "anjezičn",
+};
+
+// Црногорска слова се у ћирилици пишу комбинујућим акцентом (U+0301) јер
+// прекомпоноване варијанте не постоје. У латиници се у пракси срећу и
+// прекомпоновани и комбинујући облици.
+static ME_CYR_TO_LAT: phf::Map<&'static str, &'static str> = phf_map! {
+    "с\u{301}" => "ś",
+    "з\u{301}" => "ź",
+};
+
+static ME_LAT_TO_CYR: phf::Map<&'static str, &'static str> = phf_map! {
+    "ś" => "с\u{301}",
+    "ź" => "з\u{301}",
+    "s\u{301}" => "с\u{301}",
+    "z\u{301}" => "з\u{301}",
+};
+
+// Слова предвуковске (славеносрпске) ортографије. Јат (`ѣ`) се обрађује
+// посебно јер његово читање зависи од одабраног система.
+static HISTORICAL_CYR_TO_LAT: phf::Map<&'static str, &'static str> = phf_map! {
+    "ы" => "i",
+    "ъ" => "",
+    "ь" => "",
+    "ль" => "lj",
+    "нь" => "nj",
+    "і" => "i",
+    "ї" => "i",
+    "й" => "j",
+    "ѵ" => "i",
+    "ѳ" => "t",
+    "ѡ" => "o",
+    "ѿ" => "ot",
+    "ѕ" => "dz",
+    "ѯ" => "ks",
+    "ѱ" => "ps",
+    "ѹ" => "u",
+    "ꙋ" => "u",
+    "є" => "e",
+    "э" => "e",
+    "ѥ" => "je",
+    "ю" => "ju",
+    "я" => "ja",
+    "ꙗ" => "ja",
+    "ѧ" => "ja",
+    "щ" => "št",
+};
+
+/// Попуњава табеле пресловљавања за српски језик.
+pub(super) fn fill_tables(to_latin: &mut Table, to_cyrillic: &mut Table) {
+    for (cyr, lat) in CYR_TO_LAT.entries() {
+        to_latin.insert(&cyr.to_string(), lat);
+    }
+    for (lat, cyr) in LAT_TO_CYR.entries() {
+        to_cyrillic.insert(lat, &cyr.to_string());
+    }
+    for exception in EXCEPTIONS.iter() {
+        to_cyrillic.insert_exception(exception);
+    }
+}
+
+/// Допуњава српске табеле црногорским словима.
+pub(super) fn fill_montenegrin_tables(to_latin: &mut Table, to_cyrillic: &mut Table) {
+    for (cyr, lat) in ME_CYR_TO_LAT.entries() {
+        to_latin.insert(cyr, lat);
+    }
+    for (lat, cyr) in ME_LAT_TO_CYR.entries() {
+        to_cyrillic.insert(lat, cyr);
+    }
+}
+
+/// Допуњава српске табеле словима предвуковске ортографије. Јат се чита
+/// према датом облику (`e`, `je`, `ě`...).
+pub(super) fn fill_historical_tables(yat: &str, to_latin: &mut Table) {
+    for (cyr, lat) in HISTORICAL_CYR_TO_LAT.entries() {
+        to_latin.insert(cyr, lat);
+    }
+    to_latin.insert("ѣ", yat);
+    // `і`/`ї` испред самогласника се чита као `иј` (`Димитрїе -> Dimitrije`)
+    for i in ['і', 'ї'] {
+        for (vowel, lat) in [('а', "a"), ('е', "e"), ('о', "o"), ('у', "u")] {
+            to_latin.insert(&format!("{i}{vowel}"), &format!("ij{lat}"));
+        }
+    }
+}
@@ -0,0 +1,113 @@
+use phf::phf_map;
+
+use crate::converter::Table;
+
+// Званична романизација украјинског језика (Одлука Кабинета министара
+// Украјине бр. 55 из 2010. године). Мекни знак и апостроф се не пресловљавају.
+static KMU_2010_CYR_TO_LAT: phf::Map<char, &'static str> = phf_map! {
+    'а' => "a",
+    'б' => "b",
+    'в' => "v",
+    'г' => "h",
+    'ґ' => "g",
+    'д' => "d",
+    'е' => "e",
+    'є' => "ie",
+    'ж' => "zh",
+    'з' => "z",
+    'и' => "y",
+    'і' => "i",
+    'ї' => "i",
+    'й' => "i",
+    'к' => "k",
+    'л' => "l",
+    'м' => "m",
+    'н' => "n",
+    'о' => "o",
+    'п' => "p",
+    'р' => "r",
+    'с' => "s",
+    'т' => "t",
+    'у' => "u",
+    'ф' => "f",
+    'х' => "kh",
+    'ц' => "ts",
+    'ч' => "ch",
+    'ш' => "sh",
+    'щ' => "shch",
+    'ь' => "",
+    'ю' => "iu",
+    'я' => "ia",
+};
+
+// Облици на почетку речи
+static KMU_2010_INITIAL: phf::Map<char, &'static str> = phf_map! {
+    'є' => "ye",
+    'ї' => "yi",
+    'й' => "y",
+    'ю' => "yu",
+    'я' => "ya",
+};
+
+// Обрнута табела је приближна јер систем није повратан (нпр. `y` може бити
+// `и` или `й`, а `i` може бити `і`, `ї` или `й`).
+static KMU_2010_LAT_TO_CYR: phf::Map<&'static str, &'static str> = phf_map! {
+    "a" => "а",
+    "b" => "б",
+    "v" => "в",
+    "h" => "г",
+    "g" => "ґ",
+    "d" => "д",
+    "e" => "е",
+    "ye" => "є",
+    "ie" => "є",
+    "zh" => "ж",
+    "z" => "з",
+    "zgh" => "зг",
+    "y" => "и",
+    "i" => "і",
+    "yi" => "ї",
+    "k" => "к",
+    "l" => "л",
+    "m" => "м",
+    "n" => "н",
+    "o" => "о",
+    "p" => "п",
+    "r" => "р",
+    "s" => "с",
+    "t" => "т",
+    "u" => "у",
+    "f" => "ф",
+    "kh" => "х",
+    "ts" => "ц",
+    "ch" => "ч",
+    "sh" => "ш",
+    "shch" => "щ",
+    "yu" => "ю",
+    "iu" => "ю",
+    "ya" => "я",
+    "ia" => "я",
+};
+
+// Апостроф се у украјинском пише само испред јотованих самогласника
+const APOSTROPHES: &[char] = &['\'', '’', 'ʼ'];
+
+pub(super) fn fill_kmu_2010_tables(to_latin: &mut Table, to_cyrillic: &mut Table) {
+    for (cyr, lat) in KMU_2010_CYR_TO_LAT.entries() {
+        to_latin.insert(&cyr.to_string(), lat);
+    }
+    for (cyr, lat) in KMU_2010_INITIAL.entries() {
+        to_latin.insert_initial(&cyr.to_string(), lat);
+    }
+    // `зг` се пише као `zgh` да би се разликовало од `ж`
+    to_latin.insert("зг", "zgh");
+    for apostrophe in APOSTROPHES {
+        for cyr in ['є', 'ї', 'ю', 'я'] {
+            to_latin.insert(&format!("{apostrophe}{cyr}"), KMU_2010_CYR_TO_LAT[&cyr]);
+        }
+    }
+
+    for (lat, cyr) in KMU_2010_LAT_TO_CYR.entries() {
+        to_cyrillic.insert(lat, cyr);
+    }
+}
@@ -0,0 +1,122 @@
+//! Поправка текста откуцаног на погрешном распореду тастатуре.
+//!
+//! Српски латинични (QWERTZ) и ћирилични распоред имају слова на истим
+//! тастерима па се текст поправља пресликавањем тастер-по-тастер (нпр.
+//! `qubav -> љубав` или `хелло -> hello`).
+use phf::phf_map;
+
+// Слова српског латиничног распореда и слова ћириличног распореда на истим
+// тастерима.
+static LATIN_KEYS_TO_CYRILLIC: phf::Map<char, char> = phf_map! {
+    'q' => 'љ',
+    'w' => 'њ',
+    'e' => 'е',
+    'r' => 'р',
+    't' => 'т',
+    'z' => 'з',
+    'u' => 'у',
+    'i' => 'и',
+    'o' => 'о',
+    'p' => 'п',
+    'š' => 'ш',
+    'đ' => 'ђ',
+    'a' => 'а',
+    's' => 'с',
+    'd' => 'д',
+    'f' => 'ф',
+    'g' => 'г',
+    'h' => 'х',
+    'j' => 'ј',
+    'k' => 'к',
+    'l' => 'л',
+    'č' => 'ч',
+    'ć' => 'ћ',
+    'ž' => 'ж',
+    'y' => 'ѕ',
+    'x' => 'џ',
+    'c' => 'ц',
+    'v' => 'в',
+    'b' => 'б',
+    'n' => 'н',
+    'm' => 'м',
+};
+
+static CYRILLIC_KEYS_TO_LATIN: phf::Map<char, char> = phf_map! {
+    'љ' => 'q',
+    'њ' => 'w',
+    'е' => 'e',
+    'р' => 'r',
+    'т' => 't',
+    'з' => 'z',
+    'у' => 'u',
+    'и' => 'i',
+    'о' => 'o',
+    'п' => 'p',
+    'ш' => 'š',
+    'ђ' => 'đ',
+    'а' => 'a',
+    'с' => 's',
+    'д' => 'd',
+    'ф' => 'f',
+    'г' => 'g',
+    'х' => 'h',
+    'ј' => 'j',
+    'к' => 'k',
+    'л' => 'l',
+    'ч' => 'č',
+    'ћ' => 'ć',
+    'ж' => 'ž',
+    'ѕ' => 'y',
+    'џ' => 'x',
+    'ц' => 'c',
+    'в' => 'v',
+    'б' => 'b',
+    'н' => 'n',
+    'м' => 'm',
+};
+
+/// Поправља текст откуцан на латиничном распореду уместо на ћириличном
+/// (`qubav -> љубав`).
+pub fn latin_layout_to_cyrillic(input: &str) -> String {
+    remap(input, &LATIN_KEYS_TO_CYRILLIC)
+}
+
+/// Поправља текст откуцан на ћириличном распореду уместо на латиничном
+/// (`хелло -> hello`).
+pub fn cyrillic_layout_to_latin(input: &str) -> String {
+    remap(input, &CYRILLIC_KEYS_TO_LATIN)
+}
+
+fn remap(input: &str, keys: &phf::Map<char, char>) -> String {
+    input
+        .chars()
+        .map(|c| {
+            let lower = c.to_lowercase().next().unwrap();
+            match keys.get(&lower) {
+                Some(&mapped) if c.is_uppercase() => mapped.to_uppercase().next().unwrap(),
+                Some(&mapped) => mapped,
+                None => c,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_latin_layout_to_cyrillic() {
+        assert_eq!("Љубав и њега", latin_layout_to_cyrillic("Qubav i wega"));
+        assert_eq!("ЏЕП, 123!", latin_layout_to_cyrillic("XEP, 123!"));
+    }
+
+    #[test]
+    fn test_cyrillic_layout_to_latin() {
+        assert_eq!("Hello world", cyrillic_layout_to_latin("Хелло њорлд"));
+        assert_eq!(
+            "qubav",
+            cyrillic_layout_to_latin(&latin_layout_to_cyrillic("qubav"))
+        );
+    }
+}
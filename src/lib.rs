@@ -1,269 +1,41 @@
 use once_cell::sync::Lazy;
-use phf::{phf_map, phf_set};
-use regex::Regex;
 
-static CYR_TO_LAT: phf::Map<char, &'static str> = phf_map! {
-    'а' => "a",
-    'б' => "b",
-    'в' => "v",
-    'г' => "g",
-    'д' => "d",
-    'ђ' => "đ",
-    'е' => "e",
-    'ж' => "ž",
-    'з' => "z",
-    'и' => "i",
-    'ј' => "j",
-    'к' => "k",
-    'л' => "l",
-    'љ' => "lj",
-    'м' => "m",
-    'н' => "n",
-    'њ' => "nj",
-    'о' => "o",
-    'п' => "p",
-    'р' => "r",
-    'с' => "s",
-    'т' => "t",
-    'ћ' => "ć",
-    'у' => "u",
-    'ф' => "f",
-    'х' => "h",
-    'ц' => "c",
-    'ч' => "č",
-    'џ' => "dž",
-    'ш' => "š",
-};
-
-static LAT_TO_CYR: phf::Map<&'static str, char> = phf_map! {
-    "a" => 'а',
-    "b" => 'б',
-    "v" => 'в',
-    "g" => 'г',
-    "d" => 'д',
-    "đ" => 'ђ',
-    "e" => 'е',
-    "ž" => 'ж',
-    "z" => 'з',
-    "i" => 'и',
-    "j" => 'ј',
-    "k" => 'к',
-    "l" => 'л',
-    "m" => 'м',
-    "n" => 'н',
-    "o" => 'о',
-    "p" => 'п',
-    "r" => 'р',
-    "s" => 'с',
-    "t" => 'т',
-    "ć" => 'ћ',
-    "u" => 'у',
-    "f" => 'ф',
-    "h" => 'х',
-    "c" => 'ц',
-    "č" => 'ч',
-    "š" => 'ш',
-
-    // Мапирање двословних секвенци
-    "dž" => 'џ',
-    "lj" => 'љ',
-    "nj" => 'њ',
-};
-
-// Изузеци преузети из OOOTranslit екстензије за Либре Офис: https://extensions.libreoffice.org/en/extensions/show/oootranslit
-static EXCEPTIONS: phf::Set<&'static str> = phf_set! {
-    "tanjug",
-    "adžive",
-    "nadže",
-    "odžive",
-    "odžvaka",
-    "odžuri",
-    "džubori",
-    "onjugacij",
-    "njukcij",
-    "njekcij",
-    "anjezičn",
-};
-// Дужина најдужег изузетка
-const MAX_EXCEPTION_LEN: usize = 9;
-
-// Регуларни изрази за делове текста који не би смели да се пресловљавају.
-static SKIP_PATTERNS: Lazy<Vec<Regex>> = Lazy::new(|| {
-    // Напомена: сваки израз започети са ^ јер желимо подударање на текућој локацији
-    vec![
-        // Веб адресе
-        Regex::new(
-            r"^(https?://)?[-a-zA-Z0-9@:%._\+~#=]{1,256}\.[a-zA-Z0-9()]{1,6}\b([-a-zA-Z0-9()@:%_\+.~#?&//=]*)",
-        )
-        .unwrap(),
-        // електронска пошта
-        Regex::new(r"^[a-zA-Z0-9._%+-]+@[a-zA-Z0-9.-]+\.[a-zA-Z]{2,}").unwrap(),
-        // Хештагови
-        Regex::new(r"^#\w+").unwrap(),
-        // LaTeX
-        Regex::new(r"^\\begin\{\w+\}").unwrap(),
-        Regex::new(r"^\\end\{\w+\}").unwrap(),
-        Regex::new(r"^\\\w+").unwrap(),
-        Regex::new(r"^\$[^$]*\$").unwrap(),
-    ]
-});
-
-/// Конвертује дато ћирилично слово у латинични еквивалент
-fn cyr_to_lat_char(c: char) -> Option<&'static str> {
-    CYR_TO_LAT.get(&c).copied()
-}
+pub mod bench;
+mod converter;
+pub mod diff;
+pub mod encoding;
+mod error;
+pub mod files;
+pub mod formats;
+mod languages;
+pub mod layout;
+pub mod line_ending;
+pub mod lines;
+pub mod progress;
+pub mod rpc;
+pub mod script;
+pub mod skip;
+pub mod stats;
+pub mod typography;
+pub mod yat;
+
+pub use converter::{Converter, Direction, Span};
+pub use error::Error;
+pub use languages::{Language, Scheme};
+pub use skip::SkipCategory;
+pub use yat::{ekavian_to_ijekavian, ijekavian_to_ekavian};
+
+// Подразумевани конвертор за српски језик
+static SERBIAN: Lazy<Converter> = Lazy::new(Converter::default);
 
 /// Конверзија српске ћирилице на латиницу
 pub fn cyr_to_lat(input: &str) -> String {
-    let mut output = String::with_capacity(input.len() * 2); // Латинични облик може бити већи
-    let mut chars = input.char_indices().peekable();
-
-    while let Some((pos, c)) = chars.next() {
-        if let Some(skip_bytes) = find_skip_match(&input[pos..]) {
-            // Преузимамо текст који се прескаче без промене
-            let skipped = &input[pos..pos + skip_bytes];
-            output.push_str(skipped);
-
-            // Прескачемо skip_len-1 јер смо већ конзумирали једно слово
-            let skip_chars = skipped.chars().count();
-            for _ in 0..skip_chars - 1 {
-                chars.next();
-            }
-            continue;
-        }
-        let is_upper = c.is_uppercase();
-        let c_low = c.to_lowercase().next().unwrap();
-        match cyr_to_lat_char(c_low) {
-            Some(lat) => {
-                let converted_chars = lat.chars().collect::<Vec<char>>();
-
-                if is_upper {
-                    // ако је прво ћирилично слово било велико прво слово латинице
-                    // ће увек бити велико.
-                    output.push_str(&converted_chars[0].to_uppercase().collect::<String>());
-                } else {
-                    output.push(converted_chars[0])
-                }
-
-                // Ако је двословна секвенца
-                if converted_chars.len() > 1 {
-                    if let Some((_, c_next)) = chars.peek() {
-                        if c_next.is_uppercase() {
-                            // Ако је ћирилично слово које следи велико тада ће
-                            // и друго слово латинице бити велико
-                            output.push_str(&converted_chars[1].to_uppercase().collect::<String>());
-                            continue;
-                        }
-                    }
-                    output.push(converted_chars[1]);
-                }
-            }
-            None => output.push(c), // Ако није српска ћирилица не конвертуј,
-        }
-    }
-    output
+    SERBIAN.to_latin(input)
 }
 
 /// Конверзија српске латинице на ћирилицу
 pub fn lat_to_cyr(input: &str) -> String {
-    let mut output = String::with_capacity(input.len());
-    let mut chars = input.char_indices().peekable();
-    let mut skip_until = 0; // Колико карактера да прескочимо до следеће провере изузетака
-
-    while let Some((pos, c)) = chars.next() {
-        if let Some(skip_bytes) = find_skip_match(&input[pos..]) {
-            // Преузимамо текст који се прескаче без промене
-            let skipped = &input[pos..pos + skip_bytes];
-            output.push_str(&input[pos..pos + skip_bytes]);
-
-            // Прескачемо skip_len-1 јер смо већ конзумирали једно слово
-            let skip_chars = skipped.chars().count();
-            for _ in 0..skip_chars - 1 {
-                chars.next();
-            }
-            continue;
-        }
-
-        // Ако смо већ нашли изузетак радимо нормалну карактер-по-карактер транслацију
-        // за дужину изузетка.
-        if pos < skip_until {
-            process_char(c, &mut chars, &mut output, false);
-            continue;
-        }
-
-        // Провера изузетака
-        let remaining_len = input.len() - pos;
-        let check_len = std::cmp::min(MAX_EXCEPTION_LEN, remaining_len);
-        let mut found_exception = None;
-
-        for len in (1..=check_len).rev() {
-            if let Some(substr) = input.get(pos..pos + len) {
-                if EXCEPTIONS.contains(substr.to_lowercase().as_str()) {
-                    found_exception = Some(len);
-                    break;
-                }
-            }
-        }
-
-        if let Some(len) = found_exception {
-            skip_until = pos + len;
-            process_char(c, &mut chars, &mut output, false);
-        } else {
-            process_char(c, &mut chars, &mut output, true);
-        }
-    }
-
-    output
-}
-
-fn process_char(
-    c: char,
-    chars: &mut std::iter::Peekable<std::str::CharIndices>,
-    output: &mut String,
-    doubles: bool,
-) {
-    let mut buffer = String::new();
-    buffer.push(c.to_lowercase().next().unwrap());
-
-    // Провера двословних секвенци
-    if doubles {
-        if let Some(&(_, next_c)) = chars.peek() {
-            buffer.push(next_c.to_lowercase().next().unwrap());
-
-            if let Some(&cyr) = LAT_TO_CYR.get(&buffer[..]) {
-                // Очувај величину слова
-                output.push(if c.is_uppercase() {
-                    cyr.to_uppercase().next().unwrap()
-                } else {
-                    cyr
-                });
-                chars.next(); // прескочи друго слово
-                return;
-            }
-            buffer.pop(); // скини друго слово ако није препознато
-        }
-    }
-
-    // Провера једнословних секвенци
-    if let Some(&cyr) = LAT_TO_CYR.get(&buffer[..]) {
-        // Очувај величину слова
-        output.push(if c.is_uppercase() {
-            cyr.to_uppercase().next().unwrap()
-        } else {
-            cyr
-        });
-    } else {
-        output.push(c);
-    }
-}
-
-/// Користи листу регуларних израза за прескакање за детекцију делова текста
-/// који се не обрађују. Враћа дужину у бајтовима ако је такав сегмент пронађен.
-fn find_skip_match(input: &str) -> Option<usize> {
-    SKIP_PATTERNS
-        .iter()
-        .find_map(|re| re.find(input))
-        .map(|m| m.end())
+    SERBIAN.to_cyrillic(input)
 }
 
 #[cfg(test)]
@@ -0,0 +1,91 @@
+//! Детекција и уједначавање крајева редова (`\n` или `\r\n`).
+use std::fmt;
+use std::str::FromStr;
+
+use crate::Error;
+
+/// Крај реда.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineEnding {
+    /// `\n` (Unix)
+    Lf,
+    /// `\r\n` (Windows)
+    CrLf,
+}
+
+impl LineEnding {
+    pub fn name(self) -> &'static str {
+        match self {
+            LineEnding::Lf => "lf",
+            LineEnding::CrLf => "crlf",
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LineEnding::Lf => "\n",
+            LineEnding::CrLf => "\r\n",
+        }
+    }
+
+    /// Претежни крај реда у тексту или `None` ако текст нема више редова.
+    pub fn detect(text: &str) -> Option<LineEnding> {
+        let lines = text.matches('\n').count();
+        if lines == 0 {
+            return None;
+        }
+        let crlf = text.matches("\r\n").count();
+        Some(if crlf * 2 > lines {
+            LineEnding::CrLf
+        } else {
+            LineEnding::Lf
+        })
+    }
+
+    /// Замењује све крајеве редова у тексту овим крајем реда.
+    pub fn apply(self, text: &str) -> String {
+        let mut output = String::with_capacity(text.len());
+        for line in text.split_inclusive('\n') {
+            match line.strip_suffix('\n') {
+                Some(content) => {
+                    output.push_str(content.strip_suffix('\r').unwrap_or(content));
+                    output.push_str(self.as_str());
+                }
+                None => output.push_str(line),
+            }
+        }
+        output
+    }
+}
+
+impl fmt::Display for LineEnding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for LineEnding {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "lf" => Ok(LineEnding::Lf),
+            "crlf" => Ok(LineEnding::CrLf),
+            _ => Err(Error::UnknownLineEnding(s.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_endings() {
+        assert_eq!(None, LineEnding::detect("jedan red"));
+        assert_eq!(Some(LineEnding::Lf), LineEnding::detect("a\nb\r\nc\n"));
+        assert_eq!(Some(LineEnding::CrLf), LineEnding::detect("a\r\nb\nc\r\n"));
+        assert_eq!("a\r\nb\r\nc", LineEnding::CrLf.apply("a\nb\r\nc"));
+        assert_eq!("a\nb\n", LineEnding::Lf.apply("a\r\nb\n"));
+    }
+}
@@ -0,0 +1,224 @@
+//! Обрада текста по линијама (опсези линија, нпр. `120-340`), пасусима и
+//! реченицама.
+use std::fmt;
+use std::str::FromStr;
+
+use crate::Error;
+
+/// Опсег линија, бројаних од 1. Оба краја су укључена, а опсег без краја се
+/// простире до краја текста.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LineRange {
+    pub start: usize,
+    pub end: Option<usize>,
+}
+
+impl LineRange {
+    /// Да ли опсег садржи линију са редним бројем `line`.
+    pub fn contains(&self, line: usize) -> bool {
+        line >= self.start && self.end.is_none_or(|end| line <= end)
+    }
+}
+
+impl fmt::Display for LineRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.end {
+            Some(end) if end == self.start => write!(f, "{}", self.start),
+            Some(end) => write!(f, "{}-{end}", self.start),
+            None => write!(f, "{}-", self.start),
+        }
+    }
+}
+
+impl FromStr for LineRange {
+    type Err = Error;
+
+    /// Парсира опсег облика `120-340`, `120-` или `120`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || Error::InvalidLineRange(s.to_string());
+        let number = |n: &str| n.trim().parse::<usize>().ok().filter(|&n| n > 0);
+        let range = match s.split_once('-') {
+            Some((start, end)) if end.trim().is_empty() => LineRange {
+                start: number(start).ok_or_else(invalid)?,
+                end: None,
+            },
+            Some((start, end)) => LineRange {
+                start: number(start).ok_or_else(invalid)?,
+                end: Some(number(end).ok_or_else(invalid)?),
+            },
+            None => {
+                let line = number(s).ok_or_else(invalid)?;
+                LineRange {
+                    start: line,
+                    end: Some(line),
+                }
+            }
+        };
+        if range.end.is_some_and(|end| end < range.start) {
+            return Err(invalid());
+        }
+        Ok(range)
+    }
+}
+
+/// Конвертује функцијом `convert` узастопне линије текста које припадају
+/// неком од опсега `ranges`, док остале линије остају непромењене.
+pub fn convert_lines<E>(
+    input: &str,
+    ranges: &[LineRange],
+    mut convert: impl FnMut(&str) -> Result<String, E>,
+) -> Result<String, E> {
+    let mut output = String::with_capacity(input.len());
+    // Почетак групе узастопних линија за конверзију
+    let mut selected: Option<usize> = None;
+    let mut pos = 0;
+    for (i, line) in input.split_inclusive('\n').enumerate() {
+        let included = ranges.iter().any(|range| range.contains(i + 1));
+        match (included, selected) {
+            (true, None) => selected = Some(pos),
+            (false, Some(start)) => {
+                output.push_str(&convert(&input[start..pos])?);
+                selected = None;
+            }
+            _ => {}
+        }
+        if selected.is_none() {
+            output.push_str(line);
+        }
+        pos += line.len();
+    }
+    if let Some(start) = selected {
+        output.push_str(&convert(&input[start..])?);
+    }
+    Ok(output)
+}
+
+/// Дели текст на пасусе раздвојене празним линијама. Празне линије иза пасуса
+/// припадају том пасусу, тако да спајањем пасуса настаје полазни текст.
+pub fn paragraphs(text: &str) -> Vec<&str> {
+    let mut paragraphs = Vec::new();
+    let mut start = 0;
+    let mut pos = 0;
+    // Да ли текући пасус има непразну линију, односно да ли је претходна
+    // линија празна
+    let (mut content, mut blank) = (false, false);
+    for line in text.split_inclusive('\n') {
+        let is_blank = line.trim().is_empty();
+        if content && blank && !is_blank {
+            paragraphs.push(&text[start..pos]);
+            start = pos;
+        }
+        content |= !is_blank;
+        blank = is_blank;
+        pos += line.len();
+    }
+    if start < text.len() {
+        paragraphs.push(&text[start..]);
+    }
+    paragraphs
+}
+
+/// Дели текст на реченице које се завршавају интерпункцијом (`.`, `!`, `?`,
+/// `…`, уз наводнике и заграде које је затварају) иза које следи размак, или
+/// крајем линије. Размаци иза реченице припадају тој реченици, тако да
+/// спајањем реченица настаје полазни текст.
+pub fn sentences(text: &str) -> Vec<&str> {
+    let mut sentences = Vec::new();
+    let mut start = 0;
+    // Да ли је текућа реченица завршена, па следећи знак који није размак
+    // почиње нову, односно да ли је реченица завршена ако следи размак
+    let (mut ended, mut punctuation) = (false, false);
+    for (pos, c) in text.char_indices() {
+        if ended && !c.is_whitespace() {
+            sentences.push(&text[start..pos]);
+            start = pos;
+            ended = false;
+        }
+        if c == '\n' || (punctuation && c.is_whitespace()) {
+            ended = true;
+        }
+        punctuation = matches!(c, '.' | '!' | '?' | '…')
+            || (punctuation && matches!(c, '”' | '“' | '"' | '’' | '\'' | '»' | ')'));
+    }
+    if start < text.len() {
+        sentences.push(&text[start..]);
+    }
+    sentences
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            LineRange {
+                start: 120,
+                end: Some(340)
+            },
+            "120-340".parse().unwrap()
+        );
+        assert_eq!(
+            LineRange {
+                start: 5,
+                end: None
+            },
+            "5-".parse().unwrap()
+        );
+        assert_eq!(
+            LineRange {
+                start: 7,
+                end: Some(7)
+            },
+            "7".parse().unwrap()
+        );
+        for range in ["", "0", "a-b", "10-5", "-5"] {
+            assert!(range.parse::<LineRange>().is_err(), "{range}");
+        }
+        for range in ["120-340", "5-", "7"] {
+            assert_eq!(range, range.parse::<LineRange>().unwrap().to_string());
+        }
+    }
+
+    #[test]
+    fn test_convert_lines() {
+        let input = "a\nb\nc\nd\ne";
+        let upper = |text: &str| Ok::<_, ()>(text.to_uppercase());
+        let ranges = ["2-3".parse().unwrap(), "5-".parse().unwrap()];
+        assert_eq!(
+            Ok("a\nB\nC\nd\nE".to_string()),
+            convert_lines(input, &ranges, upper)
+        );
+        let ranges = ["3-4".parse().unwrap(), "4-10".parse().unwrap()];
+        assert_eq!(
+            Ok("a\nb\nC\nD\nE".to_string()),
+            convert_lines(input, &ranges, upper)
+        );
+        assert_eq!(Ok(input.to_string()), convert_lines(input, &[], upper));
+    }
+
+    #[test]
+    fn test_paragraphs() {
+        assert_eq!(
+            vec!["\nprvi\nred\n\n \n", "drugi\r\n\r\n", "treći"],
+            paragraphs("\nprvi\nred\n\n \ndrugi\r\n\r\ntreći")
+        );
+        assert!(paragraphs("").is_empty());
+    }
+
+    #[test]
+    fn test_sentences() {
+        assert_eq!(
+            vec![
+                "Prva rečenica. ",
+                "Druga?! ",
+                "„Citat.” ",
+                "Verzija 1.2 je\n",
+                "treća…"
+            ],
+            sentences("Prva rečenica. Druga?! „Citat.” Verzija 1.2 je\ntreća…")
+        );
+        assert!(sentences("").is_empty());
+    }
+}
@@ -1,66 +1,2184 @@
-use clap::{Arg, Command};
-use std::fs;
-use std::io::{self, Read};
-use cirko::{cyr_to_lat, lat_to_cyr};
+mod config;
+mod diagnostics;
+mod messages;
+
+use clap::builder::{PossibleValuesParser, TypedValueParser};
+use clap::{Arg, ArgMatches, Command, error::ErrorKind, parser::ValueSource};
+use clap_complete::Shell;
+use notify::{RecursiveMode, Watcher as _};
+use regex::Regex;
+use std::collections::HashSet;
+use std::fmt;
+use std::fs::{self, File};
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, mpsc};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::Duration;
+use std::io::{self, BufRead, BufReader, BufWriter, IsTerminal, Read, Write};
+use config::Config;
+use diagnostics::Kind;
+use messages::{Locale, tr};
+use cirko::bench;
+use cirko::diff::{changed_lines, round_trip_losses, unified_diff};
+use cirko::encoding::{self, BOM, Encoding};
+use cirko::files::{self, Glob, Watcher};
+use cirko::formats::Format;
+use cirko::formats::android;
+use cirko::formats::apple;
+use cirko::formats::arb;
+use cirko::formats::asciidoc;
+use cirko::formats::ass;
+use cirko::formats::bibtex;
+use cirko::formats::csv;
+use cirko::formats::eml;
+#[cfg(feature = "archives")]
+use cirko::formats::epub;
+use cirko::formats::fb2;
+use cirko::formats::fluent;
+use cirko::formats::fountain;
+use cirko::formats::i18next;
+use cirko::formats::ics;
+use cirko::formats::ipynb;
+use cirko::formats::json;
+use cirko::formats::jsonl::{self, Field};
+use cirko::formats::latex;
+use cirko::formats::markdown;
+use cirko::formats::org;
+use cirko::formats::pandoc;
+use cirko::formats::po;
+#[cfg(feature = "archives")]
+use cirko::formats::pptx;
+use cirko::formats::properties;
+use cirko::formats::resx;
+use cirko::formats::rst;
+use cirko::formats::rtf;
+use cirko::formats::site;
+use cirko::formats::sql;
+use cirko::formats::srt;
+#[cfg(feature = "archives")]
+use cirko::formats::targz;
+use cirko::formats::ts;
+use cirko::formats::typst;
+use cirko::formats::vcard;
+use cirko::formats::vtt;
+use cirko::formats::xliff;
+#[cfg(feature = "archives")]
+use cirko::formats::xlsx;
+use cirko::formats::xml::{self, AttributeSelector};
+use cirko::formats::yaml;
+#[cfg(feature = "archives")]
+use cirko::formats::zip;
+use cirko::layout::{cyrillic_layout_to_latin, latin_layout_to_cyrillic};
+use cirko::line_ending::LineEnding;
+use cirko::lines::{self, LineRange};
+use cirko::progress::Progress;
+use cirko::rpc;
+use cirko::script::{self, DetectedScript, Script, mixed_script_words, normalize_homoglyphs};
+use cirko::stats::{self, Stats};
+use cirko::typography::QuoteStyle;
+use cirko::{
+    Converter, Direction, Error, Language, Scheme, SkipCategory, ekavian_to_ijekavian,
+    ijekavian_to_ekavian,
+};
+
+// Време чекања на наредна обавештења о изменама пре конверзије измењених
+// фајлова током праћења
+const WATCH_DELAY: Duration = Duration::from_millis(100);
+
+// Величина делова текста који се конвертују при приказу напретка
+const PROGRESS_CHUNK_SIZE: usize = 1 << 20;
+
+// Опције подешавања конвертора, које служба не прима уз захтев
+const CONVERTER_OPTIONS: &[&str] = &[
+    "азбука", "шема", "табела", "изузеци", "замени", "наводници", "типографија", "прескачи", "не-прескачи",
+    "јединице", "прескочи-речи", "прескочи-речи-из", "прескочи-шаблон", "маркери", "без-маркера",
+];
 
 fn main() -> io::Result<()> {
-    let matches = Command::new("ћирко")
+    // Језик порука се одређује пре описа опција јер се на њему исписује и
+    // помоћ, а JSON облик порука пре парсирања опција због грешака у опцијама
+    messages::set_locale(Locale::from_args(std::env::args_os()));
+    diagnostics::init(0, std::env::args_os().any(|arg| arg == "--грешке-json" || arg == "--errors-json"));
+    let mut command = cli();
+
+    // Вредности из конфигурационог фајла постају подразумеване вредности
+    // опција, па их опције задате у командној линији премошћавају
+    let config_file = command
+        .clone()
+        .ignore_errors(true)
+        .get_matches()
+        .get_one::<PathBuf>("конфигурација")
+        .cloned();
+    let config = match config_file.clone().or_else(Config::default_path) {
+        Some(path) if config_file.is_some() || path.exists() => Config::load(&path).unwrap_or_else(|e| {
+            let message = format!("конфигурациони фајл „{}”: {e}", path.display());
+            fail(&mut command, ErrorKind::InvalidValue, message)
+        }),
+        _ => Config::default(),
+    };
+    command = config.apply(command);
+    let matches = command.try_get_matches_from_mut(std::env::args_os()).unwrap_or_else(|e| exit_with(e));
+    // Уз подкоманде се могу задати само опције конверзије, које важе и за
+    // подкоманде
+    if let Some((name, _)) = matches.subcommand() {
+        let given = command
+            .get_arguments()
+            .filter(|arg| !arg.is_global_set() && matches.value_source(arg.get_id().as_str()) == Some(ValueSource::CommandLine))
+            .map(|arg| arg.get_long().map_or_else(|| arg.get_id().to_string(), |long| format!("--{long}")))
+            .next();
+        if let Some(arg) = given {
+            let message = format!("опција „{arg}” се не може задати уз подкоманду „{name}”");
+            fail(&mut command, ErrorKind::ArgumentConflict, message)
+        }
+    }
+    let verbosity = matches.get_count("опширно");
+    diagnostics::init(verbosity, matches.get_flag("грешке-json"));
+
+    match matches.subcommand() {
+        Some(("допуне", matches)) => completions(&mut command, matches),
+        Some(("преименуј", matches)) => rename(&mut command, matches),
+        Some(("хук", matches)) => hook(&mut command, matches),
+        Some(("детектуј", matches)) => detect(matches),
+        Some(("бенч", matches)) => benchmark(&mut command, matches),
+        _ => convert(&mut command, &matches),
+    }
+}
+
+/// Опције и подкоманде командне линије.
+fn cli() -> Command {
+    let command = Command::new("ћирко")
         .disable_help_flag(true)
         .arg(Arg::new("help")
              .short('h')  // Остављамо -h због компатибилности
              .long("помоћ")
-             .help("Прикажи помоћ")
+             .visible_alias("help")
+             .global(true)
+             .help(tr("Прикажи помоћ"))
              .action(clap::ArgAction::Help))
-        .about("Ћирко - конвертор српске латинице у ћирилицу и обрнуто.")
+        .arg(Arg::new("језик")
+             .long("језик")
+             .visible_alias("language")
+             .value_name(tr("ЈЕЗИК"))
+             .help(tr("Језик порука и помоћи: sr-Cyrl (ћирилица), sr-Latn (латиница) или en (енглески). Подразумевано се чита из променљиве окружења CIRKO_LANG."))
+             .value_parser(PossibleValuesParser::new(Locale::ALL.iter().map(|locale| locale.tag())))
+             .global(true))
+        .arg(Arg::new("опширно")
+             .short('в')
+             .visible_short_alias('v')
+             .long("опширно")
+             .visible_alias("verbose")
+             .help(tr("Испиши обавештења о конвертованим фајловима и кодирању (-в) и примењеним изузецима (-вв)"))
+             .action(clap::ArgAction::Count)
+             .global(true))
+        .arg(Arg::new("грешке-json")
+             .long("грешке-json")
+             .visible_alias("errors-json")
+             .help(tr("Испиши грешке, упозорења и обавештења као JSON објекте, по један у реду"))
+             .action(clap::ArgAction::SetTrue)
+             .global(true))
+        .about(tr("Ћирко - конвертор српске латинице у ћирилицу и обрнуто."))
         .arg(Arg::new("улаз")
              .short('у')
              .long("улаз")
+             .visible_alias("input")
              .value_name("FILE")
-             .help("Улазни фајл (stdin подразумевано)"))
+             .help(tr("Улазни фајл (stdin подразумевано), може се задати више пута"))
+             .action(clap::ArgAction::Append))
+        .arg(Arg::new("фајлови")
+             .value_name("FILE")
+             .help(tr("Улазни фајлови, као уз -у"))
+             .num_args(1..))
         .arg(Arg::new("излаз")
              .short('и')
              .long("излаз")
+             .visible_alias("output")
              .value_name("FILE")
-             .help("Излазни фајл (stdout подразумевано)"))
+             .help(tr("Излазни фајл (stdout подразумевано)")))
+        .arg(Arg::new("излаз-дир")
+             .long("излаз-дир")
+             .visible_alias("output-dir")
+             .value_name(tr("ДИРЕКТОРИЈУМ"))
+             .help(tr("Упиши сваки улазни фајл под истим називом у излазни директоријум"))
+             .conflicts_with_all(["излаз", "у-месту"]))
+        .arg(Arg::new("преслови-називе")
+             .long("преслови-називе")
+             .visible_alias("transliterate-names")
+             .help(tr("Преслови и називе фајлова у излазном директоријуму"))
+             .requires("излаз-дир")
+             .action(clap::ArgAction::SetTrue))
+        .arg(Arg::new("рекурзивно")
+             .short('р')
+             .long("рекурзивно")
+             .visible_alias("recursive")
+             .value_name(tr("ДИРЕКТОРИЈУМ"))
+             .help(tr("Конвертуј све фајлове директоријума у излазни директоријум (-и)"))
+             .conflicts_with_all(["улаз", "фајлови", "излаз-дир"]))
+        .arg(Arg::new("укључи")
+             .long("укључи")
+             .visible_alias("include")
+             .value_name(tr("ШАБЛОН"))
+             .help(tr("Конвертуј само фајлове који одговарају шаблону (нпр. „*.md”)"))
+             .value_parser(|s: &str| s.parse::<Glob>().map_err(tr))
+             .requires("рекурзивно")
+             .action(clap::ArgAction::Append))
+        .arg(Arg::new("искључи")
+             .long("искључи")
+             .visible_alias("exclude")
+             .value_name(tr("ШАБЛОН"))
+             .help(tr("Прескочи фајлове и директоријуме који одговарају шаблону (нпр. „target/**”)"))
+             .value_parser(|s: &str| s.parse::<Glob>().map_err(tr))
+             .requires("рекурзивно")
+             .action(clap::ArgAction::Append))
+        .arg(Arg::new("врсте")
+             .long("врсте")
+             .visible_alias("types")
+             .value_name(tr("ВРСТЕ"))
+             .help(tr("Конвертуј само фајлове задатих екстензија или формата, одвојених зарезом (нпр. md,html,txt)"))
+             .requires("рекурзивно")
+             .value_delimiter(',')
+             .action(clap::ArgAction::Append))
+        .arg(Arg::new("скривени")
+             .long("скривени")
+             .visible_alias("hidden")
+             .help(tr("Конвертуј и скривене фајлове и директоријуме"))
+             .requires("рекурзивно")
+             .action(clap::ArgAction::SetTrue))
+        .arg(Arg::new("без-игнорисања")
+             .long("без-игнорисања")
+             .visible_alias("no-ignore")
+             .help(tr("Не прескачи фајлове наведене у „.gitignore” и „.ignore” ни директоријуме „target” и „node_modules”"))
+             .requires("рекурзивно")
+             .action(clap::ArgAction::SetTrue))
+        .arg(Arg::new("фајлови-из-стдин")
+             .long("фајлови-из-стдин")
+             .visible_alias("files-from-stdin")
+             .help(tr("Конвертуј фајлове чије се путање читају са стандардног улаза (по једна у реду)"))
+             .conflicts_with_all(["улаз", "фајлови", "излаз", "излаз-дир", "рекурзивно"])
+             .action(clap::ArgAction::SetTrue))
+        .arg(Arg::new("нул")
+             .short('0')
+             .long("нул")
+             .visible_alias("null")
+             .help(tr("Путање на стандардном улазу су раздвојене нул карактером (нпр. „find -print0”)"))
+             .requires("фајлови-из-стдин")
+             .action(clap::ArgAction::SetTrue))
+        .arg(Arg::new("у-месту")
+             .long("у-месту")
+             .visible_alias("in-place")
+             .help(tr("Упиши резултат конверзије у сам улазни фајл"))
+             .conflicts_with("излаз")
+             .action(clap::ArgAction::SetTrue))
+        .arg(Arg::new("сила")
+             .long("сила")
+             .visible_alias("force")
+             .help(tr("Дозволи да излазни фајл буде исти као улазни"))
+             .action(clap::ArgAction::SetTrue))
+        .arg(Arg::new("задржи-атрибуте")
+             .long("задржи-атрибуте")
+             .visible_alias("preserve")
+             .help(tr("Задржи дозволе и време измене улазног фајла у излазном фајлу"))
+             .action(clap::ArgAction::SetTrue))
+        .arg(Arg::new("преглед")
+             .long("преглед")
+             .visible_alias("preview")
+             .help(tr("Не уписуј резултат већ прикажи измене у облику диф-а"))
+             .action(clap::ArgAction::SetTrue))
+        .arg(Arg::new("боје")
+             .long("боје")
+             .visible_alias("color")
+             .help(tr("Обој измене у прегледу"))
+             .requires("преглед")
+             .action(clap::ArgAction::SetTrue))
+        .arg(Arg::new("провера")
+             .long("провера")
+             .visible_alias("check")
+             .help(tr("Не уписуј резултат већ провери да ли је текст већ у циљном писму и испиши редове који нису (излазни код 1)"))
+             .conflicts_with_all(["преглед", "прати", "линијски", "помешана-писма"])
+             .action(clap::ArgAction::SetTrue))
+        .arg(Arg::new("повратна-провера")
+             .long("повратна-провера")
+             .visible_alias("round-trip")
+             .help(tr("Не уписуј резултат већ конвертуј текст назад и испиши речи које се не враћају у изворни облик (излазни код 1)"))
+             .conflicts_with_all(["преглед", "провера", "прати", "линијски", "помешана-писма", "рекурзивно", "фајлови-из-стдин", "линије", "служба"])
+             .action(clap::ArgAction::SetTrue))
+        .arg(Arg::new("строго")
+             .long("строго")
+             .visible_alias("strict")
+             .help(tr("Заврши са грешком ако резултат и даље садржи слова писма са кога се конвертује"))
+             .conflicts_with_all(["преглед", "провера", "повратна-провера", "линијски", "служба"])
+             .action(clap::ArgAction::SetTrue))
+        .arg(Arg::new("послови")
+             .short('п')
+             .long("послови")
+             .visible_alias("jobs")
+             .value_name("N")
+             .help(tr("Број фајлова који се конвертују паралелно код рекурзивне конверзије (подразумевано број процесора)"))
+             .value_parser(clap::value_parser!(NonZeroUsize))
+             .requires("рекурзивно"))
+        .arg(Arg::new("линијски")
+             .long("линијски")
+             .visible_alias("line-buffered")
+             .help(tr("Конвертуј и испиши сваку линију улаза одмах након учитавања"))
+             .conflicts_with_all(["рекурзивно", "преглед", "прати", "помешана-писма"])
+             .action(clap::ArgAction::SetTrue))
+        .arg(Arg::new("аутоматски-по-линији")
+             .long("аутоматски-по-линији")
+             .visible_alias("per-line")
+             .help(tr("Одреди писмо сваке линије посебно (уз -л или -ћ конвертуј само линије које нису у циљном писму)"))
+             .requires("линијски")
+             .action(clap::ArgAction::SetTrue))
+        .arg(Arg::new("линије")
+             .long("линије")
+             .visible_alias("lines")
+             .value_name(tr("ОПСЕГ"))
+             .help(tr("Конвертуј само линије из опсега (нпр. „120-340”, „5-” или „7”), а остале остави непромењене. Може се навести више пута."))
+             .value_parser(|s: &str| s.parse::<LineRange>().map_err(tr))
+             .conflicts_with_all(["рекурзивно", "линијски", "служба"])
+             .action(clap::ArgAction::Append))
+        .arg(Arg::new("аутоматски-по-пасусу")
+             .long("аутоматски-по-пасусу")
+             .visible_alias("per-paragraph")
+             .help(tr("Одреди писмо сваког пасуса посебно и конвертуј само пасусе који нису у циљном писму"))
+             .action(clap::ArgAction::SetTrue))
+        .arg(Arg::new("очисти")
+             .long("очисти")
+             .visible_alias("cleanup")
+             .help(tr("Конвертуј само реченице писане писмом које није преовлађујуће у документу у преовлађујуће писмо"))
+             .conflicts_with_all(["латиница", "ћирилица", "аутоматски-по-пасусу", "линијски"])
+             .action(clap::ArgAction::SetTrue))
+        .arg(Arg::new("напредак")
+             .long("напредак")
+             .visible_alias("progress")
+             .help(tr("Прикажи напредак конверзије"))
+             .conflicts_with("линијски")
+             .action(clap::ArgAction::SetTrue))
+        .arg(Arg::new("статистика")
+             .long("статистика")
+             .visible_alias("stats")
+             .help(tr("Испиши статистику конверзије (пресловљени знакови, изузеци, прескочени делови текста) на стандардни излаз за грешке"))
+             .conflicts_with("линијски")
+             .action(clap::ArgAction::SetTrue))
+        .arg(Arg::new("json")
+             .long("json")
+             .help(tr("Испиши статистику у JSON облику"))
+             .requires("статистика")
+             .action(clap::ArgAction::SetTrue))
+        .arg(Arg::new("служба")
+             .long("служба")
+             .visible_alias("service")
+             .help(tr("Обрађуј JSON-RPC захтеве за конверзију са стандардног улаза, по један у реду (за додатке едитора)"))
+             .conflicts_with_all(["улаз", "фајлови", "излаз", "излаз-дир", "рекурзивно", "преглед", "провера", "линијски", "статистика", "помешана-писма"])
+             .action(clap::ArgAction::SetTrue))
+        .arg(Arg::new("сокет")
+             .long("сокет")
+             .visible_alias("socket")
+             .value_name(tr("СОКЕТ"))
+             .help(tr("Unix сокет службе (на Windows-у именована цев, нпр. \\\\.\\pipe\\cirko): уз --служба прима захтеве преко сокета, а иначе шаље текст на конверзију служби која већ ради"))
+             .value_parser(clap::value_parser!(PathBuf))
+             .conflicts_with_all(["излаз-дир", "рекурзивно", "преглед", "провера", "повратна-провера", "линијски", "статистика", "помешана-писма", "прати", "строго"]))
+        .arg(Arg::new("прати")
+             .long("прати")
+             .visible_alias("watch")
+             .help(tr("Прати измене улазног фајла или директоријума и поново конвертуј измењене фајлове"))
+             .conflicts_with("преглед")
+             .action(clap::ArgAction::SetTrue))
         .arg(Arg::new("латиница")
              .short('л')
              .long("латиница")
-             .help("Конвертуј у латиницу")
+             .visible_alias("to-latin")
+             .help(tr("Конвертуј у латиницу"))
              .action(clap::ArgAction::SetTrue))
         .arg(Arg::new("ћирилица")
              .short('ћ')
              .long("ћирилица")
-             .help("Конвертуј у ћирилицу")
+             .visible_alias("to-cyrillic")
+             .help(tr("Конвертуј у ћирилицу"))
              .action(clap::ArgAction::SetTrue))
-        .get_matches();
+        .arg(Arg::new("азбука")
+             .short('а')
+             .long("азбука")
+             .visible_alias("alphabet")
+             .global(true)
+             .value_name(tr("АЗБУКА"))
+             .help(tr("Азбука (језик) ћириличног текста"))
+             .value_parser(PossibleValuesParser::new(["српска", "црногорска", "руска", "украјинска", "бугарска", "предвуковска"])
+                           .map(|s| s.parse::<Language>().unwrap()))
+             .default_value("српска"))
+        .arg(Arg::new("шема")
+             .short('ш')
+             .long("шема")
+             .visible_alias("scheme")
+             .global(true)
+             .value_name(tr("ШЕМА"))
+             .help(tr("Систем романизације (подразумевано зависи од азбуке)"))
+             .value_parser(PossibleValuesParser::new(["gaj", "gost", "bgn-pcgn", "kmu-2010", "streamlined", "ekavski", "ijekavski", "etimoloski"])
+                           .map(|s| s.parse::<Scheme>().unwrap())))
+        .arg(Arg::new("табела")
+             .short('т')
+             .long("табела")
+             .visible_alias("table")
+             .global(true)
+             .value_name("FILE")
+             .help(tr("TOML фајл са корисничким табелама пресловљавања")))
+        .arg(Arg::new("изузеци")
+             .long("изузеци")
+             .visible_alias("exceptions")
+             .global(true)
+             .value_name(tr("СЕКВЕНЦЕ"))
+             .help(tr("Секвенце у којима се вишесловна пресловљавања на ћирилицу не примењују, одвојене зарезом (нпр. nadživ)"))
+             .value_delimiter(',')
+             .action(clap::ArgAction::Append))
+        .arg(Arg::new("замени")
+             .long("замени")
+             .visible_alias("replace")
+             .global(true)
+             .value_name(tr("СЛОВО=ЗАМЕНА"))
+             .help(tr("Промени пресловљавање једног слова на латиницу (нпр. ђ=dj, џ=dz)"))
+             .action(clap::ArgAction::Append))
+        .arg(Arg::new("ијекавица")
+             .long("ијекавица")
+             .visible_alias("ijekavian")
+             .help(tr("Конвертуј екавски текст у ијекавски"))
+             .conflicts_with("екавица")
+             .action(clap::ArgAction::SetTrue))
+        .arg(Arg::new("екавица")
+             .long("екавица")
+             .visible_alias("ekavian")
+             .help(tr("Конвертуј ијекавски текст у екавски"))
+             .action(clap::ArgAction::SetTrue))
+        .arg(Arg::new("распоред")
+             .long("распоред")
+             .visible_alias("layout")
+             .help(tr("Поправи текст откуцан на погрешном распореду тастатуре"))
+             .action(clap::ArgAction::SetTrue))
+        .arg(Arg::new("хомоглифи")
+             .long("хомоглифи")
+             .visible_alias("homoglyphs")
+             .help(tr("Поправи речи у којима су помешана писма (нпр. латинично „a” у ћириличној речи)"))
+             .action(clap::ArgAction::SetTrue))
+        .arg(Arg::new("помешана-писма")
+             .long("помешана-писма")
+             .visible_alias("mixed-scripts")
+             .help(tr("Не конвертуј већ испиши речи у којима су помешана писма"))
+             .action(clap::ArgAction::SetTrue))
+        .arg(Arg::new("наводници")
+             .long("наводници")
+             .visible_alias("quotes")
+             .global(true)
+             .value_name(tr("СТИЛ"))
+             .help(tr("Замени праве наводнике типографским: ниски („…”) или шиљасти (»…«)"))
+             .value_parser(PossibleValuesParser::new(["ниски", "шиљасти"])
+                           .map(|s| if s == "шиљасти" { QuoteStyle::Angle } else { QuoteStyle::Low })))
+        .arg(Arg::new("типографија")
+             .long("типографија")
+             .visible_alias("typography")
+             .global(true)
+             .help(tr("Замени три тачке и цртице типографским знацима и додај нераскидиве размаке"))
+             .action(clap::ArgAction::SetTrue))
+        .arg(Arg::new("прескачи")
+             .long("прескачи")
+             .visible_alias("skip")
+             .global(true)
+             .value_name(tr("КАТЕГОРИЈЕ"))
+             .help(tr("Укључи прескакање категорија делова текста (одвојених зарезом)"))
+             .value_delimiter(',')
+             .value_parser(skip_category_parser())
+             .action(clap::ArgAction::Append))
+        .arg(Arg::new("не-прескачи")
+             .long("не-прескачи")
+             .visible_alias("no-skip")
+             .global(true)
+             .value_name(tr("КАТЕГОРИЈЕ"))
+             .help(tr("Искључи прескакање категорија делова текста (одвојених зарезом)"))
+             .value_delimiter(',')
+             .value_parser(skip_category_parser())
+             .action(clap::ArgAction::Append))
+        .arg(Arg::new("јединице")
+             .long("јединице")
+             .visible_alias("units")
+             .global(true)
+             .value_name(tr("ЈЕДИНИЦЕ"))
+             .help(tr("Мерне јединице које после броја остају непромењене (одвојене зарезом), уместо подразумеваних"))
+             .value_delimiter(',')
+             .action(clap::ArgAction::Append))
+        .arg(Arg::new("прескочи-речи")
+             .long("прескочи-речи")
+             .visible_alias("skip-words")
+             .global(true)
+             .value_name(tr("РЕЧ"))
+             .help(tr("Реч или израз који остаје непромењен (нпр. назив производа). Може се навести више пута."))
+             .action(clap::ArgAction::Append))
+        .arg(Arg::new("прескочи-речи-из")
+             .long("прескочи-речи-из")
+             .visible_alias("skip-words-file")
+             .global(true)
+             .value_name("FILE")
+             .help(tr("Фајл са речима и изразима који остају непромењени (један по реду)"))
+             .action(clap::ArgAction::Append))
+        .arg(Arg::new("прескочи-шаблон")
+             .long("прескочи-шаблон")
+             .visible_alias("skip-pattern")
+             .global(true)
+             .value_name(tr("ИЗРАЗ"))
+             .help(tr("Регуларни израз за делове текста који остају непромењени (нпр. „ID-\\d+[A-Z]+”). Може се навести више пута."))
+             .action(clap::ArgAction::Append))
+        .arg(Arg::new("улазно-кодирање")
+             .long("улазно-кодирање")
+             .visible_alias("input-encoding")
+             .value_name(tr("КОДИРАЊЕ"))
+             .help(tr("Кодирање улазног текста (подразумевано се детектује)"))
+             .value_parser(PossibleValuesParser::new(["auto"].into_iter().chain(Encoding::ALL.iter().map(|e| e.name())))
+                           .map(|s| s.parse::<Encoding>().ok()))
+             .default_value("auto"))
+        .arg(Arg::new("излазно-кодирање")
+             .long("излазно-кодирање")
+             .visible_alias("output-encoding")
+             .value_name(tr("КОДИРАЊЕ"))
+             .help(tr("Кодирање излазног текста"))
+             .value_parser(PossibleValuesParser::new(Encoding::ALL.iter().map(|e| e.name()))
+                           .map(|s| s.parse::<Encoding>().unwrap()))
+             .default_value("utf-8"))
+        .arg(Arg::new("бом")
+             .long("бом")
+             .visible_alias("bom")
+             .value_name(tr("РЕЖИМ"))
+             .help(tr("BOM на почетку излаза: задржи (ако га је имао улаз), уклони или додај"))
+             .value_parser(PossibleValuesParser::new(["задржи", "уклони", "додај"])
+                           .map(|s| match s.as_str() { "уклони" => Bom::Strip, "додај" => Bom::Add, _ => Bom::Keep }))
+             .default_value("задржи"))
+        .arg(Arg::new("крај-реда")
+             .long("крај-реда")
+             .visible_alias("line-ending")
+             .value_name(tr("КРАЈ"))
+             .help(tr("Крај реда излаза: lf или crlf (подразумевано претежни крај реда улаза)"))
+             .value_parser(PossibleValuesParser::new(["lf", "crlf"])
+                           .map(|s| s.parse::<LineEnding>().unwrap())))
+        .arg(Arg::new("формат")
+             .short('ф')
+             .long("формат")
+             .visible_alias("format")
+             .value_name(tr("ФОРМАТ"))
+             .help(tr("Формат улаза код кога се пресловљава само текст, а структура остаје непромењена (подразумевано се одређује на основу екстензије фајла)"))
+             .value_parser(PossibleValuesParser::new(["auto"].into_iter().chain(Format::ALL.iter().map(|f| f.name())))
+                           .map(|s| s.parse::<Format>().ok()))
+             .default_value("auto"))
+        .arg(Arg::new("пандок-филтер")
+             .long("пандок-филтер")
+             .visible_alias("pandoc-filter")
+             .help(tr("Ради као Pandoc филтер (pandoc --filter): конвертуј Pandoc AST са стандардног улаза у писмо задато метаподатком lang или опцијом -л или -ћ"))
+             .conflicts_with_all(["формат", "улаз"])
+             .action(clap::ArgAction::SetTrue))
+        .arg(Arg::new("атрибути")
+             .long("атрибути")
+             .visible_alias("attributes")
+             .value_name(tr("СЕЛЕКТОРИ"))
+             .help(tr("XML атрибути који се пресловљавају, одвојени зарезом (нпр. @title,img/@alt)"))
+             .value_delimiter(',')
+             .value_parser(|s: &str| s.parse::<AttributeSelector>().map_err(tr))
+             .action(clap::ArgAction::Append))
+        .arg(Arg::new("кључеви")
+             .long("кључеви")
+             .visible_alias("keys")
+             .value_name(tr("ШАБЛОН"))
+             .help(tr("Пресловљавај и JSON кључеве који одговарају регуларном изразу"))
+             .value_parser(|s: &str| Regex::new(s)))
+        .arg(Arg::new("поља")
+             .long("поља")
+             .visible_alias("fields")
+             .value_name(tr("ПОЉА"))
+             .help(tr("JSONL поља која се пресловљавају, одвојена зарезом (нпр. naslov,autor.ime)"))
+             .value_delimiter(',')
+             .value_parser(|s: &str| s.parse::<Field>().map_err(tr))
+             .action(clap::ArgAction::Append))
+        .arg(Arg::new("колоне")
+             .long("колоне")
+             .visible_alias("columns")
+             .value_name(tr("КОЛОНЕ"))
+             .help(tr("Редни бројеви CSV/TSV/XLSX колона које се пресловљавају, одвојени зарезом (нпр. 2,5)"))
+             .value_delimiter(',')
+             .value_parser(clap::value_parser!(NonZeroUsize))
+             .action(clap::ArgAction::Append))
+        .arg(Arg::new("листови")
+             .long("листови")
+             .visible_alias("sheets")
+             .value_name(tr("ЛИСТОВИ"))
+             .help(tr("Називи XLSX листова који се пресловљавају, одвојени зарезом"))
+             .value_delimiter(',')
+             .action(clap::ArgAction::Append))
+        .arg(Arg::new("поља-заглавља")
+             .long("поља-заглавља")
+             .visible_alias("front-matter-fields")
+             .value_name(tr("ПОЉА"))
+             .help(tr("Поља заглавља страна сајта која се пресловљавају, одвојена зарезом"))
+             .value_delimiter(',')
+             .default_value("title,description")
+             .action(clap::ArgAction::Append))
+        .arg(Arg::new("излази-ћелија")
+             .long("излази-ћелија")
+             .visible_alias("cell-outputs")
+             .help(tr("Пресловљавај и текстуалне излазе ћелија кода у Jupyter свескама"))
+             .action(clap::ArgAction::SetTrue))
+        .arg(Arg::new("маркери")
+             .long("маркери")
+             .visible_alias("markers")
+             .global(true)
+             .value_names([tr("ИСКЉУЧИ"), tr("УКЉУЧИ")])
+             .num_args(2)
+             .help(tr("Маркери у тексту између којих се не ради конверзија (подразумевано „<!-- ћирко:искључи -->” и „<!-- ћирко:укључи -->”)")))
+        .arg(Arg::new("без-маркера")
+             .long("без-маркера")
+             .visible_alias("no-markers")
+             .global(true)
+             .help(tr("Не препознај маркере за искључивање конверзије"))
+             .conflicts_with("маркери")
+             .action(clap::ArgAction::SetTrue))
+        .arg(Arg::new("конфигурација")
+             .long("конфигурација")
+             .visible_alias("config")
+             .value_name("FILE")
+             .help(tr("Конфигурациони фајл са подразумеваним вредностима опција (подразумевано ~/.config/cirko/config.toml)"))
+             .value_parser(clap::value_parser!(PathBuf)))
+        .disable_help_subcommand(true)
+        .subcommand(Command::new("допуне")
+                    .visible_alias("completions")
+                    .about(tr("Испиши скрипту за допуњавање опција командног интерпретера"))
+                    .arg(Arg::new("интерпретер")
+                         .value_name("SHELL")
+                         .required(true)
+                         .value_parser(clap::value_parser!(Shell))))
+        .subcommand(Command::new("преименуј")
+                    .visible_aliases(["preimenuj", "rename"])
+                    .about(tr("Преслови називе фајлова и директоријума (не и садржај) у задатом директоријуму"))
+                    .arg(Arg::new("директоријум")
+                         .value_name(tr("ДИРЕКТОРИЈУМ"))
+                         .required(true)
+                         .value_parser(clap::value_parser!(PathBuf)))
+                    .arg(Arg::new("латиница")
+                         .short('л')
+                         .long("латиница")
+                         .visible_alias("to-latin")
+                         .help(tr("Преслови називе у латиницу"))
+                         .action(clap::ArgAction::SetTrue))
+                    .arg(Arg::new("ћирилица")
+                         .short('ћ')
+                         .long("ћирилица")
+                         .visible_alias("to-cyrillic")
+                         .help(tr("Преслови називе у ћирилицу"))
+                         .action(clap::ArgAction::SetTrue))
+                    .group(clap::ArgGroup::new("смер")
+                           .args(["латиница", "ћирилица"])
+                           .required(true))
+                    .arg(Arg::new("проба")
+                         .long("проба")
+                         .visible_alias("dry-run")
+                         .help(tr("Само испиши преименовања, без измене назива"))
+                         .action(clap::ArgAction::SetTrue)))
+        .subcommand(Command::new("хук")
+                    .visible_alias("hook")
+                    .about(tr("Провери да ли су фајлови припремљени за комит (git diff --cached) у циљном писму"))
+                    .arg(Arg::new("фајлови")
+                         .value_name("FILE")
+                         .help(tr("Фајлови који се проверавају (подразумевано фајлови припремљени за комит)"))
+                         .value_parser(clap::value_parser!(PathBuf))
+                         .num_args(0..))
+                    .arg(Arg::new("латиница")
+                         .short('л')
+                         .long("латиница")
+                         .visible_alias("to-latin")
+                         .help(tr("Фајлови морају бити на латиници"))
+                         .action(clap::ArgAction::SetTrue))
+                    .arg(Arg::new("ћирилица")
+                         .short('ћ')
+                         .long("ћирилица")
+                         .visible_alias("to-cyrillic")
+                         .help(tr("Фајлови морају бити на ћирилици"))
+                         .action(clap::ArgAction::SetTrue))
+                    .group(clap::ArgGroup::new("смер")
+                           .args(["латиница", "ћирилица"])
+                           .required(true))
+                    .arg(Arg::new("укључи")
+                         .long("укључи")
+                         .visible_alias("include")
+                         .value_name(tr("ШАБЛОН"))
+                         .help(tr("Проверавај само фајлове који одговарају шаблону (нпр. „*.md”)"))
+                         .value_parser(|s: &str| s.parse::<Glob>().map_err(tr))
+                         .action(clap::ArgAction::Append))
+                    .arg(Arg::new("поправи")
+                         .long("поправи")
+                         .visible_alias("fix")
+                         .help(tr("Конвертуј фајлове који нису у циљном писму"))
+                         .action(clap::ArgAction::SetTrue)))
+        .subcommand(Command::new("детектуј")
+                    .visible_aliases(["detektuj", "detect"])
+                    .about(tr("Одреди писмо фајлова и испиши линије у којима се појављују оба писма"))
+                    .arg(Arg::new("фајлови")
+                         .value_name("FILE")
+                         .help(tr("Фајлови чије се писмо одређује"))
+                         .value_parser(clap::value_parser!(PathBuf))
+                         .required(true)
+                         .num_args(1..)))
+        .subcommand(Command::new("бенч")
+                    .visible_aliases(["benč", "bench"])
+                    .about(tr("Измери брзину конверзије у оба смера"))
+                    .arg(Arg::new("фајл")
+                         .value_name("FILE")
+                         .help(tr("Текст на коме се мери брзина (подразумевано уграђени узорак од 1 MB)"))
+                         .value_parser(clap::value_parser!(PathBuf)))
+                    .arg(Arg::new("трајање")
+                         .long("трајање")
+                         .visible_alias("duration")
+                         .value_name(tr("СЕКУНДЕ"))
+                         .help(tr("Најмање трајање мерења за сваки смер"))
+                         .default_value("1")
+                         .value_parser(|s: &str| s.parse::<f64>()
+                                                  .ok()
+                                                  .and_then(|seconds| Duration::try_from_secs_f64(seconds).ok())
+                                                  .ok_or_else(|| tr(format!("неисправно трајање „{s}”"))))));
+    messages::localize_help(command)
+}
+
+/// Исписује скрипту допуна опција за интерпретер командне линије.
+fn completions(command: &mut Command, matches: &ArgMatches) -> io::Result<()> {
+    let shell = *matches.get_one::<Shell>("интерпретер").unwrap();
+    let name = command.get_name().to_string();
+    clap_complete::generate(shell, command, name, &mut io::stdout());
+    Ok(())
+}
+
+/// Пресловљава називе фајлова. Сва преименовања се одређују унапред да би се
+/// поклапања назива открила пре било какве измене.
+fn rename(command: &mut Command, matches: &ArgMatches) -> io::Result<()> {
+    let direction = if matches.get_flag("латиница") { Direction::ToLatin } else { Direction::ToCyrillic };
+    let converter = build_converter(command, matches);
+    let root = matches.get_one::<PathBuf>("директоријум").unwrap();
+    let renames = files::plan_renames(root, &|name| converter.convert(name, direction))
+        .unwrap_or_else(|e| fail_with(command, None, e));
+    for (from, to) in renames {
+        println!("{} → {}", from.display(), to.display());
+        if !matches.get_flag("проба") {
+            fs::rename(&from, to).unwrap_or_else(|e| fail_with(command, Some(&from), e.into()));
+        }
+    }
+    Ok(())
+}
 
-    let input = if let Some(file) = matches.get_one::<String>("улаз") {
-        fs::read_to_string(file)?
+/// Проверава фајлове припремљене за комит. Излазни код је 1 ако неки фајл
+/// није у циљном писму (или је поправљен, па га треба поново додати).
+fn hook(command: &mut Command, matches: &ArgMatches) -> ! {
+    let direction = if matches.get_flag("латиница") { Direction::ToLatin } else { Direction::ToCyrillic };
+    // Фајлови припремљени за комит се проверавају онакви какви су у индексу,
+    // а не у радном директоријуму
+    let files = match matches.get_many::<PathBuf>("фајлови") {
+        Some(files) => files.map(|path| (path.clone(), None)).collect(),
+        None => staged_files()
+            .unwrap_or_else(|e| fail_with(command, None, e.into()))
+            .into_iter()
+            .map(|(path, name)| (path, Some(name)))
+            .collect::<Vec<_>>(),
+    };
+    let include = matches.get_many::<Glob>("укључи").unwrap_or_default().collect::<Vec<_>>();
+    let converter = build_converter(command, matches);
+    let settings = Settings::default();
+    let mut failed = false;
+    for (path, staged) in files {
+        let format = Format::from_path(&path).unwrap_or(Format::Text);
+        if format.is_binary() || !include.is_empty() && !include.iter().any(|glob| glob.matches(&path)) {
+            continue;
+        }
+        let bytes = match &staged {
+            Some(name) => git(&["show", &format!(":{name}")]),
+            None => fs::read(&path),
+        };
+        let bytes = match bytes {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                failed = true;
+                diagnostics::error(Kind::Io, Some(path.as_path()), e);
+                continue;
+            }
+        };
+        if Format::from_path(&path).is_none() && encoding::is_binary(&bytes) {
+            diagnostics::warning(Kind::Binary, Some(path.as_path()), "бинарни фајл је прескочен");
+            continue;
+        }
+        // Фајлови који не могу да се декодирају се не проверавају
+        let content = match decode_text(&path, &bytes, Encoding::detect(&bytes)) {
+            Ok(content) => content,
+            Err(e) => {
+                diagnostics::warning(Kind::of(&e), Some(path.as_path()), e);
+                continue;
+            }
+        };
+        let convert = |content: &str| {
+            convert_document(format, content.as_bytes(), &settings, Some(direction), |text| converter.convert(text, direction))
+                .and_then(|converted| String::from_utf8(converted).map_err(|e| Error::Io(io::Error::new(io::ErrorKind::InvalidData, e))))
+        };
+        let result = convert(&content).and_then(|converted| {
+            if converted == content {
+                return Ok(false);
+            }
+            if !matches.get_flag("поправи") {
+                print!("{}", unconverted_lines(&content, &converted, &path.to_string_lossy()));
+                return Ok(true);
+            }
+            // Поправља се фајл у радном директоријуму, у његовом кодирању
+            let bytes = fs::read(&path)?;
+            let encoding = Encoding::detect(&bytes);
+            let converted = convert(&encoding.decode(&bytes)?)?;
+            files::write_atomic(&path, &encoding.encode(&converted)?, None)?;
+            diagnostics::info(Kind::Converted, Some(&path), "конвертован");
+            Ok(true)
+        });
+        match result {
+            Ok(unconverted) => failed |= unconverted,
+            Err(e) => {
+                failed = true;
+                diagnostics::error(Kind::of(&e), Some(path.as_path()), e);
+            }
+        }
+    }
+    std::process::exit(if failed { 1 } else { 0 })
+}
+
+/// Детектује писмо фајлова. Излазни код је 1 ако неки фајл није могао да се
+/// прочита.
+fn detect(matches: &ArgMatches) -> ! {
+    let mut failed = false;
+    for path in matches.get_many::<PathBuf>("фајлови").unwrap() {
+        let content = match fs::read(path).map_err(Error::from).and_then(|content| Encoding::detect(&content).decode(&content)) {
+            Ok(content) => content,
+            Err(e) => {
+                failed = true;
+                diagnostics::error(Kind::of(&e), Some(path.as_path()), e);
+                continue;
+            }
+        };
+        let report = script::detect(&content);
+        let script = match report.script() {
+            DetectedScript::Latin => "латиница",
+            DetectedScript::Cyrillic => "ћирилица",
+            DetectedScript::Mixed => "мешовито",
+            DetectedScript::Unknown => "без слова",
+        };
+        let summary = format!("{}: {script} (ћирилица {:.1}%, латиница {:.1}%)", path.display(), report.cyrillic_share(), report.latin_share());
+        println!("{}", tr(summary));
+        for (line, text) in report.mixed_lines {
+            println!("  {line}: {text}");
+        }
+    }
+    std::process::exit(if failed { 1 } else { 0 })
+}
+
+/// Мери брзину конверзије на задатом тексту или уграђеном узорку.
+fn benchmark(command: &mut Command, matches: &ArgMatches) -> io::Result<()> {
+    let (name, corpus) = match matches.get_one::<PathBuf>("фајл") {
+        Some(path) => {
+            let corpus = fs::read(path).map_err(Error::from).and_then(|content| Encoding::detect(&content).decode(&content))
+                                       .unwrap_or_else(|e| fail_with(command, Some(path), e));
+            (path.display().to_string(), corpus)
+        }
+        None => (tr("уграђени узорак"), bench::sample(1_000_000)),
+    };
+    println!("{}", tr(format!("корпус: {:.2} MB ({name})", corpus.len() as f64 / 1e6)));
+    let converter = Converter::default();
+    let duration = *matches.get_one::<Duration>("трајање").unwrap();
+    for measurement in bench::measure_all(&converter, &corpus, duration) {
+        let direction = match measurement.direction {
+            Direction::ToCyrillic => "латиница → ћирилица",
+            Direction::ToLatin => "ћирилица → латиница",
+        };
+        let throughput = measurement.throughput();
+        println!("{}", tr(format!("{direction}: {throughput:.1} MB/s (понављања: {})", measurement.iterations)));
+        let share = |part: Duration| 100.0 * part.as_secs_f64() / measurement.total.as_secs_f64().max(f64::MIN_POSITIVE);
+        for (label, part) in [("прескакање", measurement.skip), ("пресловљавање", measurement.mapping())] {
+            let line = format!("{label}: {:.2} ms ({:.1}%)", part.as_secs_f64() * 1e3, share(part).min(100.0));
+            println!("  {}", tr(line));
+        }
+    }
+    Ok(())
+}
+
+/// Конвертује текст са стандардног улаза, улазни фајл, листу фајлова или
+/// директоријум.
+fn convert(command: &mut Command, matches: &ArgMatches) -> io::Result<()> {
+    // Садржај сајта и рекурзивна конверзија читају фајлове директоријума
+    // Формат фајла се, ако није задат, одређује на основу екстензије, а текст
+    // се подразумева
+    // Pandoc филтеру се AST прослеђује на стандардни улаз, а назив излазног
+    // формата као аргумент који се занемарује
+    let pandoc_filter = matches.get_flag("пандок-филтер");
+    let explicit_format = if pandoc_filter { Some(Format::Pandoc) } else { *matches.get_one::<Option<Format>>("формат").unwrap() };
+    // Улазни фајлови се задају опцијом `-у` или као аргументи. Више фајлова
+    // или фајлови уз излазни директоријум се конвертују као листа фајлова.
+    let inputs = matches.get_many::<String>("улаз").into_iter().flatten()
+                        .chain(matches.get_many::<String>("фајлови").into_iter().flatten().filter(|_| !pandoc_filter))
+                        .collect::<Vec<_>>();
+    let output_dir = matches.get_one::<String>("излаз-дир").map(Path::new);
+    let multiple_inputs = inputs.len() > 1 || output_dir.is_some();
+    let input_file = inputs.first().copied().map(String::as_str).filter(|_| !multiple_inputs);
+    let format = explicit_format.or_else(|| input_file.and_then(|file| Format::from_path(Path::new(file)))).unwrap_or(Format::Text);
+    // Листа фајлова се чита са стандардног улаза уместо текста
+    let listed_files = if matches.get_flag("фајлови-из-стдин") {
+        Some(files::read_file_list(io::stdin().lock(), matches.get_flag("нул")).unwrap_or_else(|e| fail_with(command, None, e.into())))
+    } else if multiple_inputs {
+        if matches.contains_id("излаз") {
+            let message = "излазни фајл (-и) се не може задати уз више улазних фајлова; користите --излаз-дир";
+            fail(command, ErrorKind::ArgumentConflict, message)
+        }
+        Some(inputs.iter().map(PathBuf::from).collect())
+    } else {
+        None
+    };
+    let mut reader: Box<dyn BufRead> = if format == Format::Site || matches.contains_id("рекурзивно") || listed_files.is_some() {
+        Box::new(io::empty())
+    } else if let Some(file) = input_file {
+        Box::new(BufReader::new(File::open(file).unwrap_or_else(|e| fail_with(command, Some(Path::new(file)), e.into()))))
     } else {
-        let mut buffer = String::new();
-        io::stdin().read_to_string(&mut buffer)?;
-        buffer
+        Box::new(io::stdin().lock())
     };
+    // JSONL ток и текст у линијском режиму се обрађују ред по ред, па се
+    // унапред чита само први ред на основу кога се детектује смер конверзије
+    let mut input = String::new();
+    let mut binary = Vec::new();
+    let preview = matches.get_flag("преглед");
+    let check = matches.get_flag("провера");
+    let service = matches.get_flag("служба");
+    let socket = matches.get_one::<PathBuf>("сокет").map(PathBuf::as_path);
+    // Ако се текст уноси са терминала, свака линија се конвертује одмах
+    let interactive = io::stdin().is_terminal()
+        && format == Format::Text
+        && !["улаз", "фајлови", "излаз", "излаз-дир", "рекурзивно", "линије", "сокет"].iter().any(|id| matches.contains_id(id))
+        && !["служба", "линијски", "преглед", "провера", "повратна-провера", "прати", "помешана-писма", "фајлови-из-стдин"].iter().any(|id| matches.get_flag(id));
+    let line_mode = matches.get_flag("линијски");
+    // Кодирање улаза се, ако није задато, детектује за сваки фајл
+    let input_encoding = *matches.get_one::<Option<Encoding>>("улазно-кодирање").unwrap();
+    if input_encoding.is_some_and(|encoding| encoding != Encoding::Utf8) && (line_mode || format == Format::Jsonl) {
+        let message = "кодирање улаза мора бити UTF-8 код линијског режима и формата jsonl";
+        fail(command, ErrorKind::ArgumentConflict, message)
+    }
+    let output_encoding = *matches.get_one::<Encoding>("излазно-кодирање").unwrap();
+    if output_encoding != Encoding::Utf8 && (format.is_binary() || matches!(format, Format::Jsonl | Format::Site)) {
+        let message = format!("кодирање излаза мора бити UTF-8 за формат {format}");
+        fail(command, ErrorKind::ArgumentConflict, message)
+    }
+    let input_path = input_file.map(Path::new);
+    if service || interactive {
+        // Захтеви службе и интерактивни унос се читају тек након подешавања
+        // конвертора
+    } else if line_mode || format == Format::Jsonl && !matches.get_flag("помешана-писма") && !preview && !check {
+        reader.read_line(&mut input).unwrap_or_else(|e| fail_with(command, input_path, e.into()));
+    } else if format.is_binary() && !matches.get_flag("помешана-писма") {
+        reader.read_to_end(&mut binary).unwrap_or_else(|e| fail_with(command, input_path, e.into()));
+    } else {
+        reader.read_to_end(&mut binary).unwrap_or_else(|e| fail_with(command, input_path, e.into()));
+        let name = input_path.unwrap_or(Path::new("stdin"));
+        let encoding = input_encoding.unwrap_or_else(|| Encoding::detect(&binary));
+        input = decode_text(name, &binary, encoding).unwrap_or_else(|e| fail_with(command, input_path, e));
+    }
+    // BOM на почетку улаза се уклања пре конверзије
+    let input_bom = input.starts_with(BOM);
+    if input_bom {
+        input.remove(0);
+    }
 
-    // Смер конерзије се може задати опцијама команде
-    let output = if matches.get_flag("латиница") {
-        crate::cyr_to_lat(&input)
+    // Смер конерзије се може задати опцијама команде, а иначе се детектује на
+    // основу текста. Смер задат у командној линији премошћава супротан смер
+    // из конфигурационог фајла. Pandoc филтер узима смер из метаподатка
+    // `lang` ако смер није задат у командној линији.
+    let command_line_direction = ["латиница", "ћирилица"].iter().any(|id| matches.value_source(id) == Some(ValueSource::CommandLine));
+    let pandoc_direction = (pandoc_filter && !command_line_direction)
+        .then(|| pandoc::language(&input).ok().flatten().and_then(|tag| Direction::from_tag(&tag)))
+        .flatten();
+    let explicit_direction = if pandoc_direction.is_some() {
+        pandoc_direction
+    } else if matches.get_flag("латиница")
+        && matches.value_source("ћирилица") != Some(ValueSource::CommandLine)
+    {
+        Some(Direction::ToLatin)
     } else if matches.get_flag("ћирилица") {
-        crate::lat_to_cyr(&input)
+        Some(Direction::ToCyrillic)
     } else {
-        // Аутоматска детекција смера конверзије
-        if input.chars().any(|c| ('а'..='ш').contains(&c) || ('А'..='Ш').contains(&c)) {
-            crate::cyr_to_lat(&input)
+        None
+    };
+    // Текст се шаље на конверзију служби која већ ради, без подешавања
+    // конвертора
+    if let Some(socket) = socket && !service {
+        if format != Format::Text {
+            let message = format!("конверзија преко службе није подржана за формат {format}");
+            fail(command, ErrorKind::ArgumentConflict, message)
+        }
+        // Служба конвертује текст сопственим подешавањима
+        if let Some(id) = CONVERTER_OPTIONS.iter().find(|id| matches.value_source(id) == Some(ValueSource::CommandLine)) {
+            let message = format!("опција „--{id}” се не може задати при конверзији преко службе");
+            fail(command, ErrorKind::ArgumentConflict, message)
+        }
+        let output = rpc::convert_remote(socket, &input, explicit_direction).unwrap_or_else(|e| fail_with(command, Some(socket), e));
+        write_output(command, matches, output, None);
+        return Ok(());
+    }
+
+    if matches.get_flag("помешана-писма") {
+        let report = mixed_script_words(&input)
+            .iter()
+            .map(|mixed| {
+                let chars = mixed.word.chars().collect::<Vec<_>>();
+                let stray = mixed.stray.iter().map(|&i| chars[i]).collect::<String>();
+                format!("{}:{}: {} ({})\n", mixed.line, mixed.column, mixed.word, stray)
+            })
+            .collect::<String>();
+        write_output(command, matches, report, None);
+        return Ok(());
+    }
+
+    let converter = build_converter(command, matches);
+
+    // Конверзија екавице/ијекавице и поправка хомоглифа. Писмо се тада мења
+    // само ако је смер експлицитно задат.
+    let transform_only = matches.get_flag("ијекавица")
+        || matches.get_flag("екавица")
+        || matches.get_flag("хомоглифи");
+
+    // Служба за едиторе обрађује захтеве док се стандардни улаз не затвори,
+    // односно док се не заустави ако захтеве прима преко сокета
+    if service {
+        if let Some(socket) = socket {
+            return rpc::listen(socket, &converter, explicit_direction).or_else(|e| match e.kind() {
+                io::ErrorKind::AddrInUse => fail(command, ErrorKind::Io, format!("служба већ ради на сокету {}", socket.display())),
+                _ => fail_with(command, Some(socket), e.into()),
+            });
+        }
+        return rpc::serve(reader, io::stdout().lock(), &converter, explicit_direction);
+    }
+    if explicit_direction.is_none() && !transform_only && (format.is_binary() || format == Format::Site) {
+        let message = format!("смер конверзије (-л или -ћ) мора бити задат за формат {format}");
+        fail(command, ErrorKind::MissingRequiredArgument, message)
+    }
+    if line_mode && format != Format::Text {
+        let message = format!("линијски режим није подржан за формат {format}");
+        fail(command, ErrorKind::ArgumentConflict, message)
+    }
+    if matches.contains_id("линије") && !matches!(format, Format::Text | Format::Markdown) {
+        let message = format!("конверзија опсега линија није подржана за формат {format}");
+        fail(command, ErrorKind::ArgumentConflict, message)
+    }
+    if preview && (format.is_binary() || format == Format::Site) {
+        let message = format!("преглед измена није подржан за формат {format}");
+        fail(command, ErrorKind::ArgumentConflict, message)
+    }
+    if check && (format.is_binary() || format == Format::Site) {
+        let message = format!("провера није подржана за формат {format}");
+        fail(command, ErrorKind::ArgumentConflict, message)
+    }
+    let per_paragraph = matches.get_flag("аутоматски-по-пасусу");
+    if per_paragraph && explicit_direction.is_none() {
+        let message = "смер конверзије (-л или -ћ) мора бити задат за конверзију по пасусима";
+        fail(command, ErrorKind::MissingRequiredArgument, message)
+    }
+    let round_trip = matches.get_flag("повратна-провера");
+    if round_trip && (format.is_binary() || format == Format::Site) {
+        let message = format!("повратна провера није подржана за формат {format}");
+        fail(command, ErrorKind::ArgumentConflict, message)
+    }
+    if round_trip && transform_only && explicit_direction.is_none() {
+        let message = "смер конверзије (-л или -ћ) мора бити задат за повратну проверу";
+        fail(command, ErrorKind::MissingRequiredArgument, message)
+    }
+    if check && explicit_direction.is_none() {
+        let message = "смер конверзије (-л или -ћ) мора бити задат за проверу";
+        fail(command, ErrorKind::MissingRequiredArgument, message)
+    }
+    let in_place = matches.get_flag("у-месту");
+    if in_place && listed_files.is_none() && input_file.is_none() && !matches.contains_id("рекурзивно") {
+        let message = "улазни фајл, директоријум или листа фајлова мора бити задата за упис у месту";
+        fail(command, ErrorKind::MissingRequiredArgument, message)
+    }
+    // Излазни фајл који је исти као улазни се преписује само уз `--сила`
+    if let (Some(input), Some(output)) = (input_file, matches.get_one::<String>("излаз"))
+        && !matches.get_flag("сила")
+        && let (Ok(input), Ok(output)) = (fs::canonicalize(input), fs::canonicalize(output))
+        && input == output && input.is_file() {
+        let message = "излазни фајл је исти као улазни; користите --у-месту или --сила";
+        fail(command, ErrorKind::ArgumentConflict, message)
+    }
+    let settings = Settings {
+        language: converter.language(),
+        attributes: matches
+            .get_many::<AttributeSelector>("атрибути")
+            .unwrap_or_default()
+            .cloned()
+            .collect(),
+        keys: matches.get_one::<Regex>("кључеви").cloned(),
+        fields: matches
+            .get_many::<Field>("поља")
+            .unwrap_or_default()
+            .cloned()
+            .collect(),
+        columns: matches
+            .get_many::<NonZeroUsize>("колоне")
+            .map(|columns| columns.map(|column| column.get() - 1).collect()),
+        sheets: matches
+            .get_many::<String>("листови")
+            .map(|sheets| sheets.cloned().collect()),
+        cell_outputs: matches.get_flag("излази-ћелија"),
+    };
+    let conversion = Conversion {
+        matches,
+        converter,
+        settings,
+        explicit_format,
+        explicit_direction,
+        input_encoding,
+        output_encoding,
+        bom: *matches.get_one::<Bom>("бом").unwrap(),
+        newline: matches.get_one::<LineEnding>("крај-реда").copied(),
+        transform_only,
+        // Код чишћења документа се текст конвертује у преовлађујуће писмо
+        cleanup: matches.get_flag("очисти"),
+        per_paragraph,
+        strict: matches.get_flag("строго"),
+        preview,
+        check,
+        round_trip,
+        in_place,
+        preserve: matches.get_flag("задржи-атрибуте"),
+        color: matches.get_flag("боје"),
+        watch: matches.get_flag("прати"),
+        show_progress: matches.get_flag("напредак"),
+    };
+    let direction = conversion.detect_direction(&input);
+
+    if interactive {
+        return convert_interactive(&conversion, reader);
+    }
+    if matches.contains_id("рекурзивно") || listed_files.is_some() {
+        return convert_files(command, &conversion, format, listed_files, output_dir, multiple_inputs);
+    }
+    if conversion.watch && format != Format::Site {
+        return watch_file(command, &conversion, format, input_file);
+    }
+    if line_mode {
+        return convert_lines(command, &conversion, reader, input, input_file, input_bom, direction);
+    }
+    if format == Format::Jsonl && !preview && !check {
+        return convert_jsonl(command, &conversion, reader, input, input_file, input_bom, direction);
+    }
+    if format == Format::Site {
+        return convert_site(command, &conversion, input_file, direction);
+    }
+    let input = if format.is_binary() { binary } else { input.into_bytes() };
+    convert_input(command, &conversion, format, input, input_file, input_bom, direction)
+}
+
+/// Интерактивни режим, у коме се свака унета линија конвертује одмах.
+/// Командом `:смер` се мења смер конверзије.
+fn convert_interactive(conversion: &Conversion, mut reader: Box<dyn BufRead>) -> io::Result<()> {
+    let statistics = Mutex::new(Stats::default());
+    let mut direction = conversion.explicit_direction;
+    eprintln!("{}", tr("Унесите текст за конверзију (Ctrl+D за крај, „:смер [латиница|ћирилица|auto]” за промену смера)."));
+    let mut previous = String::new();
+    let mut line = String::new();
+    loop {
+        eprint!("> ");
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            eprintln!();
+            conversion.print_stats(&[("-".to_string(), statistics.into_inner().unwrap())]);
+            return Ok(());
+        }
+        // Команда и смер се могу задати и латиницом или на енглеском
+        if let Some(argument) = [":смер", ":smer", ":direction"].iter().find_map(|name| line.trim().strip_prefix(name)) {
+            let detected = direction.unwrap_or_else(|| Direction::detect(&previous));
+            direction = match argument.trim() {
+                "" if detected == Direction::ToLatin => Some(Direction::ToCyrillic),
+                "" => Some(Direction::ToLatin),
+                "латиница" | "latinica" | "latin" => Some(Direction::ToLatin),
+                "ћирилица" | "ćirilica" | "cyrillic" => Some(Direction::ToCyrillic),
+                "auto" => None,
+                argument => {
+                    eprintln!("{}", tr(format!("непознат смер „{argument}”")));
+                    direction
+                }
+            };
+            let name = match direction {
+                Some(Direction::ToLatin) => "латиница",
+                Some(Direction::ToCyrillic) => "ћирилица",
+                None => "аутоматски",
+            };
+            eprintln!("{}", tr(format!("смер: {name}")));
+            continue;
+        }
+        previous.clone_from(&line);
+        let direction = direction.or_else(|| conversion.detect_direction(&line));
+        print!("{}", conversion.convert_text(&line, direction, &statistics));
+        io::stdout().flush()?;
+    }
+}
+
+/// Рекурзивна конверзија фајлова директоријума у паралелни директоријум (или
+/// у месту) и конверзија фајлова из листе `listed_files`.
+fn convert_files(
+    command: &mut Command,
+    conversion: &Conversion,
+    format: Format,
+    listed_files: Option<Vec<PathBuf>>,
+    output_dir: Option<&Path>,
+    multiple_inputs: bool,
+) -> io::Result<()> {
+    let matches = conversion.matches;
+    let (preview, check, in_place) = (conversion.preview, conversion.check, conversion.in_place);
+    let output = output_dir.or_else(|| matches.get_one::<String>("излаз").map(Path::new));
+    if output.is_none() && !in_place && !preview && !check {
+        let message = if multiple_inputs {
+            "излазни директоријум (--излаз-дир) или упис у месту (--у-месту) мора бити задат за конверзију више фајлова"
+        } else if listed_files.is_some() {
+            "упис у месту (--у-месту) мора бити задат за конверзију фајлова из листе"
         } else {
-            crate::lat_to_cyr(&input)
+            "излазни директоријум (-и) мора бити задат за рекурзивну конверзију"
+        };
+        fail(command, ErrorKind::MissingRequiredArgument, message)
+    }
+    if format == Format::Site {
+        let message = "формат site се не може користити за конверзију више фајлова";
+        fail(command, ErrorKind::ArgumentConflict, message)
+    }
+    if let Some(path) = listed_files.iter().flatten().find(|path| !path.is_file()) {
+        let message = format!("„{}” није фајл", path.display());
+        fail(command, ErrorKind::Io, message)
+    }
+    // Фајлови се у излазни директоријум уписују под својим називима, па се
+    // два фајла истог назива не могу конвертовати заједно
+    if output_dir.is_some() {
+        let mut names = HashSet::new();
+        if let Some(name) = listed_files.iter().flatten().filter_map(|path| path.file_name()).find(|name| !names.insert(*name)) {
+            fail_with(command, None, Error::NameCollision(PathBuf::from(name)))
         }
+    }
+    let transliterate_names = matches.get_flag("преслови-називе");
+    let include = matches.get_many::<Glob>("укључи").unwrap_or_default().cloned().collect::<Vec<_>>();
+    let exclude = matches.get_many::<Glob>("искључи").unwrap_or_default().cloned().collect::<Vec<_>>();
+    let types = matches.get_many::<String>("врсте").unwrap_or_default().collect::<Vec<_>>();
+    let root = Path::new(matches.get_one::<String>("рекурзивно").map_or("", String::as_str));
+    let options = files::WalkOptions {
+        hidden: matches.get_flag("скривени"),
+        no_ignore: matches.get_flag("без-игнорисања"),
+    };
+    let list = || match &listed_files {
+        Some(files) => Ok(files.clone()),
+        None => list_files(root, &include, &exclude, options, output).map(|paths| {
+            paths.into_iter().filter(|path| types.is_empty() || types.iter().any(|kind| files::has_type(path, kind))).collect()
+        }),
     };
+    if let Some(output) = output && !preview && !check {
+        fs::create_dir_all(output).unwrap_or_else(|e| fail_with(command, Some(output), e.into()));
+    }
+    let jobs = matches.get_one::<NonZeroUsize>("послови").copied()
+                      .or_else(|| thread::available_parallelism().ok())
+                      .map_or(1, NonZeroUsize::get);
+    let mut unconverted = false;
+    // Прате се директоријуми фајлова из листе или цео улазни директоријум
+    let roots = match &listed_files {
+        Some(files) => files.iter().map(|path| (parent_dir(path), false)).collect::<HashSet<_>>().into_iter().collect(),
+        None => vec![(root, true)],
+    };
+    let result = watch_files(conversion.watch, &roots, list, |changed| {
+        let changed: &[PathBuf] = &changed
+            .iter()
+            .filter(|path| {
+                let binary = conversion.is_binary(path);
+                if binary {
+                    diagnostics::warning(Kind::Binary, Some(path), "бинарни фајл је прескочен");
+                }
+                !binary
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+        // Фајлови се конвертују паралелно, а измене код прегледа се исписују
+        // редом
+        let progress = conversion.show_progress.then(|| {
+            let total = changed.iter().filter_map(|path| fs::metadata(path).ok()).map(|metadata| metadata.len()).sum();
+            Progress::new(total, changed.len())
+        });
+        let results = parallel_map(changed, jobs, |path| -> Result<(String, Stats, Option<String>), Error> {
+            let (content, converted, input_bom, statistics) = conversion.convert_file(path)?;
+            // Текст резултата за одређивање положаја непресловљених речи
+            let text = (!statistics.unconverted.is_empty() && !conversion.file_format(path).is_binary())
+                .then(|| String::from_utf8_lossy(&converted).into_owned());
+            if let Some(progress) = &progress {
+                progress.advance(content.len() as u64, 1);
+            }
+            let file = path.strip_prefix(root).unwrap_or(path);
+            let target = match (output_dir, path.file_name().and_then(|name| name.to_str())) {
+                _ if in_place => Some(path.clone()),
+                // Назив фајла се пресловљава у смеру конверзије садржаја
+                (Some(output_dir), Some(name)) if transliterate_names => {
+                    let name = match conversion.detect_direction(&String::from_utf8_lossy(&content)) {
+                        Some(direction) => files::convert_file_name(name, |stem| conversion.converter.convert(stem, direction)),
+                        None => name.to_string(),
+                    };
+                    Some(output_dir.join(name))
+                }
+                (Some(output_dir), _) => path.file_name().map(|name| output_dir.join(name)),
+                _ => output.map(|output| output.join(file)),
+            };
+            let report = match target {
+                _ if check => Ok(unconverted_lines(&String::from_utf8_lossy(&content), &String::from_utf8_lossy(&converted), &file.to_string_lossy())),
+                Some(target) if !preview => {
+                    if let Some(parent) = target.parent() {
+                        fs::create_dir_all(parent)?;
+                    }
+                    let format = conversion.file_format(path);
+                    let output = conversion.encode(converted, conversion.output_bom(format, input_bom), conversion.line_ending(format, &content))?;
+                    files::write_atomic(&target, &output, conversion.preserve.then_some(path))?;
+                    Ok(String::new())
+                }
+                _ => {
+                    let name = file.to_string_lossy();
+                    Ok(unified_diff(&String::from_utf8_lossy(&content), &String::from_utf8_lossy(&converted), &name, &name, conversion.color))
+                }
+            };
+            report.map(|report| (report, statistics, text))
+        });
+        if let Some(progress) = progress {
+            progress.finish();
+        }
+        let mut file_stats = Vec::new();
+        for (path, result) in changed.iter().zip(results) {
+            let result = result.and_then(|(diff, statistics, text)| {
+                unconverted |= check && !diff.is_empty();
+                unconverted |= conversion.report_unconverted(path, &statistics, text.as_deref());
+                conversion.report_converted(path, &statistics);
+                file_stats.push((path.strip_prefix(root).unwrap_or(path).display().to_string(), statistics));
+                Ok(io::stdout().write_all(diff.as_bytes())?)
+            });
+            if let Err(e) = result {
+                report_error(command, conversion.watch, Some(path), e);
+            }
+        }
+        conversion.print_stats(&file_stats);
+    });
+    if unconverted {
+        std::process::exit(1)
+    }
+    result
+}
+
+/// Конвертује улазни фајл у излазни и поново га конвертује при свакој измени.
+fn watch_file(command: &mut Command, conversion: &Conversion, format: Format, input_file: Option<&str>) -> io::Result<()> {
+    let (Some(input), Some(output)) = (input_file, conversion.matches.get_one::<String>("излаз")) else {
+        let message = "улазни и излазни фајл (-у и -и) морају бити задати за праћење измена";
+        fail(command, ErrorKind::MissingRequiredArgument, message)
+    };
+    let (input, output) = (Path::new(input), Path::new(output));
+    // Прати се директоријум улазног фајла јер уређивачи фајл често замењују
+    // новим
+    watch_files(conversion.watch, &[(parent_dir(input), false)], || Ok(vec![input.to_path_buf()]), |_| {
+        let result = conversion.convert_file(input).and_then(|(content, converted, input_bom, statistics)| {
+            let text = (!format.is_binary()).then(|| String::from_utf8_lossy(&converted).into_owned());
+            let converted = conversion.encode(converted, conversion.output_bom(format, input_bom), conversion.line_ending(format, &content))?;
+            files::write_atomic(output, &converted, conversion.preserve.then_some(input))?;
+            conversion.report_unconverted(input, &statistics, text.as_deref());
+            conversion.report_converted(input, &statistics);
+            conversion.print_stats(&[(input.display().to_string(), statistics)]);
+            Ok(())
+        });
+        if let Err(e) = result {
+            report_error(command, conversion.watch, Some(input), e);
+        }
+    })
+}
 
-    if let Some(file) = matches.get_one::<String>("излаз") {
-        fs::write(file, output)?;
+/// Линијски режим, у коме се свака линија исписује чим се конвертује. Прва
+/// линија `line` је већ прочитана.
+fn convert_lines(
+    command: &mut Command,
+    conversion: &Conversion,
+    mut reader: Box<dyn BufRead>,
+    mut line: String,
+    input_file: Option<&str>,
+    input_bom: bool,
+    direction: Option<Direction>,
+) -> io::Result<()> {
+    let matches = conversion.matches;
+    let input_path = input_file.map(Path::new);
+    let statistics = Mutex::new(Stats::default());
+    // Писмо сваке линије се може одредити посебно, а уз задат смер се
+    // конвертују само линије у којима преовлађује писмо супротно циљном
+    let per_line = matches.get_flag("аутоматски-по-линији");
+    let line_direction = |line: &str| match conversion.explicit_direction {
+        Some(direction) => {
+            let source = match direction {
+                Direction::ToLatin => Script::Cyrillic,
+                Direction::ToCyrillic => Script::Latin,
+            };
+            (Script::dominant(line) == Some(source)).then_some(direction)
+        }
+        None => conversion.detect_direction(line),
+    };
+    let output_path = matches.get_one::<String>("излаз").map(Path::new);
+    let mut file = output_path.map(|file| files::AtomicFile::create(file).unwrap_or_else(|e| fail_with(command, Some(file), e.into())));
+    let mut writer: Box<dyn Write> = match &mut file {
+        Some(file) => Box::new(file),
+        None => Box::new(io::stdout().lock()),
+    };
+    let mut first = true;
+    while !line.is_empty() {
+        let converted = conversion.convert_text(&line, if per_line { line_direction(&line) } else { direction }, &statistics);
+        let converted = conversion.encode(converted.into_bytes(), first && conversion.output_bom(Format::Text, input_bom), conversion.line_ending(Format::Text, line.as_bytes()))
+            .unwrap_or_else(|e| fail_with(command, None, e));
+        writer.write_all(&converted)
+              .and_then(|_| writer.flush())
+              .unwrap_or_else(|e| fail_with(command, output_path, e.into()));
+        first = false;
+        line.clear();
+        reader.read_line(&mut line).unwrap_or_else(|e| fail_with(command, input_path, e.into()));
+    }
+    drop(writer);
+    if let Some(file) = file {
+        file.commit(input_path.filter(|_| conversion.preserve)).unwrap_or_else(|e| fail_with(command, output_path, e.into()));
+    }
+    Ok(())
+}
+
+/// Конверзија JSONL тока ред по ред. Први ред `first` је већ прочитан.
+fn convert_jsonl(
+    command: &mut Command,
+    conversion: &Conversion,
+    reader: Box<dyn BufRead>,
+    first: String,
+    input_file: Option<&str>,
+    input_bom: bool,
+    direction: Option<Direction>,
+) -> io::Result<()> {
+    let name = input_file.unwrap_or("stdin");
+    let statistics = Mutex::new(Stats::default());
+    let reader = io::Cursor::new(&first).chain(reader);
+    let output_path = conversion.matches.get_one::<String>("излаз").map(Path::new);
+    let mut file = output_path.map(|file| files::AtomicFile::create(file).unwrap_or_else(|e| fail_with(command, Some(file), e.into())));
+    let mut writer: Box<dyn Write> = match &mut file {
+        Some(file) => Box::new(BufWriter::new(file)),
+        None => Box::new(io::stdout().lock()),
+    };
+    if conversion.output_bom(Format::Jsonl, input_bom) {
+        writer.write_all(conversion.output_encoding.bom()).unwrap_or_else(|e| fail_with(command, output_path, e.into()));
+    }
+    jsonl::convert(reader, &mut writer, &conversion.settings.fields, |text| conversion.convert_text(text, direction, &statistics))
+        .unwrap_or_else(|e| fail_with(command, None, e));
+    writer.flush().unwrap_or_else(|e| fail_with(command, output_path, e.into()));
+    drop(writer);
+    if let Some(file) = file {
+        let source = input_file.map(Path::new).filter(|_| conversion.preserve);
+        file.commit(source).unwrap_or_else(|e| fail_with(command, output_path, e.into()));
+    }
+    let statistics = statistics.into_inner().unwrap();
+    let unconverted = conversion.report_unconverted(Path::new(name), &statistics, None);
+    conversion.report_converted(Path::new(name), &statistics);
+    conversion.print_stats(&[(name.to_string(), statistics)]);
+    if unconverted {
+        std::process::exit(1)
+    }
+    Ok(())
+}
+
+/// Конверзија садржаја сајта из улазног у излазни директоријум.
+fn convert_site(command: &mut Command, conversion: &Conversion, input_file: Option<&str>, direction: Option<Direction>) -> io::Result<()> {
+    let matches = conversion.matches;
+    let (Some(input), Some(output)) = (input_file, matches.get_one::<String>("излаз")) else {
+        let message = "улазни и излазни директоријум (-у и -и) морају бити задати за формат site";
+        fail(command, ErrorKind::MissingRequiredArgument, message)
+    };
+    let fields = matches
+        .get_many::<String>("поља-заглавља")
+        .unwrap_or_default()
+        .map(String::as_str)
+        .collect::<Vec<_>>();
+    // Ознака језика и писма страница (нпр. `sr-Latn`)
+    let tag = direction.map(|direction| conversion.settings.language.tag(direction));
+    let (input, output) = (Path::new(input), Path::new(output));
+    let statistics = Mutex::new(Stats::default());
+    let mut unconverted = false;
+    let list = || list_files(input, &[], &[], files::WalkOptions { hidden: true, no_ignore: true }, Some(output));
+    let result = watch_files(conversion.watch, &[(input, true)], list, |_| {
+        let transform = |text: &str| conversion.convert_text(text, direction, &statistics);
+        if let Err(e) = site::convert(input, output, &fields, tag.as_deref(), transform) {
+            report_error(command, conversion.watch, None, e);
+        }
+        let statistics = std::mem::take(&mut *statistics.lock().unwrap());
+        unconverted |= conversion.report_unconverted(input, &statistics, None);
+        conversion.report_converted(input, &statistics);
+        conversion.print_stats(&[(input.display().to_string(), statistics)]);
+    });
+    if unconverted {
+        std::process::exit(1)
+    }
+    result
+}
+
+/// Конверзија једног документа са стандардног улаза или из улазног фајла.
+/// Уместо резултата се могу исписати измене, редови који нису у циљном
+/// писму или губици повратне конверзије.
+fn convert_input(
+    command: &mut Command,
+    conversion: &Conversion,
+    format: Format,
+    input: Vec<u8>,
+    input_file: Option<&str>,
+    input_bom: bool,
+    direction: Option<Direction>,
+) -> io::Result<()> {
+    let matches = conversion.matches;
+    let (converter, settings) = (&conversion.converter, &conversion.settings);
+    let name = input_file.unwrap_or("stdin");
+    let source = input_file.map(Path::new).filter(|_| conversion.preserve);
+    let statistics = Mutex::new(Stats::default());
+    let transform = |text: &str| conversion.convert_text(text, direction, &statistics);
+    let line_ranges = matches.get_many::<LineRange>("линије").unwrap_or_default().copied().collect::<Vec<_>>();
+    let progress = conversion.show_progress.then(|| Progress::new(input.len() as u64, 1));
+    let output = match (&progress, std::str::from_utf8(&input)) {
+        // Смер конверзије се детектује за сваку групу узастопних линија
+        (_, Ok(text)) if !line_ranges.is_empty() => lines::convert_lines(text, &line_ranges, |chunk| {
+            let direction = conversion.detect_direction(chunk);
+            convert_document(format, chunk.as_bytes(), settings, direction, |text| conversion.convert_text(text, direction, &statistics))
+                .map(|output| String::from_utf8_lossy(&output).into_owned())
+        }).map(String::into_bytes),
+        // Текст се конвертује у деловима да би се напредак могао пратити
+        (Some(progress), Ok(text)) if format == Format::Text => {
+            let mut output = String::with_capacity(text.len());
+            for chunk in converter.split_chunks(text, PROGRESS_CHUNK_SIZE) {
+                output.push_str(&transform(chunk));
+                progress.advance(chunk.len() as u64, 0);
+            }
+            Ok(output.into_bytes())
+        }
+        _ => convert_document(format, &input, settings, direction, transform),
+    }.unwrap_or_else(|e| fail_with(command, None, e));
+    if let Some(progress) = &progress {
+        progress.finish();
+    }
+    let statistics = statistics.into_inner().unwrap();
+    let text = (!format.is_binary()).then(|| String::from_utf8_lossy(&output));
+    let unconverted = conversion.report_unconverted(Path::new(name), &statistics, text.as_deref());
+    conversion.report_converted(Path::new(name), &statistics);
+    conversion.print_stats(&[(name.to_string(), statistics)]);
+
+    // Код прегледа се уместо резултата исписују измене, а код провере редови
+    // који нису у циљном писму
+    if conversion.preview {
+        let diff = unified_diff(&String::from_utf8_lossy(&input), &String::from_utf8_lossy(&output), name, name, conversion.color);
+        return io::stdout().write_all(diff.as_bytes());
+    }
+    if conversion.check {
+        let report = unconverted_lines(&String::from_utf8_lossy(&input), &String::from_utf8_lossy(&output), name);
+        io::stdout().write_all(report.as_bytes())?;
+        std::process::exit(if report.is_empty() { 0 } else { 1 })
+    }
+    // Повратна провера конвертује резултат назад и исписује речи које се
+    // разликују од изворних
+    if conversion.round_trip && let Some(direction) = direction {
+        let reverse = direction.reverse();
+        let converted = String::from_utf8_lossy(&output);
+        let restored = convert_document(format, converted.as_bytes(), settings, Some(reverse), |text| converter.convert(text, reverse))
+            .unwrap_or_else(|e| fail_with(command, None, e));
+        let losses = round_trip_losses(&String::from_utf8_lossy(&input), &converted, &String::from_utf8_lossy(&restored));
+        for loss in &losses {
+            println!("{name}:{}:{}: {} → {} → {}", loss.line, loss.column, loss.original, loss.converted, loss.restored);
+        }
+        std::process::exit(if losses.is_empty() { 0 } else { 1 })
+    }
+    let output = conversion.encode(output, conversion.output_bom(format, input_bom), conversion.line_ending(format, &input))
+                           .unwrap_or_else(|e| fail_with(command, None, e));
+
+    // Android ресурси се у излазном директоријуму (`res`) уписују у варијанту
+    // за ново писмо (нпр. `values-b+sr+Latn/strings.xml`)
+    if format == Format::Android
+        && let Some(direction) = direction
+        && let Some(dir) = matches.get_one::<String>("излаз")
+        && Path::new(dir).is_dir() {
+        let name = input_file
+                          .and_then(|file| Path::new(file).file_name())
+                          .map_or("strings.xml".as_ref(), |name| name);
+        let dir = Path::new(dir).join(android::resource_directory(&settings.language.tag(direction)));
+        let path = dir.join(name);
+        fs::create_dir_all(&dir)
+            .and_then(|_| files::write_atomic(&path, &output, source))
+            .unwrap_or_else(|e| fail_with(command, Some(&path), e.into()));
+    } else if conversion.in_place && let Some(file) = input_file.map(Path::new) {
+        files::write_atomic(file, &output, source).unwrap_or_else(|e| fail_with(command, Some(file), e.into()));
     } else {
-        print!("{}", output);
+        write_output(command, matches, output, source);
+    }
+    // Код строге конверзије излазни код је 1 ако резултат садржи
+    // непресловљене речи
+    if unconverted {
+        std::process::exit(1)
+    }
+    Ok(())
+}
+
+/// Подешавања конверзије текста и фајлова задата опцијама.
+struct Conversion<'a> {
+    matches: &'a ArgMatches,
+    converter: Converter,
+    settings: Settings,
+    explicit_format: Option<Format>,
+    explicit_direction: Option<Direction>,
+    input_encoding: Option<Encoding>,
+    output_encoding: Encoding,
+    bom: Bom,
+    newline: Option<LineEnding>,
+    transform_only: bool,
+    cleanup: bool,
+    per_paragraph: bool,
+    strict: bool,
+    preview: bool,
+    check: bool,
+    round_trip: bool,
+    in_place: bool,
+    preserve: bool,
+    color: bool,
+    watch: bool,
+    show_progress: bool,
+}
+
+impl Conversion<'_> {
+    /// Формат фајла `path`, који се, ако није задат, одређује на основу
+    /// екстензије, а текст се подразумева.
+    fn file_format(&self, path: &Path) -> Format {
+        self.explicit_format.or_else(|| Format::from_path(path)).unwrap_or(Format::Text)
+    }
+
+    /// Декодира садржај фајла `file` задатим кодирањем улаза, а иначе
+    /// детектованим кодирањем.
+    fn decode(&self, file: &Path, bytes: &[u8]) -> Result<String, Error> {
+        decode_text(file, bytes, self.input_encoding.unwrap_or_else(|| Encoding::detect(bytes)))
+    }
+
+    /// Да ли је `path` бинарни фајл (нпр. слика) непознатог формата, који се
+    /// не конвертује.
+    fn is_binary(&self, path: &Path) -> bool {
+        let mut head = Vec::new();
+        self.explicit_format.is_none()
+            && self.input_encoding.is_none()
+            && Format::from_path(path).is_none()
+            && File::open(path).and_then(|file| file.take(8192).read_to_end(&mut head)).is_ok()
+            && encoding::is_binary(&head)
+    }
+
+    /// Да ли се BOM уписује на почетак излаза, што важи ако га је имао улаз
+    /// или је његово додавање задато.
+    fn output_bom(&self, format: Format, input_bom: bool) -> bool {
+        !format.is_binary() && (self.bom == Bom::Add || self.bom == Bom::Keep && input_bom)
+    }
+
+    /// Крај реда излаза, који је задат опцијом или је једнак претежном крају
+    /// реда улаза `input`.
+    fn line_ending(&self, format: Format, input: &[u8]) -> Option<LineEnding> {
+        let detect = || std::str::from_utf8(input).ok().and_then(LineEnding::detect);
+        (!format.is_binary()).then(|| self.newline.or_else(detect)).flatten()
+    }
+
+    /// Кодира резултат конверзије у кодирање излаза, са крајем реда
+    /// `line_ending` и BOM-ом ако је `with_bom` тачно.
+    fn encode(&self, output: Vec<u8>, with_bom: bool, line_ending: Option<LineEnding>) -> Result<Vec<u8>, Error> {
+        let output = match line_ending {
+            Some(line_ending) => line_ending.apply(&String::from_utf8_lossy(&output)).into_bytes(),
+            None => output,
+        };
+        let mut output = match self.output_encoding {
+            Encoding::Utf8 => output,
+            encoding => encoding.encode(&String::from_utf8_lossy(&output))?,
+        };
+        if with_bom {
+            output.splice(0..0, self.output_encoding.bom().iter().copied());
+        }
+        Ok(output)
+    }
+
+    /// Смер конверзије текста `text`. Код чишћења документа се текст
+    /// конвертује у преовлађујуће писмо.
+    fn detect_direction(&self, text: &str) -> Option<Direction> {
+        if self.cleanup {
+            Script::dominant(text).map(|script| match script {
+                Script::Latin => Direction::ToLatin,
+                Script::Cyrillic => Direction::ToCyrillic,
+            })
+        } else {
+            self.explicit_direction.or_else(|| (!self.transform_only).then(|| Direction::detect(text)))
+        }
+    }
+
+    /// Обрада текста. Код структурираних формата се позива само за
+    /// текстуалне делове документа.
+    fn convert_text(&self, text: &str, direction: Option<Direction>, statistics: &Mutex<Stats>) -> String {
+        let matches = self.matches;
+        let text = if matches.get_flag("хомоглифи") {
+            normalize_homoglyphs(text)
+        } else {
+            text.to_string()
+        };
+        let text = if matches.get_flag("ијекавица") {
+            ekavian_to_ijekavian(&text)
+        } else if matches.get_flag("екавица") {
+            ijekavian_to_ekavian(&text)
+        } else {
+            text
+        };
+        let converted = match direction {
+            Some(Direction::ToLatin) if matches.get_flag("распоред") => cyrillic_layout_to_latin(&text),
+            Some(Direction::ToCyrillic) if matches.get_flag("распоред") => latin_layout_to_cyrillic(&text),
+            // Конвертују се само пасуси, односно реченице код чишћења
+            // документа, у којима преовлађује писмо супротно циљном
+            Some(direction) if self.per_paragraph || self.cleanup => {
+                let source = match direction {
+                    Direction::ToLatin => Script::Cyrillic,
+                    Direction::ToCyrillic => Script::Latin,
+                };
+                let passages = if self.cleanup { lines::sentences(&text) } else { lines::paragraphs(&text) };
+                passages
+                    .into_iter()
+                    .map(|paragraph| match Script::dominant(paragraph) {
+                        Some(script) if script == source => self.converter.convert_with_stats(paragraph, direction, &mut statistics.lock().unwrap()),
+                        _ => paragraph.to_string(),
+                    })
+                    .collect()
+            }
+            Some(direction) => self.converter.convert_with_stats(&text, direction, &mut statistics.lock().unwrap()),
+            None => text,
+        };
+        // Код строге конверзије се бележе речи које нису пресловљене
+        if self.strict && let Some(direction) = direction {
+            let mut statistics = statistics.lock().unwrap();
+            for word in self.converter.unconverted_words(&converted, direction) {
+                *statistics.unconverted.entry(word.to_string()).or_default() += 1;
+            }
+        }
+        converted
+    }
+
+    /// Конверзија фајла код рекурзивне конверзије и праћења измена. Враћа
+    /// садржај фајла, резултат конверзије, да ли је фајл имао BOM и
+    /// статистику конверзије.
+    fn convert_file(&self, path: &Path) -> Result<(Vec<u8>, Vec<u8>, bool, Stats), Error> {
+        let format = self.file_format(path);
+        let content = fs::read(path)?;
+        let (content, input_bom) = if format.is_binary() {
+            (content, false)
+        } else {
+            let text = self.decode(path, &content)?;
+            match text.strip_prefix(BOM) {
+                Some(text) => (text.as_bytes().to_vec(), true),
+                None => (text.into_bytes(), false),
+            }
+        };
+        let direction = self.detect_direction(&String::from_utf8_lossy(&content));
+        let statistics = Mutex::new(Stats::default());
+        let converted = convert_document(format, &content, &self.settings, direction, |text| self.convert_text(text, direction, &statistics))?;
+        Ok((content, converted, input_bom, statistics.into_inner().unwrap()))
+    }
+
+    /// Исписује статистику конверзије на стандардни излаз за грешке.
+    fn print_stats(&self, files: &[(String, Stats)]) {
+        if !self.matches.get_flag("статистика") {
+            return;
+        }
+        if self.matches.get_flag("json") {
+            eprint!("{}", stats::report_json(files));
+        } else {
+            eprint!("{}", stats::report(files));
+        }
+    }
+
+    /// Обавештења о конвертованом фајлу и примењеним изузецима.
+    fn report_converted(&self, file: &Path, statistics: &Stats) {
+        if !self.preview && !self.check && !self.round_trip {
+            diagnostics::info(Kind::Converted, Some(file), "конвертован");
+        }
+        let mut exceptions = statistics.applied_exceptions.iter().collect::<Vec<_>>();
+        exceptions.sort();
+        for (exception, count) in exceptions {
+            diagnostics::debug(Kind::Exception, Some(file), format!("примењен изузетак „{exception}” (број примена: {count})"));
+        }
+    }
+
+    /// Речи које нису пресловљене код строге конверзије. Положаји речи се
+    /// исписују ако је задат текст резултата `output`, а иначе број појава
+    /// сваке речи. Враћа да ли је пронађена нека реч.
+    fn report_unconverted(&self, file: &Path, statistics: &Stats, output: Option<&str>) -> bool {
+        match output {
+            Some(output) => {
+                for (line, column, word) in script::find_words(output, |word| statistics.unconverted.contains_key(word)) {
+                    diagnostics::error(Kind::Unconverted, Some(file), format!("{line}:{column}: непресловљена реч „{word}”"));
+                }
+            }
+            None => {
+                let mut words = statistics.unconverted.iter().collect::<Vec<_>>();
+                words.sort();
+                for (word, count) in words {
+                    diagnostics::error(Kind::Unconverted, Some(file), format!("непресловљена реч „{word}” (број појава: {count})"));
+                }
+            }
+        }
+        !statistics.unconverted.is_empty()
+    }
+}
+
+/// Уписивање BOM-а на почетак излаза.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Bom {
+    /// BOM се уписује ако га је имао улаз.
+    Keep,
+    /// BOM се не уписује.
+    Strip,
+    /// BOM се увек уписује.
+    Add,
+}
+
+/// Подешавања конверзије структурираних формата задата опцијама.
+#[derive(Default)]
+struct Settings {
+    language: Language,
+    attributes: Vec<AttributeSelector>,
+    keys: Option<Regex>,
+    fields: Vec<Field>,
+    columns: Option<Vec<usize>>,
+    #[cfg_attr(not(feature = "archives"), allow(dead_code))]
+    sheets: Option<Vec<String>>,
+    cell_outputs: bool,
+}
+
+/// Пресловљава документ у формату `format` функцијом `transform`, где је
+/// `direction` смер конверзије (за ознаке језика).
+fn convert_document(
+    format: Format,
+    input: &[u8],
+    settings: &Settings,
+    direction: Option<Direction>,
+    transform: impl Fn(&str) -> String,
+) -> Result<Vec<u8>, Error> {
+    // Ознака језика и писма у књигама (нпр. `sr-Latn`)
+    let tag = direction.map(|direction| settings.language.tag(direction));
+    let columns = settings.columns.as_deref();
+    match format {
+        #[cfg(feature = "archives")]
+        Format::Epub => return epub::convert(input, tag.as_deref(), transform),
+        #[cfg(feature = "archives")]
+        Format::Xlsx => return xlsx::convert(input, settings.sheets.as_deref(), columns, transform),
+        #[cfg(feature = "archives")]
+        Format::Pptx => return pptx::convert(input, tag.as_deref(), transform),
+        // Формат фајлова у архиви се одређује на основу екстензије, а фајлови
+        // непознатог формата и угњеждене архиве се не мењају
+        #[cfg(feature = "archives")]
+        Format::Zip | Format::TarGz => {
+            let transform: &dyn Fn(&str) -> String = &transform;
+            let convert_entry = |name: &str, content: &[u8]| match Format::from_path(Path::new(name)) {
+                None | Some(Format::Zip | Format::TarGz) => Ok(None),
+                Some(format) => convert_document(format, content, settings, direction, transform)
+                    .map(Some)
+                    .map_err(|e| match e {
+                        Error::Syntax { line, message } => Error::Syntax { line, message: format!("{name}: {message}") },
+                        e => Error::Io(io::Error::other(format!("{name}: {e}"))),
+                    }),
+            };
+            return match format {
+                Format::Zip => zip::convert(input, convert_entry),
+                _ => targz::convert(input, convert_entry),
+            };
+        }
+        Format::Jsonl => {
+            let mut output = Vec::with_capacity(input.len());
+            jsonl::convert(input, &mut output, &settings.fields, transform)?;
+            return Ok(output);
+        }
+        _ => {}
     }
 
+    let input = std::str::from_utf8(input)
+        .map_err(|e| Error::Io(io::Error::new(io::ErrorKind::InvalidData, e)))?;
+    let output = match format {
+        Format::Text => Ok(transform(input)),
+        Format::Xml => xml::convert(input, &settings.attributes, transform),
+        Format::Json => json::convert(input, settings.keys.as_ref(), transform),
+        #[cfg(feature = "archives")]
+        Format::Epub | Format::Xlsx | Format::Pptx | Format::Zip | Format::TarGz => unreachable!("формат {format} се обрађује засебно"),
+        Format::Jsonl | Format::Site => unreachable!("формат {format} се обрађује засебно"),
+        Format::Yaml => Ok(yaml::convert(input, transform)),
+        Format::Markdown => Ok(markdown::convert(input, transform)),
+        Format::Csv => csv::convert(input, ',', columns, transform),
+        Format::Tsv => csv::convert(input, '\t', columns, transform),
+        Format::Srt => Ok(srt::convert(input, transform)),
+        Format::Vtt => Ok(vtt::convert(input, transform)),
+        Format::Ass => Ok(ass::convert(input, transform)),
+        Format::Fb2 => fb2::convert(input, tag.as_deref(), transform),
+        Format::Rtf => rtf::convert(input, transform),
+        Format::Po => {
+            let locale = direction.map(|direction| settings.language.locale(direction));
+            Ok(po::convert(input, locale.as_deref(), transform))
+        }
+        Format::Xliff => xliff::convert(input, tag.as_deref(), transform),
+        Format::Ts => ts::convert(input, tag.as_deref(), transform),
+        Format::Android => android::convert(input, transform),
+        Format::Strings => apple::convert_strings(input, transform),
+        Format::Stringsdict => apple::convert_stringsdict(input, transform),
+        Format::Properties => Ok(properties::convert(input, transform)),
+        Format::Resx => resx::convert(input, transform),
+        Format::Fluent => Ok(fluent::convert(input, transform)),
+        Format::I18next => i18next::convert(input, transform),
+        Format::Arb => arb::convert(input, tag.as_deref(), transform),
+        Format::Latex => Ok(latex::convert(input, transform)),
+        Format::Bibtex => Ok(bibtex::convert(input, transform)),
+        Format::Org => Ok(org::convert(input, transform)),
+        Format::Rst => Ok(rst::convert(input, transform)),
+        Format::Asciidoc => Ok(asciidoc::convert(input, transform)),
+        Format::Typst => Ok(typst::convert(input, transform)),
+        Format::Pandoc => pandoc::convert(input, transform),
+        Format::Ipynb => ipynb::convert(input, settings.cell_outputs, transform),
+        Format::Eml => eml::convert(input, tag.as_deref(), transform),
+        Format::Ics => Ok(ics::convert(input, tag.as_deref(), transform)),
+        Format::Vcard => Ok(vcard::convert(input, tag.as_deref(), transform)),
+        Format::Sql => Ok(sql::convert(input, transform)),
+        Format::Fountain => Ok(fountain::convert(input, transform)),
+    }?;
+    Ok(output.into_bytes())
+}
+
+fn skip_category_parser() -> impl TypedValueParser<Value = SkipCategory> {
+    PossibleValuesParser::new(SkipCategory::ALL.iter().map(|c| c.name()))
+        .map(|s| s.parse::<SkipCategory>().unwrap())
+}
+
+/// Конвертује фајлове које враћа `list` функцијом `convert`. Ако је `watch`
+/// тачно, након тога прати измене у директоријумима `roots` (рекурзивно ако
+/// је уз директоријум задато `true`) и поново конвертује измењене фајлове.
+fn watch_files(
+    watch: bool,
+    roots: &[(&Path, bool)],
+    list: impl Fn() -> io::Result<Vec<PathBuf>>,
+    mut convert: impl FnMut(&[PathBuf]),
+) -> io::Result<()> {
+    let mut watcher = Watcher::default();
+    let mut convert_changed = || -> io::Result<()> {
+        let changed = watcher.changed(list()?);
+        if !changed.is_empty() {
+            convert(&changed);
+        }
+        Ok(())
+    };
+    convert_changed()?;
+    if !watch {
+        return Ok(());
+    }
+    let (sender, events) = mpsc::channel();
+    let mut notifier = notify::recommended_watcher(sender).map_err(io::Error::other)?;
+    for &(root, recursive) in roots {
+        let mode = if recursive { RecursiveMode::Recursive } else { RecursiveMode::NonRecursive };
+        notifier.watch(root, mode).map_err(io::Error::other)?;
+    }
+    // Уређивачи један фајл често мењају кроз више обавештења (нпр. упис у
+    // привремени фајл и преименовање), па се обавештења која стигну у кратком
+    // размаку обрађују заједно. Која су се фајлови заиста изменили одређује
+    // се на основу времена последње измене.
+    while events.recv().is_ok() {
+        while events.recv_timeout(WATCH_DELAY).is_ok() {}
+        convert_changed()?;
+    }
     Ok(())
 }
+
+/// Директоријум у коме је фајл `path`.
+fn parent_dir(path: &Path) -> &Path {
+    path.parent().filter(|parent| !parent.as_os_str().is_empty()).unwrap_or(Path::new("."))
+}
+
+/// Редови текста `input` које конверзија мења, у облику `фајл:ред: текст`.
+fn unconverted_lines(input: &str, output: &str, name: &str) -> String {
+    changed_lines(input, output)
+        .into_iter()
+        .map(|(line, text)| format!("{name}:{line}: {text}\n"))
+        .collect()
+}
+
+/// Примењује функцију `f` на елементе `items` у `jobs` нити и враћа резултате
+/// истим редом.
+fn parallel_map<T: Sync, R: Send>(items: &[T], jobs: usize, f: impl Fn(&T) -> R + Sync) -> Vec<R> {
+    let next = AtomicUsize::new(0);
+    let results = Mutex::new((0..items.len()).map(|_| None).collect::<Vec<_>>());
+    thread::scope(|scope| {
+        for _ in 0..jobs.min(items.len()) {
+            scope.spawn(|| {
+                loop {
+                    let i = next.fetch_add(1, Ordering::Relaxed);
+                    let Some(item) = items.get(i) else { break };
+                    let result = f(item);
+                    results.lock().unwrap()[i] = Some(result);
+                }
+            });
+        }
+    });
+    results.into_inner().unwrap().into_iter().map(Option::unwrap).collect()
+}
+
+/// Путање фајлова директоријума `root` који се конвертују, изузев фајлова у
+/// излазном директоријуму `output`.
+fn list_files(root: &Path, include: &[Glob], exclude: &[Glob], options: files::WalkOptions, output: Option<&Path>) -> io::Result<Vec<PathBuf>> {
+    let skip = output.filter(|output| output.exists()).map(Path::canonicalize).transpose()?;
+    let mut paths = Vec::new();
+    for file in files::find_files(root, include, exclude, options)? {
+        let path = root.join(file);
+        if let Some(skip) = &skip && path.canonicalize()?.starts_with(skip) {
+            continue;
+        }
+        paths.push(path);
+    }
+    Ok(paths)
+}
+
+/// Декодира садржај фајла `file` у кодирању `encoding` и обавештава о
+/// кодирању које није UTF-8.
+fn decode_text(file: &Path, bytes: &[u8], encoding: Encoding) -> Result<String, Error> {
+    let text = encoding.decode(bytes)?;
+    if encoding != Encoding::Utf8 {
+        diagnostics::info(Kind::Encoding, Some(file), format!("кодирање {encoding}"));
+        if text.contains(char::REPLACEMENT_CHARACTER) {
+            diagnostics::warning(Kind::Encoding, Some(file), format!("неисправни знакови у кодирању {encoding} су замењени знаком „�”"));
+        }
+    }
+    Ok(text)
+}
+
+/// Конвертор са азбуком, табелама, заменама и прескакањем задатим опцијама
+/// командне линије и конфигурационим фајлом.
+fn build_converter(command: &mut Command, matches: &ArgMatches) -> Converter {
+    let language = *matches.get_one::<Language>("азбука").unwrap();
+    let scheme = matches
+        .get_one::<Scheme>("шема")
+        .copied()
+        .unwrap_or(language.default_scheme());
+    let mut converter = Converter::with_scheme(language, scheme)
+        .unwrap_or_else(|e| fail(command, ErrorKind::ArgumentConflict, e));
+    if let Some(file) = matches.get_one::<String>("табела") {
+        converter
+            .load_tables_file(file)
+            .unwrap_or_else(|e| fail(command, ErrorKind::InvalidValue, e));
+    }
+    for exception in matches.get_many::<String>("изузеци").unwrap_or_default() {
+        converter.add_exception(exception);
+    }
+    for mapping in matches.get_many::<String>("замени").unwrap_or_default() {
+        let result = match mapping.split_once('=') {
+            Some((from, to)) if from.chars().count() == 1 => {
+                converter.override_mapping(from.chars().next().unwrap(), to)
+            }
+            _ => Err(Error::InvalidMapping(mapping.clone())),
+        };
+        result.unwrap_or_else(|e| fail(command, ErrorKind::InvalidValue, e));
+    }
+    converter.set_quote_style(matches.get_one::<QuoteStyle>("наводници").copied());
+    converter.set_typography(matches.get_flag("типографија"));
+    // Категорије из конфигурационог фајла се примењују пре категорија
+    // задатих у командној линији
+    let mut skip = [("прескачи", true), ("не-прескачи", false)];
+    skip.sort_by_key(|(id, _)| matches.value_source(id) == Some(ValueSource::CommandLine));
+    for (id, enabled) in skip {
+        for category in matches.get_many::<SkipCategory>(id).unwrap_or_default() {
+            converter.set_skip_category(*category, enabled);
+        }
+    }
+    if let Some(units) = matches.get_many::<String>("јединице") {
+        converter.set_units(&units.collect::<Vec<_>>());
+    }
+    let mut skip_words = matches.get_many::<String>("прескочи-речи").unwrap_or_default().cloned().collect::<Vec<_>>();
+    for file in matches.get_many::<String>("прескочи-речи-из").unwrap_or_default() {
+        let words = fs::read_to_string(file).unwrap_or_else(|e| fail(command, ErrorKind::Io, format!("{file}: {}", tr(e))));
+        skip_words.extend(words.lines().map(str::trim).filter(|word| !word.is_empty()).map(String::from));
+    }
+    converter.set_skip_words(&skip_words);
+    for pattern in matches.get_many::<String>("прескочи-шаблон").unwrap_or_default() {
+        converter.add_skip_pattern(pattern).unwrap_or_else(|e| fail(command, ErrorKind::InvalidValue, e));
+    }
+    if let Some(markers) = matches.get_many::<String>("маркери") {
+        let markers = markers.collect::<Vec<_>>();
+        converter.set_skip_markers(Some((markers[0], markers[1])));
+    } else if matches.get_flag("без-маркера") {
+        converter.set_skip_markers(None);
+    }
+    converter
+}
+
+/// Покреће git са аргументима `args` и враћа његов излаз.
+fn git(args: &[&str]) -> io::Result<Vec<u8>> {
+    let output = std::process::Command::new("git").args(args).output()?;
+    if !output.status.success() {
+        return Err(io::Error::other(String::from_utf8_lossy(&output.stderr).trim().to_string()));
+    }
+    Ok(output.stdout)
+}
+
+/// Фајлови припремљени за комит у git репозиторијуму (додати, измењени или
+/// преименовани). Уз сваки фајл се враћа путања релативна у односу на текући
+/// директоријум кад год је то могуће и путања у репозиторијуму.
+fn staged_files() -> io::Result<Vec<(PathBuf, String)>> {
+    let text = |bytes: Vec<u8>| String::from_utf8(bytes).map_err(io::Error::other);
+    let root = PathBuf::from(text(git(&["rev-parse", "--show-toplevel"])?)?.trim_end());
+    let current = std::env::current_dir()?.canonicalize()?;
+    let root = root.strip_prefix(&current).map_or(root.clone(), Path::to_path_buf);
+    let files = text(git(&["diff", "--cached", "--name-only", "--diff-filter=ACMR", "-z"])?)?;
+    Ok(files.split('\0').filter(|file| !file.is_empty()).map(|file| (root.join(file), file.to_string())).collect())
+}
+
+/// Пријављује грешку конверзије фајла `file`. Током праћења измена грешка се
+/// само исписује, а иначе се програм завршава.
+fn report_error(command: &mut Command, watch: bool, file: Option<&Path>, error: Error) {
+    if watch {
+        diagnostics::error(Kind::of(&error), file, error);
+    } else {
+        fail_with(command, file, error)
+    }
+}
+
+/// Завршава програм грешком библиотеке при обради фајла `file`.
+fn fail_with(command: &mut Command, file: Option<&Path>, error: Error) -> ! {
+    if diagnostics::json() {
+        diagnostics::error(Kind::of(&error), file, error);
+        std::process::exit(2)
+    }
+    let message = match file {
+        Some(file) => format!("{}: {}", file.display(), tr(error)),
+        None => tr(error),
+    };
+    exit_with(command.error(ErrorKind::Io, message))
+}
+
+/// Завршава програм грешком `message`.
+fn fail(command: &mut Command, kind: ErrorKind, message: impl fmt::Display) -> ! {
+    exit_with(command.error(kind, tr(message)))
+}
+
+/// Завршава програм грешком у опцијама или грешком коју је пријавио
+/// `fail`. Са `--грешке-json` се исписује само порука о грешци у JSON облику.
+/// Поруке clap-а се, осим на енглеском, исписују преведене и без боја.
+fn exit_with(error: clap::Error) -> ! {
+    if !error.use_stderr() || !diagnostics::json() && messages::locale() == Locale::English {
+        error.exit()
+    }
+    let rendered = error.render().to_string();
+    if diagnostics::json() {
+        let message = rendered.split("\n\n").next().unwrap_or_default();
+        let kind = if error.kind() == ErrorKind::Io { Kind::Io } else { Kind::Usage };
+        diagnostics::error(kind, None, messages::tr_error(message.strip_prefix("error: ").unwrap_or(message)));
+    } else {
+        eprint!("{}", messages::tr_error(&rendered));
+    }
+    std::process::exit(error.exit_code())
+}
+
+/// Уписује резултат у излазни фајл (`-и`) или на стандардни излаз. Програм
+/// се завршава грешком ако упис не успе.
+fn write_output(command: &mut Command, matches: &ArgMatches, output: impl AsRef<[u8]>, source: Option<&Path>) {
+    let file = matches.get_one::<String>("излаз").map(Path::new);
+    let result = match file {
+        Some(file) => files::write_atomic(file, output.as_ref(), source),
+        None => io::stdout().write_all(output.as_ref()),
+    };
+    result.unwrap_or_else(|e| fail_with(command, file, e.into()))
+}
@@ -0,0 +1,587 @@
+//! Поруке командне линије (помоћ, упити и поруке о грешкама) на српској
+//! ћирилици, српској латиници или енглеском. Поруке се у коду пишу ћирилицом,
+//! латиничне поруке се добијају пресловљавањем, а енглеске из табеле превода.
+//! Поруке са променљивим деловима (путањама, називима формата...) су у табели
+//! задате шаблонима у којима `{}` означава променљиви део, који се не
+//! пресловљава.
+use std::ffi::OsString;
+use std::str::FromStr;
+use std::{env, fmt};
+
+use cirko::Converter;
+use clap::Command;
+use once_cell::sync::{Lazy, OnceCell};
+use phf::phf_map;
+
+/// Променљива окружења са језиком порука.
+pub const LANGUAGE_VAR: &str = "CIRKO_LANG";
+
+/// Језик порука командне линије.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Locale {
+    #[default]
+    Cyrillic,
+    Latin,
+    English,
+}
+
+impl Locale {
+    pub const ALL: &[Locale] = &[Locale::Cyrillic, Locale::Latin, Locale::English];
+
+    /// Ознака језика (`sr-Cyrl`, `sr-Latn` или `en`).
+    pub fn tag(self) -> &'static str {
+        match self {
+            Locale::Cyrillic => "sr-Cyrl",
+            Locale::Latin => "sr-Latn",
+            Locale::English => "en",
+        }
+    }
+
+    /// Језик задат опцијом `--језик` у аргументима `args` или, ако опција
+    /// није задата, променљивом окружења [`LANGUAGE_VAR`]. Језик мора бити
+    /// познат пре парсирања аргумената јер се на њему исписује и помоћ.
+    pub fn from_args(args: impl IntoIterator<Item = OsString>) -> Self {
+        let mut args = args.into_iter().skip(1);
+        let mut tag = None;
+        while let Some(arg) = args.next() {
+            let Some(arg) = arg.to_str() else { continue };
+            if arg == "--" {
+                break;
+            }
+            match arg.split_once('=') {
+                Some(("--језик" | "--language", value)) => tag = Some(value.to_string()),
+                None if arg == "--језик" || arg == "--language" => {
+                    tag = args.next().and_then(|value| value.into_string().ok())
+                }
+                _ => {}
+            }
+        }
+        tag.or_else(|| env::var(LANGUAGE_VAR).ok())
+            .and_then(|tag| tag.parse().ok())
+            .unwrap_or_default()
+    }
+}
+
+impl fmt::Display for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.tag())
+    }
+}
+
+impl FromStr for Locale {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Locale::ALL
+            .iter()
+            .copied()
+            .find(|locale| locale.tag().eq_ignore_ascii_case(s))
+            .ok_or_else(|| s.to_string())
+    }
+}
+
+static LOCALE: OnceCell<Locale> = OnceCell::new();
+
+/// Поставља језик порука. Језик се поставља једном, на почетку програма.
+pub fn set_locale(locale: Locale) {
+    let _ = LOCALE.set(locale);
+}
+
+/// Порука на задатом језику порука. Поруке којих нема у табели (нпр. поруке
+/// оперативног система) се не мењају.
+pub fn tr(message: impl fmt::Display) -> String {
+    localize(&message.to_string(), locale())
+}
+
+/// Порука о грешци у опцијама, коју clap исписује на енглеском, на задатом
+/// језику порука. Редови поруке се преводе према табели шаблона порука
+/// clap-а, а редови којих у њој нема (нпр. већ преведени разлози грешака) се
+/// не мењају.
+pub fn tr_error(rendered: &str) -> String {
+    localize_error(rendered, locale())
+}
+
+/// Задати језик порука.
+pub fn locale() -> Locale {
+    LOCALE.get().copied().unwrap_or_default()
+}
+
+/// Поставља заглавља помоћи команде и њених подкоманди, која clap иначе
+/// исписује на енглеском.
+pub fn localize_help(command: Command) -> Command {
+    let subcommands = command
+        .get_subcommands()
+        .map(|subcommand| subcommand.get_name().to_string())
+        .collect::<Vec<_>>();
+    let template = format!(
+        "{{before-help}}{{about-with-newline}}\n{} {{usage}}\n\n{{all-args}}{{after-help}}",
+        tr("Употреба:")
+    );
+    let mut command = command
+        .help_template(template)
+        .subcommand_help_heading(tr("Команде"))
+        .subcommand_value_name(tr("КОМАНДА"))
+        .mut_args(|arg| {
+            let heading = if arg.is_positional() {
+                "Аргументи"
+            } else {
+                "Опције"
+            };
+            arg.help_heading(tr(heading))
+        });
+    for name in subcommands {
+        command = command.mut_subcommand(name, localize_help);
+    }
+    command
+}
+
+// Конвертор за латиничне поруке. Називи опција (нпр. `-л`), слова у примерима
+// замене (`ђ=dj`), маркери и вредности опција се не пресловљавају.
+static LATIN: Lazy<Converter> = Lazy::new(|| {
+    let mut converter = Converter::default();
+    converter.set_skip_words(&["ниски", "шиљасти", "задржи", "уклони", "додај"]);
+    converter.set_skip_markers(None);
+    for pattern in [
+        r"--?\p{Cyrillic}[\p{Cyrillic}-]*",
+        r"\p{Cyrillic}=",
+        "<!--.*?-->",
+    ] {
+        converter.add_skip_pattern(pattern).unwrap();
+    }
+    converter
+});
+
+fn localize(message: &str, locale: Locale) -> String {
+    if locale == Locale::Cyrillic {
+        return message.to_string();
+    }
+    let Some((template, values)) = find_template(&ENGLISH, message) else {
+        return message.to_string();
+    };
+    let text = match locale {
+        Locale::English => ENGLISH[template],
+        _ => template,
+    };
+    let mut parts = text.split("{}");
+    let mut output = localize_part(parts.next().unwrap_or_default(), locale);
+    for (value, part) in values.into_iter().zip(parts) {
+        output.push_str(&localize(value, locale));
+        output.push_str(&localize_part(part, locale));
+    }
+    output
+}
+
+fn localize_error(rendered: &str, locale: Locale) -> String {
+    if locale == Locale::English {
+        return rendered.to_string();
+    }
+    rendered
+        .split('\n')
+        .map(|line| translate_error(line, locale).unwrap_or_else(|| line.to_string()))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Превод дела поруке clap-а `text` на српски. Променљиви делови се
+/// преводе ако су и сами поруке clap-а.
+fn translate_error(text: &str, locale: Locale) -> Option<String> {
+    let (template, values) = find_template(&CLAP, text)?;
+    let mut parts = CLAP[template].split("{}");
+    let mut output = localize_part(parts.next().unwrap_or_default(), locale);
+    for (value, part) in values.into_iter().zip(parts) {
+        output.push_str(&translate_error(value, locale).unwrap_or_else(|| value.to_string()));
+        output.push_str(&localize_part(part, locale));
+    }
+    Some(output)
+}
+
+/// Шаблон табеле `table` који тачно одговара поруци `message` или најдужи
+/// шаблон који јој одговара, са променљивим деловима поруке.
+fn find_template<'a>(
+    table: &phf::Map<&'static str, &'static str>,
+    message: &'a str,
+) -> Option<(&'static str, Vec<&'a str>)> {
+    match table.get_key(message) {
+        Some(template) => Some((*template, Vec::new())),
+        None => table
+            .keys()
+            .filter(|template| template.contains("{}"))
+            .filter_map(|template| Some((*template, captures(template, message)?)))
+            .max_by_key(|(template, _)| (template.len(), *template)),
+    }
+}
+
+fn localize_part(part: &str, locale: Locale) -> String {
+    match locale {
+        Locale::Latin => LATIN.to_latin(part),
+        _ => part.to_string(),
+    }
+}
+
+/// Променљиви делови поруке `message` која одговара шаблону `template`.
+fn captures<'a>(template: &str, message: &'a str) -> Option<Vec<&'a str>> {
+    let mut parts = template.split("{}");
+    let mut rest = message.strip_prefix(parts.next()?)?;
+    let parts = parts.collect::<Vec<_>>();
+    let mut values = Vec::with_capacity(parts.len());
+    for (i, part) in parts.iter().enumerate() {
+        let end = if i + 1 == parts.len() {
+            rest.strip_suffix(part)?.len()
+        } else {
+            rest.find(part)?
+        };
+        values.push(&rest[..end]);
+        rest = &rest[end + part.len()..];
+    }
+    Some(values)
+}
+
+// Преводи порука на енглески
+static ENGLISH: phf::Map<&'static str, &'static str> = phf_map! {
+    // Заглавља помоћи
+    "Употреба:" => "Usage:",
+    "Опције" => "Options",
+    "Аргументи" => "Arguments",
+    "Команде" => "Commands",
+    "КОМАНДА" => "COMMAND",
+
+    // Опис програма и подкоманди
+    "Ћирко - конвертор српске латинице у ћирилицу и обрнуто." => "Ћирко - Serbian Latin to Cyrillic converter and vice versa.",
+    "Испиши скрипту за допуњавање опција командног интерпретера" => "Print a shell completion script",
+    "Преслови називе фајлова и директоријума (не и садржај) у задатом директоријуму" => "Transliterate names of files and directories (not their contents) in the given directory",
+    "Провери да ли су фајлови припремљени за комит (git diff --cached) у циљном писму" => "Check whether files staged for commit (git diff --cached) are in the target script",
+    "Одреди писмо фајлова и испиши линије у којима се појављују оба писма" => "Detect the script of files and print lines containing both scripts",
+
+    // Називи вредности опција
+    "АЗБУКА" => "ALPHABET",
+    "ДИРЕКТОРИЈУМ" => "DIRECTORY",
+    "СОКЕТ" => "SOCKET",
+    "ЈЕДИНИЦЕ" => "UNITS",
+    "ЈЕЗИК" => "LANGUAGE",
+    "ИЗРАЗ" => "REGEX",
+    "ИСКЉУЧИ" => "OFF",
+    "КАТЕГОРИЈЕ" => "CATEGORIES",
+    "КОДИРАЊЕ" => "ENCODING",
+    "КОЛОНЕ" => "COLUMNS",
+    "КРАЈ" => "ENDING",
+    "ЛИСТОВИ" => "SHEETS",
+    "ОПСЕГ" => "RANGE",
+    "ПОЉА" => "FIELDS",
+    "РЕЖИМ" => "MODE",
+    "РЕЧ" => "WORD",
+    "СЕКВЕНЦЕ" => "SEQUENCES",
+    "СЕЛЕКТОРИ" => "SELECTORS",
+    "СЛОВО=ЗАМЕНА" => "LETTER=REPLACEMENT",
+    "СТИЛ" => "STYLE",
+    "УКЉУЧИ" => "ON",
+    "ФОРМАТ" => "FORMAT",
+    "ШАБЛОН" => "PATTERN",
+    "ШЕМА" => "SCHEME",
+
+    // Помоћ за опције
+    "Прикажи помоћ" => "Show help",
+    "Језик порука и помоћи: sr-Cyrl (ћирилица), sr-Latn (латиница) или en (енглески). Подразумевано се чита из променљиве окружења CIRKO_LANG." => "Language of messages and help: sr-Cyrl (Cyrillic), sr-Latn (Latin) or en (English). Read from the CIRKO_LANG environment variable by default.",
+    "Испиши обавештења о конвертованим фајловима и кодирању (-в) и примењеним изузецима (-вв)" => "Print notices about converted files and encodings (-в) and applied exceptions (-вв)",
+    "Испиши грешке, упозорења и обавештења као JSON објекте, по један у реду" => "Print errors, warnings and notices as JSON objects, one per line",
+    "Улазни фајл (stdin подразумевано), може се задати више пута" => "Input file (stdin by default), can be given multiple times",
+    "Улазни фајлови, као уз -у" => "Input files, same as with -у",
+    "Упиши сваки улазни фајл под истим називом у излазни директоријум" => "Write each input file under its own name to the output directory",
+    "Преслови и називе фајлова у излазном директоријуму" => "Also transliterate the file names in the output directory",
+    "Излазни фајл (stdout подразумевано)" => "Output file (stdout by default)",
+    "Конвертуј све фајлове директоријума у излазни директоријум (-и)" => "Convert all files of a directory into the output directory (-и)",
+    "Конвертуј само фајлове који одговарају шаблону (нпр. „*.md”)" => "Convert only files matching the pattern (e.g. '*.md')",
+    "Прескочи фајлове и директоријуме који одговарају шаблону (нпр. „target/**”)" => "Skip files and directories matching the pattern (e.g. 'target/**')",
+    "Конвертуј фајлове чије се путање читају са стандардног улаза (по једна у реду)" => "Convert files whose paths are read from standard input (one per line)",
+    "Путање на стандардном улазу су раздвојене нул карактером (нпр. „find -print0”)" => "Paths on standard input are separated by the null character (e.g. 'find -print0')",
+    "Упиши резултат конверзије у сам улазни фајл" => "Write the result back to the input file",
+    "Заврши са грешком ако резултат и даље садржи слова писма са кога се конвертује" => "Fail if the result still contains letters of the source script",
+    "Конвертуј само фајлове задатих екстензија или формата, одвојених зарезом (нпр. md,html,txt)" => "Convert only files with the given extensions or formats, separated by commas (e.g. md,html,txt)",
+    "ВРСТЕ" => "TYPES",
+    "Конвертуј и скривене фајлове и директоријуме" => "Also convert hidden files and directories",
+    "Не прескачи фајлове наведене у „.gitignore” и „.ignore” ни директоријуме „target” и „node_modules”" => "Do not skip files listed in '.gitignore' and '.ignore' or the 'target' and 'node_modules' directories",
+    "Дозволи да излазни фајл буде исти као улазни" => "Allow the output file to be the same as the input file",
+    "Задржи дозволе и време измене улазног фајла у излазном фајлу" => "Keep the permissions and modification time of the input file in the output file",
+    "Не уписуј резултат већ прикажи измене у облику диф-а" => "Do not write the result, show the changes as a diff instead",
+    "Обој измене у прегледу" => "Colorize changes in the preview",
+    "Не уписуј резултат већ провери да ли је текст већ у циљном писму и испиши редове који нису (излазни код 1)" => "Do not write the result, check whether the text is already in the target script and print lines that are not (exit code 1)",
+    "Не уписуј резултат већ конвертуј текст назад и испиши речи које се не враћају у изворни облик (излазни код 1)" => "Do not write the result, convert the text back and print words that do not round-trip (exit code 1)",
+    "Број фајлова који се конвертују паралелно код рекурзивне конверзије (подразумевано број процесора)" => "Number of files converted in parallel during recursive conversion (number of CPUs by default)",
+    "Одреди писмо сваке линије посебно (уз -л или -ћ конвертуј само линије које нису у циљном писму)" => "Detect the script of each line separately (with -л or -ћ convert only lines not in the target script)",
+    "Конвертуј и испиши сваку линију улаза одмах након учитавања" => "Convert and print each input line as soon as it is read",
+    "Конвертуј само линије из опсега (нпр. „120-340”, „5-” или „7”), а остале остави непромењене. Може се навести више пута." => "Convert only lines in the range (e.g. '120-340', '5-' or '7') and leave the rest unchanged. Can be given multiple times.",
+    "Одреди писмо сваког пасуса посебно и конвертуј само пасусе који нису у циљном писму" => "Detect the script of each paragraph and convert only paragraphs not in the target script",
+    "Конвертуј само реченице писане писмом које није преовлађујуће у документу у преовлађујуће писмо" => "Convert only sentences written in the script that is not dominant in the document to the dominant script",
+    "Прикажи напредак конверзије" => "Show conversion progress",
+    "Испиши статистику конверзије (пресловљени знакови, изузеци, прескочени делови текста) на стандардни излаз за грешке" => "Print conversion statistics (transliterated characters, exceptions, skipped text) to standard error",
+    "Испиши статистику у JSON облику" => "Print statistics as JSON",
+    "Обрађуј JSON-RPC захтеве за конверзију са стандардног улаза, по један у реду (за додатке едитора)" => "Serve JSON-RPC conversion requests from standard input, one per line (for editor plugins)",
+    "Unix сокет службе (на Windows-у именована цев, нпр. \\\\.\\pipe\\cirko): уз --служба прима захтеве преко сокета, а иначе шаље текст на конверзију служби која већ ради" => "Unix socket of the service (a named pipe on Windows, e.g. \\\\.\\pipe\\cirko): with --service, accept requests on the socket, otherwise send the text to an already running service for conversion",
+    "Прати измене улазног фајла или директоријума и поново конвертуј измењене фајлове" => "Watch the input file or directory and convert changed files again",
+    "Конвертуј у латиницу" => "Convert to Latin",
+    "Конвертуј у ћирилицу" => "Convert to Cyrillic",
+    "Азбука (језик) ћириличног текста" => "Alphabet (language) of the Cyrillic text",
+    "Систем романизације (подразумевано зависи од азбуке)" => "Romanization scheme (depends on the alphabet by default)",
+    "TOML фајл са корисничким табелама пресловљавања" => "TOML file with custom transliteration tables",
+    "Секвенце у којима се вишесловна пресловљавања на ћирилицу не примењују, одвојене зарезом (нпр. nadživ)" => "Comma-separated sequences in which digraphs are not converted to Cyrillic (e.g. nadživ)",
+    "Промени пресловљавање једног слова на латиницу (нпр. ђ=dj, џ=dz)" => "Change the Latin transliteration of a letter (e.g. ђ=dj, џ=dz)",
+    "Конвертуј екавски текст у ијекавски" => "Convert Ekavian text to Ijekavian",
+    "Конвертуј ијекавски текст у екавски" => "Convert Ijekavian text to Ekavian",
+    "Поправи текст откуцан на погрешном распореду тастатуре" => "Fix text typed with the wrong keyboard layout",
+    "Поправи речи у којима су помешана писма (нпр. латинично „a” у ћириличној речи)" => "Fix words with mixed scripts (e.g. a Latin 'a' in a Cyrillic word)",
+    "Не конвертуј већ испиши речи у којима су помешана писма" => "Do not convert, print words with mixed scripts instead",
+    "Замени праве наводнике типографским: ниски („…”) или шиљасти (»…«)" => "Replace straight quotes with typographic ones: ниски („…”) or шиљасти (»…«)",
+    "Замени три тачке и цртице типографским знацима и додај нераскидиве размаке" => "Replace three dots and hyphens with typographic characters and add non-breaking spaces",
+    "Укључи прескакање категорија делова текста (одвојених зарезом)" => "Enable skipping of comma-separated categories of text",
+    "Искључи прескакање категорија делова текста (одвојених зарезом)" => "Disable skipping of comma-separated categories of text",
+    "Мерне јединице које после броја остају непромењене (одвојене зарезом), уместо подразумеваних" => "Comma-separated units of measure left unchanged after a number, instead of the default ones",
+    "Реч или израз који остаје непромењен (нпр. назив производа). Може се навести више пута." => "Word or phrase left unchanged (e.g. a product name). Can be given multiple times.",
+    "Фајл са речима и изразима који остају непромењени (један по реду)" => "File with words and phrases left unchanged (one per line)",
+    "Регуларни израз за делове текста који остају непромењени (нпр. „ID-\\d+[A-Z]+”). Може се навести више пута." => "Regular expression for text left unchanged (e.g. 'ID-\\d+[A-Z]+'). Can be given multiple times.",
+    "Кодирање улазног текста (подразумевано се детектује)" => "Encoding of the input text (detected by default)",
+    "Кодирање излазног текста" => "Encoding of the output text",
+    "BOM на почетку излаза: задржи (ако га је имао улаз), уклони или додај" => "BOM at the start of the output: задржи (keep if the input had it), уклони (strip) or додај (add)",
+    "Крај реда излаза: lf или crlf (подразумевано претежни крај реда улаза)" => "Output line ending: lf or crlf (the prevailing input line ending by default)",
+    "Формат улаза код кога се пресловљава само текст, а структура остаје непромењена (подразумевано се одређује на основу екстензије фајла)" => "Input format whose text is transliterated while the structure is left unchanged (determined from the file extension by default)",
+    "Ради као Pandoc филтер (pandoc --filter): конвертуј Pandoc AST са стандардног улаза у писмо задато метаподатком lang или опцијом -л или -ћ" => "Run as a Pandoc filter (pandoc --filter): convert the Pandoc AST from standard input to the script given by the lang metadata or by -л or -ћ",
+    "XML атрибути који се пресловљавају, одвојени зарезом (нпр. @title,img/@alt)" => "Comma-separated XML attributes to transliterate (e.g. @title,img/@alt)",
+    "Пресловљавај и JSON кључеве који одговарају регуларном изразу" => "Also transliterate JSON keys matching the regular expression",
+    "JSONL поља која се пресловљавају, одвојена зарезом (нпр. naslov,autor.ime)" => "Comma-separated JSONL fields to transliterate (e.g. naslov,autor.ime)",
+    "Редни бројеви CSV/TSV/XLSX колона које се пресловљавају, одвојени зарезом (нпр. 2,5)" => "Comma-separated numbers of CSV/TSV/XLSX columns to transliterate (e.g. 2,5)",
+    "Називи XLSX листова који се пресловљавају, одвојени зарезом" => "Comma-separated names of XLSX sheets to transliterate",
+    "Поља заглавља страна сајта која се пресловљавају, одвојена зарезом" => "Comma-separated front matter fields of site pages to transliterate",
+    "Пресловљавај и текстуалне излазе ћелија кода у Jupyter свескама" => "Also transliterate text outputs of code cells in Jupyter notebooks",
+    "Маркери у тексту између којих се не ради конверзија (подразумевано „<!-- ћирко:искључи -->” и „<!-- ћирко:укључи -->”)" => "Markers in the text between which nothing is converted ('<!-- ћирко:искључи -->' and '<!-- ћирко:укључи -->' by default)",
+    "Не препознај маркере за искључивање конверзије" => "Do not recognize markers that disable conversion",
+    "Конфигурациони фајл са подразумеваним вредностима опција (подразумевано ~/.config/cirko/config.toml)" => "Configuration file with default option values (~/.config/cirko/config.toml by default)",
+    "Преслови називе у латиницу" => "Transliterate names to Latin",
+    "Преслови називе у ћирилицу" => "Transliterate names to Cyrillic",
+    "Само испиши преименовања, без измене назива" => "Only print the renames without renaming anything",
+    "Фајлови који се проверавају (подразумевано фајлови припремљени за комит)" => "Files to check (files staged for commit by default)",
+    "Фајлови морају бити на латиници" => "Files must be in Latin",
+    "Фајлови морају бити на ћирилици" => "Files must be in Cyrillic",
+    "Проверавај само фајлове који одговарају шаблону (нпр. „*.md”)" => "Check only files matching the pattern (e.g. '*.md')",
+    "Конвертуј фајлове који нису у циљном писму" => "Convert files that are not in the target script",
+    "Фајлови чије се писмо одређује" => "Files whose script is detected",
+
+    // Поруке и упити
+    "упозорење" => "warning",
+    "конвертован" => "converted",
+    "кодирање {}" => "encoding {}",
+    "неисправни знакови у кодирању {} су замењени знаком „�”" => "invalid characters in encoding {} were replaced with '�'",
+    "бинарни фајл је прескочен" => "binary file skipped",
+    "примењен изузетак „{}” (број примена: {})" => "applied exception '{}' ({} times)",
+    "{}:{}: непресловљена реч „{}”" => "{}:{}: unconverted word '{}'",
+    "непресловљена реч „{}” (број појава: {})" => "unconverted word '{}' ({} occurrences)",
+    "{}: {} (ћирилица {}%, латиница {}%)" => "{}: {} (Cyrillic {}%, Latin {}%)",
+    "Измери брзину конверзије у оба смера" => "Measure conversion throughput in both directions",
+    "Текст на коме се мери брзина (подразумевано уграђени узорак од 1 MB)" => "Text to measure on (a built-in 1 MB sample by default)",
+    "Најмање трајање мерења за сваки смер" => "Minimum measuring time for each direction",
+    "СЕКУНДЕ" => "SECONDS",
+    "неисправно трајање „{}”" => "invalid duration '{}'",
+    "уграђени узорак" => "built-in sample",
+    "корпус: {} MB ({})" => "corpus: {} MB ({})",
+    "латиница → ћирилица" => "Latin → Cyrillic",
+    "ћирилица → латиница" => "Cyrillic → Latin",
+    "{}: {} MB/s (понављања: {})" => "{}: {} MB/s ({} iterations)",
+    "прескакање: {} ms ({}%)" => "skipping: {} ms ({}%)",
+    "пресловљавање: {} ms ({}%)" => "mapping: {} ms ({}%)",
+    "латиница" => "Latin",
+    "ћирилица" => "Cyrillic",
+    "мешовито" => "mixed",
+    "без слова" => "no letters",
+    "аутоматски" => "automatic",
+    "Унесите текст за конверзију (Ctrl+D за крај, „:смер [латиница|ћирилица|auto]” за промену смера)." => "Enter text to convert (Ctrl+D to finish, ':direction [latin|cyrillic|auto]' to change the direction).",
+    "смер: {}" => "direction: {}",
+    "непознат смер „{}”" => "unknown direction '{}'",
+
+    // Грешке у опцијама
+    "конфигурациони фајл „{}”: {}" => "configuration file '{}': {}",
+    "кодирање улаза мора бити UTF-8 код линијског режима и формата jsonl" => "input encoding must be UTF-8 in line-buffered mode and for the jsonl format",
+    "кодирање излаза мора бити UTF-8 за формат {}" => "output encoding must be UTF-8 for the {} format",
+    "смер конверзије (-л или -ћ) мора бити задат за формат {}" => "conversion direction (-л or -ћ) must be given for the {} format",
+    "смер конверзије (-л или -ћ) мора бити задат за конверзију по пасусима" => "conversion direction (-л or -ћ) must be given for per-paragraph conversion",
+    "смер конверзије (-л или -ћ) мора бити задат за повратну проверу" => "conversion direction (-л or -ћ) must be given for the round-trip check",
+    "смер конверзије (-л или -ћ) мора бити задат за проверу" => "conversion direction (-л or -ћ) must be given for the check",
+    "линијски режим није подржан за формат {}" => "line-buffered mode is not supported for the {} format",
+    "конверзија опсега линија није подржана за формат {}" => "converting line ranges is not supported for the {} format",
+    "преглед измена није подржан за формат {}" => "preview is not supported for the {} format",
+    "провера није подржана за формат {}" => "check is not supported for the {} format",
+    "повратна провера није подржана за формат {}" => "round-trip check is not supported for the {} format",
+    "конверзија преко службе није подржана за формат {}" => "conversion through the service is not supported for the {} format",
+    "опција „{}” се не може задати при конверзији преко службе" => "option '{}' cannot be given when converting through the service",
+    "служба већ ради на сокету {}" => "the service is already running on socket {}",
+    "опција „{}” се не може задати уз подкоманду „{}”" => "option '{}' cannot be given with the '{}' subcommand",
+    "излазни фајл (-и) се не може задати уз више улазних фајлова; користите --излаз-дир" => "an output file (-и) cannot be given with multiple input files; use --излаз-дир",
+    "излазни директоријум (--излаз-дир) или упис у месту (--у-месту) мора бити задат за конверзију више фајлова" => "an output directory (--излаз-дир) or in-place writing (--у-месту) must be given to convert multiple files",
+    "улазни фајл, директоријум или листа фајлова мора бити задата за упис у месту" => "an input file, directory or file list must be given for in-place writing",
+    "излазни фајл је исти као улазни; користите --у-месту или --сила" => "the output file is the same as the input file; use --у-месту or --сила",
+    "упис у месту (--у-месту) мора бити задат за конверзију фајлова из листе" => "in-place writing (--у-месту) must be enabled to convert files from a list",
+    "излазни директоријум (-и) мора бити задат за рекурзивну конверзију" => "an output directory (-и) must be given for recursive conversion",
+    "формат site се не може користити за конверзију више фајлова" => "the site format cannot be used to convert multiple files",
+    "„{}” није фајл" => "'{}' is not a file",
+    "улазни и излазни фајл (-у и -и) морају бити задати за праћење измена" => "input and output files (-у and -и) must be given to watch for changes",
+    "улазни и излазни директоријум (-у и -и) морају бити задати за формат site" => "input and output directories (-у and -и) must be given for the site format",
+
+    // Грешке библиотеке
+    "систем „{}” није подржан за {} језик" => "scheme '{}' is not supported for the {} language",
+    "непозната азбука „{}”" => "unknown alphabet '{}'",
+    "непознат систем романизације „{}”" => "unknown romanization scheme '{}'",
+    "непозната категорија „{}”" => "unknown category '{}'",
+    "непознат формат „{}”" => "unknown format '{}'",
+    "непознато кодирање „{}”" => "unknown encoding '{}'",
+    "непознат крај реда „{}”" => "unknown line ending '{}'",
+    "знак „{}” се не може записати у кодирању {}" => "character '{}' cannot be encoded in {}",
+    "неисправан селектор „{}”" => "invalid selector '{}'",
+    "неисправан шаблон „{}”" => "invalid pattern '{}'",
+    "неисправан опсег линија „{}”" => "invalid line range '{}'",
+    "више фајлова би добило назив „{}”" => "multiple files would be named '{}'",
+    "грешка у {}. реду: {}" => "error on line {}: {}",
+    "неисправно пресловљавање „{}”" => "invalid mapping '{}'",
+    "грешка у TOML фајлу: {}" => "TOML error: {}",
+    "грешка у ZIP архиви: {}" => "ZIP archive error: {}",
+    "грешка службе: {}" => "service error: {}",
+    "неисправна tar архива: {}" => "invalid tar archive: {}",
+    "ставка прелази крај архиве" => "entry extends past the end of the archive",
+    "неисправна величина ставке" => "invalid entry size",
+    "фајл је превелик" => "file is too large",
+    "незатворен коментар" => "unclosed comment",
+    "незатворен наводник" => "unclosed quote",
+    "незатворен стринг" => "unclosed string",
+    "незатворена CDATA секција" => "unclosed CDATA section",
+    "незатворена декларација" => "unclosed declaration",
+    "незатворена инструкција обраде" => "unclosed processing instruction",
+    "незатворена ознака" => "unclosed tag",
+    "неисправан знак" => "invalid character",
+    "неисправна escape секвенца" => "invalid escape sequence",
+    "неочекиван знак иза наводника" => "unexpected character after quote",
+    "неочекиван знак" => "unexpected character",
+    "неочекиван крај документа" => "unexpected end of document",
+    "неочекиван садржај након краја документа" => "unexpected content after the end of the document",
+    "очекиван други део сурогатног пара" => "expected the second half of a surrogate pair",
+    "очекиван кључ" => "expected a key",
+    "очекивано `,` или `]`" => "expected `,` or `]`",
+    "очекивано `,` или `}`" => "expected `,` or `}`",
+    "очекивано `:`" => "expected `:`",
+};
+
+// Преводи порука clap-а о грешкама у опцијама на српски
+static CLAP: phf::Map<&'static str, &'static str> = phf_map! {
+    "error: {}" => "грешка: {}",
+    "Usage: {}" => "Употреба: {}",
+    "For more information, try '{}'." => "За више информација покушајте „{}”.",
+    "unexpected argument '{}' found" => "неочекиван аргумент „{}”",
+    "unexpected value '{}' for '{}' found; no more were expected" => "неочекивана вредност „{}” за „{}”; више вредности се не очекује",
+    "unrecognized subcommand '{}'" => "непозната подкоманда „{}”",
+    "the following required arguments were not provided:" => "следећи обавезни аргументи нису задати:",
+    "the argument '{}' cannot be used multiple times" => "аргумент „{}” се не може задати више пута",
+    "the argument '{}' cannot be used with '{}'" => "аргумент „{}” се не може задати уз „{}”",
+    "the argument '{}' cannot be used with:" => "аргумент „{}” се не може задати уз:",
+    "the argument '{}' cannot be used with one or more of the other specified arguments" => "аргумент „{}” се не може задати уз неке од осталих аргумената",
+    "the subcommand '{}' cannot be used with '{}'" => "подкоманда „{}” се не може задати уз „{}”",
+    "a value is required for '{}' but none was supplied" => "аргумент „{}” захтева вредност, која није задата",
+    "invalid value '{}' for '{}'" => "неисправна вредност „{}” за „{}”",
+    "invalid value '{}' for '{}': {}" => "неисправна вредност „{}” за „{}”: {}",
+    "equal sign is needed when assigning values to '{}'" => "вредност аргумента „{}” се задаје иза знака једнакости",
+    "invalid UTF-8 was detected" => "откривен је неисправан UTF-8",
+    "  [possible values: {}]" => "  [могуће вредности: {}]",
+    "  tip: a similar argument exists: '{}'" => "  савет: постоји сличан аргумент „{}”",
+    "  tip: a similar value exists: '{}'" => "  савет: постоји слична вредност „{}”",
+    "  tip: a similar subcommand exists: '{}'" => "  савет: постоји слична подкоманда „{}”",
+    "  tip: some similar arguments exist: {}" => "  савет: постоје слични аргументи {}",
+    "  tip: some similar values exist: {}" => "  савет: постоје сличне вредности {}",
+    "  tip: some similar subcommands exist: {}" => "  савет: постоје сличне подкоманде {}",
+    "  tip: to pass '{}' as a value, use '{}'" => "  савет: да бисте „{}” задали као вредност, користите „{}”",
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_locale() {
+        let args = |args: &[&str]| args.iter().map(OsString::from).collect::<Vec<_>>();
+        assert_eq!(
+            Locale::English,
+            Locale::from_args(args(&["ћирко", "-л", "--језик", "en"]))
+        );
+        assert_eq!(
+            Locale::Latin,
+            Locale::from_args(args(&["ћирко", "--language=SR-LATN"]))
+        );
+        assert_eq!(Ok(Locale::Cyrillic), "sr-Cyrl".parse());
+        assert!("de".parse::<Locale>().is_err());
+    }
+
+    #[test]
+    fn test_localize() {
+        let message = "смер конверзије (-л или -ћ) мора бити задат за формат epub";
+        assert_eq!(message, localize(message, Locale::Cyrillic));
+        assert_eq!(
+            "smer konverzije (-л ili -ћ) mora biti zadat za format epub",
+            localize(message, Locale::Latin)
+        );
+        assert_eq!(
+            "conversion direction (-л or -ћ) must be given for the epub format",
+            localize(message, Locale::English)
+        );
+        // Променљиви делови се не пресловљавају, али се преводе ако су и сами
+        // поруке из табеле
+        assert_eq!(
+            "„ћирилица.txt” nije fajl",
+            localize("„ћирилица.txt” није фајл", Locale::Latin)
+        );
+        assert_eq!(
+            "error on line 3: unclosed quote",
+            localize("грешка у 3. реду: незатворен наводник", Locale::English)
+        );
+        assert_eq!(
+            "No such file or directory",
+            localize("No such file or directory", Locale::Latin)
+        );
+    }
+
+    #[test]
+    fn test_captures() {
+        assert_eq!(
+            Some(vec!["a.txt", "мешовито", "12.5", "87.5"]),
+            captures(
+                "{}: {} (ћирилица {}%, латиница {}%)",
+                "a.txt: мешовито (ћирилица 12.5%, латиница 87.5%)"
+            )
+        );
+        assert_eq!(None, captures("„{}” није фајл", "„a” је фајл"));
+    }
+
+    #[test]
+    fn test_localize_error() {
+        let rendered = "error: unexpected argument '--непостоји' found\n\n  tip: to pass '--непостоји' as a value, use '-- --непостоји'\n\nUsage: ћирко [OPTIONS] [FILE]...\n\nFor more information, try '--помоћ'.\n";
+        assert_eq!(
+            "грешка: неочекиван аргумент „--непостоји”\n\n  савет: да бисте „--непостоји” задали као вредност, користите „-- --непостоји”\n\nУпотреба: ћирко [OPTIONS] [FILE]...\n\nЗа више информација покушајте „--помоћ”.\n",
+            localize_error(rendered, Locale::Cyrillic)
+        );
+        assert_eq!(
+            "greška: neočekivan argument „--непостоји”\n\n  savet: da biste „--непостоји” zadali kao vrednost, koristite „-- --непостоји”\n\nUpotreba: ћирко [OPTIONS] [FILE]...\n\nZa više informacija pokušajte „--помоћ”.\n",
+            localize_error(rendered, Locale::Latin)
+        );
+        assert_eq!(rendered, localize_error(rendered, Locale::English));
+        // Наведени аргументи и већ преведени разлози грешака се не мењају
+        assert_eq!(
+            "грешка: следећи обавезни аргументи нису задати:\n  <--латиница|--ћирилица>",
+            localize_error(
+                "error: the following required arguments were not provided:\n  <--латиница|--ћирилица>",
+                Locale::Cyrillic
+            )
+        );
+        assert_eq!(
+            "грешка: неисправна вредност „x” за „--бом <БОМ>”\n  [могуће вредности: задржи, уклони]",
+            localize_error(
+                "error: invalid value 'x' for '--бом <БОМ>'\n  [possible values: задржи, уклони]",
+                Locale::Cyrillic
+            )
+        );
+        assert_eq!(
+            "грешка: излазни фајл је исти као улазни",
+            localize_error("error: излазни фајл је исти као улазни", Locale::Cyrillic)
+        );
+    }
+}
@@ -0,0 +1,86 @@
+//! Приказ напретка конверзије великих улаза и великог броја фајлова на
+//! стандардном излазу за грешке.
+use indicatif::{ProgressBar, ProgressStyle};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+// Изглед траке: обрађени део улаза, обрађени и укупни бајтови, број фајлова
+// (у поруци) и брзина обраде
+const TEMPLATE: &str =
+    "[{bar:30}] {percent:>3}%  {decimal_bytes}/{decimal_total_bytes}{msg}  {decimal_bytes_per_sec}";
+
+/// Трака напретка која приказује обрађени део улаза, број обрађених фајлова и
+/// брзину обраде. Трака се не приказује ако стандардни излаз за грешке није
+/// терминал.
+#[derive(Debug)]
+pub struct Progress {
+    bar: ProgressBar,
+    total_files: usize,
+    files: AtomicUsize,
+}
+
+impl Progress {
+    /// Нова трака за обраду `total_bytes` бајтова у `total_files` фајлова.
+    pub fn new(total_bytes: u64, total_files: usize) -> Self {
+        let style = ProgressStyle::with_template(TEMPLATE)
+            .expect("исправан шаблон траке")
+            .progress_chars("██░");
+        let progress = Progress {
+            bar: ProgressBar::new(total_bytes).with_style(style),
+            total_files,
+            files: AtomicUsize::new(0),
+        };
+        progress.update_files(0);
+        progress
+    }
+
+    /// Бележи обраду још `bytes` бајтова и `files` фајлова. Трака се
+    /// поново исцртава највише неколико пута у секунди.
+    pub fn advance(&self, bytes: u64, files: usize) {
+        let files = self.files.fetch_add(files, Ordering::Relaxed) + files;
+        self.update_files(files);
+        self.bar.inc(bytes);
+    }
+
+    /// Исцртава траку за завршену обраду.
+    pub fn finish(&self) {
+        self.update_files(self.total_files);
+        if let Some(total) = self.bar.length() {
+            self.bar.set_position(total);
+        }
+        self.bar.finish();
+    }
+
+    fn update_files(&self, files: usize) {
+        if self.total_files > 1 {
+            self.bar
+                .set_message(format!("  {files}/{} фајлова", self.total_files));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indicatif::ProgressDrawTarget;
+
+    #[test]
+    fn test_progress() {
+        let progress = Progress::new(10_000_000, 4);
+        progress.bar.set_draw_target(ProgressDrawTarget::hidden());
+        progress.advance(3_000_000, 1);
+        progress.advance(2_000_000, 1);
+        assert_eq!(5_000_000, progress.bar.position());
+        assert_eq!("  2/4 фајлова", progress.bar.message());
+
+        progress.finish();
+        assert_eq!(10_000_000, progress.bar.position());
+        assert_eq!("  4/4 фајлова", progress.bar.message());
+        assert!(progress.bar.is_finished());
+
+        // Број фајлова се не приказује код конверзије једног улаза
+        let progress = Progress::new(0, 1);
+        progress.bar.set_draw_target(ProgressDrawTarget::hidden());
+        progress.advance(0, 1);
+        assert_eq!("", progress.bar.message());
+    }
+}
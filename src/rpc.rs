@@ -0,0 +1,649 @@
+//! JSON-RPC 2.0 служба за додатке едитора. Захтеви и одговори су JSON објекти
+//! у по једном реду, па се конвертор покреће само једном. Методе:
+//!
+//! - `convert` (`text`, опционо `direction`: `латиница` или `ћирилица`) враћа
+//!   пресловљени текст (`text`), смер (`direction`) и промењене делове текста
+//!   (`spans`) у облику `[почетак, крај, почетак, крај]` опсега бајтова улаза
+//!   и излаза;
+//! - `detect` (`text`) враћа смер конверзије (`direction`).
+//!
+//! Служба може да прима захтеве и преко Unix сокета, односно именоване цеви
+//! на Windows-у (`\\.\pipe\cirko`) ([`listen`]), а [`convert_remote`] шаље
+//! захтев служби која већ ради.
+use std::fmt;
+use std::io::{self, BufRead, Write};
+use std::path::Path;
+
+use crate::formats::json::json_string;
+use crate::formats::syntax_error;
+use crate::{Converter, Direction, Error};
+
+/// JSON вредност. Бројеви се чувају у изворном облику.
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Null,
+    Bool(bool),
+    Number(String),
+    String(String),
+    Array(Vec<Value>),
+    Object(Vec<(String, Value)>),
+}
+
+impl Value {
+    fn get(&self, key: &str) -> Option<&Value> {
+        match self {
+            Value::Object(members) => members.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(s) => Some(s),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Null => write!(f, "null"),
+            Value::Bool(b) => write!(f, "{b}"),
+            Value::Number(n) => write!(f, "{n}"),
+            Value::String(s) => write!(f, "{}", json_string(s)),
+            Value::Array(items) => {
+                write!(f, "[")?;
+                for (i, item) in items.iter().enumerate() {
+                    write!(f, "{}{item}", if i > 0 { "," } else { "" })?;
+                }
+                write!(f, "]")
+            }
+            Value::Object(members) => {
+                write!(f, "{{")?;
+                for (i, (key, value)) in members.iter().enumerate() {
+                    let comma = if i > 0 { "," } else { "" };
+                    write!(f, "{comma}{}:{value}", json_string(key))?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+/// Парсира JSON документ.
+fn parse(input: &str) -> Result<Value, Error> {
+    let mut parser = Parser { input, pos: 0 };
+    parser.whitespace();
+    let value = parser.value()?;
+    parser.whitespace();
+    if parser.pos < input.len() {
+        return Err(parser.error("неочекиван садржај након краја документа"));
+    }
+    Ok(value)
+}
+
+impl Parser<'_> {
+    fn error(&self, message: &str) -> Error {
+        syntax_error(self.input, self.pos, message)
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.input.as_bytes().get(self.pos).copied()
+    }
+
+    fn whitespace(&mut self) {
+        let rest = &self.input[self.pos..];
+        self.pos += rest.len() - rest.trim_start_matches([' ', '\t', '\n', '\r']).len();
+    }
+
+    fn expect(&mut self, c: u8, message: &str) -> Result<(), Error> {
+        if self.peek() == Some(c) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(self.error(message))
+        }
+    }
+
+    fn value(&mut self) -> Result<Value, Error> {
+        match self.peek() {
+            Some(b'{') => self.object(),
+            Some(b'[') => self.array(),
+            Some(b'"') => self.string().map(Value::String),
+            Some(_) => {
+                let rest = &self.input[self.pos..];
+                for (literal, value) in [
+                    ("true", Value::Bool(true)),
+                    ("false", Value::Bool(false)),
+                    ("null", Value::Null),
+                ] {
+                    if rest.starts_with(literal) {
+                        self.pos += literal.len();
+                        return Ok(value);
+                    }
+                }
+                let len = rest
+                    .find(|c: char| !matches!(c, '0'..='9' | '-' | '+' | '.' | 'e' | 'E'))
+                    .unwrap_or(rest.len());
+                if len == 0 || rest[..len].parse::<f64>().is_err() {
+                    return Err(self.error("неочекиван знак"));
+                }
+                self.pos += len;
+                Ok(Value::Number(rest[..len].to_string()))
+            }
+            None => Err(self.error("неочекиван крај документа")),
+        }
+    }
+
+    fn object(&mut self) -> Result<Value, Error> {
+        self.pos += 1;
+        let mut members = Vec::new();
+        self.whitespace();
+        if self.peek() == Some(b'}') {
+            self.pos += 1;
+            return Ok(Value::Object(members));
+        }
+        loop {
+            if self.peek() != Some(b'"') {
+                return Err(self.error("очекиван кључ"));
+            }
+            let key = self.string()?;
+            self.whitespace();
+            self.expect(b':', "очекивано `:`")?;
+            self.whitespace();
+            members.push((key, self.value()?));
+            self.whitespace();
+            if self.peek() == Some(b',') {
+                self.pos += 1;
+                self.whitespace();
+            } else {
+                self.expect(b'}', "очекивано `,` или `}`")?;
+                return Ok(Value::Object(members));
+            }
+        }
+    }
+
+    fn array(&mut self) -> Result<Value, Error> {
+        self.pos += 1;
+        let mut items = Vec::new();
+        self.whitespace();
+        if self.peek() == Some(b']') {
+            self.pos += 1;
+            return Ok(Value::Array(items));
+        }
+        loop {
+            items.push(self.value()?);
+            self.whitespace();
+            if self.peek() == Some(b',') {
+                self.pos += 1;
+                self.whitespace();
+            } else {
+                self.expect(b']', "очекивано `,` или `]`")?;
+                return Ok(Value::Array(items));
+            }
+        }
+    }
+
+    fn string(&mut self) -> Result<String, Error> {
+        self.pos += 1;
+        let mut output = String::new();
+        loop {
+            let rest = &self.input[self.pos..];
+            let len = rest
+                .find(['"', '\\'])
+                .ok_or_else(|| self.error("незатворен стринг"))?;
+            output.push_str(&rest[..len]);
+            self.pos += len + 1;
+            if rest.as_bytes()[len] == b'"' {
+                return Ok(output);
+            }
+            let escape = self.peek().ok_or_else(|| self.error("незатворен стринг"))?;
+            self.pos += 1;
+            match escape {
+                b'"' => output.push('"'),
+                b'\\' => output.push('\\'),
+                b'/' => output.push('/'),
+                b'b' => output.push('\u{8}'),
+                b'f' => output.push('\u{c}'),
+                b'n' => output.push('\n'),
+                b'r' => output.push('\r'),
+                b't' => output.push('\t'),
+                b'u' => {
+                    let high = self.hex()?;
+                    let code = if (0xd800..0xdc00).contains(&high) {
+                        self.expect(b'\\', "очекиван други део сурогатног пара")?;
+                        self.expect(b'u', "очекиван други део сурогатног пара")?;
+                        let low = self.hex()?;
+                        0x10000 + ((high - 0xd800) << 10) + low.wrapping_sub(0xdc00)
+                    } else {
+                        high
+                    };
+                    output.push(char::from_u32(code).ok_or_else(|| self.error("неисправан знак"))?);
+                }
+                _ => return Err(self.error("неисправна escape секвенца")),
+            }
+        }
+    }
+
+    fn hex(&mut self) -> Result<u32, Error> {
+        let code = self
+            .input
+            .get(self.pos..self.pos + 4)
+            .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+            .ok_or_else(|| self.error("неисправна escape секвенца"))?;
+        self.pos += 4;
+        Ok(code)
+    }
+}
+
+/// Грешка JSON-RPC захтева.
+struct RpcError {
+    code: i32,
+    message: String,
+}
+
+impl RpcError {
+    fn new(code: i32, message: impl Into<String>) -> Self {
+        RpcError {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+fn direction_name(direction: Direction) -> &'static str {
+    match direction {
+        Direction::ToLatin => "латиница",
+        Direction::ToCyrillic => "ћирилица",
+    }
+}
+
+/// Обрађује један захтев и враћа одговор, или `None` ако је захтев
+/// обавештење (без `id`). Ако смер није задат у захтеву користи се
+/// `direction`, а ако ни он није задат смер се детектује.
+pub fn handle(
+    request: &str,
+    converter: &Converter,
+    direction: Option<Direction>,
+) -> Option<String> {
+    let (id, result) = match parse(request) {
+        Ok(request) => {
+            let id = request.get("id").cloned();
+            let result = call(&request, converter, direction);
+            id.map(|id| (id, result))?
+        }
+        Err(e) => (Value::Null, Err(RpcError::new(-32700, e.to_string()))),
+    };
+    let response = match result {
+        Ok(result) => Value::Object(vec![
+            ("jsonrpc".into(), Value::String("2.0".into())),
+            ("id".into(), id),
+            ("result".into(), result),
+        ]),
+        Err(error) => Value::Object(vec![
+            ("jsonrpc".into(), Value::String("2.0".into())),
+            ("id".into(), id),
+            (
+                "error".into(),
+                Value::Object(vec![
+                    ("code".into(), Value::Number(error.code.to_string())),
+                    ("message".into(), Value::String(error.message)),
+                ]),
+            ),
+        ]),
+    };
+    Some(response.to_string())
+}
+
+fn call(
+    request: &Value,
+    converter: &Converter,
+    direction: Option<Direction>,
+) -> Result<Value, RpcError> {
+    let method = request
+        .get("method")
+        .and_then(Value::as_str)
+        .ok_or_else(|| RpcError::new(-32600, "неисправан захтев"))?;
+    if !matches!(method, "convert" | "detect") {
+        return Err(RpcError::new(
+            -32601,
+            format!("непозната метода „{method}”"),
+        ));
+    }
+    let params = request.get("params");
+    let text = params
+        .and_then(|params| params.get("text"))
+        .and_then(Value::as_str)
+        .ok_or_else(|| RpcError::new(-32602, "недостаје параметар `text`"))?;
+    if method == "detect" {
+        return Ok(Value::Object(vec![(
+            "direction".into(),
+            Value::String(direction_name(Direction::detect(text)).into()),
+        )]));
+    }
+    let direction = match params
+        .and_then(|params| params.get("direction"))
+        .map(|direction| direction.as_str())
+    {
+        Some(Some("латиница" | "latin")) => Direction::ToLatin,
+        Some(Some("ћирилица" | "cyrillic")) => Direction::ToCyrillic,
+        Some(Some(name)) => {
+            return Err(RpcError::new(-32602, format!("непознат смер „{name}”")));
+        }
+        Some(None) => return Err(RpcError::new(-32602, "неисправан параметар `direction`")),
+        None => direction.unwrap_or_else(|| Direction::detect(text)),
+    };
+    let (text, spans) = converter.convert_with_spans(text, direction);
+    let number = |n: usize| Value::Number(n.to_string());
+    let spans = spans
+        .iter()
+        .map(|span| {
+            Value::Array(vec![
+                number(span.input.start),
+                number(span.input.end),
+                number(span.output.start),
+                number(span.output.end),
+            ])
+        })
+        .collect();
+    Ok(Value::Object(vec![
+        ("text".into(), Value::String(text)),
+        (
+            "direction".into(),
+            Value::String(direction_name(direction).into()),
+        ),
+        ("spans".into(), Value::Array(spans)),
+    ]))
+}
+
+/// Обрађује захтеве из `reader` ред по ред док се улаз не затвори и уписује
+/// одговоре у `writer`.
+pub fn serve<R: BufRead, W: Write>(
+    reader: R,
+    mut writer: W,
+    converter: &Converter,
+    direction: Option<Direction>,
+) -> io::Result<()> {
+    for line in reader.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Some(response) = handle(&line, converter, direction) {
+            writeln!(writer, "{response}")?;
+            writer.flush()?;
+        }
+    }
+    Ok(())
+}
+
+/// Обрађује захтеве примљене преко Unix сокета `path`, сваку везу у посебној
+/// нити. Сокет који је остао иза службе која више не ради се уклања.
+#[cfg(unix)]
+pub fn listen(path: &Path, converter: &Converter, direction: Option<Direction>) -> io::Result<()> {
+    use std::os::unix::net::{UnixListener, UnixStream};
+
+    if path.exists() {
+        if UnixStream::connect(path).is_ok() {
+            return Err(io::ErrorKind::AddrInUse.into());
+        }
+        std::fs::remove_file(path)?;
+    }
+    let listener = UnixListener::bind(path)?;
+    std::thread::scope(|scope| {
+        for stream in listener.incoming() {
+            let stream = stream?;
+            // Грешке појединачних веза (нпр. прекинута веза) не заустављају
+            // службу
+            scope.spawn(move || -> io::Result<()> {
+                let reader = io::BufReader::new(stream.try_clone()?);
+                serve(reader, &stream, converter, direction)
+            });
+        }
+        Ok(())
+    })
+}
+
+/// Обрађује захтеве примљене преко именоване цеви `path` (нпр.
+/// `\\.\pipe\cirko`), сваку везу у посебној нити.
+#[cfg(windows)]
+pub fn listen(path: &Path, converter: &Converter, direction: Option<Direction>) -> io::Result<()> {
+    std::thread::scope(|scope| {
+        let mut first = true;
+        loop {
+            let pipe = pipe::create(path, first)?;
+            first = false;
+            pipe::connect(&pipe)?;
+            scope.spawn(move || -> io::Result<()> {
+                let reader = io::BufReader::new(pipe.try_clone()?);
+                serve(reader, &pipe, converter, direction)
+            });
+        }
+    })
+}
+
+#[cfg(not(any(unix, windows)))]
+pub fn listen(
+    _path: &Path,
+    _converter: &Converter,
+    _direction: Option<Direction>,
+) -> io::Result<()> {
+    Err(io::ErrorKind::Unsupported.into())
+}
+
+/// Шаље захтев `convert` служби на Unix сокету, односно именованој цеви
+/// `path` и враћа пресловљени текст. Ако смер није задат, смер одређује
+/// служба.
+#[cfg(unix)]
+pub fn convert_remote(
+    path: &Path,
+    text: &str,
+    direction: Option<Direction>,
+) -> Result<String, Error> {
+    let stream = std::os::unix::net::UnixStream::connect(path)?;
+    request(io::BufReader::new(&stream), &stream, text, direction)
+}
+
+#[cfg(windows)]
+pub fn convert_remote(
+    path: &Path,
+    text: &str,
+    direction: Option<Direction>,
+) -> Result<String, Error> {
+    let pipe = std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(path)?;
+    request(io::BufReader::new(&pipe), &pipe, text, direction)
+}
+
+#[cfg(not(any(unix, windows)))]
+pub fn convert_remote(
+    _path: &Path,
+    _text: &str,
+    _direction: Option<Direction>,
+) -> Result<String, Error> {
+    Err(io::Error::from(io::ErrorKind::Unsupported).into())
+}
+
+/// Именоване цеви на Windows-у. Свака веза добија засебну инстанцу цеви.
+#[cfg(windows)]
+mod pipe {
+    use std::fs::File;
+    use std::io;
+    use std::os::windows::ffi::OsStrExt;
+    use std::os::windows::io::FromRawHandle;
+    use std::path::Path;
+
+    use windows_sys::Win32::Foundation::{
+        ERROR_ACCESS_DENIED, ERROR_PIPE_CONNECTED, INVALID_HANDLE_VALUE,
+    };
+    use windows_sys::Win32::Storage::FileSystem::{
+        FILE_FLAG_FIRST_PIPE_INSTANCE, PIPE_ACCESS_DUPLEX,
+    };
+    use windows_sys::Win32::System::Pipes::{
+        ConnectNamedPipe, CreateNamedPipeW, PIPE_READMODE_BYTE, PIPE_REJECT_REMOTE_CLIENTS,
+        PIPE_TYPE_BYTE, PIPE_UNLIMITED_INSTANCES, PIPE_WAIT,
+    };
+
+    const BUFFER_SIZE: u32 = 64 * 1024;
+
+    /// Прави нову инстанцу цеви `path`. Ако је `first` тачно, а цев већ
+    /// постоји (ради друга служба), враћа грешку `AddrInUse`.
+    pub(super) fn create(path: &Path, first: bool) -> io::Result<File> {
+        let name: Vec<u16> = path.as_os_str().encode_wide().chain([0]).collect();
+        let mut open_mode = PIPE_ACCESS_DUPLEX;
+        if first {
+            open_mode |= FILE_FLAG_FIRST_PIPE_INSTANCE;
+        }
+        let pipe_mode =
+            PIPE_TYPE_BYTE | PIPE_READMODE_BYTE | PIPE_WAIT | PIPE_REJECT_REMOTE_CLIENTS;
+        // SAFETY: назив је исправан UTF-16 низ са нулом на крају, а
+        // безбедносни атрибути нису задати
+        let handle = unsafe {
+            CreateNamedPipeW(
+                name.as_ptr(),
+                open_mode,
+                pipe_mode,
+                PIPE_UNLIMITED_INSTANCES,
+                BUFFER_SIZE,
+                BUFFER_SIZE,
+                0,
+                std::ptr::null(),
+            )
+        };
+        if handle == INVALID_HANDLE_VALUE {
+            let error = io::Error::last_os_error();
+            if first && error.raw_os_error() == Some(ERROR_ACCESS_DENIED as i32) {
+                return Err(io::ErrorKind::AddrInUse.into());
+            }
+            return Err(error);
+        }
+        // SAFETY: ручка је исправна и нико други је не поседује
+        Ok(unsafe { File::from_raw_handle(handle) })
+    }
+
+    /// Чека да се клијент повеже на инстанцу цеви `pipe`.
+    pub(super) fn connect(pipe: &File) -> io::Result<()> {
+        use std::os::windows::io::AsRawHandle;
+
+        // SAFETY: ручка цеви је исправна, а цев није отворена за асинхрони рад
+        if unsafe { ConnectNamedPipe(pipe.as_raw_handle(), std::ptr::null_mut()) } != 0 {
+            return Ok(());
+        }
+        // Клијент се повезао између прављења цеви и позива
+        match io::Error::last_os_error() {
+            error if error.raw_os_error() == Some(ERROR_PIPE_CONNECTED as i32) => Ok(()),
+            error => Err(error),
+        }
+    }
+}
+
+/// Уписује захтев `convert` у `writer` и чита одговор из `reader`.
+fn request<R: BufRead, W: Write>(
+    mut reader: R,
+    mut writer: W,
+    text: &str,
+    direction: Option<Direction>,
+) -> Result<String, Error> {
+    let mut params = vec![("text".into(), Value::String(text.into()))];
+    if let Some(direction) = direction {
+        params.push((
+            "direction".into(),
+            Value::String(direction_name(direction).into()),
+        ));
+    }
+    let request = Value::Object(vec![
+        ("jsonrpc".into(), Value::String("2.0".into())),
+        ("id".into(), Value::Number("1".into())),
+        ("method".into(), Value::String("convert".into())),
+        ("params".into(), Value::Object(params)),
+    ]);
+    writeln!(writer, "{request}")?;
+    writer.flush()?;
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+    let response = parse(&line)?;
+    if let Some(error) = response.get("error") {
+        let message = error.get("message").and_then(Value::as_str);
+        return Err(Error::Service(message.unwrap_or_default().to_string()));
+    }
+    response
+        .get("result")
+        .and_then(|result| result.get("text"))
+        .and_then(Value::as_str)
+        .map(String::from)
+        .ok_or_else(|| Error::Service("неисправан одговор".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let value = parse(r#" {"a": [1, -2.5e3, true, null], "b": "ж\n😀"} "#).unwrap();
+        assert_eq!(
+            r#"{"a":[1,-2.5e3,true,null],"b":"ж\n😀"}"#,
+            value.to_string()
+        );
+        assert!(parse(r#"{"a": }"#).is_err());
+        assert!(parse(r#"["a"] x"#).is_err());
+    }
+
+    #[test]
+    fn test_handle() {
+        let converter = Converter::default();
+        let handle = |request: &str| handle(request, &converter, None);
+        assert_eq!(
+            Some(
+                r#"{"jsonrpc":"2.0","id":1,"result":{"text":"Њива ж","direction":"ћирилица","spans":[[0,5,0,8],[6,8,9,11]]}}"#
+                    .to_string()
+            ),
+            handle(r#"{"jsonrpc":"2.0","id":1,"method":"convert","params":{"text":"Njiva ž"}}"#)
+        );
+        assert_eq!(
+            Some(r#"{"jsonrpc":"2.0","id":"a","result":{"direction":"латиница"}}"#.to_string()),
+            handle(r#"{"jsonrpc":"2.0","id":"a","method":"detect","params":{"text":"Ж"}}"#)
+        );
+        assert_eq!(
+            None,
+            handle(r#"{"jsonrpc":"2.0","method":"convert","params":{"text":"x"}}"#)
+        );
+        assert_eq!(
+            Some(
+                r#"{"jsonrpc":"2.0","id":2,"error":{"code":-32601,"message":"непозната метода „x”"}}"#
+                    .to_string()
+            ),
+            handle(r#"{"jsonrpc":"2.0","id":2,"method":"x"}"#)
+        );
+        assert!(
+            handle("{")
+                .unwrap()
+                .contains(r#""id":null,"error":{"code":-32700"#)
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_request() {
+        use std::os::unix::net::UnixStream;
+
+        let converter = Converter::default();
+        let (client, server) = UnixStream::pair().unwrap();
+        std::thread::scope(|scope| {
+            scope.spawn(|| serve(io::BufReader::new(&server), &server, &converter, None).unwrap());
+            let convert =
+                |text, direction| request(io::BufReader::new(&client), &client, text, direction);
+            assert_eq!("Њива\nж", convert("Njiva\nž", None).unwrap());
+            assert_eq!("Njiva", convert("Њива", Some(Direction::ToLatin)).unwrap());
+            client.shutdown(std::net::Shutdown::Write).unwrap();
+        });
+    }
+}
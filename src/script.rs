@@ -0,0 +1,395 @@
+//! Анализа писма текста и поправка речи у којима су помешана писма.
+use phf::phf_map;
+
+/// Писмо.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Script {
+    Latin,
+    Cyrillic,
+}
+
+impl Script {
+    /// Писмо датог слова или `None` ако карактер није латинично нити
+    /// ћирилично слово.
+    pub fn of(c: char) -> Option<Script> {
+        if !c.is_alphabetic() {
+            None
+        } else if ('\u{400}'..='\u{52F}').contains(&c) {
+            Some(Script::Cyrillic)
+        } else if c.is_ascii_alphabetic() || ('\u{C0}'..='\u{24F}').contains(&c) {
+            Some(Script::Latin)
+        } else {
+            None
+        }
+    }
+
+    /// Преовлађујуће писмо текста (писмо већине слова) или `None` ако текст
+    /// нема слова или су оба писма подједнако заступљена.
+    pub fn dominant(text: &str) -> Option<Script> {
+        let (latin, cyrillic) =
+            text.chars()
+                .filter_map(Script::of)
+                .fold((0, 0), |(latin, cyrillic), script| match script {
+                    Script::Latin => (latin + 1, cyrillic),
+                    Script::Cyrillic => (latin, cyrillic + 1),
+                });
+        match latin.cmp(&cyrillic) {
+            std::cmp::Ordering::Greater => Some(Script::Latin),
+            std::cmp::Ordering::Less => Some(Script::Cyrillic),
+            std::cmp::Ordering::Equal => None,
+        }
+    }
+}
+
+/// Најмањи удео слова једног писма (у процентима) да би се текст сматрао
+/// текстом на том писму. Текст у коме ниједно писмо нема овај удео је
+/// мешовит.
+pub const DOMINANT_SHARE: f64 = 90.0;
+
+/// Писмо текста одређено функцијом [`detect`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DetectedScript {
+    Latin,
+    Cyrillic,
+    /// Ниједно писмо нема удео од бар [`DOMINANT_SHARE`] процената слова.
+    Mixed,
+    /// Текст нема латиничних нити ћириличних слова.
+    Unknown,
+}
+
+/// Анализа писма текста.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScriptReport<'a> {
+    /// Број латиничних слова.
+    pub latin: usize,
+    /// Број ћириличних слова.
+    pub cyrillic: usize,
+    /// Редни бројеви (од 1) и садржај линија које садрже слова оба писма.
+    pub mixed_lines: Vec<(usize, &'a str)>,
+}
+
+impl ScriptReport<'_> {
+    /// Удео латиничних слова у процентима.
+    pub fn latin_share(&self) -> f64 {
+        share(self.latin, self.latin + self.cyrillic)
+    }
+
+    /// Удео ћириличних слова у процентима.
+    pub fn cyrillic_share(&self) -> f64 {
+        share(self.cyrillic, self.latin + self.cyrillic)
+    }
+
+    /// Писмо текста.
+    pub fn script(&self) -> DetectedScript {
+        if self.latin + self.cyrillic == 0 {
+            DetectedScript::Unknown
+        } else if self.latin_share() >= DOMINANT_SHARE {
+            DetectedScript::Latin
+        } else if self.cyrillic_share() >= DOMINANT_SHARE {
+            DetectedScript::Cyrillic
+        } else {
+            DetectedScript::Mixed
+        }
+    }
+}
+
+fn share(count: usize, total: usize) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        count as f64 * 100.0 / total as f64
+    }
+}
+
+/// Одређује писмо текста на основу броја латиничних и ћириличних слова и
+/// проналази линије у којима се појављују оба писма.
+pub fn detect(input: &str) -> ScriptReport<'_> {
+    let mut report = ScriptReport {
+        latin: 0,
+        cyrillic: 0,
+        mixed_lines: Vec::new(),
+    };
+    for (i, line) in input.lines().enumerate() {
+        let (mut latin, mut cyrillic) = (0, 0);
+        for script in line.chars().filter_map(Script::of) {
+            match script {
+                Script::Latin => latin += 1,
+                Script::Cyrillic => cyrillic += 1,
+            }
+        }
+        if latin > 0 && cyrillic > 0 {
+            report.mixed_lines.push((i + 1, line));
+        }
+        report.latin += latin;
+        report.cyrillic += cyrillic;
+    }
+    report
+}
+
+// Латинична слова која изгледају исто као ћирилична (хомоглифи).
+static LATIN_TO_CYRILLIC_HOMOGLYPHS: phf::Map<char, char> = phf_map! {
+    'a' => 'а',
+    'c' => 'с',
+    'e' => 'е',
+    'i' => 'і',
+    'j' => 'ј',
+    'o' => 'о',
+    'p' => 'р',
+    's' => 'ѕ',
+    'x' => 'х',
+    'y' => 'у',
+    'A' => 'А',
+    'B' => 'В',
+    'C' => 'С',
+    'E' => 'Е',
+    'H' => 'Н',
+    'I' => 'І',
+    'J' => 'Ј',
+    'K' => 'К',
+    'M' => 'М',
+    'O' => 'О',
+    'P' => 'Р',
+    'S' => 'Ѕ',
+    'T' => 'Т',
+    'X' => 'Х',
+};
+
+static CYRILLIC_TO_LATIN_HOMOGLYPHS: phf::Map<char, char> = phf_map! {
+    'а' => 'a',
+    'с' => 'c',
+    'е' => 'e',
+    'і' => 'i',
+    'ј' => 'j',
+    'о' => 'o',
+    'р' => 'p',
+    'ѕ' => 's',
+    'х' => 'x',
+    'у' => 'y',
+    'А' => 'A',
+    'В' => 'B',
+    'С' => 'C',
+    'Е' => 'E',
+    'Н' => 'H',
+    'І' => 'I',
+    'Ј' => 'J',
+    'К' => 'K',
+    'М' => 'M',
+    'О' => 'O',
+    'Р' => 'P',
+    'Ѕ' => 'S',
+    'Т' => 'T',
+    'Х' => 'X',
+};
+
+/// Одређује преовлађујуће писмо речи. Враћа `None` ако реч садржи само једно
+/// писмо или ако ниједно писмо не преовлађује.
+fn dominant_script(word: &str) -> Option<Script> {
+    let (latin, cyrillic) = word
+        .chars()
+        .fold((0, 0), |(l, c), ch| match Script::of(ch) {
+            Some(Script::Latin) => (l + 1, c),
+            Some(Script::Cyrillic) => (l, c + 1),
+            None => (l, c),
+        });
+    match (latin, cyrillic) {
+        (0, _) | (_, 0) => None,
+        (l, c) if l > c => Some(Script::Latin),
+        (l, c) if c > l => Some(Script::Cyrillic),
+        _ => None,
+    }
+}
+
+/// Поправља речи у којима су помешана писма тако што слова мањинског писма
+/// која имају хомоглиф у преовлађујућем писму замењује тим хомоглифом (нпр.
+/// латинично `a` у ћириличној речи `мaма`).
+pub fn normalize_homoglyphs(input: &str) -> String {
+    let mut output = String::with_capacity(input.len());
+    for (word, is_word) in split_words(input) {
+        match dominant_script(word).filter(|_| is_word) {
+            Some(script) => {
+                let homoglyphs = match script {
+                    Script::Latin => &CYRILLIC_TO_LATIN_HOMOGLYPHS,
+                    Script::Cyrillic => &LATIN_TO_CYRILLIC_HOMOGLYPHS,
+                };
+                output.extend(word.chars().map(|c| *homoglyphs.get(&c).unwrap_or(&c)));
+            }
+            None => output.push_str(word),
+        }
+    }
+    output
+}
+
+/// Реч у којој су помешана писма.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MixedWord {
+    pub word: String,
+    /// Позиција почетка речи у бајтовима.
+    pub offset: usize,
+    /// Ред у коме се реч налази (од 1).
+    pub line: usize,
+    /// Колона у карактерима у којој реч почиње (од 1).
+    pub column: usize,
+    /// Позиције (у карактерима унутар речи) слова мањинског писма. Ако ниједно
+    /// писмо не преовлађује садржи позиције латиничних слова.
+    pub stray: Vec<usize>,
+}
+
+/// Проналази речи у којима су помешана писма, нпр. ради провере документа пре
+/// конверзије или откривања злоупотребе хомоглифа.
+pub fn mixed_script_words(input: &str) -> Vec<MixedWord> {
+    let mut result = Vec::new();
+    let (mut offset, mut line, mut column) = (0, 1, 1);
+    for (part, is_word) in split_words(input) {
+        if is_word {
+            let scripts = part.chars().map(Script::of).collect::<Vec<_>>();
+            let has = |script| scripts.contains(&Some(script));
+            if has(Script::Latin) && has(Script::Cyrillic) {
+                let minority = match dominant_script(part) {
+                    Some(Script::Latin) => Script::Cyrillic,
+                    _ => Script::Latin,
+                };
+                result.push(MixedWord {
+                    word: part.to_string(),
+                    offset,
+                    line,
+                    column,
+                    stray: (0..scripts.len())
+                        .filter(|&i| scripts[i] == Some(minority))
+                        .collect(),
+                });
+            }
+        }
+        for c in part.chars() {
+            if c == '\n' {
+                line += 1;
+                column = 1;
+            } else {
+                column += 1;
+            }
+        }
+        offset += part.len();
+    }
+    result
+}
+
+/// Проналази речи текста за које `matches` враћа `true`. Уз сваку реч враћа
+/// ред и колону у карактерима у којој почиње (од 1).
+pub fn find_words(input: &str, matches: impl Fn(&str) -> bool) -> Vec<(usize, usize, &str)> {
+    let mut result = Vec::new();
+    let (mut line, mut column) = (1, 1);
+    for (part, is_word) in split_words(input) {
+        if is_word && matches(part) {
+            result.push((line, column, part));
+        }
+        for c in part.chars() {
+            if c == '\n' {
+                line += 1;
+                column = 1;
+            } else {
+                column += 1;
+            }
+        }
+    }
+    result
+}
+
+/// Дели текст на речи (низове слова) и делове између њих. Уз сваки део враћа
+/// да ли је у питању реч.
+pub(crate) fn split_words(input: &str) -> impl Iterator<Item = (&str, bool)> {
+    let mut rest = input;
+    std::iter::from_fn(move || {
+        let first = rest.chars().next()?;
+        let is_word = first.is_alphabetic();
+        let len = rest
+            .find(|c: char| c.is_alphabetic() != is_word)
+            .unwrap_or(rest.len());
+        let (part, tail) = rest.split_at(len);
+        rest = tail;
+        Some((part, is_word))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dominant() {
+        assert_eq!(Some(Script::Latin), Script::dominant("Stiže novi iPhone"));
+        assert_eq!(
+            Some(Script::Cyrillic),
+            Script::dominant("Стиже нови iPhone телефон")
+        );
+        assert_eq!(None, Script::dominant("12:30, ok ок"));
+        assert_eq!(None, Script::dominant(""));
+    }
+
+    #[test]
+    fn test_detect() {
+        let report = detect("Ово је ћирилица.\nНови iPhone.\n\nKraj.");
+        assert_eq!(10, report.latin);
+        assert_eq!(17, report.cyrillic);
+        assert_eq!(vec![(2, "Нови iPhone.")], report.mixed_lines);
+        assert_eq!(DetectedScript::Mixed, report.script());
+        assert_eq!(
+            DetectedScript::Cyrillic,
+            detect(
+                "Нови iPhone телефон стиже у продавнице широм целе наше земље и читавог региона"
+            )
+            .script()
+        );
+        assert_eq!(DetectedScript::Latin, detect("Latinica").script());
+        assert_eq!(DetectedScript::Unknown, detect("123").script());
+        assert_eq!(100.0, detect("Latinica").latin_share());
+    }
+
+    #[test]
+    fn test_normalize_homoglyphs() {
+        // Латинична `a` и `o` у ћириличним речима
+        assert_eq!("мама и тата воле", normalize_homoglyphs("мaма и тaта вoле"));
+        // Ћирилична `е` и `о` у латиничним речима
+        assert_eq!("Hello world", normalize_homoglyphs("Hеllо world"));
+        // Речи без преовлађујућег писма се не мењају
+        assert_eq!("аb", normalize_homoglyphs("аb"));
+        assert_eq!("Čiča, Чича!", normalize_homoglyphs("Čiča, Чича!"));
+    }
+
+    #[test]
+    fn test_mixed_script_words() {
+        let mixed = mixed_script_words("Чиста реч.\nПрљaва рeч и Pаypal");
+        assert_eq!(
+            vec![
+                MixedWord {
+                    word: "Прљaва".into(),
+                    offset: 19,
+                    line: 2,
+                    column: 1,
+                    stray: vec![3],
+                },
+                MixedWord {
+                    word: "рeч".into(),
+                    offset: 31,
+                    line: 2,
+                    column: 8,
+                    stray: vec![1],
+                },
+                MixedWord {
+                    word: "Pаypal".into(),
+                    offset: 40,
+                    line: 2,
+                    column: 14,
+                    stray: vec![1],
+                },
+            ],
+            mixed
+        );
+    }
+
+    #[test]
+    fn test_find_words() {
+        assert_eq!(
+            vec![(1, 1, "Wи"), (2, 7, "Wи")],
+            find_words("Wи-Фи\nмрежа Wи-Фи", |word| word.contains('W'))
+        );
+    }
+}
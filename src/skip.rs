@@ -0,0 +1,793 @@
+//! Делови текста који се не пресловљавају (веб адресе, LaTeX команде...).
+use std::collections::HashSet;
+use std::fmt;
+use std::str::FromStr;
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use crate::Error;
+
+// Регуларни изрази за делове текста који не би смели да се пресловљавају.
+// Напомена: сваки израз започети са ^ јер желимо подударање на текућој локацији
+static WEB: Lazy<Vec<Regex>> = Lazy::new(|| {
+    vec![
+        Regex::new(
+            r"^(https?://)?[-a-zA-Z0-9@:%._\+~#=]{1,256}\.[a-zA-Z0-9()]{1,6}\b([-a-zA-Z0-9()@:%_\+.~#?&//=]*)",
+        )
+        .unwrap(),
+    ]
+});
+
+static EMAIL: Lazy<Vec<Regex>> =
+    Lazy::new(|| vec![Regex::new(r"^[a-zA-Z0-9._%+-]+@[a-zA-Z0-9.-]+\.[a-zA-Z]{2,}").unwrap()]);
+
+static PUBLICATION: Lazy<Vec<Regex>> = Lazy::new(|| {
+    vec![
+        // DOI (`10.1007/978-3-540-24777-7_1`, `doi:10.1000/182`)
+        Regex::new(
+            r"^((?i:doi):\s?|https?://(dx\.)?doi\.org/)?10\.\d{4,9}/[-._;()/:a-zA-Z0-9]*[a-zA-Z0-9]",
+        )
+        .unwrap(),
+        // ISBN-10 и ISBN-13
+        Regex::new(r"^ISBN(-1[03])?:?\s?(97[89][-\s]?)?(\d[-\s]?){9}[\dXx]\b").unwrap(),
+        // arXiv (`arXiv:2101.00001v2`, `arXiv:math.GT/0309136`)
+        Regex::new(r"^(?i:arxiv):\s?(\d{4}\.\d{4,5}|[a-z-]+(\.[A-Z]{2})?/\d{7})(v\d+)?").unwrap(),
+    ]
+});
+
+static PATH: Lazy<Vec<Regex>> = Lazy::new(|| {
+    vec![
+        // Unix путање (апсолутне, релативне у односу на `~`, `.` или `..`)
+        Regex::new(r"^(~|\.\.?)?(/[\w.~+@-]*[\w~+@-])+/?").unwrap(),
+        // Windows путање (`C:\Users`) и мрежне путање (`\\server\share`)
+        Regex::new(r"^([a-zA-Z]:|\\\\[\w.-]+)(\\[\w.~+@$-]+)+\\?").unwrap(),
+    ]
+});
+
+static IP_ADDRESS: Lazy<Vec<Regex>> = Lazy::new(|| {
+    let h = "[0-9a-fA-F]{1,4}";
+    vec![
+        // IPv4 са опционом маском мреже
+        Regex::new(r"^((25[0-5]|2[0-4]\d|1?\d?\d)\.){3}(25[0-5]|2[0-4]\d|1?\d?\d)(/\d{1,2})?\b")
+            .unwrap(),
+        // IPv6, пун и скраћени облик (`fe80::1`)
+        Regex::new(&format!(r"^({h}:){{7}}{h}\b")).unwrap(),
+        Regex::new(&format!(r"^({h}(:{h})*)?::{h}(:{h})*\b")).unwrap(),
+        Regex::new(&format!(r"^{h}(:{h})*::")).unwrap(),
+    ]
+});
+
+static HEX: Lazy<Vec<Regex>> = Lazy::new(|| {
+    vec![
+        // UUID
+        Regex::new(r"^[0-9a-fA-F]{8}(-[0-9a-fA-F]{4}){3}-[0-9a-fA-F]{12}\b").unwrap(),
+        // Хексадецимални бројеви и хешеви (git commit, контролне суме)
+        Regex::new(r"^0[xX][0-9a-fA-F]+\b").unwrap(),
+        Regex::new(r"^[0-9a-fA-F]{7,}\b").unwrap(),
+    ]
+});
+
+static HASHTAG: Lazy<Vec<Regex>> = Lazy::new(|| vec![Regex::new(r"^#\w+").unwrap()]);
+
+static MENTION: Lazy<Vec<Regex>> = Lazy::new(|| vec![Regex::new(r"^@\w+").unwrap()]);
+
+static EMOJI: Lazy<Vec<Regex>> = Lazy::new(|| vec![Regex::new(r"^:[a-z0-9_+-]+:").unwrap()]);
+
+static ROMAN_NUMERAL: Lazy<Vec<Regex>> = Lazy::new(|| {
+    vec![Regex::new(r"^M{0,3}(CM|CD|D?C{0,3})(XC|XL|L?X{0,3})(IX|IV|V?I{0,3})\b").unwrap()]
+});
+
+// Број испред мерне јединице (`5`, `3,5`, `1.000`)
+static NUMBER: Lazy<Regex> = Lazy::new(|| Regex::new(r"^\d+([.,]\d+)*[ \u{a0}]?").unwrap());
+
+static CODE: Lazy<Vec<Regex>> = Lazy::new(|| {
+    vec![
+        // camelCase и PascalCase
+        Regex::new(r"^[a-z]+[A-Z][a-zA-Z0-9]*\b").unwrap(),
+        Regex::new(r"^[A-Z][a-z0-9]+[A-Z][a-zA-Z0-9]*\b").unwrap(),
+        // snake_case и SCREAMING_CASE
+        Regex::new(r"^_*[a-zA-Z][a-zA-Z0-9]*(_+[a-zA-Z0-9]+)+_*\b").unwrap(),
+        // Идентификатори који почињу доњом цртом (`_private`, `__init__`)
+        Regex::new(r"^_+[a-zA-Z][a-zA-Z0-9_]*\b").unwrap(),
+    ]
+});
+
+static TEMPLATE: Lazy<Vec<Regex>> = Lazy::new(|| {
+    vec![
+        // Jinja, Django, Handlebars (`{{ name }}`, `{% if x %}`)
+        Regex::new(r"^(?s)\{\{.*?\}\}").unwrap(),
+        Regex::new(r"^(?s)\{%.*?%\}").unwrap(),
+        // printf и Python ознаке (`%s`, `%1$s`, `%.2f`, `%(name)s`)
+        Regex::new(r"^%(\d+\$)?[-+#0]*\d*(\.\d+)?(hh|ll|[hlL])?[sdiufFxXoeEgGc@]").unwrap(),
+        Regex::new(r"^%\([^)\s]+\)[sdrfi]").unwrap(),
+        // .NET, Java MessageFormat и Python (`{0}`, `{1:N2}`, `{}`)
+        Regex::new(r"^\{\d*(:[^{}\s]*)?\}").unwrap(),
+        // Променљиве шаблона (`${var}`)
+        Regex::new(r"^\$\{[^{}]*\}").unwrap(),
+    ]
+});
+
+// HTML и XML ентитети (`&amp;`, `&scaron;`, `&#353;`, `&#x161;`)
+static ENTITY: Lazy<Vec<Regex>> =
+    Lazy::new(|| vec![Regex::new(r"^&(#[0-9]+|#[xX][0-9a-fA-F]+|[a-zA-Z][a-zA-Z0-9]*);").unwrap()]);
+
+static LATEX: Lazy<Vec<Regex>> = Lazy::new(|| {
+    vec![
+        Regex::new(r"^\\begin\{\w+\}").unwrap(),
+        Regex::new(r"^\\end\{\w+\}").unwrap(),
+        Regex::new(r"^\\\w+").unwrap(),
+        Regex::new(r"^\$[^$]*\$").unwrap(),
+    ]
+});
+
+/// Категорија делова текста који се прескачу при конверзији.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SkipCategory {
+    /// Речи и изрази задати за текућу конверзију (нпр. називи производа).
+    /// Погледати [`crate::Converter::set_skip_words`].
+    Word,
+    /// Делови текста који одговарају регуларним изразима задатим за текућу
+    /// конверзију. Погледати [`crate::Converter::add_skip_pattern`].
+    Pattern,
+    /// Ознаке за замену у шаблонима и преводима (`{{ name }}`, `{% if %}`,
+    /// `%s`, `%1$s`, `{0}`, `${var}`).
+    Template,
+    /// Референце на HTML и XML ентитете (`&amp;`, `&scaron;`, `&#353;`).
+    Entity,
+    /// Идентификатори публикација (DOI, ISBN, arXiv).
+    Publication,
+    /// Веб адресе.
+    Web,
+    /// Адресе електронске поште.
+    Email,
+    /// Путање фајлова (`/usr/local/bin`, `C:\Users`).
+    Path,
+    /// IPv4 и IPv6 адресе.
+    IpAddress,
+    /// UUID-ови и дужи хексадецимални низови (хешеви, контролне суме).
+    Hex,
+    /// Хештагови.
+    Hashtag,
+    /// Помињања корисника (`@igordejanovic`).
+    Mention,
+    /// Кратки кодови емоџија (`:smile:`).
+    Emoji,
+    /// Римски бројеви (`XIX век`, `Карло V`).
+    RomanNumeral,
+    /// Бројеви са мерним јединицама (`5 kWh`, `100 km/h`). Погледати
+    /// [`DEFAULT_UNITS`].
+    Unit,
+    /// LaTeX команде и формуле.
+    Latex,
+    /// Идентификатори из програмског кода (`getUserName`, `parse_tree`,
+    /// `MAX_SIZE`). Хеуристика која подразумевано није укључена.
+    Code,
+}
+
+impl SkipCategory {
+    /// Све категорије редом којим се проверавају.
+    pub const ALL: &'static [SkipCategory] = &[
+        SkipCategory::Word,
+        SkipCategory::Pattern,
+        SkipCategory::Template,
+        SkipCategory::Entity,
+        SkipCategory::Publication,
+        SkipCategory::Web,
+        SkipCategory::Email,
+        SkipCategory::Path,
+        SkipCategory::IpAddress,
+        SkipCategory::Hex,
+        SkipCategory::Hashtag,
+        SkipCategory::Mention,
+        SkipCategory::Emoji,
+        SkipCategory::RomanNumeral,
+        SkipCategory::Unit,
+        SkipCategory::Latex,
+        SkipCategory::Code,
+    ];
+
+    /// Назив категорије (нпр. `путање`).
+    pub fn name(self) -> &'static str {
+        match self {
+            SkipCategory::Word => "речи",
+            SkipCategory::Pattern => "шаблони",
+            SkipCategory::Template => "замене",
+            SkipCategory::Entity => "ентитети",
+            SkipCategory::Publication => "публикације",
+            SkipCategory::Web => "веб",
+            SkipCategory::Email => "пошта",
+            SkipCategory::Path => "путање",
+            SkipCategory::IpAddress => "ип-адресе",
+            SkipCategory::Hex => "хешеви",
+            SkipCategory::Hashtag => "хештагови",
+            SkipCategory::Mention => "помињања",
+            SkipCategory::Emoji => "емоџи",
+            SkipCategory::RomanNumeral => "римски-бројеви",
+            SkipCategory::Unit => "јединице",
+            SkipCategory::Latex => "латех",
+            SkipCategory::Code => "код",
+        }
+    }
+
+    fn patterns(self) -> &'static [Regex] {
+        match self {
+            // Речи, шаблони и јединице се задају у правилима за прескакање
+            SkipCategory::Word | SkipCategory::Pattern | SkipCategory::Unit => &[],
+            SkipCategory::Template => &TEMPLATE,
+            SkipCategory::Entity => &ENTITY,
+            SkipCategory::Publication => &PUBLICATION,
+            SkipCategory::Web => &WEB,
+            SkipCategory::Email => &EMAIL,
+            SkipCategory::Path => &PATH,
+            SkipCategory::IpAddress => &IP_ADDRESS,
+            SkipCategory::Hex => &HEX,
+            SkipCategory::Hashtag => &HASHTAG,
+            SkipCategory::Mention => &MENTION,
+            SkipCategory::Emoji => &EMOJI,
+            SkipCategory::RomanNumeral => &ROMAN_NUMERAL,
+            SkipCategory::Latex => &LATEX,
+            SkipCategory::Code => &CODE,
+        }
+    }
+
+    /// Да ли се категорија препознаје само на почетку речи (нпр. путања не
+    /// сме да почне у средини израза `ћирилица/латиница`).
+    fn word_start_only(self) -> bool {
+        matches!(
+            self,
+            SkipCategory::Word
+                | SkipCategory::Pattern
+                | SkipCategory::Publication
+                | SkipCategory::Path
+                | SkipCategory::IpAddress
+                | SkipCategory::Hex
+                | SkipCategory::Mention
+                | SkipCategory::RomanNumeral
+                | SkipCategory::Unit
+                | SkipCategory::Code
+        )
+    }
+
+    /// Дужина у бајтовима дела текста ове категорије који почиње на бајту
+    /// `pos`.
+    fn find(self, text: &str, pos: usize) -> Option<usize> {
+        let len = self
+            .patterns()
+            .iter()
+            .find_map(|re| re.find(&text[pos..]))
+            .map(|m| m.end())?;
+        match self {
+            SkipCategory::RomanNumeral => {
+                // У тексту писаном великим словима речи као што су `MI` и `VI`
+                // нису римски бројеви
+                let numeral = &text[pos..pos + len];
+                let prev_word = text[..pos]
+                    .trim_end_matches(|c: char| !c.is_alphabetic())
+                    .rsplit(|c: char| !c.is_alphabetic())
+                    .next()
+                    .unwrap_or_default();
+                let next_word = text[pos + len..]
+                    .trim_start_matches(|c: char| !c.is_alphabetic())
+                    .split(|c: char| !c.is_alphabetic())
+                    .next()
+                    .unwrap_or_default();
+                let upper_case_text =
+                    is_upper_case_word(prev_word) || is_upper_case_word(next_word);
+                let after_name = text[..pos]
+                    .strip_suffix(' ')
+                    .is_some_and(|before| before.ends_with(prev_word))
+                    && is_capitalized_word(prev_word);
+                let before_century = ["vek", "век"]
+                    .iter()
+                    .any(|century| next_word.to_lowercase().starts_with(century));
+                let after = &text[pos + len..];
+                let valid = match numeral {
+                    "" => false,
+                    // Самостално слово је најчешће иницијал (`M. Ilić`),
+                    // ознака (`vitamin C`, `Grupa D`) или везник `I`, па се
+                    // сматра римским бројем само иза имена (`Karlo V`) или
+                    // испред речи „век” (`X vek`)
+                    "V" | "X" => !after.starts_with('.') && (after_name || before_century),
+                    // Бројеви који су уједно и речи (`VI`, `MI`) се сматрају
+                    // римским бројем и када иза њих следи тачка редног броја
+                    // (`VI. razred`)
+                    numeral if WORD_NUMERALS.contains(&numeral) => {
+                        let ordinal = after.strip_prefix(". ").is_some_and(|rest| {
+                            rest.starts_with(|c: char| c.is_lowercase() || c.is_ascii_digit())
+                        });
+                        after_name || before_century || ordinal
+                    }
+                    _ => numeral.len() > 1,
+                };
+                (valid && !upper_case_text).then_some(len)
+            }
+            _ => Some(len),
+        }
+    }
+
+    /// Да ли је категорија подразумевано укључена.
+    pub fn enabled_by_default(self) -> bool {
+        self != SkipCategory::Code
+    }
+}
+
+/// Римски бројеви који су уједно и речи или скраћенице.
+const WORD_NUMERALS: &[&str] = &["VI", "MI", "DI", "LI", "CV", "CD", "MIX", "DIV"];
+
+/// Да ли је реч од више слова писана великим почетним словом (`Karlo`).
+fn is_capitalized_word(word: &str) -> bool {
+    let mut chars = word.chars();
+    chars.next().is_some_and(char::is_uppercase)
+        && word.chars().count() > 1
+        && chars.all(char::is_lowercase)
+}
+
+/// Да ли је реч од више слова писана великим словима, а није римски број.
+fn is_upper_case_word(word: &str) -> bool {
+    word.chars().count() > 1
+        && word.chars().all(char::is_uppercase)
+        && ROMAN_NUMERAL[0]
+            .find(word)
+            .is_none_or(|m| m.len() != word.len())
+}
+
+impl fmt::Display for SkipCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for SkipCategory {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        SkipCategory::ALL
+            .iter()
+            .find(|category| category.name() == s)
+            .copied()
+            .ok_or_else(|| Error::UnknownSkipCategory(s.to_string()))
+    }
+}
+
+/// Подразумевани маркери у тексту који искључују, односно поново укључују
+/// конверзију.
+pub const DEFAULT_MARKERS: (&str, &str) = ("<!-- ћирко:искључи -->", "<!-- ћирко:укључи -->");
+
+/// Подразумевана листа мерних јединица које после броја остају на латиници.
+/// Јединице од једног слова (`s`, `g`, `h`, `m`...) нису у листи јер се
+/// поклапају са предлозима и скраћеницама (`3 s porukom`, `1990 g.`).
+pub const DEFAULT_UNITS: &[&str] = &[
+    "mm", "cm", "dm", "km", "mm²", "cm²", "m²", "km²", "cm³", "m³", "ha", "mg", "kg", "ml", "dl",
+    "ms", "min", "km/h", "m/s", "W", "kW", "MW", "GW", "Wh", "kWh", "MWh", "kV", "mV", "mA", "Hz",
+    "kHz", "MHz", "GHz", "kB", "KB", "MB", "GB", "TB", "bit", "kb/s", "Mb/s", "Gb/s", "°C", "°F",
+    "Pa", "kPa", "hPa", "bar", "kJ", "kcal", "dB", "mol", "px", "pt", "rpm",
+];
+
+/// Правила за прескакање делова текста: скуп укључених категорија, маркери
+/// за искључивање конверзије и листа мерних јединица. Подразумевано су укључене категорије за које
+/// [`SkipCategory::enabled_by_default`] враћа `true`.
+#[derive(Debug, Clone)]
+pub(crate) struct SkipRules {
+    categories: HashSet<SkipCategory>,
+    markers: Option<(String, String)>,
+    // Сортиране од најдуже
+    units: Vec<String>,
+    // Сортиране од најдуже
+    words: Vec<String>,
+    patterns: Vec<Regex>,
+}
+
+impl Default for SkipRules {
+    fn default() -> Self {
+        let (off, on) = DEFAULT_MARKERS;
+        SkipRules {
+            categories: SkipCategory::ALL
+                .iter()
+                .copied()
+                .filter(|category| category.enabled_by_default())
+                .collect(),
+            markers: Some((off.to_string(), on.to_string())),
+            units: Vec::new(),
+            words: Vec::new(),
+            patterns: Vec::new(),
+        }
+        .with_units(DEFAULT_UNITS)
+    }
+}
+
+// Подразумевана правила за слободне функције
+pub(crate) static DEFAULT_SKIP_RULES: Lazy<SkipRules> = Lazy::new(SkipRules::default);
+
+impl SkipRules {
+    pub(crate) fn set(&mut self, category: SkipCategory, enabled: bool) {
+        if enabled {
+            self.categories.insert(category);
+        } else {
+            self.categories.remove(&category);
+        }
+    }
+
+    fn with_units<S: AsRef<str>>(mut self, units: &[S]) -> Self {
+        self.set_units(units);
+        self
+    }
+
+    pub(crate) fn set_units<S: AsRef<str>>(&mut self, units: &[S]) {
+        self.units = units.iter().map(|unit| unit.as_ref().to_string()).collect();
+        self.units.sort_by_key(|unit| std::cmp::Reverse(unit.len()));
+    }
+
+    /// Број са мерном јединицом на почетку текста.
+    fn find_unit(&self, input: &str) -> Option<usize> {
+        let number = NUMBER.find(input)?.end();
+        let rest = &input[number..];
+        self.units
+            .iter()
+            .find(|unit| {
+                rest.starts_with(unit.as_str())
+                    && !rest[unit.len()..].starts_with(char::is_alphanumeric)
+            })
+            .map(|unit| number + unit.len())
+    }
+
+    pub(crate) fn set_words<S: AsRef<str>>(&mut self, words: &[S]) {
+        self.words = words
+            .iter()
+            .map(|word| word.as_ref().to_string())
+            .filter(|word| !word.is_empty())
+            .collect();
+        self.words.sort_by_key(|word| std::cmp::Reverse(word.len()));
+    }
+
+    /// Задата реч или израз на почетку текста.
+    fn find_word(&self, input: &str) -> Option<usize> {
+        self.words
+            .iter()
+            .find(|word| {
+                input.starts_with(word.as_str())
+                    && !input[word.len()..].starts_with(char::is_alphanumeric)
+            })
+            .map(String::len)
+    }
+
+    /// Додаје регуларни израз за делове текста који се прескачу.
+    pub(crate) fn add_pattern(&mut self, pattern: &str) -> Result<(), Error> {
+        let regex = Regex::new(&format!("^(?:{pattern})"))
+            .map_err(|_| Error::InvalidPattern(pattern.to_string()))?;
+        self.patterns.push(regex);
+        Ok(())
+    }
+
+    /// Најдужи непразан део текста на почетку улаза који одговара неком од
+    /// задатих регуларних израза.
+    fn find_pattern(&self, input: &str) -> Option<usize> {
+        self.patterns
+            .iter()
+            .filter_map(|re| re.find(input))
+            .map(|m| m.end())
+            .filter(|&len| len > 0)
+            .max()
+    }
+
+    pub(crate) fn set_markers(&mut self, markers: Option<(&str, &str)>) {
+        self.markers = markers.map(|(off, on)| (off.to_string(), on.to_string()));
+    }
+
+    pub(crate) fn markers(&self) -> Option<(&str, &str)> {
+        self.markers
+            .as_ref()
+            .map(|(off, on)| (off.as_str(), on.as_str()))
+    }
+
+    /// Користи маркере и листу регуларних израза укључених категорија за
+    /// детекцију делова текста који се не обрађују, почевши од бајта `pos`.
+    /// Враћа дужину у бајтовима ако је такав сегмент пронађен.
+    pub(crate) fn find_match(&self, text: &str, pos: usize) -> Option<usize> {
+        self.find_segment(text, pos).map(|(len, _)| len)
+    }
+
+    /// Као [`SkipRules::find_match`], уз категорију пронађеног сегмента
+    /// (`None` за део текста искључен маркерима).
+    pub(crate) fn find_segment(
+        &self,
+        text: &str,
+        pos: usize,
+    ) -> Option<(usize, Option<SkipCategory>)> {
+        let input = &text[pos..];
+        if let Some((off, on)) = &self.markers
+            && input.starts_with(off.as_str())
+        {
+            // Прескаче се све до маркера за укључивање (заједно са њим) или до
+            // краја текста
+            return Some((
+                input[off.len()..]
+                    .find(on.as_str())
+                    .map_or(input.len(), |end| off.len() + end + on.len()),
+                None,
+            ));
+        }
+        let word_start = text[..pos]
+            .chars()
+            .next_back()
+            .is_none_or(|c| !c.is_alphanumeric());
+        SkipCategory::ALL
+            .iter()
+            .filter(|category| self.categories.contains(category))
+            .filter(|category| word_start || !category.word_start_only())
+            .find_map(|category| {
+                let len = match category {
+                    SkipCategory::Word => self.find_word(input),
+                    SkipCategory::Pattern => self.find_pattern(input),
+                    SkipCategory::Unit => self.find_unit(input),
+                    _ => category.find(text, pos),
+                }?;
+                Some((len, Some(*category)))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn skipped<'a>(rules: &SkipRules, text: &'a str, pos: usize) -> Option<&'a str> {
+        rules.find_match(text, pos).map(|len| &text[pos..pos + len])
+    }
+
+    #[test]
+    fn test_paths() {
+        let rules = SkipRules::default();
+        assert_eq!(
+            Some("/usr/local/bin"),
+            skipped(&rules, "/usr/local/bin и", 0)
+        );
+        assert_eq!(
+            Some("~/.config/cirko/"),
+            skipped(&rules, "~/.config/cirko/ и", 0)
+        );
+        assert_eq!(
+            Some("../src/main.rs"),
+            skipped(&rules, "../src/main.rs.", 0)
+        );
+        assert_eq!(
+            Some(r"C:\Users\Igor"),
+            skipped(&rules, r"C:\Users\Igor и", 0)
+        );
+        assert_eq!(
+            Some(r"\\server\share\"),
+            skipped(&rules, r"\\server\share\ и", 0)
+        );
+        // Коса црта унутар речи није путања
+        assert_eq!(None, skipped(&rules, "ћирилица/латиница", 16));
+    }
+
+    #[test]
+    fn test_addresses_and_hashes() {
+        let rules = SkipRules::default();
+        assert_eq!(Some("192.168.0.1"), skipped(&rules, "192.168.0.1, ", 0));
+        assert_eq!(Some("10.0.0.0/8"), skipped(&rules, "10.0.0.0/8 ", 0));
+        assert_eq!(
+            Some("fe80::1ff:fe23:4567:890a"),
+            skipped(&rules, "fe80::1ff:fe23:4567:890a и", 0)
+        );
+        assert_eq!(
+            Some("2001:0db8:85a3:0000:0000:8a2e:0370:7334"),
+            skipped(&rules, "2001:0db8:85a3:0000:0000:8a2e:0370:7334", 0)
+        );
+        assert_eq!(Some("::1"), skipped(&rules, "::1", 0));
+        assert_eq!(
+            Some("123e4567-e89b-12d3-a456-426614174000"),
+            skipped(&rules, "123e4567-e89b-12d3-a456-426614174000.", 0)
+        );
+        assert_eq!(Some("a6d0d33"), skipped(&rules, "a6d0d33 ", 0));
+        assert_eq!(Some("0xff"), skipped(&rules, "0xff", 0));
+        // Обичне речи и хексадецималне цифре унутар речи
+        assert_eq!(None, skipped(&rules, "deca", 0));
+        assert_eq!(None, skipped(&rules, "std::io", 2));
+    }
+
+    #[test]
+    fn test_mentions() {
+        let mut rules = SkipRules::default();
+        assert_eq!(
+            Some("@igordejanovic"),
+            skipped(&rules, "@igordejanovic, ", 0)
+        );
+        // Део адресе електронске поште није помињање
+        rules.set(SkipCategory::Email, false);
+        rules.set(SkipCategory::Web, false);
+        assert_eq!(None, skipped(&rules, "ime@primer.rs", 3));
+    }
+
+    #[test]
+    fn test_emoji_shortcodes() {
+        let rules = SkipRules::default();
+        assert_eq!(Some(":smile:"), skipped(&rules, ":smile: и", 0));
+        assert_eq!(Some(":+1:"), skipped(&rules, ":+1:", 0));
+        assert_eq!(None, skipped(&rules, ": smile:", 0));
+    }
+
+    #[test]
+    fn test_publications() {
+        let mut rules = SkipRules::default();
+        // Веб адресе прекривају део идентификатора
+        rules.set(SkipCategory::Web, false);
+        assert_eq!(
+            Some("10.1007/978-3-540-24777-7_1"),
+            skipped(&rules, "10.1007/978-3-540-24777-7_1).", 0)
+        );
+        assert_eq!(
+            Some("doi:10.1000/182"),
+            skipped(&rules, "doi:10.1000/182 ", 0)
+        );
+        assert_eq!(
+            Some("ISBN 978-86-17-09811-2"),
+            skipped(&rules, "ISBN 978-86-17-09811-2, ", 0)
+        );
+        assert_eq!(
+            Some("ISBN-10: 0-306-40615-X"),
+            skipped(&rules, "ISBN-10: 0-306-40615-X", 0)
+        );
+        assert_eq!(
+            Some("arXiv:2101.00001v2"),
+            skipped(&rules, "arXiv:2101.00001v2.", 0)
+        );
+        assert_eq!(
+            Some("arXiv:math.GT/0309136"),
+            skipped(&rules, "arXiv:math.GT/0309136", 0)
+        );
+    }
+
+    #[test]
+    fn test_template_placeholders() {
+        let rules = SkipRules::default();
+        assert_eq!(
+            Some("{{ user.name }}"),
+            skipped(&rules, "Zdravo {{ user.name }}!", 7)
+        );
+        assert_eq!(
+            Some("{% if admin %}"),
+            skipped(&rules, "{% if admin %}Admin{% endif %}", 0)
+        );
+        assert_eq!(Some("%s"), skipped(&rules, "%s fajlova", 0));
+        assert_eq!(Some("%1$s"), skipped(&rules, "%1$s i %2$d", 0));
+        assert_eq!(Some("%.2f"), skipped(&rules, "%.2f dinara", 0));
+        assert_eq!(Some("%(ime)s"), skipped(&rules, "%(ime)s je", 0));
+        assert_eq!(Some("{0}"), skipped(&rules, "Fajl {0} je", 5));
+        assert_eq!(Some("{1:N2}"), skipped(&rules, "{1:N2} din", 0));
+        assert_eq!(Some("${ime}"), skipped(&rules, "${ime} je", 0));
+        // Ознака залепљена уз реч
+        assert_eq!(Some("{0}"), skipped(&rules, "Fajl{0}", 4));
+        // Проценти и заграде у обичном тексту
+        assert_eq!(None, skipped(&rules, "% dobro", 0));
+        assert_eq!(None, skipped(&rules, "{ime}", 0));
+    }
+
+    #[test]
+    fn test_entities() {
+        let rules = SkipRules::default();
+        assert_eq!(Some("&amp;"), skipped(&rules, "&amp; ", 0));
+        assert_eq!(Some("&scaron;"), skipped(&rules, "Pri&scaron;tina", 3));
+        assert_eq!(Some("&#353;"), skipped(&rules, "&#353;uma", 0));
+        assert_eq!(Some("&#x161;"), skipped(&rules, "&#x161;", 0));
+        assert_eq!(None, skipped(&rules, "& Co;", 0));
+    }
+
+    #[test]
+    fn test_code_identifiers() {
+        let mut rules = SkipRules::default();
+        assert_eq!(None, skipped(&rules, "getUserName", 0));
+        rules.set(SkipCategory::Code, true);
+        assert_eq!(Some("getUserName"), skipped(&rules, "getUserName()", 0));
+        assert_eq!(Some("HashMap"), skipped(&rules, "HashMap.", 0));
+        assert_eq!(Some("parse_tree"), skipped(&rules, "parse_tree ", 0));
+        assert_eq!(Some("MAX_SIZE"), skipped(&rules, "MAX_SIZE,", 0));
+        assert_eq!(Some("__init__"), skipped(&rules, "__init__", 0));
+        // Обичне речи
+        assert_eq!(None, skipped(&rules, "Beograd", 0));
+        assert_eq!(None, skipped(&rules, "NATO", 0));
+        assert_eq!(None, skipped(&rules, "reč", 0));
+    }
+
+    #[test]
+    fn test_markers() {
+        let mut rules = SkipRules::default();
+        let text = "a <!-- ћирко:искључи -->b<!-- ћирко:укључи --> c";
+        assert_eq!(
+            Some("<!-- ћирко:искључи -->b<!-- ћирко:укључи -->"),
+            skipped(&rules, text, 2)
+        );
+        rules.set_markers(Some(("% off", "% on")));
+        assert_eq!(Some("% off x % on"), skipped(&rules, "% off x % on y", 0));
+        // Без маркера за укључивање прескаче се до краја текста
+        assert_eq!(Some("% off\nx y"), skipped(&rules, "% off\nx y", 0));
+        assert_eq!(None, skipped(&rules, text, 2));
+        rules.set_markers(None);
+        assert_eq!(None, skipped(&rules, "% off x % on", 0));
+    }
+
+    #[test]
+    fn test_roman_numerals() {
+        let rules = SkipRules::default();
+        assert_eq!(Some("XIX"), skipped(&rules, "XIX vek", 0));
+        assert_eq!(Some("V"), skipped(&rules, "Karlo V je", 6));
+        assert_eq!(Some("X"), skipped(&rules, "u X veku", 2));
+        assert_eq!(Some("XIV"), skipped(&rules, "Luj XIV.", 4));
+        assert_eq!(Some("MCMXCIV"), skipped(&rules, "MCMXCIV", 0));
+        // Обичне речи
+        assert_eq!(None, skipped(&rules, "vi", 0));
+        assert_eq!(None, skipped(&rules, "I onda", 0));
+        assert_eq!(None, skipped(&rules, "DIM", 0));
+        assert_eq!(None, skipped(&rules, "Mi", 0));
+        // Иницијали и ознаке од једног слова
+        assert_eq!(None, skipped(&rules, "Tom Cruise, M. Ilić", 12));
+        assert_eq!(None, skipped(&rules, "Karlo V.", 6));
+        assert_eq!(None, skipped(&rules, "Vitamin C i vitamin D.", 8));
+        assert_eq!(None, skipped(&rules, "Vitamin C i vitamin D.", 20));
+        assert_eq!(None, skipped(&rules, "Grupa C", 6));
+        assert_eq!(None, skipped(&rules, "Grupa D", 6));
+        // Текст писан великим словима
+        assert_eq!(None, skipped(&rules, "MI SMO", 0));
+        assert_eq!(None, skipped(&rules, "ZNATE LI", 6));
+        assert_eq!(Some("XIX"), skipped(&rules, "XIX–XX", 0));
+    }
+
+    #[test]
+    fn test_units() {
+        let mut rules = SkipRules::default();
+        assert_eq!(Some("5 kWh"), skipped(&rules, "5 kWh.", 0));
+        assert_eq!(Some("100km/h"), skipped(&rules, "100km/h", 0));
+        assert_eq!(Some("3,5 mm²"), skipped(&rules, "3,5 mm², ", 0));
+        assert_eq!(None, skipped(&rules, "5 mačaka", 0));
+        assert_eq!(None, skipped(&rules, "5 kWhx", 0));
+        assert_eq!(None, skipped(&rules, "3 s porukom", 0));
+        assert_eq!(None, skipped(&rules, "1990 g. u 7 h", 0));
+        rules.set_units(&["din"]);
+        assert_eq!(Some("100 din"), skipped(&rules, "100 din", 0));
+        assert_eq!(None, skipped(&rules, "5 km", 0));
+    }
+
+    #[test]
+    fn test_words() {
+        let mut rules = SkipRules::default();
+        assert_eq!(None, skipped(&rules, "Cirko Pro", 0));
+        rules.set_words(&["Cirko", "Cirko Pro", ""]);
+        assert_eq!(Some("Cirko Pro"), skipped(&rules, "Cirko Pro 2", 0));
+        assert_eq!(Some("Cirko"), skipped(&rules, "Cirko, ", 0));
+        assert_eq!(None, skipped(&rules, "Cirkom", 0));
+        assert_eq!(None, skipped(&rules, "NoviCirko", 4));
+    }
+
+    #[test]
+    fn test_patterns() {
+        let mut rules = SkipRules::default();
+        rules.add_pattern(r"ID-\d+[A-Z]+").unwrap();
+        rules.add_pattern(r"ID-\d+").unwrap();
+        rules.add_pattern(r"x*").unwrap();
+        assert_eq!(Some("ID-42AB"), skipped(&rules, "ID-42AB je", 0));
+        assert_eq!(Some("ID-42"), skipped(&rules, "ID-42 je", 0));
+        assert_eq!(None, skipped(&rules, "sID-42AB", 1));
+        assert_eq!(None, skipped(&rules, "ne", 0));
+        assert!(matches!(
+            rules.add_pattern("ID-(\\d"),
+            Err(Error::InvalidPattern(_))
+        ));
+    }
+
+    #[test]
+    fn test_disabled_category() {
+        let mut rules = SkipRules::default();
+        rules.set(SkipCategory::Path, false);
+        assert_eq!(None, skipped(&rules, "/usr/local/bin", 0));
+        assert_eq!(Some("#tag"), skipped(&rules, "#tag", 0));
+    }
+
+    #[test]
+    fn test_category_names() {
+        for &category in SkipCategory::ALL {
+            assert_eq!(category, category.to_string().parse().unwrap());
+        }
+        assert!("непозната".parse::<SkipCategory>().is_err());
+    }
+}
@@ -0,0 +1,183 @@
+//! Статистика конверзије: број знакова, пресловљених знакова, примењених
+//! изузетака и прескочених делова текста по категоријама. Погледати
+//! [`crate::Converter::convert_with_stats`].
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::formats::json::json_string;
+use crate::skip::SkipCategory;
+
+/// Статистика конверзије једног или више текстова.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Stats {
+    /// Број знакова текста прослеђеног конверзији.
+    pub characters: usize,
+    /// Број знакова који су пресловљени.
+    pub converted: usize,
+    /// Број примењених изузетака.
+    pub exceptions: usize,
+    /// Број примена сваког од изузетака (малим словима).
+    pub applied_exceptions: HashMap<String, usize>,
+    /// Број делова текста искључених маркерима.
+    pub markers: usize,
+    /// Број појава речи резултата које и даље садрже слова писма са кога се
+    /// конвертује. Попуњава се само када је потребно, погледати
+    /// [`crate::Converter::unconverted_words`].
+    pub unconverted: HashMap<String, usize>,
+    /// Број прескочених делова текста по категоријама.
+    pub skipped: HashMap<SkipCategory, usize>,
+}
+
+impl Stats {
+    /// Додаје статистику `other` овој статистици.
+    pub fn add(&mut self, other: &Stats) {
+        self.characters += other.characters;
+        self.converted += other.converted;
+        self.exceptions += other.exceptions;
+        self.markers += other.markers;
+        for (exception, count) in &other.applied_exceptions {
+            *self
+                .applied_exceptions
+                .entry(exception.clone())
+                .or_default() += count;
+        }
+        for (word, count) in &other.unconverted {
+            *self.unconverted.entry(word.clone()).or_default() += count;
+        }
+        for (category, count) in &other.skipped {
+            *self.skipped.entry(*category).or_default() += count;
+        }
+    }
+
+    /// Бележи прескочени део текста. Са `None` део је искључен маркерима.
+    pub(crate) fn skip(&mut self, category: Option<SkipCategory>) {
+        match category {
+            Some(category) => *self.skipped.entry(category).or_default() += 1,
+            None => self.markers += 1,
+        }
+    }
+
+    /// Број прескочених делова по називу категорије, редом категорија
+    /// [`SkipCategory::ALL`] и са маркерима на крају.
+    fn skipped_counts(&self) -> Vec<(&'static str, usize)> {
+        SkipCategory::ALL
+            .iter()
+            .filter_map(|category| Some((category.name(), *self.skipped.get(category)?)))
+            .chain((self.markers > 0).then_some(("маркери", self.markers)))
+            .collect()
+    }
+
+    /// Статистика у облику JSON објекта.
+    pub fn to_json(&self) -> String {
+        let skipped = self
+            .skipped_counts()
+            .iter()
+            .map(|(name, count)| format!("{}:{count}", json_string(name)))
+            .collect::<Vec<_>>()
+            .join(",");
+        format!(
+            "{{\"characters\":{},\"converted\":{},\"exceptions\":{},\"skipped\":{{{skipped}}}}}",
+            self.characters, self.converted, self.exceptions
+        )
+    }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "знакова {}, пресловљено {}, изузетака {}, прескочено {}",
+            self.characters,
+            self.converted,
+            self.exceptions,
+            self.skipped.values().sum::<usize>() + self.markers
+        )?;
+        let skipped = self.skipped_counts();
+        if !skipped.is_empty() {
+            let skipped = skipped
+                .iter()
+                .map(|(name, count)| format!("{name}: {count}"))
+                .collect::<Vec<_>>()
+                .join(", ");
+            write!(f, " ({skipped})")?;
+        }
+        Ok(())
+    }
+}
+
+/// Извештај са статистиком по фајловима и, ако их има више, укупном
+/// статистиком.
+pub fn report(files: &[(String, Stats)]) -> String {
+    let mut output = files
+        .iter()
+        .map(|(name, stats)| format!("{name}: {stats}\n"))
+        .collect::<String>();
+    if files.len() > 1 {
+        output.push_str(&format!("укупно: {}\n", total(files)));
+    }
+    output
+}
+
+/// Извештај у JSON облику са статистиком по фајловима (`files`) и укупном
+/// статистиком (`total`).
+pub fn report_json(files: &[(String, Stats)]) -> String {
+    let total = total(files).to_json();
+    let files = files
+        .iter()
+        .map(|(name, stats)| {
+            let stats = stats.to_json();
+            format!("{{\"file\":{},{}", json_string(name), &stats[1..])
+        })
+        .collect::<Vec<_>>()
+        .join(",");
+    format!("{{\"files\":[{files}],\"total\":{total}}}\n")
+}
+
+fn total(files: &[(String, Stats)]) -> Stats {
+    let mut total = Stats::default();
+    for (_, stats) in files {
+        total.add(stats);
+    }
+    total
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report() {
+        let mut first = Stats {
+            characters: 10,
+            converted: 6,
+            exceptions: 1,
+            applied_exceptions: HashMap::from([("nadživ".to_string(), 1)]),
+            markers: 0,
+            unconverted: HashMap::new(),
+            skipped: HashMap::new(),
+        };
+        first.skip(Some(SkipCategory::Web));
+        let mut second = Stats::default();
+        second.skip(None);
+        let files = [
+            ("пост.md".to_string(), first),
+            ("\"b\".txt".to_string(), second),
+        ];
+        assert_eq!(
+            "пост.md: знакова 10, пресловљено 6, изузетака 1, прескочено 1 (веб: 1)
+\"b\".txt: знакова 0, пресловљено 0, изузетака 0, прескочено 1 (маркери: 1)
+укупно: знакова 10, пресловљено 6, изузетака 1, прескочено 2 (веб: 1, маркери: 1)
+",
+            report(&files)
+        );
+        assert_eq!(
+            concat!(
+                r#"{"files":[{"file":"пост.md","characters":10,"converted":6,"exceptions":1,"skipped":{"веб":1}},"#,
+                r#"{"file":"\"b\".txt","characters":0,"converted":0,"exceptions":0,"skipped":{"маркери":1}}],"#,
+                r#""total":{"characters":10,"converted":6,"exceptions":1,"skipped":{"веб":1,"маркери":1}}}"#,
+                "\n"
+            ),
+            report_json(&files)
+        );
+    }
+}
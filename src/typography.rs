@@ -0,0 +1,190 @@
+//! Типографске обраде које се опционо раде након конверзије.
+use phf::phf_set;
+
+use crate::skip::{DEFAULT_SKIP_RULES, SkipCategory, SkipRules};
+
+const NBSP: char = '\u{a0}';
+
+// Јединице испред којих се после броја ставља нераскидиви размак.
+static UNITS: phf::Set<&'static str> = phf_set! {
+    "mm", "cm", "dm", "m", "km", "m²", "m³", "km²", "ha",
+    "mg", "g", "kg", "t",
+    "ml", "dl", "l",
+    "ms", "s", "min", "h",
+    "%", "‰", "°", "°C",
+    "W", "kW", "MW", "Wh", "kWh", "MWh", "V", "kV", "A", "mA",
+    "Hz", "kHz", "MHz", "GHz",
+    "B", "kB", "KB", "MB", "GB", "TB",
+    "din", "RSD", "EUR", "€", "$",
+    "мм", "цм", "дм", "м", "км", "м²", "м³", "км²", "ха",
+    "мг", "г", "кг", "т",
+    "мл", "дл", "л",
+    "мс", "с", "мин", "ч",
+    "дин",
+};
+
+/// Стил типографских наводника.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuoteStyle {
+    /// „Наводници” (унутрашњи ‚полунаводници’).
+    Low,
+    /// »Наводници« (унутрашњи ›полунаводници‹).
+    Angle,
+}
+
+impl QuoteStyle {
+    /// Отварајући и затварајући наводници и полунаводници.
+    fn marks(self) -> (char, char, char, char) {
+        match self {
+            QuoteStyle::Low => ('„', '”', '‚', '’'),
+            QuoteStyle::Angle => ('»', '«', '›', '‹'),
+        }
+    }
+}
+
+/// Замењује праве наводнике (`"` и `'`) српским типографским наводницима.
+/// Апострофи унутар речи (`Mar'jana`) се не мењају, као ни делови текста који
+/// се прескачу при конверзији (веб адресе, LaTeX...).
+pub fn normalize_quotes(input: &str, style: QuoteStyle) -> String {
+    normalize_quotes_with(input, style, &DEFAULT_SKIP_RULES)
+}
+
+pub(crate) fn normalize_quotes_with(input: &str, style: QuoteStyle, skip: &SkipRules) -> String {
+    let (open, close, open_single, close_single) = style.marks();
+    let mut output = String::with_capacity(input.len() + input.len() / 8);
+    let mut prev: Option<char> = None;
+    let mut pos = 0;
+
+    while let Some(c) = input[pos..].chars().next() {
+        if let Some(skip_bytes) = skip.find_match(input, pos) {
+            let skipped = &input[pos..pos + skip_bytes];
+            output.push_str(skipped);
+            prev = skipped.chars().last();
+            pos += skip_bytes;
+            continue;
+        }
+        let next = input[pos + c.len_utf8()..].chars().next();
+        let opening = prev.is_none_or(|p| p.is_whitespace() || "([{–—-".contains(p));
+        match c {
+            '"' if opening => output.push(open),
+            '"' => output.push(close),
+            '\'' if prev.is_some_and(char::is_alphanumeric)
+                && next.is_some_and(char::is_alphanumeric) =>
+            {
+                output.push(c)
+            }
+            '\'' if opening && next.is_some_and(|n| !n.is_whitespace()) => output.push(open_single),
+            '\'' if !opening => output.push(close_single),
+            _ => output.push(c),
+        }
+        prev = Some(c);
+        pos += c.len_utf8();
+    }
+    output
+}
+
+/// Типографска обрада текста: три тачке се замењују знаком `…`, `--` и `---`
+/// цртом (`–`) и изломљеном цртом (`—`), а цртица окружена размацима цртом.
+/// Између броја и мерне јединице, као и после иницијала, размак се замењује
+/// нераскидивим размаком. Делови текста који се прескачу при конверзији се не
+/// мењају.
+pub fn apply_typography(input: &str) -> String {
+    apply_typography_with(input, &DEFAULT_SKIP_RULES)
+}
+
+pub(crate) fn apply_typography_with(input: &str, skip: &SkipRules) -> String {
+    // Бројеви са јединицама се не прескачу јер се између њих ставља
+    // нераскидиви размак
+    let mut skip = skip.clone();
+    skip.set(SkipCategory::Unit, false);
+    let mut output = String::with_capacity(input.len());
+    let mut pos = 0;
+
+    while let Some(c) = input[pos..].chars().next() {
+        let rest = &input[pos..];
+        if let Some(skip_bytes) = skip.find_match(input, pos) {
+            output.push_str(&rest[..skip_bytes]);
+            pos += skip_bytes;
+            continue;
+        }
+        let prev = output.chars().last();
+        let (replacement, len) = if rest.starts_with("...") {
+            ('…', 3)
+        } else if rest.starts_with("---") {
+            ('—', 3)
+        } else if rest.starts_with("--") {
+            ('–', 2)
+        } else if c == '-'
+            && prev.is_some_and(char::is_whitespace)
+            && rest[1..].starts_with(char::is_whitespace)
+        {
+            ('–', 1)
+        } else if c == ' '
+            && (is_unit_after_number(&output, &rest[1..]) || is_after_initial(&output, &rest[1..]))
+        {
+            (NBSP, 1)
+        } else {
+            (c, c.len_utf8())
+        };
+        output.push(replacement);
+        pos += len;
+    }
+    output
+}
+
+/// Да ли се размак налази између броја и мерне јединице.
+fn is_unit_after_number(before: &str, after: &str) -> bool {
+    let unit_len = after
+        .find(|c: char| c.is_whitespace() || ",.;:!?)]".contains(c))
+        .unwrap_or(after.len());
+    before.ends_with(|c: char| c.is_ascii_digit()) && UNITS.contains(&after[..unit_len])
+}
+
+/// Да ли се размак налази после иницијала (нпр. `И. Андрић`).
+fn is_after_initial(before: &str, after: &str) -> bool {
+    let mut tail = before.chars().rev();
+    let initial = matches!(
+        (tail.next(), tail.next(), tail.next()),
+        (Some('.'), Some(letter), boundary)
+            if letter.is_uppercase() && boundary.is_none_or(|b| !b.is_alphanumeric())
+    );
+    initial && after.starts_with(char::is_uppercase)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_quotes() {
+        assert_eq!(
+            "Рекао је: „Видимо се ‚сутра’” и отишао.",
+            normalize_quotes("Рекао је: \"Видимо се 'сутра'\" и отишао.", QuoteStyle::Low)
+        );
+        assert_eq!(
+            "»Ћирко« (»алат«) и Mar'jana",
+            normalize_quotes("\"Ћирко\" (\"алат\") и Mar'jana", QuoteStyle::Angle)
+        );
+        // Наводници унутар делова који се прескачу се не мењају
+        assert_eq!(
+            "„x” $a=\"b\"$",
+            normalize_quotes("\"x\" $a=\"b\"$", QuoteStyle::Low)
+        );
+    }
+
+    #[test]
+    fn test_apply_typography() {
+        assert_eq!(
+            "Чекај… 1941–1945 — крај – почетак",
+            apply_typography("Чекај... 1941--1945 --- крај - почетак")
+        );
+        assert_eq!(
+            "Пут од 12\u{a0}km, 5\u{a0}кг и 20\u{a0}%. И.\u{a0}Андрић и Ivo A.\u{a0}Sever",
+            apply_typography("Пут од 12 km, 5 кг и 20 %. И. Андрић и Ivo A. Sever")
+        );
+        // Број испред речи која није јединица
+        assert_eq!("5 мачака", apply_typography("5 мачака"));
+        // Делови који се прескачу
+        assert_eq!("https://a.com/x--y", apply_typography("https://a.com/x--y"));
+    }
+}
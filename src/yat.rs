@@ -0,0 +1,366 @@
+//! Конверзија између екавице и ијекавице (`mleko <-> mlijeko`).
+//!
+//! Рефлекс јата се не може поуздано одредити само на основу правила па се
+//! користи хибридни приступ: речник основа са припадајућим парадигмама
+//! (правилима промене) из кога се генеришу сви облици речи, допуњен речником
+//! непроменљивих и неправилних облика.
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+use once_cell::sync::Lazy;
+use phf::phf_map;
+
+use crate::script::split_words;
+use crate::{cyr_to_lat, lat_to_cyr};
+
+/// Парадигма промене основе. Наставци су дати као парови (екавски, ијекавски).
+#[derive(Debug, Clone, Copy)]
+enum Paradigm {
+    /// Именице женског рода на `-а` (`pesma`).
+    FeminineA,
+    /// Именице женског рода на сугласник (`vest`).
+    FeminineI,
+    /// Именице мушког рода (`cvet`).
+    Masculine,
+    /// Именице средњег рода (`mleko`).
+    Neuter,
+    /// Придеви (`lep`).
+    Adjective,
+    /// Глаголи на `-ati` са јатом у корену (`pevati`).
+    VerbA,
+    /// Глаголи на `-eti` са кратким јатом у наставку (`videti -> vidjeti`).
+    VerbShort,
+    /// Глаголи на `-eti` са дугим јатом у наставку (`doneti -> donijeti`).
+    VerbLong,
+}
+
+impl Paradigm {
+    fn endings(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Paradigm::FeminineA => &[
+                ("a", "a"),
+                ("e", "e"),
+                ("i", "i"),
+                ("u", "u"),
+                ("o", "o"),
+                ("om", "om"),
+                ("ama", "ama"),
+            ],
+            Paradigm::FeminineI => &[("", ""), ("i", "i"), ("ju", "ju"), ("ima", "ima")],
+            Paradigm::Masculine => &[
+                ("", ""),
+                ("a", "a"),
+                ("u", "u"),
+                ("om", "om"),
+                ("e", "e"),
+                ("i", "i"),
+                ("ima", "ima"),
+                ("ovi", "ovi"),
+                ("ova", "ova"),
+                ("ove", "ove"),
+                ("ovima", "ovima"),
+            ],
+            Paradigm::Neuter => &[
+                ("o", "o"),
+                ("a", "a"),
+                ("u", "u"),
+                ("om", "om"),
+                ("ima", "ima"),
+            ],
+            Paradigm::Adjective => &[
+                ("", ""),
+                ("a", "a"),
+                ("o", "o"),
+                ("i", "i"),
+                ("e", "e"),
+                ("u", "u"),
+                ("og", "og"),
+                ("oga", "oga"),
+                ("om", "om"),
+                ("ome", "ome"),
+                ("omu", "omu"),
+                ("oj", "oj"),
+                ("ih", "ih"),
+                ("im", "im"),
+                ("ima", "ima"),
+            ],
+            Paradigm::VerbA => &[
+                ("ati", "ati"),
+                ("am", "am"),
+                ("aš", "aš"),
+                ("a", "a"),
+                ("amo", "amo"),
+                ("ate", "ate"),
+                ("aju", "aju"),
+                ("ao", "ao"),
+                ("ala", "ala"),
+                ("alo", "alo"),
+                ("ali", "ali"),
+                ("ale", "ale"),
+                ("ajući", "ajući"),
+            ],
+            Paradigm::VerbShort => &[
+                ("eti", "jeti"),
+                ("eo", "io"),
+                ("ela", "jela"),
+                ("elo", "jelo"),
+                ("eli", "jeli"),
+                ("ele", "jele"),
+                ("evši", "jevši"),
+            ],
+            Paradigm::VerbLong => &[
+                ("eti", "ijeti"),
+                ("eo", "io"),
+                ("ela", "ijela"),
+                ("elo", "ijelo"),
+                ("eli", "ijeli"),
+                ("ele", "ijele"),
+                ("evši", "ijevši"),
+            ],
+        }
+    }
+}
+
+// Основе у екавском и ијекавском облику са парадигмом промене.
+static STEMS: &[(&str, &str, Paradigm)] = &[
+    // Именице женског рода
+    ("pesm", "pjesm", Paradigm::FeminineA),
+    ("rek", "rijek", Paradigm::FeminineA),
+    ("ver", "vjer", Paradigm::FeminineA),
+    ("mer", "mjer", Paradigm::FeminineA),
+    ("senk", "sjenk", Paradigm::FeminineA),
+    ("strel", "strijel", Paradigm::FeminineA),
+    ("cen", "cijen", Paradigm::FeminineA),
+    ("nedelj", "nedjelj", Paradigm::FeminineA),
+    ("zvezd", "zvijezd", Paradigm::FeminineA),
+    ("devojk", "djevojk", Paradigm::FeminineA),
+    ("sred", "srijed", Paradigm::FeminineA),
+    ("pen", "pjen", Paradigm::FeminineA),
+    ("sveć", "svijeć", Paradigm::FeminineA),
+    ("ocen", "ocjen", Paradigm::FeminineA),
+    ("promen", "promjen", Paradigm::FeminineA),
+    ("vežb", "vježb", Paradigm::FeminineA),
+    ("dec", "djec", Paradigm::FeminineA),
+    ("bed", "bijed", Paradigm::FeminineA),
+    ("zamer", "zamjer", Paradigm::FeminineA),
+    ("reč", "riječ", Paradigm::FeminineI),
+    ("vest", "vijest", Paradigm::FeminineI),
+    ("svest", "svijest", Paradigm::FeminineI),
+    ("svetlost", "svjetlost", Paradigm::FeminineI),
+    // Именице мушког рода
+    ("pesnik", "pjesnik", Paradigm::Masculine),
+    ("svet", "svijet", Paradigm::Masculine),
+    ("cvet", "cvijet", Paradigm::Masculine),
+    ("vek", "vijek", Paradigm::Masculine),
+    ("sneg", "snijeg", Paradigm::Masculine),
+    ("hleb", "hljeb", Paradigm::Masculine),
+    ("vetr", "vjetr", Paradigm::Masculine),
+    ("greh", "grijeh", Paradigm::Masculine),
+    ("ded", "djed", Paradigm::Masculine),
+    ("lek", "lijek", Paradigm::Masculine),
+    ("lekar", "ljekar", Paradigm::Masculine),
+    ("rečnik", "rječnik", Paradigm::Masculine),
+    ("mesec", "mjesec", Paradigm::Masculine),
+    ("sever", "sjever", Paradigm::Masculine),
+    ("predsednik", "predsjednik", Paradigm::Masculine),
+    ("čovek", "čovjek", Paradigm::Masculine),
+    ("obed", "objed", Paradigm::Masculine),
+    ("primer", "primjer", Paradigm::Masculine),
+    ("savet", "savjet", Paradigm::Masculine),
+    ("uspeh", "uspjeh", Paradigm::Masculine),
+    ("smeh", "smijeh", Paradigm::Masculine),
+    ("nemac", "nijemac", Paradigm::Masculine),
+    // Именице средњег рода
+    ("mlek", "mlijek", Paradigm::Neuter),
+    ("mest", "mjest", Paradigm::Neuter),
+    ("del", "djel", Paradigm::Neuter),
+    ("tel", "tijel", Paradigm::Neuter),
+    ("let", "ljet", Paradigm::Neuter),
+    ("odel", "odijel", Paradigm::Neuter),
+    ("odeljenj", "odjeljenj", Paradigm::Neuter),
+    ("sen", "sijen", Paradigm::Neuter),
+    // Придеви
+    ("lep", "lijep", Paradigm::Adjective),
+    ("lepš", "ljepš", Paradigm::Adjective),
+    ("bel", "bijel", Paradigm::Adjective),
+    ("cel", "cijel", Paradigm::Adjective),
+    ("bled", "blijed", Paradigm::Adjective),
+    ("svetl", "svjetl", Paradigm::Adjective),
+    ("večn", "vječn", Paradigm::Adjective),
+    ("nemačk", "njemačk", Paradigm::Adjective),
+    ("dečj", "dječj", Paradigm::Adjective),
+    ("uspešn", "uspješn", Paradigm::Adjective),
+    ("mesn", "mjesn", Paradigm::Adjective),
+    // Глаголи
+    ("pev", "pjev", Paradigm::VerbA),
+    ("verov", "vjerov", Paradigm::VerbA),
+    ("menj", "mjenj", Paradigm::VerbA),
+    ("vid", "vid", Paradigm::VerbShort),
+    ("vol", "vol", Paradigm::VerbShort),
+    ("živ", "živ", Paradigm::VerbShort),
+    ("žel", "žel", Paradigm::VerbShort),
+    ("trp", "trp", Paradigm::VerbShort),
+    ("let", "let", Paradigm::VerbShort),
+    ("um", "um", Paradigm::VerbShort),
+    ("ht", "ht", Paradigm::VerbShort),
+    ("sm", "sm", Paradigm::VerbShort),
+    ("razum", "razum", Paradigm::VerbShort),
+    ("mrz", "mrz", Paradigm::VerbShort),
+    ("gor", "gor", Paradigm::VerbShort),
+    ("bol", "bol", Paradigm::VerbShort),
+    ("don", "don", Paradigm::VerbLong),
+    ("un", "un", Paradigm::VerbLong),
+    ("pren", "pren", Paradigm::VerbLong),
+    ("odn", "odn", Paradigm::VerbLong),
+    ("zan", "zan", Paradigm::VerbLong),
+];
+
+// Непроменљиве речи и неправилни облици (екавски -> ијекавски).
+static WORDS: phf::Map<&'static str, &'static str> = phf_map! {
+    "dete" => "dijete",
+    "deteta" => "djeteta",
+    "detetu" => "djetetu",
+    "detetom" => "djetetom",
+    "vreme" => "vrijeme",
+    "mleko" => "mlijeko",
+    "deo" => "dio",
+    "beo" => "bijel",
+    "ceo" => "cio",
+    "vetar" => "vjetar",
+    "pesak" => "pijesak",
+    "gde" => "gdje",
+    "ovde" => "ovdje",
+    "onde" => "ondje",
+    "negde" => "negdje",
+    "nigde" => "nigdje",
+    "igde" => "igdje",
+    "uvek" => "uvijek",
+    "pre" => "prije",
+    "posle" => "poslije",
+    "sme" => "smije",
+    "smem" => "smijem",
+    "smeš" => "smiješ",
+    "smemo" => "smijemo",
+    "smete" => "smijete",
+    "smeju" => "smiju",
+    "lepše" => "ljepše",
+};
+
+static TO_IJEKAVIAN: Lazy<HashMap<String, String>> =
+    Lazy::new(|| build_index(|ek, ijek| (ek.to_string(), ijek.to_string())));
+static TO_EKAVIAN: Lazy<HashMap<String, String>> =
+    Lazy::new(|| build_index(|ek, ijek| (ijek.to_string(), ek.to_string())));
+
+/// Генерише све облике речи из речника. Речник непроменљивих облика има
+/// предност, а међу основама важи прва наведена.
+fn build_index(pair: impl Fn(&str, &str) -> (String, String)) -> HashMap<String, String> {
+    let mut index = HashMap::new();
+    for (ek, ijek) in WORDS.entries() {
+        let (from, to) = pair(ek, ijek);
+        index.insert(from, to);
+    }
+    for &(ek_stem, ijek_stem, paradigm) in STEMS {
+        for &(ek_end, ijek_end) in paradigm.endings() {
+            let (from, to) = pair(
+                &format!("{ek_stem}{ek_end}"),
+                &format!("{ijek_stem}{ijek_end}"),
+            );
+            index.entry(from).or_insert(to);
+        }
+    }
+    index
+}
+
+/// Конверзија екавског текста у ијекавски. Ради и за ћирилицу и за латиницу.
+pub fn ekavian_to_ijekavian(input: &str) -> String {
+    convert_words(input, &TO_IJEKAVIAN)
+}
+
+/// Конверзија ијекавског текста у екавски. Ради и за ћирилицу и за латиницу.
+pub fn ijekavian_to_ekavian(input: &str) -> String {
+    convert_words(input, &TO_EKAVIAN)
+}
+
+fn convert_words(input: &str, index: &HashMap<String, String>) -> String {
+    split_words(input)
+        .map(
+            |(part, is_word)| match is_word.then(|| convert_word(part, index)).flatten() {
+                Some(converted) => Cow::Owned(converted),
+                None => Cow::Borrowed(part),
+            },
+        )
+        .collect()
+}
+
+fn convert_word(word: &str, index: &HashMap<String, String>) -> Option<String> {
+    let cyrillic = word.chars().any(|c| ('\u{400}'..='\u{4FF}').contains(&c));
+    let latin = if cyrillic {
+        cyr_to_lat(word)
+    } else {
+        word.to_string()
+    };
+    let converted = index.get(&latin.to_lowercase())?;
+    let cased = apply_case(&latin, converted);
+    Some(if cyrillic { lat_to_cyr(&cased) } else { cased })
+}
+
+/// Преноси величину слова оригиналне речи на конвертовану реч.
+fn apply_case(original: &str, converted: &str) -> String {
+    let mut chars = original.chars();
+    let first_upper = chars.next().is_some_and(char::is_uppercase);
+    let all_upper = first_upper && original.chars().count() > 1 && chars.all(char::is_uppercase);
+    if all_upper {
+        converted.to_uppercase()
+    } else if first_upper {
+        let mut chars = converted.chars();
+        chars
+            .next()
+            .map(|c| c.to_uppercase().chain(chars).collect())
+            .unwrap_or_default()
+    } else {
+        converted.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ekavian_to_ijekavian() {
+        assert_eq!(
+            "Lijepo dijete pije mlijeko u pjesmi.",
+            ekavian_to_ijekavian("Lepo dete pije mleko u pesmi.")
+        );
+        assert_eq!(
+            "Vidio je svijet i htio da živi.",
+            ekavian_to_ijekavian("Video je svet i hteo da živi.")
+        );
+        assert_eq!(
+            "Donijela je cvijet? VRIJEME!",
+            ekavian_to_ijekavian("Donela je cvet? VREME!")
+        );
+        assert_eq!(
+            "Гдје је ријека? Дјеца воле да пјевају.",
+            ekavian_to_ijekavian("Где је река? Деца воле да певају.")
+        );
+        // Речи ван речника се не мењају
+        assert_eq!(
+            "Rekao je da pere.",
+            ekavian_to_ijekavian("Rekao je da pere.")
+        );
+    }
+
+    #[test]
+    fn test_ijekavian_to_ekavian() {
+        assert_eq!(
+            "Lepo dete pije mleko u pesmi.",
+            ijekavian_to_ekavian("Lijepo dijete pije mlijeko u pjesmi.")
+        );
+        assert_eq!(
+            "Voleo je svoju devojku.",
+            ijekavian_to_ekavian("Volio je svoju djevojku.")
+        );
+        assert_eq!("Свет је леп.", ijekavian_to_ekavian("Свијет је лијеп."));
+    }
+}
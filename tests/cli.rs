@@ -0,0 +1,189 @@
+//! Тестови командне линије који покрећу извршни фајл `ћирко`.
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+
+/// Привремени директоријум теста, који се брише по завршетку.
+struct TempDir(PathBuf);
+
+impl TempDir {
+    fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("cirko-cli-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(&path).unwrap();
+        TempDir(path)
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+/// Покреће `ћирко` у датом директоријуму, без конфигурационог фајла и са
+/// порукама на српском.
+fn cirko(dir: &Path, args: &[&str], input: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_ћирко"))
+        .args(args)
+        .current_dir(dir)
+        .env("XDG_CONFIG_HOME", dir)
+        .env("APPDATA", dir)
+        .env("CIRKO_LANG", "sr-Cyrl")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(input.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stderr).into_owned()
+}
+
+#[test]
+fn test_socket_client_rejects_converter_options() {
+    let dir = TempDir::new("socket");
+    let socket = dir.0.join("cirko.sock");
+    let socket = socket.to_str().unwrap();
+    let output = cirko(&dir.0, &["--сокет", socket, "--азбука", "руска"], "Njiva");
+    assert_eq!(Some(2), output.status.code());
+    assert!(stderr(&output).contains("--азбука"), "{}", stderr(&output));
+    // Смер конверзије се може задати
+    let output = cirko(&dir.0, &["--сокет", socket, "-ћ"], "Njiva");
+    assert!(!stderr(&output).contains("службе"), "{}", stderr(&output));
+}
+
+#[test]
+fn test_overwrite_requires_force() {
+    let dir = TempDir::new("force");
+    let file = dir.0.join("tekst.txt");
+    fs::write(&file, "Njiva\n").unwrap();
+    let output = cirko(&dir.0, &["-ћ", "-у", "tekst.txt", "-и", "tekst.txt"], "");
+    assert_eq!(Some(2), output.status.code());
+    assert!(stderr(&output).contains("--сила"), "{}", stderr(&output));
+    assert_eq!("Njiva\n", fs::read_to_string(&file).unwrap());
+
+    let output = cirko(
+        &dir.0,
+        &["-ћ", "-у", "tekst.txt", "-и", "tekst.txt", "--сила"],
+        "",
+    );
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!("Њива\n", fs::read_to_string(&file).unwrap());
+}
+
+#[test]
+fn test_output_dir() {
+    let dir = TempDir::new("output-dir");
+    for (path, content) in [
+        ("a/knjiga.txt", "Knjiga\n"),
+        ("b/pesma.txt", "Pesma\n"),
+        ("b/knjiga.txt", "Knjiga\n"),
+    ] {
+        let path = dir.0.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+    // Фајлови се уписују под својим називима, без директоријума
+    let output = cirko(
+        &dir.0,
+        &["-ћ", "--излаз-дир", "izlaz", "a/knjiga.txt", "b/pesma.txt"],
+        "",
+    );
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(
+        "Књига\n",
+        fs::read_to_string(dir.0.join("izlaz/knjiga.txt")).unwrap()
+    );
+    assert_eq!(
+        "Песма\n",
+        fs::read_to_string(dir.0.join("izlaz/pesma.txt")).unwrap()
+    );
+
+    let output = cirko(
+        &dir.0,
+        &[
+            "-ћ",
+            "--излаз-дир",
+            "cirilica",
+            "--преслови-називе",
+            "a/knjiga.txt",
+        ],
+        "",
+    );
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(dir.0.join("cirilica/књига.txt").is_file());
+
+    // Фајлови истог назива се не могу уписати у исти директоријум
+    let output = cirko(
+        &dir.0,
+        &["-ћ", "--излаз-дир", "isti", "a/knjiga.txt", "b/knjiga.txt"],
+        "",
+    );
+    assert_eq!(Some(2), output.status.code());
+    assert!(
+        stderr(&output).contains("knjiga.txt"),
+        "{}",
+        stderr(&output)
+    );
+    assert!(!dir.0.join("isti").exists());
+}
+
+#[test]
+fn test_check_and_strict_exit_codes() {
+    let dir = TempDir::new("check");
+    let output = cirko(&dir.0, &["-ћ", "--провера"], "Њива\n");
+    assert_eq!(Some(0), output.status.code());
+    assert_eq!("", stdout(&output));
+
+    let output = cirko(&dir.0, &["-ћ", "--провера"], "Њива\nNjiva\n");
+    assert_eq!(Some(1), output.status.code());
+    assert_eq!("stdin:2: Njiva\n", stdout(&output));
+
+    let output = cirko(&dir.0, &["-ћ", "--строго"], "Njiva\n");
+    assert_eq!(Some(0), output.status.code());
+    assert_eq!("Њива\n", stdout(&output));
+
+    let output = cirko(&dir.0, &["-ћ", "--строго"], "Njiva Wxy\n");
+    assert_eq!(Some(1), output.status.code());
+    assert!(stderr(&output).contains("1:6"), "{}", stderr(&output));
+}
+
+#[test]
+fn test_hook_exit_status() {
+    let dir = TempDir::new("hook");
+    fs::write(dir.0.join("cirilica.txt"), "Њива\n").unwrap();
+    fs::write(dir.0.join("latinica.txt"), "Njiva\n").unwrap();
+    let output = cirko(&dir.0, &["хук", "-ћ", "cirilica.txt"], "");
+    assert_eq!(Some(0), output.status.code());
+
+    let output = cirko(&dir.0, &["хук", "-ћ", "cirilica.txt", "latinica.txt"], "");
+    assert_eq!(Some(1), output.status.code());
+    assert_eq!("latinica.txt:1: Njiva\n", stdout(&output));
+
+    // Поправљен фајл треба поново додати, па је излазни код и тада 1
+    let output = cirko(&dir.0, &["хук", "-ћ", "--поправи", "latinica.txt"], "");
+    assert_eq!(Some(1), output.status.code());
+    assert_eq!(
+        "Њива\n",
+        fs::read_to_string(dir.0.join("latinica.txt")).unwrap()
+    );
+    let output = cirko(&dir.0, &["хук", "-ћ", "latinica.txt"], "");
+    assert_eq!(Some(0), output.status.code());
+
+    let output = cirko(&dir.0, &["хук", "-ћ", "nema.txt"], "");
+    assert_eq!(Some(1), output.status.code());
+}